@@ -18,6 +18,10 @@ use hdk_proc_macros::zome;
 use hc_zome_rea_agreement_defs::{ entry_def, base_entry_def };
 use hc_zome_rea_agreement_rpc::*;
 use hc_zome_rea_agreement_lib::*;
+use hdk_graph_helpers::integrity::IndexIntegrityReport;
+use hdk_graph_helpers::tombstones::tombstone_entry_def;
+use hc_zome_rea_agreement_storage_consts::AGREEMENT_BASE_ENTRY_TYPE;
+use hdk_graph_helpers::idempotency::idempotency_key_entry_def;
 
 
 // Zome entry type wrappers
@@ -44,6 +48,16 @@ mod rea_agreement_zome {
         base_entry_def()
     }
 
+    #[entry_def]
+    fn agreement_idempotency_key_entry_def() -> ValidatingEntryType {
+        idempotency_key_entry_def(AGREEMENT_BASE_ENTRY_TYPE)
+    }
+
+    #[entry_def]
+    fn record_tombstone_entry_def() -> ValidatingEntryType {
+        tombstone_entry_def()
+    }
+
     #[zome_fn("hc_public")]
     fn create_agreement(agreement: CreateRequest) -> ZomeApiResult<ResponseData> {
         receive_create_agreement(agreement)
@@ -54,6 +68,16 @@ mod rea_agreement_zome {
         receive_get_agreement(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn get_agreements_by_ids(addresses: Vec<AgreementAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+        receive_get_agreements_by_ids(addresses)
+    }
+
+    #[zome_fn("hc_public")]
+    fn get_agreement_revisions(address: AgreementAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+        receive_get_agreement_revisions(address)
+    }
+
     #[zome_fn("hc_public")]
     fn update_agreement(agreement: UpdateRequest) -> ZomeApiResult<ResponseData> {
         receive_update_agreement(agreement)
@@ -64,6 +88,24 @@ mod rea_agreement_zome {
         receive_delete_agreement(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn restore_agreement(address: AgreementAddress) -> ZomeApiResult<bool> {
+        receive_restore_agreement(address)
+    }
+
+    // admin API
+
+    #[zome_fn("hc_public")]
+    fn check_index_integrity() -> ZomeApiResult<IndexIntegrityReport> {
+        receive_check_index_integrity(false)
+    }
+
+    // repairs remove links & entries, so are not exposed to other agents
+    #[zome_fn("admin")]
+    fn repair_indexes() -> ZomeApiResult<IndexIntegrityReport> {
+        receive_check_index_integrity(true)
+    }
+
     // #[zome_fn("hc_public")]
     // fn query_agreements(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>>{
     //     receive_query_agreements(params)
@@ -21,8 +21,8 @@ use hdk_graph_helpers::{
 };
 
 use vf_core::type_aliases::{ AgreementAddress, CommitmentAddress };
-use hc_zome_rea_agreement_storage_consts::{ AGREEMENT_BASE_ENTRY_TYPE, AGREEMENT_COMMITMENTS_LINK_TYPE, AGREEMENT_COMMITMENTS_LINK_TAG };
-use hc_zome_rea_commitment_storage_consts::{ COMMITMENT_BASE_ENTRY_TYPE, COMMITMENT_CLAUSE_OF_LINK_TYPE, COMMITMENT_CLAUSE_OF_LINK_TAG };
+use hc_zome_rea_agreement_storage_consts::{ AGREEMENT_BASE_ENTRY_TYPE, AGREEMENT_COMMITMENTS_LINK_TYPE };
+use hc_zome_rea_commitment_storage_consts::{ COMMITMENT_BASE_ENTRY_TYPE, COMMITMENT_CLAUSE_OF_LINK_TYPE, COMMITMENT_CLAUSE_OF_INDEX };
 
 #[zome]
 mod rea_commitment_index_zome {
@@ -76,11 +76,9 @@ mod rea_commitment_index_zome {
     #[zome_fn("hc_public")]
     fn index_commitments(base_entry: CommitmentAddress, target_entries: Vec<AgreementAddress>, removed_entries: Vec<AgreementAddress>) -> ZomeApiResult<RemoteEntryLinkResponse> {
 let _ = hdk::debug(format!("EEP RCV sync request [{:?}] +({:?}) -({:?})", base_entry, target_entries, removed_entries));
-        handle_sync_direct_remote_index_destination(
-            COMMITMENT_BASE_ENTRY_TYPE,
-            COMMITMENT_CLAUSE_OF_LINK_TYPE, COMMITMENT_CLAUSE_OF_LINK_TAG,
-            AGREEMENT_COMMITMENTS_LINK_TYPE, AGREEMENT_COMMITMENTS_LINK_TAG,
+        Ok(handle_sync_direct_remote_index_destination(
+            &COMMITMENT_CLAUSE_OF_INDEX,
             &base_entry, target_entries, removed_entries
-        )
+        )?)
     }
 }
@@ -21,8 +21,8 @@ use hdk_graph_helpers::{
 };
 
 use vf_core::type_aliases::{ AgreementAddress, EventAddress };
-use hc_zome_rea_agreement_storage_consts::{ AGREEMENT_BASE_ENTRY_TYPE, AGREEMENT_EVENTS_LINK_TYPE, AGREEMENT_EVENTS_LINK_TAG };
-use hc_zome_rea_economic_event_storage_consts::{ EVENT_BASE_ENTRY_TYPE, EVENT_REALIZATION_OF_LINK_TYPE, EVENT_REALIZATION_OF_LINK_TAG };
+use hc_zome_rea_agreement_storage_consts::{ AGREEMENT_BASE_ENTRY_TYPE, AGREEMENT_EVENTS_LINK_TYPE };
+use hc_zome_rea_economic_event_storage_consts::{ EVENT_BASE_ENTRY_TYPE, EVENT_REALIZATION_OF_LINK_TYPE, EVENT_REALIZATION_OF_INDEX };
 
 #[zome]
 mod rea_commitment_index_zome {
@@ -76,11 +76,9 @@ mod rea_commitment_index_zome {
     #[zome_fn("hc_public")]
     fn index_events(base_entry: EventAddress, target_entries: Vec<AgreementAddress>, removed_entries: Vec<AgreementAddress>) -> ZomeApiResult<RemoteEntryLinkResponse> {
 let _ = hdk::debug(format!("EEP RCV sync request [{:?}] +({:?}) -({:?})", base_entry, target_entries, removed_entries));
-        handle_sync_direct_remote_index_destination(
-            EVENT_BASE_ENTRY_TYPE,
-            EVENT_REALIZATION_OF_LINK_TYPE, EVENT_REALIZATION_OF_LINK_TAG,
-            AGREEMENT_EVENTS_LINK_TYPE, AGREEMENT_EVENTS_LINK_TAG,
+        Ok(handle_sync_direct_remote_index_destination(
+            &EVENT_REALIZATION_OF_INDEX,
             &base_entry, target_entries, removed_entries
-        )
+        )?)
     }
 }
@@ -18,14 +18,23 @@ use hdk_proc_macros::zome;
 use hc_zome_rea_economic_event_defs::*;
 use hc_zome_rea_economic_event_lib::*;
 use hc_zome_rea_economic_event_rpc::*;
+use hdk_graph_helpers::remote_sync::{
+    pending_sync_entry_def,
+    retry_pending_remote_index_syncs,
+    RemoteIndexSyncReport,
+};
+use hdk_graph_helpers::integrity::IndexIntegrityReport;
 use hc_zome_rea_economic_resource_rpc::CreateRequest as EconomicResourceCreateRequest;
+use hc_zome_rea_economic_event_storage_consts::EVENT_BASE_ENTRY_TYPE;
+use hdk_graph_helpers::idempotency::idempotency_key_entry_def;
+use hdk_graph_helpers::bridges::validate_bridge_config;
 
 #[zome]
 mod rea_economic_event_zome {
 
     #[init]
     fn init() {
-        Ok(())
+        validate_bridge_config()
     }
 
     #[validate_agent]
@@ -43,19 +52,44 @@ mod rea_economic_event_zome {
         base_entry_def()
     }
 
+    #[entry_def]
+    fn event_idempotency_key_entry_def() -> ValidatingEntryType {
+        idempotency_key_entry_def(EVENT_BASE_ENTRY_TYPE)
+    }
+
     #[entry_def]
     fn event_root_entry_def() -> ValidatingEntryType {
         root_entry_def()
     }
 
+    #[entry_def]
+    fn event_time_index_entry_def() -> ValidatingEntryType {
+        time_index_entry_def()
+    }
+
+    #[entry_def]
+    fn pending_remote_index_sync_entry_def() -> ValidatingEntryType {
+        pending_sync_entry_def()
+    }
+
     #[zome_fn("hc_public")]
     fn create_event(event: CreateRequest, new_inventoried_resource: Option<EconomicResourceCreateRequest>) -> ZomeApiResult<ResponseData> {
         receive_create_economic_event(event, new_inventoried_resource)
     }
 
     #[zome_fn("hc_public")]
-    fn get_event(address: EventAddress) -> ZomeApiResult<ResponseData> {
-        receive_get_economic_event(address)
+    fn get_event(address: EventAddress, fields: Option<Vec<String>>) -> ZomeApiResult<ResponseData> {
+        receive_get_economic_event(address, fields)
+    }
+
+    #[zome_fn("hc_public")]
+    fn get_events_by_ids(addresses: Vec<EventAddress>, fields: Option<Vec<String>>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+        receive_get_economic_events_by_ids(addresses, fields)
+    }
+
+    #[zome_fn("hc_public")]
+    fn get_event_revisions(address: EventAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+        receive_get_economic_event_revisions(address)
     }
 
     #[zome_fn("hc_public")]
@@ -69,8 +103,8 @@ mod rea_economic_event_zome {
     }
 
     #[zome_fn("hc_public")]
-    fn get_all_events() -> ZomeApiResult<Vec<ResponseData>> {
-        receive_get_all_economic_events()
+    fn get_all_events(fields: Option<Vec<String>>) -> ZomeApiResult<Vec<ResponseData>> {
+        receive_get_all_economic_events(fields)
     }
 
     #[zome_fn("hc_public")]
@@ -78,6 +112,40 @@ mod rea_economic_event_zome {
         receive_query_events(params)
     }
 
+    #[zome_fn("hc_public")]
+    fn query_events_paginated(params: QueryParams) -> ZomeApiResult<ResponsePage> {
+        receive_query_events_paginated(params)
+    }
+
+    #[zome_fn("hc_public")]
+    fn query_events_by_time(params: TimeQueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+        receive_query_events_by_time(params)
+    }
+
+    // admin API
+
+    #[zome_fn("hc_public")]
+    fn check_index_integrity() -> ZomeApiResult<IndexIntegrityReport> {
+        receive_check_index_integrity(false)
+    }
+
+    // repairs remove links & entries, so are not exposed to other agents
+    #[zome_fn("admin")]
+    fn repair_indexes() -> ZomeApiResult<IndexIntegrityReport> {
+        receive_check_index_integrity(true)
+    }
+
+    // moves records between indexes, so is not exposed to other agents
+    #[zome_fn("admin")]
+    fn migrate_legacy_indexes() -> ZomeApiResult<usize> {
+        receive_migrate_legacy_indexes()
+    }
+
+    #[zome_fn("hc_public")]
+    fn retry_remote_index_syncs() -> ZomeApiResult<RemoteIndexSyncReport> {
+        Ok(retry_pending_remote_index_syncs()?)
+    }
+
 
 
     // :TODO:
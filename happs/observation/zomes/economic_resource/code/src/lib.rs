@@ -18,14 +18,21 @@ use hdk_proc_macros::zome;
 use hc_zome_rea_economic_resource_defs::*;
 use hc_zome_rea_economic_resource_lib::*;
 use hc_zome_rea_economic_resource_rpc::*;
-use hc_zome_rea_economic_event_rpc::ResourceResponseData as ResponseData;
+use hc_zome_rea_economic_event_rpc::{
+    ResourceResponseData as ResponseData,
+    TimeQueryParams,
+};
+use hdk_graph_helpers::{
+    bridges::validate_bridge_config,
+    traversal::Subgraph,
+};
 
 #[zome]
 mod rea_economic_resource_zome {
 
     #[init]
     fn init() {
-        Ok(())
+        validate_bridge_config()
     }
 
     #[validate_agent]
@@ -48,9 +55,19 @@ mod rea_economic_resource_zome {
         root_entry_def()
     }
 
+    #[entry_def]
+    fn resource_time_index_entry_def() -> ValidatingEntryType {
+        time_index_entry_def()
+    }
+
     #[zome_fn("hc_public")]
-    fn get_resource(address: ResourceAddress) -> ZomeApiResult<ResponseData> {
-        receive_get_economic_resource(address)
+    fn get_resource(address: ResourceAddress, fields: Option<Vec<String>>) -> ZomeApiResult<ResponseData> {
+        receive_get_economic_resource(address, fields)
+    }
+
+    #[zome_fn("hc_public")]
+    fn get_resources_by_ids(addresses: Vec<ResourceAddress>, fields: Option<Vec<String>>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+        receive_get_economic_resources_by_ids(addresses, fields)
     }
 
     #[zome_fn("hc_public")]
@@ -59,8 +76,8 @@ mod rea_economic_resource_zome {
     }
 
     #[zome_fn("hc_public")]
-    fn get_all_resources() -> ZomeApiResult<Vec<ResponseData>> {
-        receive_get_all_economic_resources()
+    fn get_all_resources(fields: Option<Vec<String>>) -> ZomeApiResult<Vec<ResponseData>> {
+        receive_get_all_economic_resources(fields)
     }
 
 
@@ -69,6 +86,20 @@ mod rea_economic_resource_zome {
         receive_query_economic_resources(params)
     }
 
+    #[zome_fn("hc_public")]
+    fn query_resources_paginated(params: QueryParams) -> ZomeApiResult<ResponsePage> {
+        receive_query_economic_resources_paginated(params)
+    }
+
+    #[zome_fn("hc_public")]
+    fn query_resources_by_time(params: TimeQueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+        receive_query_economic_resources_by_time(params)
+    }
+
+    #[zome_fn("hc_public")]
+    fn trace_resource_upstream(address: ResourceAddress, max_depth: Option<usize>, max_nodes: Option<usize>) -> ZomeApiResult<Subgraph> {
+        receive_trace_economic_resource_upstream(address, max_depth, max_nodes)
+    }
 
     // :TODO:
     // receive: |from, payload| {
@@ -21,6 +21,8 @@ use hdk_proc_macros::zome;
 use hc_zome_rea_fulfillment_defs::{ entry_def, remote_entry_def };
 use hc_zome_rea_fulfillment_rpc::*;
 use hc_zome_rea_fulfillment_lib_destination::*;
+use hc_zome_rea_fulfillment_storage_consts::FULFILLMENT_BASE_ENTRY_TYPE;
+use hdk_graph_helpers::idempotency::idempotency_key_entry_def;
 
 #[zome]
 mod rea_fulfillment_zome {
@@ -45,6 +47,11 @@ mod rea_fulfillment_zome {
         remote_entry_def()
     }
 
+    #[entry_def]
+    fn fulfillment_idempotency_key_entry_def() -> ValidatingEntryType {
+        idempotency_key_entry_def(FULFILLMENT_BASE_ENTRY_TYPE)
+    }
+
     #[zome_fn("hc_public")]
     fn fulfillment_created(fulfillment: CreateRequest) -> ZomeApiResult<ResponseData> {
         receive_create_fulfillment(fulfillment)
@@ -65,6 +72,11 @@ mod rea_fulfillment_zome {
         receive_get_fulfillment(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn get_fulfillments_by_ids(addresses: Vec<FulfillmentAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+        receive_get_fulfillments_by_ids(addresses)
+    }
+
     #[zome_fn("hc_public")]
     fn query_fulfillments(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
         receive_query_fulfillments(params)
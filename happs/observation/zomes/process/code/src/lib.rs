@@ -35,6 +35,7 @@ use hc_zome_rea_process_defs::{ entry_def, base_entry_def };
 use hc_zome_rea_process_storage_consts::*;
 use hc_zome_rea_process_rpc::*;
 use hc_zome_rea_process_lib::*;
+use hdk_graph_helpers::idempotency::idempotency_key_entry_def;
 
 
 // Zome entry type wrappers
@@ -61,6 +62,11 @@ mod rea_process_zome {
         base_entry_def()
     }
 
+    #[entry_def]
+    fn process_idempotency_key_entry_def() -> ValidatingEntryType {
+        idempotency_key_entry_def(PROCESS_BASE_ENTRY_TYPE)
+    }
+
     #[entry_def]
     fn commitment_base_entry_def() -> ValidatingEntryType {
         entry!(
@@ -145,6 +151,11 @@ mod rea_process_zome {
         receive_get_process(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn get_processes_by_ids(addresses: Vec<ProcessAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+        receive_get_processes_by_ids(addresses)
+    }
+
     #[zome_fn("hc_public")]
     fn update_process(process: UpdateRequest) -> ZomeApiResult<ResponseData> {
         receive_update_process(process)
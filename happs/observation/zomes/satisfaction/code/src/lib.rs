@@ -20,6 +20,8 @@ use hdk_proc_macros::zome;
 use hc_zome_rea_satisfaction_defs::{ entry_def, remote_entry_def };
 use hc_zome_rea_satisfaction_rpc::*;
 use hc_zome_rea_satisfaction_lib_destination::*;
+use hc_zome_rea_satisfaction_storage_consts::SATISFACTION_BASE_ENTRY_TYPE;
+use hdk_graph_helpers::idempotency::idempotency_key_entry_def;
 
 #[zome]
 mod rea_satisfaction_zome {
@@ -44,6 +46,11 @@ mod rea_satisfaction_zome {
         remote_entry_def()
     }
 
+    #[entry_def]
+    fn satisfaction_idempotency_key_entry_def() -> ValidatingEntryType {
+        idempotency_key_entry_def(SATISFACTION_BASE_ENTRY_TYPE)
+    }
+
     #[zome_fn("hc_public")]
     fn satisfaction_created(satisfaction: CreateRequest) -> ZomeApiResult<ResponseData> {
         receive_create_satisfaction(satisfaction)
@@ -64,6 +71,11 @@ mod rea_satisfaction_zome {
         receive_get_satisfaction(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn get_satisfactions_by_ids(addresses: Vec<SatisfactionAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+        receive_get_satisfactions_by_ids(addresses)
+    }
+
     #[zome_fn("hc_public")]
     fn query_satisfactions(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
         receive_query_satisfactions(params)
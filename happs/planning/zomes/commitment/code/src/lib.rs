@@ -22,6 +22,16 @@ use hdk_proc_macros::zome;
 use hc_zome_rea_commitment_defs::{ entry_def, base_entry_def };
 use hc_zome_rea_commitment_rpc::*;
 use hc_zome_rea_commitment_lib::*;
+use hdk_graph_helpers::remote_sync::{
+    pending_sync_entry_def,
+    retry_pending_remote_index_syncs,
+    RemoteIndexSyncReport,
+};
+use hdk_graph_helpers::integrity::IndexIntegrityReport;
+use hdk_graph_helpers::tombstones::tombstone_entry_def;
+use hc_zome_rea_commitment_storage_consts::COMMITMENT_BASE_ENTRY_TYPE;
+use hdk_graph_helpers::idempotency::idempotency_key_entry_def;
+use hdk_graph_helpers::bridges::validate_bridge_config;
 
 // Zome entry type wrappers
 #[zome]
@@ -29,7 +39,7 @@ mod rea_commitment_zome {
 
     #[init]
     fn init() {
-        Ok(())
+        validate_bridge_config()
     }
 
     #[validate_agent]
@@ -47,6 +57,21 @@ mod rea_commitment_zome {
         base_entry_def()
     }
 
+    #[entry_def]
+    fn commitment_idempotency_key_entry_def() -> ValidatingEntryType {
+        idempotency_key_entry_def(COMMITMENT_BASE_ENTRY_TYPE)
+    }
+
+    #[entry_def]
+    fn record_tombstone_entry_def() -> ValidatingEntryType {
+        tombstone_entry_def()
+    }
+
+    #[entry_def]
+    fn pending_remote_index_sync_entry_def() -> ValidatingEntryType {
+        pending_sync_entry_def()
+    }
+
     #[zome_fn("hc_public")]
     fn create_commitment(commitment: CreateRequest) -> ZomeApiResult<ResponseData> {
         receive_create_commitment(commitment)
@@ -57,6 +82,16 @@ mod rea_commitment_zome {
         receive_get_commitment(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn get_commitments_by_ids(addresses: Vec<CommitmentAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+        receive_get_commitments_by_ids(addresses)
+    }
+
+    #[zome_fn("hc_public")]
+    fn get_commitment_revisions(address: CommitmentAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+        receive_get_commitment_revisions(address)
+    }
+
     #[zome_fn("hc_public")]
     fn update_commitment(commitment: UpdateRequest) -> ZomeApiResult<ResponseData> {
         receive_update_commitment(commitment)
@@ -67,11 +102,39 @@ mod rea_commitment_zome {
         receive_delete_commitment(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn restore_commitment(address: CommitmentAddress) -> ZomeApiResult<bool> {
+        receive_restore_commitment(address)
+    }
+
     #[zome_fn("hc_public")]
     fn query_commitments(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>>{
         receive_query_commitments(params)
     }
 
+    #[zome_fn("hc_public")]
+    fn query_commitments_paginated(params: QueryParams) -> ZomeApiResult<ResponsePage> {
+        receive_query_commitments_paginated(params)
+    }
+
+    // admin API
+
+    #[zome_fn("hc_public")]
+    fn check_index_integrity() -> ZomeApiResult<IndexIntegrityReport> {
+        receive_check_index_integrity(false)
+    }
+
+    // repairs remove links & entries, so are not exposed to other agents
+    #[zome_fn("admin")]
+    fn repair_indexes() -> ZomeApiResult<IndexIntegrityReport> {
+        receive_check_index_integrity(true)
+    }
+
+    #[zome_fn("hc_public")]
+    fn retry_remote_index_syncs() -> ZomeApiResult<RemoteIndexSyncReport> {
+        Ok(retry_pending_remote_index_syncs()?)
+    }
+
     // :TODO:
     // receive: |from, payload| {
     //     format!("Received: {} from {}", payload, from)
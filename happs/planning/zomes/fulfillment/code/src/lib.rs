@@ -18,6 +18,9 @@ use hdk_proc_macros::zome;
 use hc_zome_rea_fulfillment_defs::{ entry_def, base_entry_def };
 use hc_zome_rea_fulfillment_rpc::*;
 use hc_zome_rea_fulfillment_lib_origin::*;
+use hc_zome_rea_fulfillment_storage_consts::FULFILLMENT_BASE_ENTRY_TYPE;
+use hdk_graph_helpers::idempotency::idempotency_key_entry_def;
+use hdk_graph_helpers::bridges::validate_bridge_config;
 
 // Zome entry type wrappers
 #[zome]
@@ -25,7 +28,7 @@ mod rea_fulfillment_zome {
 
     #[init]
     fn init() {
-        Ok(())
+        validate_bridge_config()
     }
 
     #[validate_agent]
@@ -43,6 +46,11 @@ mod rea_fulfillment_zome {
         base_entry_def()
     }
 
+    #[entry_def]
+    fn fulfillment_idempotency_key_entry_def() -> ValidatingEntryType {
+        idempotency_key_entry_def(FULFILLMENT_BASE_ENTRY_TYPE)
+    }
+
     #[zome_fn("hc_public")]
     fn create_fulfillment(fulfillment: CreateRequest) -> ZomeApiResult<ResponseData> {
         receive_create_fulfillment(fulfillment)
@@ -53,6 +61,11 @@ mod rea_fulfillment_zome {
         receive_get_fulfillment(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn get_fulfillments_by_ids(addresses: Vec<FulfillmentAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+        receive_get_fulfillments_by_ids(addresses)
+    }
+
     #[zome_fn("hc_public")]
     fn update_fulfillment(fulfillment: UpdateRequest) -> ZomeApiResult<ResponseData> {
         receive_update_fulfillment(fulfillment)
@@ -18,6 +18,15 @@ use hdk_proc_macros::zome;
 use hc_zome_rea_intent_defs::{ entry_def, base_entry_def };
 use hc_zome_rea_intent_rpc::*;
 use hc_zome_rea_intent_lib::*;
+use hdk_graph_helpers::remote_sync::{
+    pending_sync_entry_def,
+    retry_pending_remote_index_syncs,
+    RemoteIndexSyncReport,
+};
+use hdk_graph_helpers::tombstones::tombstone_entry_def;
+use hc_zome_rea_intent_storage_consts::INTENT_BASE_ENTRY_TYPE;
+use hdk_graph_helpers::idempotency::idempotency_key_entry_def;
+use hdk_graph_helpers::bridges::validate_bridge_config;
 
 // Zome entry type wrappers
 #[zome]
@@ -25,7 +34,7 @@ mod rea_intent_zome {
 
     #[init]
     fn init() {
-        Ok(())
+        validate_bridge_config()
     }
 
     #[validate_agent]
@@ -43,6 +52,21 @@ mod rea_intent_zome {
         base_entry_def()
     }
 
+    #[entry_def]
+    fn intent_idempotency_key_entry_def() -> ValidatingEntryType {
+        idempotency_key_entry_def(INTENT_BASE_ENTRY_TYPE)
+    }
+
+    #[entry_def]
+    fn record_tombstone_entry_def() -> ValidatingEntryType {
+        tombstone_entry_def()
+    }
+
+    #[entry_def]
+    fn pending_remote_index_sync_entry_def() -> ValidatingEntryType {
+        pending_sync_entry_def()
+    }
+
     #[zome_fn("hc_public")]
     fn create_intent(intent: CreateRequest) -> ZomeApiResult<ResponseData> {
         receive_create_intent(intent)
@@ -53,6 +77,11 @@ mod rea_intent_zome {
         receive_get_intent(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn get_intents_by_ids(addresses: Vec<IntentAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+        receive_get_intents_by_ids(addresses)
+    }
+
     #[zome_fn("hc_public")]
     fn update_intent(intent: UpdateRequest) -> ZomeApiResult<ResponseData> {
         receive_update_intent(intent)
@@ -63,11 +92,26 @@ mod rea_intent_zome {
         receive_delete_intent(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn restore_intent(address: IntentAddress) -> ZomeApiResult<bool> {
+        receive_restore_intent(address)
+    }
+
     #[zome_fn("hc_public")]
     fn query_intents(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>>{
         receive_query_intents(params)
     }
 
+    #[zome_fn("hc_public")]
+    fn query_intents_paginated(params: QueryParams) -> ZomeApiResult<ResponsePage> {
+        receive_query_intents_paginated(params)
+    }
+
+    #[zome_fn("hc_public")]
+    fn retry_remote_index_syncs() -> ZomeApiResult<RemoteIndexSyncReport> {
+        Ok(retry_pending_remote_index_syncs()?)
+    }
+
     // :TODO: wire up remote indexing API if necessary
 
     // :TODO:
@@ -20,6 +20,8 @@ use hdk_proc_macros::zome;
 use hc_zome_rea_proposed_intent_defs::{base_entry_def, entry_def};
 use hc_zome_rea_proposed_intent_lib_destination_planning::*;
 use hc_zome_rea_proposed_intent_rpc::*;
+use hc_zome_rea_proposed_intent_storage_consts::PROPOSED_INTENT_BASE_ENTRY_TYPE;
+use hdk_graph_helpers::idempotency::idempotency_key_entry_def;
 
 // Zome entry type wrappers
 #[zome]
@@ -45,6 +47,11 @@ mod rea_proposed_intent_zome {
         base_entry_def()
     }
 
+    #[entry_def]
+    fn proposed_intent_idempotency_key_entry_def() -> ValidatingEntryType {
+        idempotency_key_entry_def(PROPOSED_INTENT_BASE_ENTRY_TYPE)
+    }
+
     #[zome_fn("hc_public")]
     fn created_proposed_intent(proposed_intent: CreateRequest) -> ZomeApiResult<ResponseData> {
         receive_create_proposed_intent(proposed_intent)
@@ -55,6 +62,11 @@ mod rea_proposed_intent_zome {
         receive_get_proposed_intent(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn get_proposed_intents_by_ids(addresses: Vec<ProposedIntentAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+        receive_get_proposed_intents_by_ids(addresses)
+    }
+
     #[zome_fn("hc_public")]
     fn deleted_proposed_intent(address: ProposedIntentAddress) -> ZomeApiResult<bool> {
         receive_delete_proposed_intent(address)
@@ -18,6 +18,9 @@ use hdk_proc_macros::zome;
 use hc_zome_rea_satisfaction_defs::{ entry_def, base_entry_def };
 use hc_zome_rea_satisfaction_rpc::*;
 use hc_zome_rea_satisfaction_lib_origin::*;
+use hc_zome_rea_satisfaction_storage_consts::SATISFACTION_BASE_ENTRY_TYPE;
+use hdk_graph_helpers::idempotency::idempotency_key_entry_def;
+use hdk_graph_helpers::bridges::validate_bridge_config;
 
 // Zome entry type wrappers
 #[zome]
@@ -25,7 +28,7 @@ mod rea_satisfaction_zome {
 
     #[init]
     fn init() {
-        Ok(())
+        validate_bridge_config()
     }
 
     #[validate_agent]
@@ -43,6 +46,11 @@ mod rea_satisfaction_zome {
         base_entry_def()
     }
 
+    #[entry_def]
+    fn satisfaction_idempotency_key_entry_def() -> ValidatingEntryType {
+        idempotency_key_entry_def(SATISFACTION_BASE_ENTRY_TYPE)
+    }
+
     #[zome_fn("hc_public")]
     fn create_satisfaction(satisfaction: CreateRequest) -> ZomeApiResult<ResponseData> {
         receive_create_satisfaction(satisfaction)
@@ -53,6 +61,11 @@ mod rea_satisfaction_zome {
         receive_get_satisfaction(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn get_satisfactions_by_ids(addresses: Vec<SatisfactionAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+        receive_get_satisfactions_by_ids(addresses)
+    }
+
     #[zome_fn("hc_public")]
     fn update_satisfaction(satisfaction: UpdateRequest) -> ZomeApiResult<ResponseData> {
         receive_update_satisfaction(satisfaction)
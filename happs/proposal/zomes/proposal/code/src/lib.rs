@@ -16,7 +16,10 @@ use vf_core::type_aliases::ProposalAddress;
 
 use hc_zome_rea_proposal_defs::{base_entry_def, entry_def};
 use hc_zome_rea_proposal_lib::*;
+use hdk_graph_helpers::integrity::IndexIntegrityReport;
 use hc_zome_rea_proposal_rpc::*;
+use hc_zome_rea_proposal_storage_consts::PROPOSAL_BASE_ENTRY_TYPE;
+use hdk_graph_helpers::idempotency::idempotency_key_entry_def;
 
 // Zome entry type wrappers
 #[zome]
@@ -41,6 +44,11 @@ mod rea_proposal_zome {
         base_entry_def()
     }
 
+    #[entry_def]
+    fn proposal_idempotency_key_entry_def() -> ValidatingEntryType {
+        idempotency_key_entry_def(PROPOSAL_BASE_ENTRY_TYPE)
+    }
+
     #[zome_fn("hc_public")]
     fn create_proposal(proposal: CreateRequest) -> ZomeApiResult<ResponseData> {
         receive_create_proposal(proposal)
@@ -51,6 +59,11 @@ mod rea_proposal_zome {
         receive_get_proposal(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn get_proposals_by_ids(addresses: Vec<ProposalAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+        receive_get_proposals_by_ids(addresses)
+    }
+
     #[zome_fn("hc_public")]
     fn update_proposal(proposal: UpdateRequest) -> ZomeApiResult<ResponseData> {
         receive_update_proposal(proposal)
@@ -61,6 +74,19 @@ mod rea_proposal_zome {
         receive_delete_proposal(address)
     }
 
+    // admin API
+
+    #[zome_fn("hc_public")]
+    fn check_index_integrity() -> ZomeApiResult<IndexIntegrityReport> {
+        receive_check_index_integrity(false)
+    }
+
+    // repairs remove links & entries, so are not exposed to other agents
+    #[zome_fn("admin")]
+    fn repair_indexes() -> ZomeApiResult<IndexIntegrityReport> {
+        receive_check_index_integrity(true)
+    }
+
     // #[zome_fn("hc_public")]
     // fn query_proposals(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
     //     receive_query_proposals(params)
@@ -23,6 +23,8 @@ use hc_zome_rea_proposed_to_lib::*;
 use hc_zome_rea_proposed_to_rpc::*;
 
 use vf_core::type_aliases::ProposedToAddress;
+use hc_zome_rea_proposed_to_storage_consts::PROPOSED_TO_BASE_ENTRY_TYPE;
+use hdk_graph_helpers::idempotency::idempotency_key_entry_def;
 
 #[zome]
 mod rea_proposed_to_zome {
@@ -47,6 +49,11 @@ mod rea_proposed_to_zome {
         base_entry_def()
     }
 
+    #[entry_def]
+    fn proposed_to_idempotency_key_entry_def() -> ValidatingEntryType {
+        idempotency_key_entry_def(PROPOSED_TO_BASE_ENTRY_TYPE)
+    }
+
     #[zome_fn("hc_public")]
     fn create_proposed_to(proposed_to: CreateRequest) -> ZomeApiResult<ResponseData> {
         receive_create_proposed_to(proposed_to)
@@ -57,6 +64,11 @@ mod rea_proposed_to_zome {
         receive_get_proposed_to(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn get_proposed_tos_by_ids(addresses: Vec<ProposedToAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+        receive_get_proposed_tos_by_ids(addresses)
+    }
+
     #[zome_fn("hc_public")]
     fn delete_proposed_to(address: ProposedToAddress) -> ZomeApiResult<bool> {
         receive_delete_proposed_to(address)
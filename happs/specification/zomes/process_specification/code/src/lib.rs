@@ -18,6 +18,9 @@ use hdk_proc_macros::zome;
 use hc_zome_rea_process_specification_defs::{ entry_def, base_entry_def };
 use hc_zome_rea_process_specification_rpc::*;
 use hc_zome_rea_process_specification_lib::*;
+use hc_zome_rea_process_specification_storage_consts::PROCESS_SPECIFICATION_BASE_ENTRY_TYPE;
+use hdk_graph_helpers::integrity::IndexIntegrityReport;
+use hdk_graph_helpers::idempotency::idempotency_key_entry_def;
 
 
 // Zome entry type wrappers
@@ -44,6 +47,11 @@ mod rea_process_specification_zome {
         base_entry_def()
     }
 
+    #[entry_def]
+    fn process_specification_idempotency_key_entry_def() -> ValidatingEntryType {
+        idempotency_key_entry_def(PROCESS_SPECIFICATION_BASE_ENTRY_TYPE)
+    }
+
     #[zome_fn("hc_public")]
     fn create_process_specification(process_specification: CreateRequest) -> ZomeApiResult<ResponseData> {
         receive_create_process_specification(process_specification)
@@ -54,6 +62,11 @@ mod rea_process_specification_zome {
         receive_get_process_specification(address)
     }
 
+    #[zome_fn("hc_public")]
+    fn get_process_specifications_by_ids(addresses: Vec<ProcessSpecificationAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+        receive_get_process_specifications_by_ids(addresses)
+    }
+
     #[zome_fn("hc_public")]
     fn update_process_specification(process_specification: UpdateRequest) -> ZomeApiResult<ResponseData> {
         receive_update_process_specification(process_specification)
@@ -69,6 +82,19 @@ mod rea_process_specification_zome {
         receive_query_process_specifications(params)
     }
 
+    // admin API
+
+    #[zome_fn("hc_public")]
+    fn check_index_integrity() -> ZomeApiResult<IndexIntegrityReport> {
+        receive_check_index_integrity(false)
+    }
+
+    // repairs remove links & entries, so are not exposed to other agents
+    #[zome_fn("admin")]
+    fn repair_indexes() -> ZomeApiResult<IndexIntegrityReport> {
+        receive_check_index_integrity(true)
+    }
+
     // :TODO: wire up remote indexing API if necessary
 
     // :TODO:
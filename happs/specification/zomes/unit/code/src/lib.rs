@@ -54,6 +54,11 @@ mod rea_unit_zome {
         receive_get_unit(id)
     }
 
+    #[zome_fn("hc_public")]
+    fn get_units_by_ids(ids: Vec<UnitId>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+        receive_get_units_by_ids(ids)
+    }
+
     #[zome_fn("hc_public")]
     fn update_unit(unit: UpdateRequest) -> ZomeApiResult<ResponseData> {
         receive_update_unit(unit)
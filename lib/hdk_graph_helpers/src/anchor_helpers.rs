@@ -11,27 +11,33 @@
  */
 use std::convert::{ TryFrom };
 use hdk::{
-    holochain_persistence_api::cas::content::Address,
+    holochain_persistence_api::cas::content::{ Address, AddressableContent },
     holochain_core_types::{
+        entry::Entry,
         entry::Entry::App as AppEntry,
         entry::entry_type::AppEntryType,
         entry::AppEntryValue,
+        link::Link,
     },
-    error::{ ZomeApiResult, ZomeApiError },
-    entry_address,
-    commit_entry,
-    remove_entry,
-    link_entries,
-    remove_link,
+    LinkValidationData,
 };
 
 use super::{
-    identifiers::{ ANCHOR_POINTER_LINK_TAG, ERR_MSG_ENTRY_NOT_FOUND },
+    hdk_api::{
+        entry_address,
+        commit_entry,
+        remove_entry,
+        link_entries,
+        remove_link,
+    },
+    identifiers::{ ANCHOR_POINTER_LINK_TAG, ERR_MSG_ANCHOR_CONFLICT },
+    errors::{ GraphHelperError, GraphHelperResult },
     links::{
         get_linked_addresses,
     },
-    local_indexes::query_direct_index_with_foreign_key,
-    type_wrappers::Addressable,
+    entries::get_entries_by_key_index,
+    local_indexes::load_page_by_key_index,
+    pagination::{ PageRequest, Page },
 };
 
 //--------------------------------[ READ ]--------------------------------------
@@ -40,7 +46,7 @@ pub fn get_anchor_index_entry_address<E>(
     id_entry_type: &E,
     id_link_type: &str,
     id_string: &String,
-) -> ZomeApiResult<Option<Address>>
+) -> GraphHelperResult<Option<Address>>
     where E: Into<AppEntryType> + Clone,
 {
     // determine anchor entry address
@@ -48,7 +54,12 @@ pub fn get_anchor_index_entry_address<E>(
 
     // query linked entry
     let mut entries: Vec<Address> = get_linked_addresses(&anchor_address, id_link_type, ANCHOR_POINTER_LINK_TAG)?;
-    // :TODO: ensure only 1 anchor per entry?
+
+    // concurrent writes by different agents may still result in multiple entries, which cannot be disambiguated.
+    // Such conflicts must be resolved by replacing the record, @see `upsert_anchored_record`.
+    if entries.len() > 1 {
+        return Err(GraphHelperError::AnchorConflict(id_string.to_owned()));
+    }
     Ok(entries.pop())
 }
 
@@ -69,7 +80,7 @@ pub fn read_anchored_record_entries<T, E, A>(
     anchor_entry_type: &E,
     anchor_link_type: &str,
     anchor_string: &String,
-) -> ZomeApiResult<Vec<(A, Option<T>)>>
+) -> GraphHelperResult<Vec<(A, Option<T>)>>
     where E: Into<AppEntryType> + Clone,
         A: From<Address>,
         T: Clone + TryFrom<AppEntryValue>,
@@ -77,17 +88,36 @@ pub fn read_anchored_record_entries<T, E, A>(
     // determine ID anchor entry address
     let anchor_address = determine_anchor_index_address(anchor_entry_type, anchor_string)?;
     // retrieve the indexed records by querying the anchor index
-    query_direct_index_with_foreign_key(&Addressable::from(anchor_address), anchor_link_type, ANCHOR_POINTER_LINK_TAG)
+    get_entries_by_key_index(get_linked_addresses(&anchor_address, anchor_link_type, ANCHOR_POINTER_LINK_TAG)?, false)
 }
 
-fn determine_anchor_index_address<E>(
+/// Paginated version of `read_anchored_record_entries`. Reads only the entries for a single page
+/// of the anchor index, and returns the cursor needed to continue reading.
+///
+/// @see hdk_graph_helpers::pagination
+///
+pub fn read_anchored_record_entries_paginated<T, E, A>(
+    anchor_entry_type: &E,
+    anchor_link_type: &str,
+    anchor_string: &String,
+    page: &PageRequest,
+) -> GraphHelperResult<Page<(A, Option<T>)>>
+    where E: Into<AppEntryType> + Clone,
+        A: From<Address>,
+        T: Clone + TryFrom<AppEntryValue>,
+{
+    let anchor_address = determine_anchor_index_address(anchor_entry_type, anchor_string)?;
+    load_page_by_key_index(get_linked_addresses(&anchor_address, anchor_link_type, ANCHOR_POINTER_LINK_TAG)?, page, false)
+}
+
+pub (crate) fn determine_anchor_index_address<E>(
     id_entry_type: &E,
     id_string: &String,
-) -> ZomeApiResult<Address>
+) -> GraphHelperResult<Address>
     where E: Into<AppEntryType> + Clone,
 {
     let anchor_entry = AppEntry(id_entry_type.to_owned().into(), Some((*id_string).to_owned()).into());
-    entry_address(&anchor_entry)
+    Ok(entry_address(&anchor_entry)?)
 }
 
 //-------------------------------[ CREATE ]-------------------------------------
@@ -103,7 +133,7 @@ pub fn create_anchor_index<E>(
     id_link_type: &str,
     id_string: &String,
     entry_address: &Address,
-) -> ZomeApiResult<Address>
+) -> GraphHelperResult<Address>
     where E: Into<AppEntryType> + Clone,
 {
     let anchor_entry = AppEntry(id_entry_type.to_owned().into(), Some((*id_string).to_owned()).into());
@@ -114,12 +144,73 @@ pub fn create_anchor_index<E>(
             link_entries(&anchor_address, entry_address, id_link_type, ANCHOR_POINTER_LINK_TAG)?;  // :TODO: error handling? Probably fine to treat as critical?
             Ok(anchor_address)
         },
-        Err(e) => Err(e),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Creates an `anchor index` as per `create_anchor_index`, but fails with an `AnchorConflict`
+/// error if the anchor already references some entry other than `entry_address`.
+///
+/// Use this for anchors which act as unique identifiers, rather than as lists of entries.
+///
+pub fn create_unique_anchor_index<E>(
+    id_entry_type: &E,
+    id_link_type: &str,
+    id_string: &String,
+    entry_address: &Address,
+) -> GraphHelperResult<Address>
+    where E: Into<AppEntryType> + Clone,
+{
+    check_anchor_available(id_entry_type, id_link_type, id_string, entry_address)?;
+    create_anchor_index(id_entry_type, id_link_type, id_string, entry_address)
+}
+
+fn check_anchor_available<E>(
+    id_entry_type: &E,
+    id_link_type: &str,
+    id_string: &String,
+    entry_address: &Address,
+) -> GraphHelperResult<()>
+    where E: Into<AppEntryType> + Clone,
+{
+    let anchor_address = determine_anchor_index_address(id_entry_type, id_string)?;
+    let existing = get_linked_addresses(&anchor_address, id_link_type, ANCHOR_POINTER_LINK_TAG)?;
+
+    if existing.iter().any(|addr| addr != entry_address) {
+        return Err(GraphHelperError::AnchorConflict(id_string.to_owned()));
     }
+    Ok(())
 }
 
 //-------------------------------[ UPDATE ]-------------------------------------
 
+/// Points a unique `anchor index` at `entry_address`, replacing any entries it previously referenced.
+///
+/// Returns the address of the anchor index, and the addresses of any entries which were replaced.
+///
+pub fn replace_anchor_index<E>(
+    id_entry_type: &E,
+    id_link_type: &str,
+    id_string: &String,
+    entry_address: &Address,
+) -> GraphHelperResult<(Address, Vec<Address>)>
+    where E: Into<AppEntryType> + Clone,
+{
+    let anchor_address = determine_anchor_index_address(id_entry_type, id_string)?;
+    let replaced: Vec<Address> = get_linked_addresses(&anchor_address, id_link_type, ANCHOR_POINTER_LINK_TAG)?
+        .into_iter()
+        .filter(|addr| addr != entry_address)
+        .collect();
+
+    // previous pointers must be cleared before linking, else a new link following any written by
+    // this agent will fail `validate_unique_anchor_link`
+    for addr in replaced.iter() {
+        remove_link(&anchor_address, addr, id_link_type, ANCHOR_POINTER_LINK_TAG)?;
+    }
+
+    Ok((create_anchor_index(id_entry_type, id_link_type, id_string, entry_address)?, replaced))
+}
+
 /// Update an `anchor index` by changing the identifier from `old_id_string` to
 /// `new_id_string` for the given `entry_address`.
 ///
@@ -131,7 +222,7 @@ pub fn update_anchor_index<E>(
     entry_address: &Address,
     old_id_string: &String,
     new_id_string: &String,
-) -> ZomeApiResult<Address>
+) -> GraphHelperResult<Address>
     where E: Into<AppEntryType> + Clone,
 {
     // determine anchor entry address
@@ -142,6 +233,9 @@ pub fn update_anchor_index<E>(
         return Ok(old_anchor_address);
     }
 
+    // ensure the new ID is not taken before modifying anything
+    check_anchor_available(id_entry_type, id_link_type, new_id_string, entry_address)?;
+
     // wipe old anchor
     remove_link(&old_anchor_address, entry_address, id_link_type, ANCHOR_POINTER_LINK_TAG)?;
     remove_entry(&old_anchor_address)?;
@@ -156,7 +250,7 @@ pub fn delete_anchor_index<E>(
     id_entry_type: &E,
     id_link_type: &str,
     id_string: &String,
-) -> ZomeApiResult<bool>
+) -> GraphHelperResult<bool>
     where E: Into<AppEntryType> + Clone,
 {
     // determine anchor entry address
@@ -167,7 +261,7 @@ pub fn delete_anchor_index<E>(
 
     // if all validates, wipe anchoring entry & corresponding link
     match check_entry_addr {
-        None => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.to_string())),
+        None => Err(GraphHelperError::EntryNotFound),
         Some(entry_addr) => {
             remove_link(&anchor_address, &entry_addr, id_link_type, ANCHOR_POINTER_LINK_TAG)?;
             remove_entry(&anchor_address)?;
@@ -175,3 +269,114 @@ pub fn delete_anchor_index<E>(
         },
     }
 }
+
+/// Removes `entry_address` from an `anchor index` which lists many entries, leaving the
+/// anchor itself in place for the others.
+///
+pub fn remove_from_anchor_index<E>(
+    id_entry_type: &E,
+    id_link_type: &str,
+    id_string: &String,
+    entry_address: &Address,
+) -> GraphHelperResult<()>
+    where E: Into<AppEntryType> + Clone,
+{
+    let anchor_address = determine_anchor_index_address(id_entry_type, id_string)?;
+    Ok(remove_link(&anchor_address, entry_address, id_link_type, ANCHOR_POINTER_LINK_TAG)?)
+}
+
+//-----------------------------[ VALIDATION ]-----------------------------------
+
+/// Link validation rule for unique `anchor indexes`, which rejects any pointer link from an
+/// anchor that the link's author has already pointed at a different entry (and not since unlinked).
+/// Use as the `validation` callback for the pointer link type in an anchor's entry definition,
+/// with `ValidationPackageDefinition::ChainEntries` as its validation package.
+///
+/// The check is made against the author's own source chain rather than the DHT, so that all
+/// validators reach the same result. Agents linking the same anchor concurrently cannot see
+/// one another's links and so may still both pass, in which case reads fail with an `AnchorConflict`.
+///
+pub fn validate_unique_anchor_link(validation_data: &LinkValidationData) -> Result<(), String> {
+    match validation_data {
+        LinkValidationData::LinkAdd { link, validation_data } => {
+            let link = link.link();
+            if link.tag() != ANCHOR_POINTER_LINK_TAG {
+                return Ok(());
+            }
+
+            let chain_entries = validation_data.package.source_chain_entries.to_owned().unwrap_or_default();
+            if has_other_live_pointer(link, &chain_entries) {
+                return Err(ERR_MSG_ANCHOR_CONFLICT.to_string());
+            }
+            Ok(())
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Determine whether `chain_entries` contain a link from the same anchor as `link` to some other
+/// entry, which has not been removed by a later `LinkRemove` on the same chain.
+fn has_other_live_pointer(link: &Link, chain_entries: &[Entry]) -> bool {
+    let removed: Vec<Address> = chain_entries.iter()
+        .filter_map(|entry| match entry {
+            Entry::LinkRemove((_, removed_adds)) => Some(removed_adds.to_owned()),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+
+    chain_entries.iter().any(|entry| match entry {
+        Entry::LinkAdd(existing) => {
+            let existing_link = existing.link();
+            existing_link.base() == link.base()
+                && existing_link.link_type() == link.link_type()
+                && existing_link.tag() == link.tag()
+                && existing_link.target() != link.target()
+                && !removed.contains(&entry.address())
+        },
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hdk::holochain_core_types::{
+        agent::AgentId,
+        chain_header::test_chain_header,
+        link::link_data::LinkData,
+    };
+
+    fn pointer_link(base: &str, target: &str) -> LinkData {
+        LinkData::new_add(
+            &Address::from(base), &Address::from(target), ANCHOR_POINTER_LINK_TAG, "test_anchor_pointer",
+            test_chain_header(), AgentId::generate_fake("sally"),
+        )
+    }
+
+    #[test]
+    fn test_second_pointer_rejected() {
+        let first = pointer_link("kg", "QmFirst");
+        let second = pointer_link("kg", "QmSecond");
+        let other_anchor = pointer_link("m", "QmSecond");
+        let chain_entries = vec![Entry::LinkAdd(first.to_owned())];
+
+        assert!(has_other_live_pointer(second.link(), &chain_entries));
+        assert!(!has_other_live_pointer(first.link(), &chain_entries), "relinking the same entry is allowed");
+        assert!(!has_other_live_pointer(other_anchor.link(), &chain_entries));
+        assert!(!has_other_live_pointer(second.link(), &[]));
+    }
+
+    #[test]
+    fn test_pointer_allowed_after_unlinking() {
+        let first = pointer_link("kg", "QmFirst");
+        let second = pointer_link("kg", "QmSecond");
+        let first_add = Entry::LinkAdd(first.to_owned());
+        let chain_entries = vec![
+            Entry::LinkRemove((first, vec![first_add.address()])),
+            first_add,
+        ];
+
+        assert!(!has_other_live_pointer(second.link(), &chain_entries));
+    }
+}
@@ -0,0 +1,131 @@
+/**
+ * Runtime configuration of the bridges used to reach other DNAs.
+ *
+ * Zomes refer to other DNAs by the bridge handles declared as `BRIDGED_*` constants in
+ * their `storage_consts` crates. A DNA may map any of these to a different handle via
+ * its `bridges` property, eg.
+ *
+ *     "properties": { "bridges": { "vf_specification": "shared_specification" } }
+ *
+ * such that several networks can share a single DNA whilst bridging to it under
+ * different handles. Handles without a mapping are used as-is, so DNAs which do not
+ * set the property behave exactly as before.
+ *
+ * Cross-DNA calls made via `hdk_graph_helpers::rpc` and `remote_indexes` are resolved
+ * automatically. The property is parsed once per zome instance. Zomes making such calls
+ * should check the configuration in their `init` callback with `validate_bridge_config`,
+ * so that a malformed property prevents the DNA from starting; should it get past `init`,
+ * every cross-DNA call fails with `GraphHelperError::InvalidBridgeConfig` rather than
+ * silently calling the default handle.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-06-21
+ */
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use super::{
+    hdk_api::property,
+    identifiers::BRIDGES_DNA_PROPERTY,
+    errors::{ GraphHelperError, GraphHelperResult },
+};
+
+type BridgeConfig = Result<HashMap<String, String>, GraphHelperError>;
+
+// DNA properties cannot change while a zome is running, so are only parsed on first use.
+// :NOTE: zomes are single-threaded, a thread local is used to keep each mock HDK test's DNA separate
+thread_local! {
+    static BRIDGE_CONFIG: RefCell<Option<BridgeConfig>> = RefCell::new(None);
+}
+
+/// Parse the bridge handle mappings configured for the DNA. An unset or `null`
+/// property configures no mappings.
+///
+fn parse_bridge_config() -> BridgeConfig {
+    let configured = match property(BRIDGES_DNA_PROPERTY) {
+        Ok(json) => json,
+        Err(_) => return Ok(HashMap::new()),
+    };
+
+    let parsed: Result<Option<HashMap<String, String>>, _> = serde_json::from_str(&String::from(configured));
+    match parsed {
+        Ok(mappings) => Ok(mappings.unwrap_or_default()),
+        Err(e) => Err(GraphHelperError::InvalidBridgeConfig(e.to_string())),
+    }
+}
+
+/// Read the bridge handle mappings configured for the DNA, parsing them if not yet done
+///
+fn read_bridge_config() -> BridgeConfig {
+    BRIDGE_CONFIG.with(|config| {
+        config.borrow_mut()
+            .get_or_insert_with(parse_bridge_config)
+            .to_owned()
+    })
+}
+
+/// Determine the bridge handle to call in place of the default `bridge_id`.
+///
+/// Fails if the DNA's bridge configuration is malformed, rather than guessing at the handle.
+///
+pub fn resolve_bridge_id(bridge_id: &str) -> GraphHelperResult<String> {
+    let mappings = read_bridge_config()?;
+    Ok(mappings.get(bridge_id).cloned().unwrap_or_else(|| bridge_id.to_string()))
+}
+
+/// Check that the DNA's bridge configuration can be read.
+/// Intended for use in zome `init` callbacks.
+///
+pub fn validate_bridge_config() -> Result<(), String> {
+    read_bridge_config().map(|_| ()).map_err(|e| e.to_string())
+}
+
+#[cfg(all(test, feature = "mock-hdk"))]
+mod tests {
+    use super::*;
+    use hdk::holochain_json_api::json::JsonString;
+    use crate::mock_hdk::set_dna_property;
+
+    use crate::identifiers::ERR_MSG_INVALID_BRIDGE_CONFIG;
+
+    #[test]
+    fn test_defaults_used_when_unconfigured() {
+        assert_eq!(resolve_bridge_id("vf_specification"), Ok("vf_specification".to_string()));
+        assert_eq!(validate_bridge_config(), Ok(()));
+    }
+
+    #[test]
+    fn test_null_config_ignored() {
+        set_dna_property(BRIDGES_DNA_PROPERTY, JsonString::null());
+        assert_eq!(resolve_bridge_id("vf_specification"), Ok("vf_specification".to_string()));
+    }
+
+    #[test]
+    fn test_configured_bridges_resolved() {
+        set_dna_property(BRIDGES_DNA_PROPERTY, JsonString::from_json("{\"vf_specification\":\"shared_specification\"}"));
+
+        assert_eq!(resolve_bridge_id("vf_specification"), Ok("shared_specification".to_string()));
+        assert_eq!(resolve_bridge_id("vf_agreement"), Ok("vf_agreement".to_string()), "unmapped bridges use their default handle");
+    }
+
+    #[test]
+    fn test_config_parsed_once() {
+        set_dna_property(BRIDGES_DNA_PROPERTY, JsonString::from_json("{\"vf_specification\":\"shared_specification\"}"));
+        assert_eq!(resolve_bridge_id("vf_specification"), Ok("shared_specification".to_string()));
+
+        set_dna_property(BRIDGES_DNA_PROPERTY, JsonString::from_json("[\"vf_specification\"]"));
+        assert_eq!(resolve_bridge_id("vf_specification"), Ok("shared_specification".to_string()));
+    }
+
+    #[test]
+    fn test_malformed_config_rejected() {
+        set_dna_property(BRIDGES_DNA_PROPERTY, JsonString::from_json("[\"vf_specification\"]"));
+
+        assert!(validate_bridge_config().unwrap_err().starts_with(ERR_MSG_INVALID_BRIDGE_CONFIG));
+        match resolve_bridge_id("vf_specification") {
+            Err(GraphHelperError::InvalidBridgeConfig(_)) => (),
+            other => panic!("malformed config must not fall back to the default handle, got {:?}", other),
+        }
+    }
+}
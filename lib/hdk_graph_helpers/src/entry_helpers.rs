@@ -14,20 +14,21 @@ use hdk::{
         entry::entry_type::AppEntryType,
         entry::AppEntryValue,
     },
-    error::{ ZomeApiError, ZomeApiResult },
-    entry_address,
-    get_entry,
-    commit_entry,
-    update_entry as hdk_update_entry,
-    remove_entry,
-    utils:: {
-        get_as_type,    // :TODO: switch this method to one which doesn't consume the input
-    },
+    error::{ ZomeApiResult },
 };
 
 use super::{
-    identifiers::{ ERR_MSG_ENTRY_NOT_FOUND, ERR_MSG_ENTRY_WRONG_TYPE },
+    hdk_api::{
+        get_as_type,
+        entry_address,
+        get_entry,
+        commit_entry,
+        update_entry as hdk_update_entry,
+        remove_entry,
+    },
+    errors::{ GraphHelperError, GraphHelperResult },
     record_interface::Updateable,
+    tombstones::exclude_deleted_records,
 };
 
 //--------------------------------[ READ ]--------------------------------------
@@ -35,71 +36,79 @@ use super::{
 /// Loads up all entry data for the input list of `Addresses` and returns a vector
 /// of tuples corresponding to the entry address and deserialized entry data.
 ///
-pub (crate) fn get_entries_by_address<R, A>(addresses: Vec<Address>) -> ZomeApiResult<Vec<(A, Option<R>)>>
+/// Entries which no longer exist are returned without any data. Any other failure to
+/// load or decode an entry is returned as an error.
+///
+pub (crate) fn get_entries_by_address<R, A>(addresses: Vec<Address>) -> GraphHelperResult<Vec<(A, Option<R>)>>
     where R: Clone + TryFrom<AppEntryValue>,
         A: From<Address>,
 {
-    let entries: Vec<Option<R>> = addresses.iter()
-        .map(|address| {
-            let entry = get_entry(&address);
-            try_decode_entry(entry)
-        })
-        .filter_map(Result::ok)
-        .collect();
-
-    Ok(addresses.iter()
+    addresses.into_iter()
         .map(|address| {
-            address.to_owned().into()
+            let entry = skip_missing_entry(try_decode_entry(get_entry(&address)))?;
+            Ok((address.into(), entry))
         })
-        .zip(entries)
         .collect()
-    )
 }
 
 /// Loads up all entry data for the input list of `key indexes` and returns a vector
 /// of tuples corresponding to the entry key's address and deserialized entry data.
 ///
-pub (crate) fn get_entries_by_key_index<R, A>(addresses: Vec<Address>) -> ZomeApiResult<Vec<(A, Option<R>)>>
+/// Soft-deleted records are omitted from the results unless `include_deleted` is set.
+/// Records which have been deleted outright are returned without any data. Any other
+/// failure to load or decode an entry is returned as an error.
+///
+pub (crate) fn get_entries_by_key_index<R, A>(addresses: Vec<Address>, include_deleted: bool) -> GraphHelperResult<Vec<(A, Option<R>)>>
     where R: Clone + TryFrom<AppEntryValue>,
         A: From<Address>,
 {
-    let entries: Vec<Option<R>> = addresses.iter()
-        .map(|address| {
-            let entry_address = get_entry(&address)?;
-            let entry = match entry_address {
-                Some(AppEntry(_, entry_address_value)) => {
-                    get_entry(&Address::try_from(entry_address_value)?)
-                },
-                _ => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.to_string())),
-            };
-
-            try_decode_entry(entry)
-        })
-        .filter_map(Result::ok)
-        .collect();
+    let addresses = if include_deleted { addresses } else { exclude_deleted_records(addresses)? };
 
-    Ok(addresses.iter()
+    addresses.into_iter()
         .map(|address| {
-            address.to_owned().into()
+            let entry = skip_missing_entry(get_key_indexed_entry(&address))?;
+            Ok((address.into(), entry))
         })
-        .zip(entries)
         .collect()
-    )
+}
+
+/// Load the entry data referenced by a `key index`
+///
+fn get_key_indexed_entry<R>(address: &Address) -> GraphHelperResult<Option<R>>
+    where R: TryFrom<AppEntryValue>,
+{
+    match get_entry(address)? {
+        Some(AppEntry(_, entry_address_value)) => {
+            try_decode_entry(get_entry(&Address::try_from(entry_address_value)?))
+        },
+        _ => Err(GraphHelperError::EntryNotFound),
+    }
+}
+
+/// Treat entries which cannot be found as empty results, so that records deleted
+/// whilst still referenced by an index do not fail reads of the whole index.
+///
+fn skip_missing_entry<R>(entry: GraphHelperResult<Option<R>>) -> GraphHelperResult<Option<R>> {
+    match entry {
+        Err(GraphHelperError::EntryNotFound) => Ok(None),
+        entry => entry,
+    }
 }
 
 /// Helper for handling decoding of entry data to requested entry struct type
 ///
-pub (crate) fn try_decode_entry<R>(entry: ZomeApiResult<Option<Entry>>) -> ZomeApiResult<Option<R>>
+pub (crate) fn try_decode_entry<R>(entry: ZomeApiResult<Option<Entry>>) -> GraphHelperResult<Option<R>>
     where R: TryFrom<AppEntryValue>,
 {
     match entry {
         Ok(Some(AppEntry(_, entry_value))) => {
             match R::try_from(entry_value.to_owned()) {
                 Ok(val) => Ok(Some(val)),
-                Err(_) => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_WRONG_TYPE.to_string())),
+                Err(_) => Err(GraphHelperError::WrongType),
             }
         },
-        _ => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.to_string())),
+        Err(e) => Err(e.into()),
+        _ => Err(GraphHelperError::EntryNotFound),
     }
 }
 
@@ -114,7 +123,7 @@ pub (crate) fn try_decode_entry<R>(entry: ZomeApiResult<Option<Entry>>) -> ZomeA
 pub fn create_entry<E, C, S>(
     entry_type: S,
     create_payload: C,
-) -> ZomeApiResult<(Address, E)>
+) -> GraphHelperResult<(Address, E)>
     where E: Clone + Into<AppEntryValue>,
         C: Into<E>,
         S: Into<AppEntryType>,
@@ -144,17 +153,33 @@ pub fn update_entry<E, U, A, S>(
     entry_type: S,
     address: &A,
     update_payload: &U,
-) -> ZomeApiResult<(Address, E)>
+) -> GraphHelperResult<(Address, E)>
     where E: Clone + TryFrom<AppEntryValue> + Into<AppEntryValue> + Updateable<U>,
         S: Into<AppEntryType> + Clone,
         A: AsRef<Address>,
+{
+    update_entry_with(entry_type, address, |prev_entry: &E| Ok(prev_entry.update_with(update_payload)))
+}
+
+/// Same as `update_entry`, except that the new entry data is determined by `update`, which
+/// may reject the change. Nothing is written if it does, and its error is returned.
+///
+pub fn update_entry_with<E, A, S, F>(
+    entry_type: S,
+    address: &A,
+    update: F,
+) -> GraphHelperResult<(Address, E)>
+    where E: Clone + TryFrom<AppEntryValue> + Into<AppEntryValue>,
+        S: Into<AppEntryType> + Clone,
+        A: AsRef<Address>,
+        F: FnOnce(&E) -> GraphHelperResult<E>,
 {
     let prev_entry: E = get_as_type((*(address.as_ref())).clone())?;
     // :NOTE: to handle update checks we need the *exact* most recent entry address, not that of the head of the entry chain
     let data_address = entry_address(&(AppEntry(entry_type.clone().into(), prev_entry.to_owned().into())))?;
 
     // perform update logic
-    let new_entry = prev_entry.update_with(update_payload);
+    let new_entry = update(&prev_entry)?;
 
     // clone entry for returning to caller
     // :TODO: should not need to do this if AppEntry stops consuming the value
@@ -179,7 +204,7 @@ pub fn update_entry<E, U, A, S>(
 ///
 pub fn delete_entry<T>(
     addr: &Address,
-) -> ZomeApiResult<bool>
+) -> GraphHelperResult<bool>
     where T: TryFrom<AppEntryValue>
 {
     let entry_data: ZomeApiResult<T> = get_as_type(addr.to_owned());
@@ -188,6 +213,79 @@ pub fn delete_entry<T>(
             remove_entry(&addr)?;
             Ok(true)
         },
-        Err(_) => Err(ZomeApiError::ValidationFailed("incorrect record type specified for deletion".to_string())),
+        Err(_) => Err(GraphHelperError::ValidationFailed("incorrect record type specified for deletion".to_string())),
+    }
+}
+
+#[cfg(all(test, feature = "mock-hdk"))]
+mod tests {
+    use super::*;
+    use hdk::holochain_json_api::{ json::JsonString, error::JsonError };
+    use holochain_json_derive::{ DefaultJson };
+    use crate::records::{ create_record, delete_record };
+    use crate::type_wrappers::Addressable;
+
+    const BASE_ENTRY_TYPE: &str = "test_base";
+    const ENTRY_TYPE: &str = "test_entry";
+    const INITIAL_ENTRY_LINK_TYPE: &str = "test_initial_entry";
+
+    #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+    struct TestEntry {
+        note: String,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+    struct OtherEntry {
+        count: u32,
+    }
+
+    fn test_entry(note: &str) -> TestEntry {
+        TestEntry { note: note.to_string() }
+    }
+
+    fn notes(results: Vec<(Address, Option<TestEntry>)>) -> Vec<(Address, Option<String>)> {
+        results.into_iter().map(|(address, entry)| (address, entry.map(|e| e.note))).collect()
+    }
+
+    #[test]
+    fn test_missing_entries_keep_their_place() {
+        let (first, _) = create_entry::<TestEntry, _, _>(ENTRY_TYPE, test_entry("first")).unwrap();
+        let (missing, _) = create_entry::<TestEntry, _, _>(ENTRY_TYPE, test_entry("missing")).unwrap();
+        let (last, _) = create_entry::<TestEntry, _, _>(ENTRY_TYPE, test_entry("last")).unwrap();
+        remove_entry(&missing).unwrap();
+
+        let read = get_entries_by_address(vec![first.to_owned(), missing.to_owned(), last.to_owned()]).unwrap();
+        assert_eq!(notes(read), vec![
+            (first, Some("first".to_string())),
+            (missing, None),
+            (last, Some("last".to_string())),
+        ]);
+    }
+
+    #[test]
+    fn test_deleted_records_keep_their_place() {
+        let create = |note: &str| -> Addressable {
+            let (address, _): (Addressable, TestEntry) = create_record(
+                BASE_ENTRY_TYPE, ENTRY_TYPE, INITIAL_ENTRY_LINK_TYPE, None, test_entry(note),
+            ).unwrap();
+            address
+        };
+        let (first, deleted, last) = (create("first"), create("deleted"), create("last"));
+        assert_eq!(delete_record::<TestEntry>(&deleted), Ok(true));
+        let (first, deleted, last) = (first.as_ref().to_owned(), deleted.as_ref().to_owned(), last.as_ref().to_owned());
+
+        let read = get_entries_by_key_index(vec![first.to_owned(), deleted.to_owned(), last.to_owned()], false).unwrap();
+        assert_eq!(notes(read), vec![
+            (first, Some("first".to_string())),
+            (deleted, None),
+            (last, Some("last".to_string())),
+        ]);
+    }
+
+    #[test]
+    fn test_wrong_entry_type_reported() {
+        let (address, _) = create_entry::<OtherEntry, _, _>(ENTRY_TYPE, OtherEntry { count: 1 }).unwrap();
+        let read: GraphHelperResult<Vec<(Address, Option<TestEntry>)>> = get_entries_by_address(vec![address]);
+        assert_eq!(read, Err(GraphHelperError::WrongType));
     }
 }
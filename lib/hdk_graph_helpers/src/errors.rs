@@ -0,0 +1,242 @@
+/**
+ * Error types for graph helper operations.
+ *
+ * Helpers describe failures with a `GraphHelperError`, which converts into a `ZomeApiError`
+ * when returned across the zome API boundary. The human-readable message of each variant
+ * is preserved in the converted error so that callers can display the underlying reason.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-06-05
+ */
+use std::fmt;
+use hdk::{
+    holochain_json_api::error::JsonError,
+    holochain_persistence_api::cas::content::Address,
+    error::{ ZomeApiError },
+};
+
+use super::identifiers::{
+    ERR_MSG_ENTRY_NOT_FOUND,
+    ERR_MSG_ENTRY_WRONG_TYPE,
+    ERR_MSG_INDEX_NOT_FOUND,
+    ERR_MSG_REMOTE_REQUEST_ERR,
+    ERR_MSG_REMOTE_UNREACHABLE,
+    ERR_MSG_REMOTE_RESPONSE_FORMAT_ERR,
+    ERR_MSG_REMOTE_INDEXING_ERR,
+    ERR_MSG_EMPTY_QUERY,
+    ERR_MSG_INVALID_CURSOR,
+    ERR_MSG_INVALID_PAGE_SIZE,
+    ERR_MSG_PAGINATION_UNSUPPORTED,
+    ERR_MSG_INVALID_TIMESTAMP,
+    ERR_MSG_ANCHOR_CONFLICT,
+    ERR_MSG_IDEMPOTENT_RECORD_DELETED,
+    ERR_MSG_REVISION_CONFLICT,
+    ERR_MSG_ROLLBACK_FAILED,
+    ERR_MSG_INVALID_BRIDGE_CONFIG,
+};
+
+pub type GraphHelperResult<T> = Result<T, GraphHelperError>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphHelperError {
+    /// No entry exists at the requested address, or it has been deleted
+    EntryNotFound,
+    /// An entry exists at the requested address, but could not be decoded as the requested type
+    WrongType,
+    /// An index expected to be present for a record does not exist
+    IndexMissing,
+    /// A call to another zome or DNA failed. `cause` contains the error reported by the callee.
+    RemoteCallFailed { dna: String, zome: String, fn_name: String, cause: String },
+    /// Another zome or DNA could not be reached, eg. because the bridged DNA is not running.
+    /// `cause` contains the error reported by the conductor.
+    RemoteUnreachable { dna: String, zome: String, fn_name: String, cause: String },
+    /// A call to another zome or DNA returned a response which could not be decoded
+    RemoteResponseFormat { dna: String, zome: String, fn_name: String, cause: String },
+    /// Another zome or DNA was reached, but failed to write some of the links requested of it
+    RemoteIndexingFailed { dna: String, zome: String, fn_name: String, cause: String },
+    /// Some input data was rejected as invalid
+    ValidationFailed(String),
+    /// A query was made without specifying any filters
+    EmptyQuery,
+    /// A pagination cursor does not reference a record in the index being read
+    InvalidCursor,
+    /// A page of results was requested with a `limit` of zero
+    InvalidPageSize,
+    /// Pagination parameters were passed to an endpoint which returns all results at once
+    PaginationUnsupported,
+    /// A timestamp could not be used for indexing
+    InvalidTimestamp,
+    /// An `anchor index` with the given ID already references a different entry
+    AnchorConflict(String),
+    /// An `idempotency key` was reused for a record which has since been deleted.
+    /// Contains the ID of the deleted record.
+    IdempotentRecordDeleted(Address),
+    /// An update was prepared against a revision of a record which is no longer the latest.
+    /// Contains the ID of the current revision.
+    RevisionConflict(Address),
+    /// An operation failed, and some of the steps it had already completed could not be undone.
+    /// `cause` describes the original failure, `steps` the steps left in place.
+    RollbackFailed { cause: String, steps: Vec<String> },
+    /// The DNA's `bridges` property could not be parsed. Contains the reason.
+    InvalidBridgeConfig(String),
+    /// Errors from the underlying HDK, passed through unchanged
+    Hdk(ZomeApiError),
+}
+
+impl GraphHelperError {
+    pub fn remote_call_failed(dna: &str, zome: &str, fn_name: &str, cause: &str) -> Self {
+        GraphHelperError::RemoteCallFailed {
+            dna: dna.to_string(),
+            zome: zome.to_string(),
+            fn_name: fn_name.to_string(),
+            cause: cause.to_string(),
+        }
+    }
+
+    pub fn remote_unreachable(dna: &str, zome: &str, fn_name: &str, cause: &str) -> Self {
+        GraphHelperError::RemoteUnreachable {
+            dna: dna.to_string(),
+            zome: zome.to_string(),
+            fn_name: fn_name.to_string(),
+            cause: cause.to_string(),
+        }
+    }
+
+    pub fn remote_response_format(dna: &str, zome: &str, fn_name: &str, cause: &str) -> Self {
+        GraphHelperError::RemoteResponseFormat {
+            dna: dna.to_string(),
+            zome: zome.to_string(),
+            fn_name: fn_name.to_string(),
+            cause: cause.to_string(),
+        }
+    }
+
+    pub fn remote_indexing_failed(dna: &str, zome: &str, fn_name: &str, cause: &str) -> Self {
+        GraphHelperError::RemoteIndexingFailed {
+            dna: dna.to_string(),
+            zome: zome.to_string(),
+            fn_name: fn_name.to_string(),
+            cause: cause.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for GraphHelperError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GraphHelperError::EntryNotFound => write!(f, "{}", ERR_MSG_ENTRY_NOT_FOUND),
+            GraphHelperError::WrongType => write!(f, "{}", ERR_MSG_ENTRY_WRONG_TYPE),
+            GraphHelperError::IndexMissing => write!(f, "{}", ERR_MSG_INDEX_NOT_FOUND),
+            GraphHelperError::RemoteCallFailed { dna, zome, fn_name, cause } => write!(f, "{}{}/{}/{}: {}", ERR_MSG_REMOTE_REQUEST_ERR, dna, zome, fn_name, cause),
+            GraphHelperError::RemoteUnreachable { dna, zome, fn_name, cause } => write!(f, "{}{}/{}/{}: {}", ERR_MSG_REMOTE_UNREACHABLE, dna, zome, fn_name, cause),
+            GraphHelperError::RemoteResponseFormat { dna, zome, fn_name, cause } => write!(f, "{}{}/{}/{}: {}", ERR_MSG_REMOTE_RESPONSE_FORMAT_ERR, dna, zome, fn_name, cause),
+            GraphHelperError::RemoteIndexingFailed { dna, zome, fn_name, cause } => write!(f, "{}{}/{}/{}: {}", ERR_MSG_REMOTE_INDEXING_ERR, dna, zome, fn_name, cause),
+            GraphHelperError::ValidationFailed(msg) => write!(f, "{}", msg),
+            GraphHelperError::EmptyQuery => write!(f, "{}", ERR_MSG_EMPTY_QUERY),
+            GraphHelperError::InvalidCursor => write!(f, "{}", ERR_MSG_INVALID_CURSOR),
+            GraphHelperError::InvalidPageSize => write!(f, "{}", ERR_MSG_INVALID_PAGE_SIZE),
+            GraphHelperError::PaginationUnsupported => write!(f, "{}", ERR_MSG_PAGINATION_UNSUPPORTED),
+            GraphHelperError::InvalidTimestamp => write!(f, "{}", ERR_MSG_INVALID_TIMESTAMP),
+            GraphHelperError::AnchorConflict(id) => write!(f, "{}: {}", ERR_MSG_ANCHOR_CONFLICT, id),
+            GraphHelperError::IdempotentRecordDeleted(id) => write!(f, "{} {}", ERR_MSG_IDEMPOTENT_RECORD_DELETED, id),
+            GraphHelperError::RevisionConflict(current) => write!(f, "{} {}", ERR_MSG_REVISION_CONFLICT, current),
+            GraphHelperError::RollbackFailed { cause, steps } => write!(f, "{} {} ({})", ERR_MSG_ROLLBACK_FAILED, steps.join(", "), cause),
+            GraphHelperError::InvalidBridgeConfig(cause) => write!(f, "{}: {}", ERR_MSG_INVALID_BRIDGE_CONFIG, cause),
+            GraphHelperError::Hdk(err) => write!(f, "{}", describe_zome_api_error(err)),
+        }
+    }
+}
+
+impl From<ZomeApiError> for GraphHelperError {
+    fn from(err: ZomeApiError) -> Self {
+        GraphHelperError::Hdk(err)
+    }
+}
+
+impl From<JsonError> for GraphHelperError {
+    fn from(err: JsonError) -> Self {
+        GraphHelperError::Hdk(err.into())
+    }
+}
+
+impl From<GraphHelperError> for ZomeApiError {
+    fn from(err: GraphHelperError) -> Self {
+        match err {
+            GraphHelperError::Hdk(err) => err,
+            GraphHelperError::ValidationFailed(msg) => ZomeApiError::ValidationFailed(msg),
+            _ => ZomeApiError::Internal(err.to_string()),
+        }
+    }
+}
+
+/// Extract the message from a `ZomeApiError` for reporting in other error contexts
+pub fn describe_zome_api_error(err: &ZomeApiError) -> String {
+    match err {
+        ZomeApiError::Internal(msg) => msg.to_owned(),
+        ZomeApiError::ValidationFailed(msg) => msg.to_owned(),
+        _ => format!("{:?}", err),
+    }
+}
+
+/// Collapse the per-item results of a multi-part operation (such as writing the links for an index),
+/// returning the first error encountered.
+pub fn throw_any_error<T, E>(results: Vec<Result<T, E>>) -> Result<Vec<T>, E> {
+    results.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_preserved() {
+        let err: ZomeApiError = GraphHelperError::EntryNotFound.into();
+        assert_eq!(err, ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.to_string()));
+    }
+
+    #[test]
+    fn test_remote_cause_reported() {
+        let err: ZomeApiError = GraphHelperError::remote_call_failed("dna", "zome", "method", ERR_MSG_ENTRY_NOT_FOUND).into();
+        assert_eq!(err, ZomeApiError::Internal(format!("{}dna/zome/method: {}", ERR_MSG_REMOTE_REQUEST_ERR, ERR_MSG_ENTRY_NOT_FOUND)));
+    }
+
+    #[test]
+    fn test_anchor_conflict_reports_id() {
+        let err: ZomeApiError = GraphHelperError::AnchorConflict("kg".to_string()).into();
+        assert_eq!(err, ZomeApiError::Internal(format!("{}: kg", ERR_MSG_ANCHOR_CONFLICT)));
+    }
+
+    #[test]
+    fn test_idempotent_record_deleted_reports_id() {
+        let err: ZomeApiError = GraphHelperError::IdempotentRecordDeleted(Address::from("QmDeleted")).into();
+        assert_eq!(err, ZomeApiError::Internal(format!("{} QmDeleted", ERR_MSG_IDEMPOTENT_RECORD_DELETED)));
+    }
+
+    #[test]
+    fn test_revision_conflict_reports_current_revision() {
+        let err: ZomeApiError = GraphHelperError::RevisionConflict(Address::from("QmCurrent")).into();
+        assert_eq!(err, ZomeApiError::Internal(format!("{} QmCurrent", ERR_MSG_REVISION_CONFLICT)));
+    }
+
+    #[test]
+    fn test_rollback_failure_reports_steps_and_cause() {
+        let err: ZomeApiError = GraphHelperError::RollbackFailed {
+            cause: "event invalid".to_string(),
+            steps: vec!["update resource".to_string(), "create resource".to_string()],
+        }.into();
+        assert_eq!(err, ZomeApiError::Internal(format!("{} update resource, create resource (event invalid)", ERR_MSG_ROLLBACK_FAILED)));
+    }
+
+    #[test]
+    fn test_first_error_thrown() {
+        let results: Vec<GraphHelperResult<u8>> = vec![Ok(1), Err(GraphHelperError::IndexMissing), Err(GraphHelperError::WrongType)];
+        assert_eq!(throw_any_error(results), Err(GraphHelperError::IndexMissing));
+    }
+
+    #[test]
+    fn test_validation_errors_passed_through() {
+        let err: ZomeApiError = GraphHelperError::ValidationFailed("bad".to_string()).into();
+        assert_eq!(err, ZomeApiError::ValidationFailed("bad".to_string()));
+    }
+}
@@ -0,0 +1,80 @@
+/**
+ * Sparse field selection for record responses.
+ *
+ * Some response fields are not stored in the record's entry, but are assembled from
+ * `indexes` or derived from other records at read time. Requests may list the fields
+ * they need, so that the links and entries backing unrequested fields are not read.
+ *
+ * Field names are those of the serialized response (ie. camelCase).
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-06-18
+ */
+use std::collections::HashSet;
+
+/// The set of response fields requested by a read operation.
+/// A selection of `None` (the default) includes every field.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FieldSelection {
+    only: Option<HashSet<String>>,
+}
+
+impl FieldSelection {
+    /// Select every field
+    pub fn all() -> Self {
+        FieldSelection { only: None }
+    }
+
+    /// Select only the named fields
+    pub fn only<S: AsRef<str>>(fields: &[S]) -> Self {
+        FieldSelection {
+            only: Some(fields.iter().map(|f| f.as_ref().to_string()).collect()),
+        }
+    }
+
+    /// Determine whether `field` should be loaded for the response
+    pub fn includes(&self, field: &str) -> bool {
+        match &self.only {
+            None => true,
+            Some(fields) => fields.contains(field),
+        }
+    }
+}
+
+/// Convert from the `fields` parameter of a request, where omitting it requests every field
+impl From<Option<Vec<String>>> for FieldSelection {
+    fn from(fields: Option<Vec<String>>) -> Self {
+        match fields {
+            None => FieldSelection::all(),
+            Some(fields) => FieldSelection::only(&fields),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_fields_by_default() {
+        let selection = FieldSelection::from(None);
+
+        assert_eq!(selection, FieldSelection::default());
+        assert!(selection.includes("contains"));
+    }
+
+    #[test]
+    fn test_only_selected_fields() {
+        let selection = FieldSelection::from(Some(vec!["id".to_string(), "containedIn".to_string()]));
+
+        assert!(selection.includes("containedIn"));
+        assert!(!selection.includes("contains"));
+        assert!(!selection.includes("contained_in"), "field names match the serialized response");
+    }
+
+    #[test]
+    fn test_empty_selection() {
+        assert!(!FieldSelection::only::<&str>(&[]).includes("id"));
+    }
+}
@@ -0,0 +1,52 @@
+/**
+ * Zome API methods used by the helpers in this crate.
+ *
+ * All reads and writes against the DHT are made via these re-exports, so that
+ * they can be swapped for the in-memory implementations in `mock_hdk` when the
+ * `mock-hdk` feature is enabled.
+ *
+ * The module itself is private, so these are not part of the crate's public API.
+ *
+ * @package HDK Graph Helpers
+ * @since   2020-06-12
+ */
+
+#[cfg(not(feature = "mock-hdk"))]
+pub use hdk::{
+    entry_address,
+    commit_entry,
+    get_entry,
+    get_entry_result,
+    update_entry,
+    remove_entry,
+    link_entries,
+    remove_link,
+    get_links_with_options,
+    query,
+    call,
+    property,
+    utils::get_as_type,     // :TODO: switch this method to one which doesn't consume the input
+};
+
+#[cfg(not(feature = "mock-hdk"))]
+pub fn agent_address() -> hdk::holochain_persistence_api::cas::content::Address {
+    hdk::AGENT_ADDRESS.to_owned()
+}
+
+#[cfg(feature = "mock-hdk")]
+pub use crate::mock_hdk::{
+    entry_address,
+    commit_entry,
+    get_entry,
+    get_entry_result,
+    update_entry,
+    remove_entry,
+    link_entries,
+    remove_link,
+    get_links_with_options,
+    query,
+    call,
+    property,
+    get_as_type,
+    agent_address,
+};
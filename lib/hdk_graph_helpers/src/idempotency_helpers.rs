@@ -0,0 +1,134 @@
+/**
+ * Helpers related to record `idempotency keys`.
+ *
+ * Clients which may retry a create request (eg. after a timeout, or when replaying
+ * requests queued while offline) can send a unique key along with it. The first
+ * record created with a key is referenced from an `anchor index` derived from the key,
+ * the record's base entry type and the creating agent; later requests from the same
+ * agent with the same key resolve to that record instead of creating another. Keys are
+ * not released when the record is deleted (whether outright or soft-deleted), so such
+ * requests fail with an `IdempotentRecordDeleted` error rather than silently re-creating
+ * the record.
+ *
+ * Any zome which accepts idempotency keys must register `idempotency_key_entry_def`
+ * as one of its entry types for this to work.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-06-19
+ */
+use hdk::{
+    holochain_persistence_api::cas::content::Address,
+    holochain_core_types::dna::entry_types::Sharing,
+    entry_definition::ValidatingEntryType,
+};
+
+use super::{
+    errors::{ GraphHelperError, GraphHelperResult },
+    hdk_api::{ agent_address, get_entry },
+    tombstones::is_record_deleted,
+    identifiers::{ IDEMPOTENCY_KEY_ENTRY_TYPE, IDEMPOTENCY_KEY_LINK_TYPE },
+    anchors::{
+        get_anchor_index_entry_address,
+        create_unique_anchor_index,
+        delete_anchor_index,
+    },
+};
+
+/// Entry type definition for idempotency key anchors, which link to the `key indexes`
+/// of records with the given `base_entry_type`.
+///
+pub fn idempotency_key_entry_def(base_entry_type: &'static str) -> ValidatingEntryType {
+    hdk::entry!(
+        name: IDEMPOTENCY_KEY_ENTRY_TYPE,
+        description: "Anchors the records created by an agent to the idempotency keys they were requested with",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            hdk::to!(
+                base_entry_type,
+                link_type: IDEMPOTENCY_KEY_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
+
+/// Keys are scoped to the record type and the requesting agent, so that clients need only
+/// ensure their keys are unique amongst their own requests.
+fn idempotency_anchor_id(base_entry_type: &str, idempotency_key: &str) -> String {
+    format!("{}:{}:{}", base_entry_type, agent_address(), idempotency_key)
+}
+
+//--------------------------------[ READ ]--------------------------------------
+
+/// Locate the `key index` of the record with `base_entry_type` which the current agent
+/// previously created using `idempotency_key`, if any.
+///
+/// Errors with `IdempotentRecordDeleted` if that record has since been deleted or soft-deleted.
+///
+pub fn get_idempotent_record_address<A: From<Address>>(
+    base_entry_type: &str,
+    idempotency_key: Option<&String>,
+) -> GraphHelperResult<Option<A>> {
+    let key = match idempotency_key {
+        None => return Ok(None),
+        Some(key) => key,
+    };
+    let existing = get_anchor_index_entry_address(
+        &IDEMPOTENCY_KEY_ENTRY_TYPE.to_string(), IDEMPOTENCY_KEY_LINK_TYPE,
+        &idempotency_anchor_id(base_entry_type, key),
+    )?;
+    match existing {
+        None => Ok(None),
+        Some(address) => {
+            // records deleted outright no longer have a `key index`
+            if get_entry(&address)?.is_none() || is_record_deleted(&address)? {
+                return Err(GraphHelperError::IdempotentRecordDeleted(address));
+            }
+            Ok(Some(A::from(address)))
+        },
+    }
+}
+
+//-------------------------------[ CREATE ]-------------------------------------
+
+/// Reference the `key index` at `record` from the anchor for `idempotency_key`
+///
+pub (crate) fn write_idempotency_key(
+    base_entry_type: &str,
+    idempotency_key: &String,
+    record: &Address,
+) -> GraphHelperResult<Address> {
+    create_unique_anchor_index(
+        &IDEMPOTENCY_KEY_ENTRY_TYPE.to_string(), IDEMPOTENCY_KEY_LINK_TYPE,
+        &idempotency_anchor_id(base_entry_type, idempotency_key),
+        record,
+    )
+}
+
+//-------------------------------[ DELETE ]-------------------------------------
+
+/// Release `idempotency_key` for reuse, where the record created with it has been discarded
+///
+pub (crate) fn delete_idempotency_key(
+    base_entry_type: &str,
+    idempotency_key: &String,
+) -> GraphHelperResult<bool> {
+    delete_anchor_index(
+        &IDEMPOTENCY_KEY_ENTRY_TYPE.to_string(), IDEMPOTENCY_KEY_LINK_TYPE,
+        &idempotency_anchor_id(base_entry_type, idempotency_key),
+    )
+}
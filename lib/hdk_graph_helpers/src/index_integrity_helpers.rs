@@ -0,0 +1,298 @@
+/**
+ * Diagnostics for detecting and repairing damaged `indexes`.
+ *
+ * Indexes are composed of several independent writes (key index entries plus a
+ * pair of links in each direction), so partial failures or record deletions can
+ * leave them in an inconsistent state. Given the `BidirectionalIndex` definitions
+ * of each record type, the methods in this module walk all `key indexes`
+ * of those types on the agent's source chain and report:
+ *
+ * - links whose reciprocal link is missing
+ * - links which point to deleted records
+ * - `key indexes` which point to entry data that no longer exists
+ *
+ * Since only the agent's own source chain is walked, problems caused by other
+ * agents' writes will not be detected until those agents run the check themselves.
+ *
+ * Records stored in other DNAs are represented locally only by the `key indexes`
+ * written for `remote indexes`. Such record types should be marked as `remote`, so
+ * that their links are checked without their (unreachable) entry data being
+ * reported as missing.
+ *
+ * Issues which cannot be repaired are listed in the report alongside the reason,
+ * and do not prevent the remaining issues from being repaired.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-06-09
+ */
+use hdk::{
+    holochain_json_api::{ json::JsonString, error::JsonError },
+    holochain_persistence_api::cas::content::Address,
+};
+use holochain_json_derive::{ DefaultJson };
+
+use super::{
+    errors::GraphHelperResult,
+    hdk_api::{
+        get_entry,
+        link_entries,
+        remove_link,
+        remove_entry,
+        query,
+    },
+    keys::get_key_index_address,
+    links::get_linked_addresses,
+    link_specs::BidirectionalIndex,
+};
+
+/// Describes all the indexes of a record type which should be checked. Each index
+/// is given from the perspective of the record type, such that its `forward` links
+/// originate from records of `base_entry_type`.
+///
+/// `remote` record types are those stored in another DNA, whose `key indexes` were
+/// created locally as the destination of a `remote index`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordIndexDefinition<'a> {
+    pub base_entry_type: &'a str,
+    pub indexes: &'a [BidirectionalIndex],
+    pub remote: bool,
+}
+
+/// A problem found in an index. Link issues describe the problematic link itself-
+/// for a missing reverse link, this is the link which should be present.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "issue", rename_all = "camelCase")]
+pub enum IndexIntegrityIssue {
+    #[serde(rename_all = "camelCase")]
+    MissingReverseLink { base: Address, target: Address, link_type: String, link_tag: String },
+    #[serde(rename_all = "camelCase")]
+    LinkToDeletedEntry { base: Address, target: Address, link_type: String, link_tag: String },
+    #[serde(rename_all = "camelCase")]
+    OrphanedKeyIndex { key_index: Address, entry: Address },
+}
+
+/// An issue which could not be repaired, along with the error encountered in repairing it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexRepairFailure {
+    pub issue: IndexIntegrityIssue,
+    pub error: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexIntegrityReport {
+    pub records_checked: usize,
+    pub issues: Vec<IndexIntegrityIssue>,
+    pub repaired: usize,
+    pub repair_failures: Vec<IndexRepairFailure>,
+}
+
+//--------------------------------[ READ ]--------------------------------------
+
+/// Checks the indexes of all records of the given types authored by the current agent.
+///
+/// If `repair` is `true`, each issue found is also fixed: missing reverse links are
+/// created, links to deleted records are removed and orphaned `key indexes` are deleted.
+/// Issues which fail to be repaired are recorded in `repair_failures`.
+///
+pub fn check_index_integrity(
+    record_types: &[RecordIndexDefinition],
+    repair: bool,
+) -> GraphHelperResult<IndexIntegrityReport> {
+    let mut report = IndexIntegrityReport::default();
+
+    for record_type in record_types.iter() {
+        let mut key_indexes = query(record_type.base_entry_type.into(), 0, 0)?;
+        key_indexes.sort();
+        key_indexes.dedup();
+
+        for key_index in key_indexes.iter() {
+            for issue in check_key_index(key_index, record_type.indexes, record_type.remote)? {
+                // the same link may be found from either side of an index, if both record types are being checked
+                if report.issues.contains(&issue) { continue; }
+
+                if repair {
+                    match repair_issue(&issue) {
+                        Ok(()) => report.repaired += 1,
+                        Err(e) => report.repair_failures.push(IndexRepairFailure {
+                            issue: issue.to_owned(),
+                            error: e.to_string(),
+                        }),
+                    }
+                }
+                report.issues.push(issue);
+            }
+
+            report.records_checked += 1;
+        }
+    }
+
+    Ok(report)
+}
+
+fn check_key_index(
+    key_index: &Address,
+    indexes: &[BidirectionalIndex],
+    remote: bool,
+) -> GraphHelperResult<Vec<IndexIntegrityIssue>> {
+    let mut issues = vec![];
+
+    // determine whether the record still exists. Deleted records have their `key index` removed.
+    // Remote records cannot be read from here, so only their `key index` is considered.
+    let record_exists = match get_entry(key_index)? {
+        None => false,
+        Some(_) if remote => true,
+        Some(_) => {
+            let entry_address = get_key_index_address(key_index)?;
+            match get_entry(&entry_address)? {
+                Some(_) => true,
+                None => {
+                    issues.push(IndexIntegrityIssue::OrphanedKeyIndex {
+                        key_index: key_index.to_owned(),
+                        entry: entry_address,
+                    });
+                    false
+                },
+            }
+        },
+    };
+
+    for index in indexes.iter() {
+        let (forward, reverse) = (&index.forward, &index.reverse);
+        let targets = get_linked_addresses(key_index, forward.link_type, forward.link_tag)?;
+
+        for target in targets.iter() {
+            let reverse_links = get_linked_addresses(target, reverse.link_type, reverse.link_tag)?;
+            let reverse_link_present = reverse_links.contains(key_index);
+
+            if !record_exists {
+                // links remain after `delete_record`, so the other side of the index may still refer to this one
+                if reverse_link_present {
+                    issues.push(IndexIntegrityIssue::LinkToDeletedEntry {
+                        base: target.to_owned(),
+                        target: key_index.to_owned(),
+                        link_type: reverse.link_type.to_string(),
+                        link_tag: reverse.link_tag.to_string(),
+                    });
+                }
+            } else if get_entry(target)?.is_none() {
+                issues.push(IndexIntegrityIssue::LinkToDeletedEntry {
+                    base: key_index.to_owned(),
+                    target: target.to_owned(),
+                    link_type: forward.link_type.to_string(),
+                    link_tag: forward.link_tag.to_string(),
+                });
+            } else if !reverse_link_present {
+                issues.push(IndexIntegrityIssue::MissingReverseLink {
+                    base: target.to_owned(),
+                    target: key_index.to_owned(),
+                    link_type: reverse.link_type.to_string(),
+                    link_tag: reverse.link_tag.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+//-------------------------------[ UPDATE ]-------------------------------------
+
+fn repair_issue(issue: &IndexIntegrityIssue) -> GraphHelperResult<()> {
+    match issue {
+        IndexIntegrityIssue::MissingReverseLink { base, target, link_type, link_tag } => {
+            link_entries(base, target, link_type.as_str(), link_tag.as_str())?;
+        },
+        IndexIntegrityIssue::LinkToDeletedEntry { base, target, link_type, link_tag } => {
+            remove_link(base, target, link_type.as_str(), link_tag.as_str())?;
+        },
+        IndexIntegrityIssue::OrphanedKeyIndex { key_index, entry: _ } => {
+            remove_entry(key_index)?;
+        },
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "mock-hdk"))]
+mod tests {
+    use super::*;
+    use hdk::holochain_core_types::entry::Entry::App as AppEntry;
+    use crate::{
+        mock_hdk,
+        LinkSpec,
+        hdk_api::commit_entry,
+        keys::create_key_index,
+    };
+
+    const THING_BASE_ENTRY_TYPE: &str = "test_thing_baseurl";
+    const REMOTE_BASE_ENTRY_TYPE: &str = "test_remote_baseurl";
+
+    const THING_REMOTE_INDEX: BidirectionalIndex = BidirectionalIndex {
+        forward: LinkSpec { base_entry_type: THING_BASE_ENTRY_TYPE, link_type: "test_thing_remote", link_tag: "remote" },
+        reverse: LinkSpec { base_entry_type: REMOTE_BASE_ENTRY_TYPE, link_type: "test_remote_thing", link_tag: "thing" },
+    };
+    const REMOTE_THING_INDEX: BidirectionalIndex = THING_REMOTE_INDEX.reversed();
+
+    fn record_types(remote: bool) -> Vec<RecordIndexDefinition<'static>> {
+        vec![
+            RecordIndexDefinition { base_entry_type: THING_BASE_ENTRY_TYPE, indexes: &[THING_REMOTE_INDEX], remote: false },
+            RecordIndexDefinition { base_entry_type: REMOTE_BASE_ENTRY_TYPE, indexes: &[REMOTE_THING_INDEX], remote },
+        ]
+    }
+
+    /// Creates a local record's `key index`, and the `key index` of a record in another DNA which it references
+    fn create_records() -> (Address, Address) {
+        let thing_entry = commit_entry(&AppEntry("test_thing".into(), JsonString::from_json("\"thing\""))).unwrap();
+        let thing = create_key_index(&THING_BASE_ENTRY_TYPE.into(), &thing_entry).unwrap();
+        let remote = create_key_index(&REMOTE_BASE_ENTRY_TYPE.into(), &Address::from("QmRemoteRecord")).unwrap();
+        (thing, remote)
+    }
+
+    #[test]
+    fn test_remote_records_not_orphaned() {
+        let (thing, remote) = create_records();
+        link_entries(&thing, &remote, "test_thing_remote", "remote").unwrap();
+        link_entries(&remote, &thing, "test_remote_thing", "thing").unwrap();
+
+        let report = check_index_integrity(&record_types(true), false).unwrap();
+        assert_eq!(report.records_checked, 2);
+        assert_eq!(report.issues, vec![]);
+
+        let report = check_index_integrity(&record_types(false), false).unwrap();
+        assert_eq!(report.issues, vec![
+            IndexIntegrityIssue::OrphanedKeyIndex {
+                key_index: remote.to_owned(),
+                entry: Address::from("QmRemoteRecord"),
+            },
+            IndexIntegrityIssue::LinkToDeletedEntry {
+                base: thing,
+                target: remote,
+                link_type: "test_thing_remote".to_string(),
+                link_tag: "remote".to_string(),
+            },
+        ], "records in other DNAs appear deleted unless marked as remote");
+    }
+
+    #[test]
+    fn test_remote_index_links_repaired() {
+        let (thing, remote) = create_records();
+        // link written by the remote DNA's request, without the local record's reciprocal link
+        link_entries(&remote, &thing, "test_remote_thing", "thing").unwrap();
+
+        let report = check_index_integrity(&record_types(true), true).unwrap();
+        assert_eq!(report.issues, vec![IndexIntegrityIssue::MissingReverseLink {
+            base: thing.to_owned(),
+            target: remote.to_owned(),
+            link_type: "test_thing_remote".to_string(),
+            link_tag: "remote".to_string(),
+        }]);
+        assert_eq!(report.repaired, 1);
+        assert_eq!(report.repair_failures, vec![]);
+        assert_eq!(mock_hdk::count_links(&thing, "test_thing_remote", "remote"), 1);
+
+        let report = check_index_integrity(&record_types(true), false).unwrap();
+        assert_eq!(report.issues, vec![]);
+    }
+}
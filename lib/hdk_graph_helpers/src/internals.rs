@@ -7,11 +7,12 @@
  */
 use hdk::{
     holochain_persistence_api::cas::content::Address,
-    error::{ ZomeApiResult },
 };
 
 use crate::{
     MaybeUndefined,
+    errors::GraphHelperResult,
+    link_specs::BidirectionalIndex,
     keys::{
         get_key_index_address,
     },
@@ -80,20 +81,13 @@ pub (crate) fn dereferenced_link_matches<'a, B>(new_dest: &'a MaybeUndefined<B>)
 
 /// Iterator processor to wipe all links originating from a given `source` address
 pub (crate) fn wipe_links_from_origin<'a, A, B>(
-    link_type: &'a str,
-    link_name: &'a str,
-    link_type_reciprocal: &'a str,
-    link_name_reciprocal: &'a str,
+    index: &'a BidirectionalIndex,
     source: &'a A,
-) -> Box<dyn Fn(&'a B) -> Vec<ZomeApiResult<()>> + 'a>
+) -> Box<dyn Fn(&'a B) -> Vec<GraphHelperResult<()>> + 'a>
     where A: AsRef<Address>,
         B: AsRef<Address> + From<Address> + Clone + PartialEq,
 {
     Box::new(move |remove_link| {
-        delete_direct_index(
-            source.as_ref(), remove_link.as_ref(),
-            link_type, link_name,
-            link_type_reciprocal, link_name_reciprocal,
-        )
+        delete_direct_index(source.as_ref(), remove_link.as_ref(), index)
     })
 }
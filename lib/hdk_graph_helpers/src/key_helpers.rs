@@ -19,13 +19,23 @@ use hdk::{
     holochain_core_types::{
         entry::Entry::App as AppEntry,
         entry::entry_type::AppEntryType,
+        chain_header::ChainHeader,
+    },
+    holochain_wasm_utils::api_serialization::get_entry::{
+        GetEntryOptions, GetEntryResultType, StatusRequestKind,
     },
     error::{ ZomeApiResult },
-    entry_address,
-    commit_entry,
-    utils:: {
-        get_as_type,    // :TODO: switch this method to one which doesn't consume the input
+};
+
+use super::{
+    hdk_api::{
+        get_as_type,
+        get_entry_result,
+        entry_address,
+        commit_entry,
     },
+    errors::GraphHelperResult,
+    time_indexes::normalise_timestamp,
 };
 
 //--------------------------------[ READ ]--------------------------------------
@@ -33,32 +43,60 @@ use hdk::{
 /// Query the `entry` address for a given `key index` address and return the result in an Address
 /// NewType wrapper of the expected type.
 ///
-pub fn get_key_index_address_as_type<A>(key_address: &Address) -> ZomeApiResult<A>
+pub fn get_key_index_address_as_type<A>(key_address: &Address) -> GraphHelperResult<A>
     where A: AsRef<Address> + From<Address>,
 {
     let result: ZomeApiResult<Address> = get_as_type(key_address.clone());
 
     match result {
         Ok(res) => Ok(res.into()),
-        Err(e) => Err(e),
+        Err(e) => Err(e.into()),
     }
 }
 
 /// Query the underlying `entry` address for a given `key index` address and return as a raw Address
 ///
-pub (crate) fn get_key_index_address(key_address: &Address) -> ZomeApiResult<Address> {
-    get_as_type(key_address.clone())
+pub (crate) fn get_key_index_address(key_address: &Address) -> GraphHelperResult<Address> {
+    Ok(get_as_type(key_address.clone())?)
+}
+
+/// Reads the headers of every commit of the `key index` entry at `key_address`.
+///
+/// Since entries are content-addressed, agents other than the record's author may have
+/// committed identical `key index` entries since. @see `earliest_header`
+///
+pub (crate) fn get_key_index_headers(key_address: &Address) -> GraphHelperResult<Vec<ChainHeader>> {
+    let result = get_entry_result(key_address, GetEntryOptions {
+        status_request: StatusRequestKind::Initial,
+        entry: false,
+        headers: true,
+        ..GetEntryOptions::default()
+    })?;
+
+    Ok(match result.result {
+        GetEntryResultType::Single(item) => item.headers,
+        GetEntryResultType::All(history) => history.items.into_iter().flat_map(|item| item.headers).collect(),
+    })
+}
+
+/// Selects the earliest of a `key index` entry's headers, being that written when the record was created
+///
+pub (crate) fn earliest_header(headers: &[ChainHeader]) -> Option<(String, &ChainHeader)> {
+    headers.iter()
+        .filter_map(|header| {
+            normalise_timestamp(&header.timestamp().to_string()).ok().map(|timestamp| (timestamp, header))
+        })
+        .min_by(|(a, _), (b, _)| a.cmp(b))
 }
 
 /// Determine the underlying `entry` address for a given external `base_address`, without querying the DHT.
 /// The `base_entry_type` must be provided in order to calculate the entry hash.
 ///
-pub (crate) fn determine_key_index_address<A, S>(base_entry_type: S, base_address: &Address) -> ZomeApiResult<A>
+pub (crate) fn determine_key_index_address<A, S>(base_entry_type: S, base_address: &Address) -> GraphHelperResult<A>
     where S: Into<AppEntryType>,
         A: From<Address>,
 {
-    entry_address(&AppEntry(base_entry_type.into(), (*base_address).clone().into()))
-        .map(|addr| { addr.into() })
+    Ok(entry_address(&AppEntry(base_entry_type.into(), (*base_address).clone().into()))?.into())
 }
 
 //-------------------------------[ CREATE ]-------------------------------------
@@ -69,7 +107,7 @@ pub (crate) fn determine_key_index_address<A, S>(base_entry_type: S, base_addres
 pub (crate) fn create_key_index(
     base_entry_type: &AppEntryType,
     referenced_address: &Address,
-) -> ZomeApiResult<Address> {
+) -> GraphHelperResult<Address> {
     let base_entry = AppEntry(base_entry_type.clone().into(), referenced_address.into());
-    commit_entry(&base_entry)
+    Ok(commit_entry(&base_entry)?)
 }
@@ -7,18 +7,32 @@ extern crate serde_derive;
 extern crate serde_json;
 extern crate hdk;
 
+// allows derive macros to refer to this crate by name when used internally
+extern crate self as hdk_graph_helpers;
+
 // dependencies
 
+#[macro_use]
+mod record_schema;
 mod internals;
+mod hdk_api;
 
 mod entry_helpers;
 mod anchor_helpers;
 mod key_helpers;
 mod local_index_helpers;
+mod time_index_helpers;
 mod remote_index_helpers;
+mod remote_sync_helpers;
+mod index_integrity_helpers;
+mod tombstone_helpers;
+mod idempotency_helpers;
 mod record_helpers;
+mod saga_helpers;
 mod link_helpers;
+mod traversal_helpers;
 mod rpc_helpers;
+mod bridge_helpers;
 
 // API interfaces
 
@@ -26,28 +40,69 @@ pub mod type_wrappers;  // :TODO: make private once unit_requests.rs dependency
 pub mod maybe_undefined;
 pub use maybe_undefined::MaybeUndefined as MaybeUndefined;
 pub mod record_interface;
+pub mod pagination;
+pub mod field_selection;
+pub mod link_specs;
+pub use link_specs::{ LinkSpec, BidirectionalIndex };
+pub mod errors;
+pub use errors::{ GraphHelperError, GraphHelperResult };
+#[cfg(feature = "mock-hdk")]
+pub mod mock_hdk;
 
 // helper functions API
 
 pub mod entries { pub use crate::entry_helpers::*; }
 pub mod anchors { pub use crate::anchor_helpers::*; }
 pub mod links { pub use crate::link_helpers::*; }
+pub mod traversal { pub use crate::traversal_helpers::*; }
 pub mod keys { pub use crate::key_helpers::*; }
 pub mod local_indexes { pub use crate::local_index_helpers::*; }
+pub mod time_indexes { pub use crate::time_index_helpers::*; }
 pub mod remote_indexes { pub use crate::remote_index_helpers::*; }
+pub mod remote_sync { pub use crate::remote_sync_helpers::*; }
+pub mod integrity { pub use crate::index_integrity_helpers::*; }
+pub mod tombstones { pub use crate::tombstone_helpers::*; }
+pub mod idempotency { pub use crate::idempotency_helpers::*; }
 pub mod rpc { pub use crate::rpc_helpers::*; }
+pub mod bridges { pub use crate::bridge_helpers::*; }
 pub mod records { pub use crate::record_helpers::*; }
+pub mod saga { pub use crate::saga_helpers::*; }
 
 pub mod identifiers {
     // Holochain DHT storage type IDs
     pub const RECORD_INITIAL_ENTRY_LINK_TAG: &str = "initial_entry";
     pub const ANCHOR_POINTER_LINK_TAG: &str = "referenced_entry";
+    pub const REMOTE_INDEX_PENDING_SYNC_ENTRY_TYPE: &str = "__remote_index_pending_sync";
+    pub const RECORD_TOMBSTONE_ENTRY_TYPE: &str = "__record_tombstone";
+    pub const IDEMPOTENCY_KEY_ENTRY_TYPE: &str = "__idempotency_key";
+    pub const IDEMPOTENCY_KEY_LINK_TYPE: &str = "idempotent_record";
+
+    // DNA property names
+    pub const BRIDGES_DNA_PROPERTY: &str = "bridges";
 
     // Error message strings
     pub const ERR_MSG_ENTRY_NOT_FOUND: &str = "No entry at this address";
     pub const ERR_MSG_ENTRY_WRONG_TYPE: &str = "Could not convert entry to requested type";
     pub const ERR_MSG_REMOTE_INDEXING_ERR: &str = "Indexing error in remote DNA call ";
     pub const ERR_MSG_REMOTE_REQUEST_ERR: &str = "Error in zome RPC call ";
+    pub const ERR_MSG_REMOTE_UNREACHABLE: &str = "Could not reach zome for RPC call ";
     pub const ERR_MSG_REMOTE_RESPONSE_FORMAT_ERR: &str = "Bad zome RPC response format from ";
     pub const ERR_MSG_INDEX_NOT_FOUND: &str = "Given index does not exist";
+    pub const ERR_MSG_EMPTY_QUERY: &str = "No query filters provided";
+    pub const ERR_MSG_INVALID_CURSOR: &str = "Pagination cursor does not reference a record in this index";
+    pub const ERR_MSG_INVALID_PAGE_SIZE: &str = "Page limit must be at least 1";
+    pub const ERR_MSG_PAGINATION_UNSUPPORTED: &str = "Pagination parameters are only accepted by paginated query endpoints";
+    pub const ERR_MSG_ANCHOR_CONFLICT: &str = "An entry is already anchored with this ID";
+    pub const ERR_MSG_IDEMPOTENT_RECORD_DELETED: &str = "Idempotency key was already used to create a record which has since been deleted:";
+    pub const ERR_MSG_INVALID_TIMESTAMP: &str = "Timestamp must begin with an ISO8601 date (YYYY-MM-DD)";
+    pub const ERR_MSG_REVISION_CONFLICT: &str = "Record has been modified since it was read, current revision is";
+    pub const ERR_MSG_ROLLBACK_FAILED: &str = "Could not undo steps of failed operation:";
+    pub const ERR_MSG_INVALID_BRIDGE_CONFIG: &str = "DNA property 'bridges' must map bridge IDs to bridge handles";
+    pub const ERR_MSG_TOMBSTONE_NOT_AUTHOR: &str = "Records may only be deleted or restored by their author";
+    pub const ERR_MSG_TOMBSTONE_RECORD_CHANGED: &str = "Tombstones cannot be moved to a different record";
+    pub const ERR_MSG_TOMBSTONE_REVISION: &str = "Tombstone revisions must increase by one on each update";
+    pub const ERR_MSG_TOMBSTONE_REMOVED: &str = "Tombstones cannot be removed";
+
+    // Error message strings from the conductor
+    pub const HDK_ERR_MSG_CAPABILITY_CHECK_FAILED: &str = "Caller does not have Capability to make that call";
 }
@@ -15,18 +15,20 @@ use hdk::{
         link::LinkMatch,
     },
     holochain_wasm_utils::api_serialization::get_links::GetLinksOptions,
-    error::{ ZomeApiResult },
-    get_links_with_options,
 };
 
 use super::{
+    hdk_api::{
+        get_links_with_options,
+    },
+    errors::GraphHelperResult,
     keys::{
         get_key_index_address,
     },
 };
 
 // HDK re-exports
-pub use hdk::link_entries;
+pub use crate::hdk_api::{ link_entries, remove_link };
 
 //--------------------------------[ READ ]--------------------------------------
 
@@ -37,25 +39,20 @@ pub use hdk::link_entries;
 /// such that it can be cheaply passed by reference into Serde output
 /// structs & functions via `Cow::into_owned`.
 ///
-/// :TODO: propagate errors in link retrieval
-///
 /// @see `type_aliases.rs`
 ///
 pub fn get_linked_addresses_as_type<'a, T, I>(
     base_address: I,
     link_type: &str,
     link_tag: &str,
-) -> Cow<'a, Vec<T>>
+) -> GraphHelperResult<Cow<'a, Vec<T>>>
     where T: From<Address> + Clone, I: AsRef<Address>
 {
-    let addrs = get_linked_addresses(base_address.as_ref(), link_type, link_tag);
-    if let Err(_get_links_err) = addrs {
-        return Cow::Owned(vec![]);  // :TODO: improve error handling
-    }
+    let addrs = get_linked_addresses(base_address.as_ref(), link_type, link_tag)?;
 
-    Cow::Owned(addrs.unwrap().iter()
+    Ok(Cow::Owned(addrs.iter()
         .map(|addr| { T::from(addr.to_owned()) })
-        .collect())
+        .collect()))
 }
 
 /// Similar to `get_linked_addresses_as_type` except that the returned addresses
@@ -68,23 +65,16 @@ pub fn get_linked_addresses_with_foreign_key_as_type<'a, T, I>(
     base_address: I,
     link_type: &str,
     link_tag: &str,
-) -> Cow<'a, Vec<T>>
+) -> GraphHelperResult<Cow<'a, Vec<T>>>
     where T: From<Address> + Clone, I: AsRef<Address>
 {
-    let addrs = get_linked_addresses(base_address.as_ref(), link_type, link_tag);
-    if let Err(_get_links_err) = addrs {
-        return Cow::Owned(vec![]);  // :TODO: improve error handling
-    }
+    let addrs = get_linked_addresses(base_address.as_ref(), link_type, link_tag)?;
 
-    Cow::Owned(addrs.unwrap().iter()
-        .filter_map(|addr| {
-            let base_addr_request = get_key_index_address(&addr);
-            match base_addr_request {
-                Ok(remote_addr) => Some(T::from(remote_addr)),
-                Err(_) => None,     // :TODO: handle errors gracefully
-            }
-        })
-        .collect())
+    let remote_addrs: GraphHelperResult<Vec<T>> = addrs.iter()
+        .map(|addr| { get_key_index_address(&addr).map(T::from) })
+        .collect();
+
+    Ok(Cow::Owned(remote_addrs?))
 }
 
 /// Load any set of addresses that are linked from the
@@ -94,7 +84,7 @@ pub (crate) fn get_linked_addresses(
     base_address: &Address,
     link_type: &str,
     link_tag: &str,
-) -> ZomeApiResult<Vec<Address>> {
+) -> GraphHelperResult<Vec<Address>> {
     let get_links_result = get_links_with_options(
         base_address,
         LinkMatch::Exactly(link_type),
@@ -102,8 +92,27 @@ pub (crate) fn get_linked_addresses(
         GetLinksOptions::default(),
     );
     if let Err(get_links_err) = get_links_result {
-        return Err(get_links_err);
+        return Err(get_links_err.into());
     }
 
     Ok(get_links_result.unwrap().addresses())
 }
+
+/// Load the addresses linked from the `base_address` entry via `link_type`, along with
+/// the tag of each link. Links of any tag are returned.
+///
+pub (crate) fn get_linked_addresses_with_tags(
+    base_address: &Address,
+    link_type: &str,
+) -> GraphHelperResult<Vec<(String, Address)>> {
+    let get_links_result = get_links_with_options(
+        base_address,
+        LinkMatch::Exactly(link_type),
+        LinkMatch::Any,
+        GetLinksOptions::default(),
+    )?;
+
+    Ok(get_links_result.links().iter()
+        .map(|link| { (link.tag.to_owned(), link.address.to_owned()) })
+        .collect())
+}
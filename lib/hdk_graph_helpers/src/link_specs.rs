@@ -0,0 +1,65 @@
+/**
+ * Typed descriptions of the links which make up `indexes`.
+ *
+ * An index between two record types is composed of a pair of links, one in each
+ * direction. Rather than passing the link types & tags (and the entry types they join)
+ * as separate strings- which are easily transposed- index helpers accept a
+ * `BidirectionalIndex` describing both sides at once, or a `LinkSpec` where only one
+ * direction is traversed.
+ *
+ * Instances for each record type are exported by its `storage_consts` crate.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-06-15
+ */
+
+/// One direction of an index: links of `link_type` and `link_tag`, originating from
+/// entries of `base_entry_type`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkSpec {
+    pub base_entry_type: &'static str,
+    pub link_type: &'static str,
+    pub link_tag: &'static str,
+}
+
+/// Both directions of an index. `forward` links point from `forward.base_entry_type`
+/// entries to `reverse.base_entry_type` entries, and `reverse` links point back again.
+///
+/// Where one side of an index references records in another DNA, the base entry type
+/// for that side is the type of the `key indexes` which stand in for those records locally.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BidirectionalIndex {
+    pub forward: LinkSpec,
+    pub reverse: LinkSpec,
+}
+
+impl BidirectionalIndex {
+    /// The same index, as seen from the record type on its other side
+    pub const fn reversed(self) -> Self {
+        BidirectionalIndex {
+            forward: self.reverse,
+            reverse: self.forward,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INDEX: BidirectionalIndex = BidirectionalIndex {
+        forward: LinkSpec { base_entry_type: "thing_baseurl", link_type: "thing_owner", link_tag: "owner" },
+        reverse: LinkSpec { base_entry_type: "owner_baseurl", link_type: "owner_things", link_tag: "things" },
+    };
+
+    #[test]
+    fn test_reversed() {
+        const REVERSED: BidirectionalIndex = INDEX.reversed();
+
+        assert_eq!(REVERSED.forward, INDEX.reverse);
+        assert_eq!(REVERSED.reverse, INDEX.forward);
+        assert_eq!(REVERSED.reversed(), INDEX);
+    }
+}
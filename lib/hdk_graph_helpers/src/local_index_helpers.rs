@@ -9,19 +9,24 @@
  * @since   2019-05-16
  */
 use std::convert::{ TryFrom };
+use std::collections::HashSet;
 use hdk::{
     holochain_json_api::{ json::JsonString },
     holochain_persistence_api::cas::content::Address,
     holochain_core_types::{
         entry::AppEntryValue,
     },
-    error::{ ZomeApiResult },
-    link_entries,
-    remove_link,
 };
 
 use super::{
+    hdk_api::{
+        link_entries,
+        remove_link,
+    },
     MaybeUndefined,
+    link_specs::{ LinkSpec, BidirectionalIndex },
+    errors::{ GraphHelperError, GraphHelperResult, throw_any_error },
+    pagination::{ PageRequest, Page, paginate_addresses, paginate_addresses_where },
     entries::{
         get_entries_by_address,
         get_entries_by_key_index,
@@ -33,6 +38,7 @@ use super::{
     keys::{
         determine_key_index_address,
     },
+    tombstones::is_record_deleted,
     internals::{
         wipe_links_from_origin,
         link_matches,
@@ -45,20 +51,17 @@ use super::{
 /// Load any set of records of type `R` that are:
 /// - linked locally (in the same DNA) from the `base_address`
 /// - linked directly to the `base_address`, without any indirection
-/// - linked via `link_type` and `link_name`
-///
-/// :TODO: return errors, improve error handling
+/// - linked via the `link_type` and `link_tag` of `link`
 ///
 pub fn query_direct_index<R, F, A>(
     base_address: &F,
-    link_type: &str,
-    link_name: &str,
-) -> ZomeApiResult<Vec<(A, Option<R>)>>
+    link: &LinkSpec,
+) -> GraphHelperResult<Vec<(A, Option<R>)>>
     where R: Clone + TryFrom<AppEntryValue>,
         A: From<Address>,
         F: AsRef<Address>,
 {
-    let addrs_result = get_linked_addresses(base_address.as_ref(), link_type, link_name);
+    let addrs_result = get_linked_addresses(base_address.as_ref(), link.link_type, link.link_tag);
     if let Err(get_links_err) = addrs_result {
         return Err(get_links_err);
     }
@@ -68,85 +71,273 @@ pub fn query_direct_index<R, F, A>(
 /// Load any set of records of type `R` that are:
 /// - linked locally (in the same DNA) from the `base_address`
 /// - linked via their own local indirect indexes (`base_address` -> entry base -> entry data)
-/// - linked via `link_type` and `link_name`
+/// - linked via the `link_type` and `link_tag` of `link`
 ///
 /// Results are automatically deserialized into `R` as they are retrieved from the DHT.
-/// Any entries that either fail to load or cannot be converted to the type will be dropped.
-///
-/// :TODO: return errors, improve error handling
+/// Records which have been deleted are returned without entry data, and any other
+/// failure to load or convert an entry is returned as an error.
 ///
 pub fn query_direct_index_with_foreign_key<R, F, A>(
     base_address: &F,
-    link_type: &str,
-    link_name: &str,
-) -> ZomeApiResult<Vec<(A, Option<R>)>>
+    link: &LinkSpec,
+) -> GraphHelperResult<Vec<(A, Option<R>)>>
     where R: Clone + TryFrom<AppEntryValue>,
         A: From<Address>,
         F: AsRef<Address>,
 {
-    let addrs_result = get_linked_addresses(base_address.as_ref(), link_type, link_name);
+    let addrs_result = get_linked_addresses(base_address.as_ref(), link.link_type, link.link_tag);
     if let Err(get_links_err) = addrs_result {
         return Err(get_links_err);
     }
-    get_entries_by_key_index(addrs_result.unwrap())
+    get_entries_by_key_index(addrs_result.unwrap(), false)
 }
 
 /// Load any set of records of type `R` that are:
 /// - linked remotely (from an external DNA) from the `base_address`
 /// - linked via their own local indirect indexes (`base_address` -> entry base -> entry data)
-/// - linked via `link_type` and `link_name`
+/// - linked via the `link_type` and `link_tag` of `link`, from the local `key index` of
+///   type `link.base_entry_type` which references the `base_address`
 ///
 /// Results are automatically deserialized into `R` as they are retrieved from the DHT.
-/// Any entries that either fail to load or cannot be converted to the type will be dropped.
+/// Records which have been deleted are returned without entry data, and any other
+/// failure to load or convert an entry is returned as an error.
 ///
-/// :TODO: return errors, improve error handling
-///
-pub fn query_direct_remote_index_with_foreign_key<'a, R, F, A>(
+pub fn query_direct_remote_index_with_foreign_key<R, F, A>(
     base_address: &F,
-    base_entry_type: &'a str,
-    link_type: &str,
-    link_name: &str,
-) -> ZomeApiResult<Vec<(A, Option<R>)>>
+    link: &LinkSpec,
+) -> GraphHelperResult<Vec<(A, Option<R>)>>
     where R: Clone + TryFrom<AppEntryValue>,
         A: From<Address>,
         F: AsRef<Address> + Into<JsonString> + Clone,
 {
-    let query_address = determine_key_index_address(base_entry_type.to_string(), base_address.as_ref());
+    let query_address = determine_key_index_address(link.base_entry_type.to_string(), base_address.as_ref());
     if let Err(resolve_remote_err) = query_address {
         return Err(resolve_remote_err);
     }
 
-    let addrs_result = get_linked_addresses(&query_address.unwrap(), link_type, link_name);
+    let addrs_result = get_linked_addresses(&query_address.unwrap(), link.link_type, link.link_tag);
     if let Err(get_links_err) = addrs_result {
         return Err(get_links_err);
     }
-    get_entries_by_key_index(addrs_result.unwrap())
+    get_entries_by_key_index(addrs_result.unwrap(), false)
+}
+
+/// Paginated version of `query_direct_index_with_foreign_key`.
+///
+/// Only the entries for the requested page are loaded from the DHT.
+///
+/// @see hdk_graph_helpers::pagination
+///
+pub fn query_direct_index_with_foreign_key_paginated<R, F, A>(
+    base_address: &F,
+    link: &LinkSpec,
+    page: &PageRequest,
+) -> GraphHelperResult<Page<(A, Option<R>)>>
+    where R: Clone + TryFrom<AppEntryValue>,
+        A: From<Address>,
+        F: AsRef<Address>,
+{
+    let addrs = get_linked_addresses(base_address.as_ref(), link.link_type, link.link_tag)?;
+    load_page_by_key_index(addrs, page, false)
+}
+
+/// A single index predicate or compound expression of predicates, used to build queries that
+/// filter records by more than one relationship at once.
+///
+/// Leaf predicates resolve to the set of record `key index` addresses reachable through a single
+/// index; `And` and `Or` intersect and union the address sets of their children respectively.
+///
+/// @see query_indexes_with_foreign_key
+///
+#[derive(Debug, Clone)]
+pub enum IndexQuery {
+    /// Records linked locally from `base_address` via `link`.
+    /// Behaves as `query_direct_index_with_foreign_key`.
+    Local {
+        base_address: Address,
+        link: LinkSpec,
+    },
+    /// Records linked from `base_address` in an external DNA, via the local `key index` of type `link.base_entry_type`.
+    /// Behaves as `query_direct_remote_index_with_foreign_key`.
+    Remote {
+        base_address: Address,
+        link: LinkSpec,
+    },
+    /// Records matched by every child predicate
+    And(Vec<IndexQuery>),
+    /// Records matched by any child predicate
+    Or(Vec<IndexQuery>),
+}
+
+impl IndexQuery {
+    pub fn local<F: AsRef<Address>>(base_address: &F, link: &LinkSpec) -> Self {
+        IndexQuery::Local {
+            base_address: base_address.as_ref().clone(),
+            link: *link,
+        }
+    }
+
+    pub fn remote<F: AsRef<Address>>(base_address: &F, link: &LinkSpec) -> Self {
+        IndexQuery::Remote {
+            base_address: base_address.as_ref().clone(),
+            link: *link,
+        }
+    }
+
+    /// Determine whether this query contains any leaf predicates to be evaluated
+    pub fn is_empty(&self) -> bool {
+        match self {
+            IndexQuery::And(children) | IndexQuery::Or(children) => children.iter().all(IndexQuery::is_empty),
+            _ => false,
+        }
+    }
+}
+
+/// Load any set of records of type `R` matching the compound `query`.
+///
+/// All index predicates are resolved to sets of record `key index` addresses, combined as per
+/// the `And` / `Or` structure of the query, and the matching entries are then loaded once.
+/// Result ordering follows that of the first (leftmost) predicate in the query.
+///
+/// An error is returned if the query contains no predicates, since an unfiltered query would
+/// otherwise silently return nothing.
+///
+/// Soft-deleted records are omitted from the results unless `include_deleted` is set.
+///
+pub fn query_indexes_with_foreign_key<R, A>(
+    query: &IndexQuery,
+    include_deleted: bool,
+) -> GraphHelperResult<Vec<(A, Option<R>)>>
+    where R: Clone + TryFrom<AppEntryValue>,
+        A: From<Address>,
+{
+    if query.is_empty() {
+        return Err(GraphHelperError::EmptyQuery);
+    }
+    get_entries_by_key_index(resolve_index_query(query)?, include_deleted)
+}
+
+/// Paginated version of `query_indexes_with_foreign_key`.
+///
+/// The full set of matching addresses is resolved from the indexes, but only the entries
+/// for the requested page are loaded from the DHT. Pages are ordered by record address,
+/// rather than by that of the leftmost predicate.
+///
+/// @see hdk_graph_helpers::pagination
+///
+pub fn query_indexes_with_foreign_key_paginated<R, A>(
+    query: &IndexQuery,
+    page: &PageRequest,
+    include_deleted: bool,
+) -> GraphHelperResult<Page<(A, Option<R>)>>
+    where R: Clone + TryFrom<AppEntryValue>,
+        A: From<Address>,
+{
+    if query.is_empty() {
+        return Err(GraphHelperError::EmptyQuery);
+    }
+    load_page_by_key_index(resolve_index_query(query)?, page, include_deleted)
+}
+
+/// Slice a set of `key index` addresses into the requested page and load the associated entries
+///
+/// Soft-deleted records are skipped whilst filling the page, so that pages are not left short.
+/// Only the records needed to fill the page are checked for deletion, rather than the whole index.
+///
+pub (crate) fn load_page_by_key_index<R, A>(
+    addresses: Vec<Address>,
+    page: &PageRequest,
+    include_deleted: bool,
+) -> GraphHelperResult<Page<(A, Option<R>)>>
+    where R: Clone + TryFrom<AppEntryValue>,
+        A: From<Address>,
+{
+    let (page_addresses, next_cursor) = if include_deleted {
+        paginate_addresses(addresses, page)?
+    } else {
+        paginate_addresses_where(addresses, page, |address| Ok(!is_record_deleted(address)?))?
+    };
+    Ok(Page {
+        results: get_entries_by_key_index(page_addresses, true)?,
+        next_cursor,
+    })
+}
+
+/// Resolve an `IndexQuery` to the (ordered, deduplicated) list of record `key index` addresses it matches.
+///
+pub (crate) fn resolve_index_query(query: &IndexQuery) -> GraphHelperResult<Vec<Address>> {
+    match query {
+        IndexQuery::Local { base_address, link } => {
+            Ok(dedupe_addresses(get_linked_addresses(base_address, link.link_type, link.link_tag)?))
+        },
+        IndexQuery::Remote { base_address, link } => {
+            let query_address: Address = determine_key_index_address(link.base_entry_type.to_string(), base_address)?;
+            Ok(dedupe_addresses(get_linked_addresses(&query_address, link.link_type, link.link_tag)?))
+        },
+        IndexQuery::And(children) => {
+            let mut result: Option<Vec<Address>> = None;
+            for child in children.iter().filter(|c| !c.is_empty()) {
+                let child_addrs = resolve_index_query(child)?;
+                result = Some(match result {
+                    None => child_addrs,
+                    Some(current) => {
+                        let child_set: HashSet<Address> = child_addrs.into_iter().collect();
+                        current.into_iter().filter(|a| child_set.contains(a)).collect()
+                    },
+                });
+                // short-circuit once nothing can match
+                if let Some(current) = &result {
+                    if current.is_empty() { break; }
+                }
+            }
+            Ok(result.unwrap_or_default())
+        },
+        IndexQuery::Or(children) => {
+            let mut result: Vec<Address> = vec![];
+            let mut seen: HashSet<Address> = HashSet::new();
+            for child in children.iter().filter(|c| !c.is_empty()) {
+                for addr in resolve_index_query(child)? {
+                    if seen.insert(addr.clone()) {
+                        result.push(addr);
+                    }
+                }
+            }
+            Ok(result)
+        },
+    }
+}
+
+fn dedupe_addresses(addrs: Vec<Address>) -> Vec<Address> {
+    let mut seen: HashSet<Address> = HashSet::with_capacity(addrs.len());
+    addrs.into_iter()
+        .filter(|addr| seen.insert(addr.clone()))
+        .collect()
 }
 
 //-------------------------------[ CREATE ]-------------------------------------
 
 /// Creates a bidirectional link between two entry addresses, and returns a vector
 /// of the addresses of the (respectively) forward & reciprocal links created.
-pub fn create_direct_index<S: Into<String>>(
+///
+/// `source` is linked to `dest` via the `forward` side of `index`, and `dest` back
+/// to `source` via the `reverse` side.
+///
+pub fn create_direct_index(
     source: &Address,
     dest: &Address,
-    link_type: S,
-    link_name: S,
-    link_type_reciprocal: S,
-    link_name_reciprocal: S,
-) -> Vec<ZomeApiResult<Address>> {
+    index: &BidirectionalIndex,
+) -> Vec<GraphHelperResult<Address>> {
     vec! [
-        link_entries(source, dest, link_type, link_name),
-        link_entries(dest, source, link_type_reciprocal, link_name_reciprocal),
+        link_entries(source, dest, index.forward.link_type, index.forward.link_tag).map_err(GraphHelperError::from),
+        link_entries(dest, source, index.reverse.link_type, index.reverse.link_tag).map_err(GraphHelperError::from),
     ]
 }
 
 //-------------------------------[ UPDATE ]-------------------------------------
 
-/// Remove any links of `link_type`/`link_name` and their reciprocal links of
-/// `link_type_reciprocal`/`link_name_reciprocal` that might be present on `source`;
-/// then add new links of the given types & names that instead point from `source`
-/// to `new_dest`.
+/// Remove any `forward` links of `index` and their `reverse` links that might be
+/// present on `source`; then add new links of the same types & tags that instead
+/// point from `source` to `new_dest`.
 ///
 /// If `new_dest` is `MaybeUndefined::None`, the links are simply removed.
 /// If `new_dest` is `MaybeUndefined::Undefined`, this is a no-op.
@@ -154,16 +345,12 @@ pub fn create_direct_index<S: Into<String>>(
 /// Returns the addresses of the previously erased link targets, if any.
 ///
 /// :TODO: update to accept multiple targets for the replacement links
-/// :TODO: propagate errors to allow non-critical failures in calling code
 ///
 pub fn replace_direct_index<A, B>(
     source: &A,
     new_dest: &MaybeUndefined<B>,
-    link_type: &str,
-    link_name: &str,
-    link_type_reciprocal: &str,
-    link_name_reciprocal: &str,
-) -> ZomeApiResult<Vec<ZomeApiResult<Address>>>
+    index: &BidirectionalIndex,
+) -> GraphHelperResult<Vec<GraphHelperResult<Address>>>
     where A: AsRef<Address> + From<Address> + Clone,
         B: AsRef<Address> + From<Address> + Clone + PartialEq,
 {
@@ -173,38 +360,27 @@ pub fn replace_direct_index<A, B>(
     }
 
     // load any existing linked entries from the originating address
-    let existing_links: Vec<B> = get_linked_addresses_as_type(source, link_type, link_name).into_owned();
+    let existing_links: Vec<B> = get_linked_addresses_as_type(source, index.forward.link_type, index.forward.link_tag)?.into_owned();
 
     // determine links to erase
     let to_erase: Vec<B> = existing_links.iter()
         .filter(link_does_not_match(new_dest)).map(|x| { (*x).clone() }).collect();
 
     // wipe stale links
-    // :TODO: propagate errors
-    let _erased: Vec<ZomeApiResult<()>> = to_erase.iter().flat_map(wipe_links_from_origin(
-        link_type, link_name,
-        link_type_reciprocal, link_name_reciprocal,
-        source,
-    )).collect();
+    to_erase.iter().flat_map(wipe_links_from_origin(index, source)).collect::<GraphHelperResult<Vec<()>>>()?;
 
     // get base addresses of erased items
-    let erased: Vec<ZomeApiResult<Address>> = to_erase.iter().map(|addr| { Ok((*addr).as_ref().clone()) }).collect();
+    let erased: Vec<GraphHelperResult<Address>> = to_erase.iter().map(|addr| { Ok((*addr).as_ref().clone()) }).collect();
 
     // run insert if needed
     match new_dest {
         MaybeUndefined::Some(new_link) => {
             let already_present = existing_links.iter().filter(link_matches(new_dest)).count() > 0;
 
-            if already_present {
-                Ok(erased)
-            } else {
-                create_direct_index(
-                    source.as_ref(), new_link.as_ref(),
-                    link_type, link_name,
-                    link_type_reciprocal, link_name_reciprocal
-                );
-                Ok(erased)
+            if !already_present {
+                throw_any_error(create_direct_index(source.as_ref(), new_link.as_ref(), index))?;
             }
+            Ok(erased)
         },
         _ => Ok(erased),
     }
@@ -217,16 +393,13 @@ pub fn replace_direct_index<A, B>(
 ///
 /// :TODO: filter empty success tuples from results and return as flattened error array
 ///
-pub fn delete_direct_index<S: Into<String>>(
+pub fn delete_direct_index(
     source: &Address,
     dest: &Address,
-    link_type: S,
-    link_name: S,
-    link_type_reciprocal: S,
-    link_name_reciprocal: S,
-) -> Vec<ZomeApiResult<()>> {
+    index: &BidirectionalIndex,
+) -> Vec<GraphHelperResult<()>> {
     vec! [
-        remove_link(source, dest, link_type, link_name),
-        remove_link(dest, source, link_type_reciprocal, link_name_reciprocal),
+        remove_link(source, dest, index.forward.link_type, index.forward.link_tag).map_err(GraphHelperError::from),
+        remove_link(dest, source, index.reverse.link_type, index.reverse.link_tag).map_err(GraphHelperError::from),
     ]
 }
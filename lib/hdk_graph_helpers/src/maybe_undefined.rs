@@ -28,6 +28,47 @@ impl<T> MaybeUndefined<T> where T: Clone {
     }
 }
 
+// merge helpers for applying update requests to entry data
+// @see hdk_graph_helpers_derive::Updateable
+impl<T> MaybeUndefined<T> where T: Clone {
+    /// Update an optional value. Undefined leaves `current` untouched, null erases it.
+    pub fn update_optional(&self, current: &Option<T>) -> Option<T> {
+        match self {
+            MaybeUndefined::Some(val) => Some(val.clone()),
+            MaybeUndefined::None => None,
+            MaybeUndefined::Undefined => current.clone(),
+        }
+    }
+
+    /// Update a required value. Since it cannot be erased, both null and undefined leave `current` untouched.
+    pub fn update_required(&self, current: &T) -> T {
+        match self {
+            MaybeUndefined::Some(val) => val.clone(),
+            _ => current.clone(),
+        }
+    }
+}
+
+impl<T> MaybeUndefined<Vec<T>> where T: Clone + PartialEq {
+    /// Update an optional set of values by appending any new values to those in `current`.
+    /// Undefined leaves `current` untouched, null erases it.
+    pub fn merge_set(&self, current: &Option<Vec<T>>) -> Option<Vec<T>> {
+        match self {
+            MaybeUndefined::Some(vals) => {
+                let mut results = current.clone().unwrap_or_default();
+                for val in vals.iter() {
+                    if !results.contains(val) {
+                        results.push(val.clone());
+                    }
+                }
+                Some(results)
+            },
+            MaybeUndefined::None => None,
+            MaybeUndefined::Undefined => current.clone(),
+        }
+    }
+}
+
 impl<T> MaybeUndefined<T> {
     pub fn is_undefined(&self) -> bool {
         match self {
@@ -150,6 +191,33 @@ mod tests {
         let _another: TestEntry = entry.into();
     }
 
+    #[test]
+    fn test_update_optional() {
+        let current = Some("old".to_string());
+        assert_eq!(MaybeUndefined::Undefined.update_optional(&current), current);
+        assert_eq!(MaybeUndefined::None.update_optional(&current), None);
+        assert_eq!(MaybeUndefined::Some("new".to_string()).update_optional(&current), Some("new".to_string()));
+    }
+
+    #[test]
+    fn test_update_required() {
+        assert_eq!(MaybeUndefined::Undefined.update_required(&true), true);
+        assert_eq!(MaybeUndefined::None.update_required(&true), true);
+        assert_eq!(MaybeUndefined::Some(false).update_required(&true), false);
+    }
+
+    #[test]
+    fn test_merge_set() {
+        let current = Some(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(
+            MaybeUndefined::Some(vec!["b".to_string(), "c".to_string(), "c".to_string()]).merge_set(&current),
+            Some(vec!["a".to_string(), "b".to_string(), "c".to_string()]),
+        );
+        assert_eq!(MaybeUndefined::Some(vec!["a".to_string()]).merge_set(&None), Some(vec!["a".to_string()]));
+        assert_eq!(MaybeUndefined::Undefined.merge_set(&current), current);
+        assert_eq!(MaybeUndefined::None.merge_set(&current), None);
+    }
+
     #[test]
     fn test_deserialization_some() {
         let expected = TestEntrySimple { test_field: MaybeUndefined::Some("blah".to_string()) };
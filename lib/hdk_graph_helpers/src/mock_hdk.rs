@@ -0,0 +1,401 @@
+/**
+ * In-memory stand-in for the Holochain zome API, for unit-testing zome logic.
+ *
+ * Enabled by the `mock-hdk` cargo feature. When active, all helpers in this crate
+ * read and write entries and links via the methods in this module instead of the
+ * conductor, such that they (and any library code built on them) can be exercised
+ * by an ordinary `cargo test`.
+ *
+ * State is held per-thread, and so is isolated between tests run by the default
+ * test harness. Cross-zome and cross-DNA `call`s are routed to handlers registered
+ * with `register_zome_fn`; all DNAs share the same storage.
+ *
+ * Behaviour mirrors that of the conductor where the helpers depend on it:
+ *
+ * - `get_entry` follows updates to the most recent revision, and returns `None` for deleted entries
+ * - updating an entry to the content of one of its earlier revisions makes that revision current again
+ * - links may only be created between entries which exist
+ * - `query` returns entries in the order they were committed by the (single) agent
+ * - every commit of an entry adds a header signed by the current agent, one second after the previous commit
+ * - `agent_address` returns a fixed address, which can be changed with `set_agent_address`
+ * - `property` returns DNA properties set with `set_dna_property`, and an error for any others
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-06-12
+ */
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use hdk::{
+    holochain_json_api::json::JsonString,
+    holochain_persistence_api::cas::content::{ Address, AddressableContent },
+    holochain_core_types::{
+        entry::Entry,
+        entry::Entry::App as AppEntry,
+        entry::AppEntryValue,
+        crud_status::CrudStatus,
+        chain_header::ChainHeader,
+        signature::{ Provenance, Signature },
+        time::Iso8601,
+        link::LinkMatch,
+    },
+    holochain_wasm_utils::api_serialization::{
+        QueryArgsNames,
+        get_entry::{
+            GetEntryOptions,
+            GetEntryResult,
+            GetEntryResultType,
+            GetEntryResultItem,
+            EntryResultMeta,
+            EntryHistory,
+            StatusRequestKind,
+        },
+        get_links::{ GetLinksOptions, GetLinksResult, LinksResult },
+    },
+    error::{ ZomeApiError, ZomeApiResult },
+};
+
+use super::identifiers::{ ERR_MSG_ENTRY_NOT_FOUND, ERR_MSG_ENTRY_WRONG_TYPE };
+
+/// Handler for a mocked zome function. Receives the JSON-encoded function arguments
+/// and must return the JSON-encoded `ZomeApiResult`, as a real zome would.
+pub type MockZomeFn = Rc<dyn Fn(JsonString) -> ZomeApiResult<JsonString>>;
+
+/// Timestamp of the first commit made in each test, as seconds since the UNIX epoch (2020-01-01T00:00:00Z)
+const MOCK_EPOCH: i64 = 1_577_836_800;
+
+#[derive(Debug, Clone, PartialEq)]
+struct MockLink {
+    base: Address,
+    target: Address,
+    link_type: String,
+    tag: String,
+}
+
+#[derive(Default)]
+struct MockStore {
+    entries: HashMap<Address, Entry>,
+    /// update chain, from each revision to the one which replaced it
+    updates: HashMap<Address, Address>,
+    deleted: Vec<Address>,
+    /// headers of every commit of each entry, in commit order
+    headers: HashMap<Address, Vec<ChainHeader>>,
+    commit_count: i64,
+    /// source chain of the agent, as (entry type, entry address)
+    chain: Vec<(String, Address)>,
+    links: Vec<MockLink>,
+    agent: Option<Address>,
+    properties: HashMap<String, JsonString>,
+    zome_fns: HashMap<(String, String, String), MockZomeFn>,
+}
+
+thread_local! {
+    static STORE: RefCell<MockStore> = RefCell::new(MockStore::default());
+}
+
+impl MockStore {
+    fn latest_revision(&self, address: &Address) -> Address {
+        let mut current = address.to_owned();
+        while let Some(next) = self.updates.get(&current) {
+            current = next.to_owned();
+        }
+        current
+    }
+
+    fn is_deleted(&self, address: &Address) -> bool {
+        self.deleted.contains(address)
+    }
+
+    fn exists(&self, address: &Address) -> bool {
+        self.entries.contains_key(address)
+    }
+
+    fn agent(&self) -> Address {
+        self.agent.to_owned().unwrap_or(Address::from("mock_agent"))
+    }
+
+    fn commit(&mut self, entry: &Entry) -> Address {
+        let address = entry.address();
+        self.entries.insert(address.to_owned(), entry.to_owned());
+
+        let header = ChainHeader::new(
+            &entry.entry_type(), &address,
+            &[Provenance::new(self.agent(), Signature::from("mock_signature"))],
+            &None, &None, &None,
+            &Iso8601::new(MOCK_EPOCH + self.commit_count, 0),
+        );
+        self.commit_count += 1;
+        self.headers.entry(address.to_owned()).or_default().push(header);
+
+        // re-committing identical content (eg. anchors, key indexes) revives it
+        self.deleted.retain(|a| a != &address);
+
+        if let AppEntry(entry_type, _) = entry {
+            self.chain.push((String::from(entry_type.to_owned()), address.to_owned()));
+        }
+        address
+    }
+}
+
+fn not_found() -> ZomeApiError {
+    ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.to_string())
+}
+
+fn link_matches(pattern: &LinkMatch<&str>, value: &str) -> bool {
+    match pattern {
+        LinkMatch::Any => true,
+        LinkMatch::Exactly(expected) => *expected == value,
+        // regular expressions are not supported by the mock; treat as exact
+        LinkMatch::Regex(expected) => *expected == value,
+    }
+}
+
+//----------------------------[ TEST CONTROLS ]---------------------------------
+
+/// Erase all entries, links and registered zome functions for the current thread
+///
+pub fn reset() {
+    STORE.with(|store| { *store.borrow_mut() = MockStore::default(); });
+}
+
+/// Route `call`s to the given DNA instance, zome and function name to `handler`
+///
+pub fn register_zome_fn<F>(instance_handle: &str, zome_name: &str, fn_name: &str, handler: F)
+    where F: Fn(JsonString) -> ZomeApiResult<JsonString> + 'static,
+{
+    STORE.with(|store| {
+        store.borrow_mut().zome_fns.insert(
+            (instance_handle.to_string(), zome_name.to_string(), fn_name.to_string()),
+            Rc::new(handler),
+        );
+    });
+}
+
+/// Act as a different agent for subsequent calls. The source chain and DHT are not changed.
+///
+pub fn set_agent_address(agent: &Address) {
+    STORE.with(|store| { store.borrow_mut().agent = Some(agent.to_owned()); });
+}
+
+/// Set the value returned by `property` for the DNA property `name`
+///
+pub fn set_dna_property(name: &str, value: JsonString) {
+    STORE.with(|store| { store.borrow_mut().properties.insert(name.to_string(), value); });
+}
+
+/// Count the links currently present from `base` of the given type and tag
+///
+pub fn count_links(base: &Address, link_type: &str, tag: &str) -> usize {
+    STORE.with(|store| {
+        store.borrow().links.iter()
+            .filter(|l| &l.base == base && l.link_type == link_type && l.tag == tag)
+            .count()
+    })
+}
+
+//------------------------------[ ZOME API ]------------------------------------
+
+pub fn agent_address() -> Address {
+    STORE.with(|store| store.borrow().agent())
+}
+
+pub fn property<S: Into<String>>(name: S) -> ZomeApiResult<JsonString> {
+    let name = name.into();
+    STORE.with(|store| store.borrow().properties.get(&name).cloned())
+        .ok_or_else(|| ZomeApiError::Internal(format!("DNA property {} not set", name)))
+}
+
+pub fn entry_address(entry: &Entry) -> ZomeApiResult<Address> {
+    Ok(entry.address())
+}
+
+pub fn commit_entry(entry: &Entry) -> ZomeApiResult<Address> {
+    Ok(STORE.with(|store| store.borrow_mut().commit(entry)))
+}
+
+pub fn get_entry(address: &Address) -> ZomeApiResult<Option<Entry>> {
+    STORE.with(|store| {
+        let store = store.borrow();
+        let latest = store.latest_revision(address);
+        if store.is_deleted(&latest) {
+            return Ok(None);
+        }
+        Ok(store.entries.get(&latest).cloned())
+    })
+}
+
+pub fn get_entry_result(address: &Address, options: GetEntryOptions) -> ZomeApiResult<GetEntryResult> {
+    STORE.with(|store| {
+        let store = store.borrow();
+        if !store.exists(address) {
+            return Ok(GetEntryResult { result: GetEntryResultType::Single(GetEntryResultItem {
+                meta: None,
+                entry: None,
+                headers: vec![],
+            }) });
+        }
+
+        let mut revisions = vec![address.to_owned()];
+        let mut crud_links = HashMap::new();
+        while let Some(next) = store.updates.get(revisions.last().unwrap()) {
+            crud_links.insert(revisions.last().unwrap().to_owned(), next.to_owned());
+            revisions.push(next.to_owned());
+        }
+
+        let last_index = revisions.len() - 1;
+        let mut items: Vec<GetEntryResultItem> = revisions.iter().enumerate()
+            .map(|(i, addr)| {
+                let entry = store.entries.get(addr).cloned().unwrap();
+                GetEntryResultItem {
+                    meta: Some(EntryResultMeta {
+                        address: addr.to_owned(),
+                        entry_type: entry.entry_type(),
+                        crud_status: if store.is_deleted(addr) {
+                            CrudStatus::Deleted
+                        } else if i < last_index {
+                            CrudStatus::Modified
+                        } else {
+                            CrudStatus::Live
+                        },
+                    }),
+                    entry: if options.entry { Some(entry) } else { None },
+                    headers: if options.headers { store.headers.get(addr).cloned().unwrap_or_default() } else { vec![] },
+                }
+            })
+            .collect();
+
+        Ok(GetEntryResult { result: match options.status_request {
+            StatusRequestKind::All => GetEntryResultType::All(EntryHistory { items, crud_links }),
+            StatusRequestKind::Initial => GetEntryResultType::Single(items.remove(0)),
+            _ => GetEntryResultType::Single(items.pop().unwrap()),
+        } })
+    })
+}
+
+pub fn update_entry(new_entry: Entry, address: &Address) -> ZomeApiResult<Address> {
+    STORE.with(|store| {
+        let mut store = store.borrow_mut();
+        if !store.exists(address) || store.is_deleted(address) {
+            return Err(not_found());
+        }
+        if store.updates.contains_key(address) {
+            return Err(ZomeApiError::ValidationFailed("Entry has already been updated".to_string()));
+        }
+        let new_address = store.commit(&new_entry);
+        store.updates.insert(address.to_owned(), new_address.to_owned());
+        Ok(new_address)
+    })
+}
+
+pub fn remove_entry(address: &Address) -> ZomeApiResult<Address> {
+    STORE.with(|store| {
+        let mut store = store.borrow_mut();
+        let latest = store.latest_revision(address);
+        if !store.exists(&latest) || store.is_deleted(&latest) {
+            return Err(not_found());
+        }
+        store.deleted.push(latest.to_owned());
+        Ok(latest)
+    })
+}
+
+pub fn link_entries<S: Into<String>, TS: Into<String>>(
+    base: &Address,
+    target: &Address,
+    link_type: S,
+    tag: TS,
+) -> ZomeApiResult<Address> {
+    STORE.with(|store| {
+        let mut store = store.borrow_mut();
+        if !store.exists(base) || !store.exists(target) {
+            return Err(ZomeApiError::Internal("Base or target for link not found".to_string()));
+        }
+        let link = MockLink {
+            base: base.to_owned(),
+            target: target.to_owned(),
+            link_type: link_type.into(),
+            tag: tag.into(),
+        };
+        if !store.links.contains(&link) {
+            store.links.push(link);
+        }
+        Ok(target.to_owned())
+    })
+}
+
+pub fn remove_link<S: Into<String>, TS: Into<String>>(
+    base: &Address,
+    target: &Address,
+    link_type: S,
+    tag: TS,
+) -> ZomeApiResult<()> {
+    let link_type = link_type.into();
+    let tag = tag.into();
+    STORE.with(|store| {
+        store.borrow_mut().links.retain(|l| {
+            !(&l.base == base && &l.target == target && l.link_type == link_type && l.tag == tag)
+        });
+    });
+    Ok(())
+}
+
+pub fn get_links_with_options(
+    base: &Address,
+    link_type: LinkMatch<&str>,
+    tag: LinkMatch<&str>,
+    _options: GetLinksOptions,
+) -> ZomeApiResult<GetLinksResult> {
+    STORE.with(|store| {
+        Ok(GetLinksResult::new(store.borrow().links.iter()
+            .filter(|l| &l.base == base && link_matches(&link_type, &l.link_type) && link_matches(&tag, &l.tag))
+            .map(|l| LinksResult {
+                address: l.target.to_owned(),
+                headers: vec![],
+                tag: l.tag.to_owned(),
+                status: CrudStatus::Live,
+            })
+            .collect()))
+    })
+}
+
+pub fn query(entry_type_names: QueryArgsNames, start: usize, limit: usize) -> ZomeApiResult<Vec<Address>> {
+    let names = match entry_type_names {
+        QueryArgsNames::QueryName(name) => vec![name],
+        QueryArgsNames::QueryList(names) => names,
+    };
+    STORE.with(|store| {
+        let matching = store.borrow().chain.iter()
+            .filter(|(entry_type, _)| names.contains(entry_type))
+            .map(|(_, address)| address.to_owned())
+            .skip(start);
+        Ok(if limit == 0 { matching.collect() } else { matching.take(limit).collect() })
+    })
+}
+
+pub fn call<S: Into<String>>(
+    instance_handle: S,
+    zome_name: S,
+    _cap_token: Address,
+    fn_name: S,
+    fn_parameters: JsonString,
+) -> ZomeApiResult<JsonString> {
+    let key = (instance_handle.into(), zome_name.into(), fn_name.into());
+    // release the store before invoking the handler, since it will likely access it too
+    let handler = STORE.with(|store| store.borrow().zome_fns.get(&key).cloned());
+    match handler {
+        Some(handler) => handler(fn_parameters),
+        None => Err(ZomeApiError::Internal(format!("No mock zome function registered for {}/{}/{}", key.0, key.1, key.2))),
+    }
+}
+
+/// Equivalent of `hdk::utils::get_as_type`
+///
+pub fn get_as_type<R: TryFrom<AppEntryValue>>(address: Address) -> ZomeApiResult<R> {
+    match get_entry(&address)? {
+        Some(AppEntry(_, value)) => R::try_from(value)
+            .map_err(|_| ZomeApiError::Internal(ERR_MSG_ENTRY_WRONG_TYPE.to_string())),
+        _ => Err(not_found()),
+    }
+}
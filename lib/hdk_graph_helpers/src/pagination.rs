@@ -0,0 +1,252 @@
+/**
+ * Cursor-based pagination for index queries.
+ *
+ * Index reads resolve to a set of `key index` addresses before any entry data is loaded.
+ * Paginated reads slice that set and only load the entries for the requested page,
+ * returning an opaque cursor which can be passed back to continue reading.
+ *
+ * Since link ordering is not stable between DHT reads, addresses are sorted before slicing
+ * so that successive pages neither skip nor repeat records. Time-ordered reads are paginated
+ * separately, @see `hdk_graph_helpers::time_indexes`.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-06-02
+ */
+use hdk::{
+    holochain_persistence_api::cas::content::Address,
+};
+
+use super::errors::{ GraphHelperError, GraphHelperResult };
+
+/// Number of results returned by paginated reads where no `limit` is requested
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Request parameters for reading a single page of results. `limit` must be at least 1.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PageRequest {
+    pub limit: usize,
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+impl PageRequest {
+    pub fn new(limit: Option<usize>, cursor: Option<String>) -> Self {
+        PageRequest {
+            limit: limit.unwrap_or(DEFAULT_PAGE_SIZE),
+            cursor,
+        }
+    }
+}
+
+/// A single page of results, along with the cursor to pass back in order to read the next page.
+/// `next_cursor` is `None` once the final page has been read.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub results: Vec<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// Convert the results of this page, retaining the cursor
+    pub fn map<U, F>(self, f: F) -> Page<U>
+        where F: FnMut(T) -> U,
+    {
+        Page {
+            results: self.results.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+/// Determine whether query parameters ask for a page of results. Unpaginated query
+/// endpoints use this to reject pagination parameters rather than ignoring them.
+///
+pub fn is_page_request(limit: &Option<usize>, cursor: &Option<String>) -> bool {
+    limit.is_some() || cursor.is_some()
+}
+
+/// Encode an opaque cursor from some position value, such that clients do not come to depend on its format
+///
+pub (crate) fn encode_cursor_value(value: &str) -> String {
+    value.bytes()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Decode a cursor created by `encode_cursor_value` back into the position value it refers to
+///
+pub (crate) fn decode_cursor_value(cursor: &str) -> GraphHelperResult<String> {
+    if cursor.len() % 2 != 0 || !cursor.is_ascii() {
+        return Err(GraphHelperError::InvalidCursor);
+    }
+    let bytes: Result<Vec<u8>, _> = (0..cursor.len()).step_by(2)
+        .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16))
+        .collect();
+    bytes.ok()
+        .and_then(|b| String::from_utf8(b).ok())
+        .ok_or(GraphHelperError::InvalidCursor)
+}
+
+/// Encode the address of the last record in a page as a cursor to continue reading from
+///
+fn encode_cursor(address: &Address) -> String {
+    encode_cursor_value(&address.to_string())
+}
+
+/// Decode a cursor created by `encode_cursor` back into the address it refers to
+///
+fn decode_cursor(cursor: &str) -> Result<Address, GraphHelperError> {
+    decode_cursor_value(cursor).map(Address::from)
+}
+
+/// Slice a set of index addresses into the page of addresses requested by `page`.
+///
+/// Addresses are sorted prior to slicing, such that pages are stable regardless of the
+/// order in which links were returned from the DHT.
+///
+/// Cursors refer to the last address of the previous page. If the cursor cannot be decoded or
+/// located in the list (eg. because the referenced record has since been removed from the index),
+/// an error is returned.
+///
+pub (crate) fn paginate_addresses(
+    addresses: Vec<Address>,
+    page: &PageRequest,
+) -> GraphHelperResult<(Vec<Address>, Option<String>)> {
+    paginate_addresses_where(addresses, page, |_| Ok(true))
+}
+
+/// Same as `paginate_addresses`, except that addresses for which `include` returns `false` are skipped.
+///
+/// `include` is only called for the addresses following the cursor, until the page (plus one more
+/// address, to determine whether another page follows) has been filled.
+///
+pub (crate) fn paginate_addresses_where<F>(
+    mut addresses: Vec<Address>,
+    page: &PageRequest,
+    mut include: F,
+) -> GraphHelperResult<(Vec<Address>, Option<String>)>
+    where F: FnMut(&Address) -> GraphHelperResult<bool>,
+{
+    if page.limit == 0 {
+        return Err(GraphHelperError::InvalidPageSize);
+    }
+
+    addresses.sort();
+    addresses.dedup();
+
+    let start = match &page.cursor {
+        None => 0,
+        Some(cursor) => {
+            let cursor_address = decode_cursor(cursor)?;
+            match addresses.binary_search(&cursor_address) {
+                Ok(pos) => pos + 1,
+                Err(_) => return Err(GraphHelperError::InvalidCursor),
+            }
+        },
+    };
+
+    let mut page_addresses: Vec<Address> = vec![];
+    let mut has_more = false;
+    for address in addresses[start..].iter() {
+        if !include(address)? { continue; }
+        if page_addresses.len() == page.limit {
+            has_more = true;
+            break;
+        }
+        page_addresses.push(address.to_owned());
+    }
+
+    let next_cursor = if has_more {
+        page_addresses.last().map(encode_cursor)
+    } else {
+        None
+    };
+
+    Ok((page_addresses, next_cursor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addrs(ids: &[&str]) -> Vec<Address> {
+        ids.iter().map(|id| Address::from(id.to_string())).collect()
+    }
+
+    #[test]
+    fn test_first_page() {
+        let (page, cursor) = paginate_addresses(addrs(&["a", "b", "c"]), &PageRequest::new(Some(2), None)).unwrap();
+        assert_eq!(page, addrs(&["a", "b"]));
+        assert_eq!(cursor, Some(encode_cursor(&Address::from("b"))));
+    }
+
+    #[test]
+    fn test_continue_from_cursor() {
+        let (first_page, cursor) = paginate_addresses(addrs(&["a", "b", "c"]), &PageRequest::new(Some(2), None)).unwrap();
+        assert_eq!(first_page, addrs(&["a", "b"]));
+
+        let (page, cursor) = paginate_addresses(addrs(&["a", "b", "c"]), &PageRequest::new(Some(2), cursor)).unwrap();
+        assert_eq!(page, addrs(&["c"]));
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn test_pages_stable_across_link_order() {
+        let (first_page, cursor) = paginate_addresses(addrs(&["c", "a", "b"]), &PageRequest::new(Some(2), None)).unwrap();
+        assert_eq!(first_page, addrs(&["a", "b"]));
+
+        let (page, cursor) = paginate_addresses(addrs(&["b", "c", "a"]), &PageRequest::new(Some(2), cursor)).unwrap();
+        assert_eq!(page, addrs(&["c"]));
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn test_skipped_addresses_fill_page_lazily() {
+        let mut checked = vec![];
+        let (page, cursor) = paginate_addresses_where(addrs(&["a", "b", "c", "d", "e", "f"]), &PageRequest::new(Some(2), None), |address| {
+            checked.push(address.to_owned());
+            Ok(address != &Address::from("b"))
+        }).unwrap();
+        assert_eq!(page, addrs(&["a", "c"]));
+        assert_eq!(checked, addrs(&["a", "b", "c", "d"]), "only enough addresses to fill the page are checked");
+
+        let (page, cursor) = paginate_addresses_where(addrs(&["a", "b", "c", "d", "e", "f"]), &PageRequest::new(Some(2), cursor), |address| {
+            Ok(address != &Address::from("f"))
+        }).unwrap();
+        assert_eq!(page, addrs(&["d", "e"]));
+        assert_eq!(cursor, None, "no further included addresses");
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let address = Address::from("QmXyz123");
+        assert_ne!(encode_cursor(&address), "QmXyz123", "cursors are opaque");
+        assert_eq!(decode_cursor(&encode_cursor(&address)), Ok(address));
+        assert_eq!(decode_cursor("not a cursor"), Err(GraphHelperError::InvalidCursor));
+    }
+
+    #[test]
+    fn test_empty_page_rejected() {
+        assert_eq!(
+            paginate_addresses(addrs(&["a", "b"]), &PageRequest::new(Some(0), None)),
+            Err(GraphHelperError::InvalidPageSize),
+        );
+    }
+
+    #[test]
+    fn test_exact_final_page() {
+        let (page, cursor) = paginate_addresses(addrs(&["a", "b"]), &PageRequest::new(Some(2), None)).unwrap();
+        assert_eq!(page, addrs(&["a", "b"]));
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn test_unknown_cursor() {
+        let cursor = encode_cursor(&Address::from("z"));
+        assert!(paginate_addresses(addrs(&["a", "b"]), &PageRequest::new(Some(2), Some(cursor))).is_err());
+        assert!(paginate_addresses(addrs(&["a", "b"]), &PageRequest::new(Some(2), Some("b".to_string()))).is_err());
+    }
+}
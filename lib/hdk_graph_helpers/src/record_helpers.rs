@@ -16,44 +16,104 @@ use hdk::{
         entry::{
             entry_type::AppEntryType,
             AppEntryValue,
+            Entry::App as AppEntry,
         },
+        crud_status::CrudStatus,
+    },
+    holochain_wasm_utils::api_serialization::get_entry::{
+        GetEntryOptions,
+        GetEntryResultType,
+        StatusRequestKind,
     },
-    error::{ ZomeApiResult, ZomeApiError },
-    link_entries,
-    get_entry,
-    remove_entry,
 };
 
 use super::{
-    identifiers::{ RECORD_INITIAL_ENTRY_LINK_TAG, ERR_MSG_ENTRY_NOT_FOUND },
+    hdk_api::{
+        link_entries,
+        get_entry,
+        get_entry_result,
+        get_as_type,
+        entry_address,
+        remove_entry,
+        update_entry as hdk_update_entry,
+    },
+    identifiers::{ RECORD_INITIAL_ENTRY_LINK_TAG },
+    errors::{ GraphHelperError, GraphHelperResult },
     type_wrappers::Addressable,
-    record_interface::{ Updateable, UniquelyIdentifiable, UpdateableIdentifier },
+    record_interface::{ Updateable, UniquelyIdentifiable, UpdateableIdentifier, Revisioned },
     entries::{
         create_entry,
         try_decode_entry,
         update_entry,
+        update_entry_with,
         delete_entry,
     },
     keys::{
         create_key_index,
         get_key_index_address,
         get_key_index_address_as_type,
+        get_key_index_headers,
+        earliest_header,
+    },
+    links::get_linked_addresses,
+    tombstones::{
+        is_record_deleted,
+        write_tombstone,
+    },
+    idempotency::{
+        get_idempotent_record_address,
+        write_idempotency_key,
+        delete_idempotency_key,
     },
     anchors::{
-        create_anchor_index,
+        create_unique_anchor_index,
+        replace_anchor_index,
         get_anchor_index_entry_address,
         update_anchor_index,
         delete_anchor_index,
     },
 };
 
+/// A single revision of a record's entry data, as returned by `read_record_history`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordRevision<T> {
+    pub revision_id: Address,
+    pub author: Option<Address>,
+    pub timestamp: Option<String>,
+    pub deleted: bool,
+    #[serde(flatten)]
+    pub entry: T,
+}
+
+impl<T> RecordRevision<T> {
+    /// Convert the entry data of this revision to some other format (eg. an API response)
+    pub fn map<U, F>(self, f: F) -> RecordRevision<U>
+        where F: FnOnce(T) -> U,
+    {
+        RecordRevision {
+            revision_id: self.revision_id,
+            author: self.author,
+            timestamp: self.timestamp,
+            deleted: self.deleted,
+            entry: f(self.entry),
+        }
+    }
+}
+
 //--------------------------------[ READ ]--------------------------------------
 
 /// Read a record's entry data by its `key index` (static id).
 ///
+/// Records which have been soft-deleted are treated as missing.
+///
 pub fn read_record_entry<T: TryFrom<AppEntryValue>, A: AsRef<Address>>(
     address: &A,
-) -> ZomeApiResult<T> {
+) -> GraphHelperResult<T> {
+    if is_record_deleted(address.as_ref())? {
+        return Err(GraphHelperError::EntryNotFound);
+    }
+
     // read base entry to determine dereferenced entry address
     let data_address = get_key_index_address(address.as_ref());
 
@@ -66,13 +126,117 @@ pub fn read_record_entry<T: TryFrom<AppEntryValue>, A: AsRef<Address>>(
                 Ok(Some(entry)) => {
                     Ok(entry)
                 },
-                _ => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.to_string())),
+                _ => Err(GraphHelperError::EntryNotFound),
             }
         },
         Err(e) => Err(e),
     }
 }
 
+/// Read the entry data of several records by their `key indexes` (static ids).
+///
+/// Results are returned in the same order as `addresses`, with an error in place of
+/// any record which could not be read. As with `read_record_entry`, soft-deleted
+/// records are treated as missing.
+///
+pub fn read_record_entries<T: TryFrom<AppEntryValue>, A: AsRef<Address>>(
+    addresses: &[A],
+) -> Vec<GraphHelperResult<T>> {
+    addresses.iter()
+        .map(read_record_entry)
+        .collect()
+}
+
+/// Determine the revision ID of some record's entry data.
+///
+/// The revision ID is the address of the entry itself, and so changes whenever the record is updated.
+/// Clients can pass it back with their next update so that it is rejected if the record has been
+/// modified in the meantime.
+///
+/// @see update_record
+///
+pub fn get_revision_id<E, S>(
+    entry_type: S,
+    entry: &E,
+) -> GraphHelperResult<Address>
+    where E: Clone + Into<AppEntryValue>,
+        S: Into<AppEntryType>,
+{
+    Ok(entry_address(&AppEntry(entry_type.into(), entry.to_owned().into()))?)
+}
+
+/// Determine the time at which the record with the given `key index` was created, as a normalised
+/// UTC timestamp. This is read from the earliest header of its `key index`, and so is `None` for
+/// records which have been deleted outright.
+///
+pub fn get_record_created_time(address: &dyn AsRef<Address>) -> GraphHelperResult<Option<String>> {
+    let headers = get_key_index_headers(address.as_ref())?;
+    Ok(earliest_header(&headers).map(|(timestamp, _)| timestamp))
+}
+
+/// Read every revision of a record's entry data by its `key index` (static id), oldest first.
+///
+/// The entry update chain is followed from the entry referenced by the record's `initial_entry` link.
+/// The author and timestamp of each revision are read from the header of the entry which created it.
+/// If the record has been deleted (or soft-deleted), the final revision is flagged as such.
+///
+pub fn read_record_history<T, A>(
+    address: &A,
+    initial_entry_link_type: &str,
+) -> GraphHelperResult<Vec<RecordRevision<T>>>
+    where T: TryFrom<AppEntryValue>,
+        A: AsRef<Address>,
+{
+    // the initial entry link was not always written, fall back to the key index for older records
+    let initial_address = match get_linked_addresses(address.as_ref(), initial_entry_link_type, RECORD_INITIAL_ENTRY_LINK_TAG)?.pop() {
+        Some(addr) => addr,
+        None => get_key_index_address(address.as_ref())?,
+    };
+
+    let history = get_entry_result(&initial_address, GetEntryOptions {
+        status_request: StatusRequestKind::All,
+        entry: true,
+        headers: true,
+        ..GetEntryOptions::default()
+    })?;
+
+    let items = match history.result {
+        GetEntryResultType::All(history) => history.items,
+        GetEntryResultType::Single(item) => vec![item],
+    };
+
+    let mut revisions = items.into_iter()
+        .map(|item| {
+            let meta = item.meta.ok_or(GraphHelperError::EntryNotFound)?;
+            let entry = match try_decode_entry(Ok(item.entry))? {
+                Some(entry) => entry,
+                None => return Err(GraphHelperError::EntryNotFound),
+            };
+            let header = item.headers.first();
+
+            Ok(RecordRevision {
+                revision_id: meta.address,
+                author: header.and_then(|h| h.provenances().first().map(|p| p.source())),
+                timestamp: header.map(|h| h.timestamp().to_string()),
+                deleted: match meta.crud_status {
+                    CrudStatus::Deleted => true,
+                    _ => false,
+                },
+                entry,
+            })
+        })
+        .collect::<GraphHelperResult<Vec<RecordRevision<T>>>>()?;
+
+    // soft-deleted records have no deleted entry in the update chain
+    if is_record_deleted(address.as_ref())? {
+        if let Some(last) = revisions.last_mut() {
+            last.deleted = true;
+        }
+    }
+
+    Ok(revisions)
+}
+
 /// Reads an entry via its `anchor index`.
 ///
 /// Follows an anchor identified by `id_entry_type`, `id_link_type` and
@@ -85,7 +249,7 @@ pub fn read_anchored_record_entry<T, E>(
     id_entry_type: &E,
     id_link_type: &str,
     id_string: &String,
-) -> ZomeApiResult<T>
+) -> GraphHelperResult<T>
     where E: Into<AppEntryType> + Clone,
         T: TryFrom<AppEntryValue>,
 {
@@ -99,10 +263,10 @@ pub fn read_anchored_record_entry<T, E>(
                 Ok(Some(entry)) => {
                     Ok(entry)
                 },
-                _ => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.to_string())),
+                _ => Err(GraphHelperError::EntryNotFound),
             }
         },
-        None => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.to_string())),
+        None => Err(GraphHelperError::EntryNotFound),
     }
 }
 
@@ -116,25 +280,47 @@ pub fn read_anchored_record_entry<T, E>(
 /// It is recommended that you include a creation timestamp in newly created records, to avoid
 /// them conflicting with previously entered entries that may be of the same content.
 ///
+/// If an `idempotency_key` is provided and the current agent has already created a record
+/// of this type with the same key, nothing is written and the existing record is returned.
+/// If that record has since been soft-deleted, an `IdempotentRecordDeleted` error is returned.
+/// Callers which perform other writes after creating a record should check for this with
+/// `get_idempotent_record_address` beforehand.
+///
+/// @see hdk_graph_helpers::idempotency
+///
 pub fn create_record<E, C, A, S>(
     base_entry_type: S,
     entry_type: S,
     initial_entry_link_type: &str,
+    idempotency_key: Option<&String>,
     create_payload: C,
-) -> ZomeApiResult<(A, E)>
-    where E: Clone + Into<AppEntryValue>,
+) -> GraphHelperResult<(A, E)>
+    where E: Clone + TryFrom<AppEntryValue> + Into<AppEntryValue>,
         C: Into<E>,
         S: Into<AppEntryType>,
         A: From<Address>,
 {
+    let base_entry_type: AppEntryType = base_entry_type.into();
+    let key_scope = String::from(base_entry_type.to_owned());
+
+    // retried requests resolve to the record created by the original
+    if let Some(existing) = get_idempotent_record_address::<Address>(&key_scope, idempotency_key)? {
+        let entry = read_record_entry(&existing)?;
+        return Ok((A::from(existing), entry));
+    }
+
     // write underlying entry
     let (address, entry_resp) = create_entry(entry_type, create_payload)?;
 
     // create a key index pointer
-    let base_address = create_key_index(&(base_entry_type.into()), &address)?;
+    let base_address = create_key_index(&base_entry_type, &address)?;
     // :NOTE: link is just for inference by external tools, it's not actually needed to query
     link_entries(&base_address, &address, initial_entry_link_type, RECORD_INITIAL_ENTRY_LINK_TAG)?;
 
+    if let Some(key) = idempotency_key {
+        write_idempotency_key(&key_scope, key, &base_address)?;
+    }
+
     Ok((A::from(base_address), entry_resp))
 }
 
@@ -145,25 +331,64 @@ pub fn create_record<E, C, A, S>(
 /// It is recommended that you include a creation timestamp in newly created records, to avoid
 /// them conflicting with previously entered entries that may be of the same content.
 ///
+/// If a record already exists with the same `anchor index`, an `AnchorConflict` error is returned.
+/// Use `upsert_anchored_record` where overwriting the existing record is intended.
+///
 pub fn create_anchored_record<E, C, S>(
     base_entry_type: S,
     id_link_type: &str,
     entry_type: S,
     create_payload: C,
-) -> ZomeApiResult<(String, E)>
+) -> GraphHelperResult<(String, E)>
     where E: Clone + Into<AppEntryValue>,
         C: Into<E> + UniquelyIdentifiable,
         S: Into<AppEntryType>,
 {
     // determine unique anchor index key
-    // :TODO: deal with collisions
     let entry_id = create_payload.get_anchor_key();
+    let id_entry_type: AppEntryType = base_entry_type.into();
+
+    // check for collisions prior to writing anything
+    if get_anchor_index_entry_address(&id_entry_type, id_link_type, &entry_id)?.is_some() {
+        return Err(GraphHelperError::AnchorConflict(entry_id));
+    }
 
     // write underlying entry
     let (address, entry_resp) = create_entry(entry_type, create_payload)?;
 
     // write primary key index
-    let _ = create_anchor_index(&base_entry_type.into(), id_link_type, &entry_id, &address)?;
+    let _ = create_unique_anchor_index(&id_entry_type, id_link_type, &entry_id, &address)?;
+
+    Ok((entry_id, entry_resp))
+}
+
+/// Creates a new record in the DHT with a manually specified `anchor index`, as per
+/// `create_anchored_record`. If a record already exists with the same `anchor index`,
+/// it is deleted and the anchor is pointed at the new record instead.
+///
+/// This is also the means of resolving an `AnchorConflict` caused by agents creating
+/// records with the same ID concurrently, since all conflicting records are replaced.
+///
+pub fn upsert_anchored_record<E, C, S>(
+    base_entry_type: S,
+    id_link_type: &str,
+    entry_type: S,
+    create_payload: C,
+) -> GraphHelperResult<(String, E)>
+    where E: Clone + Into<AppEntryValue>,
+        C: Into<E> + UniquelyIdentifiable,
+        S: Into<AppEntryType>,
+{
+    let entry_id = create_payload.get_anchor_key();
+
+    // write underlying entry
+    let (address, entry_resp) = create_entry(entry_type, create_payload)?;
+
+    // point the anchor at the new entry and remove any it replaced
+    let (_anchor, replaced) = replace_anchor_index(&base_entry_type.into(), id_link_type, &entry_id, &address)?;
+    for old_address in replaced.iter() {
+        remove_entry(old_address)?;
+    }
 
     Ok((entry_id, entry_resp))
 }
@@ -175,26 +400,98 @@ pub fn create_anchored_record<E, C, S>(
 /// The way in which the input update payload is applied to the existing
 /// entry data is up to the implementor of `Updateable<U>` for the entry type.
 ///
+/// If a `revision_id` is provided, the update is only applied if it matches the
+/// current revision of the record. Otherwise a `RevisionConflict` error containing
+/// the current revision ID is returned, and the client should re-read the record
+/// before trying again.
+///
 /// @see hdk_graph_helpers::record_interface::Updateable
+/// @see get_revision_id
 ///
 pub fn update_record<E, U, A, S>(
     entry_type: S,
     address: &A,
+    revision_id: Option<&Address>,
     update_payload: &U,
-) -> ZomeApiResult<E>
+) -> GraphHelperResult<E>
     where E: Clone + TryFrom<AppEntryValue> + Into<AppEntryValue> + Updateable<U>,
         S: Into<AppEntryType> + Clone,
         A: AsRef<Address>,
 {
+    update_record_with(entry_type, address, revision_id, |entry: &E| Ok(entry.update_with(update_payload)))
+}
+
+/// Same as `update_record`, except that the new entry data is determined by `update`, which
+/// may reject the change- eg. where the update must first be checked against data held elsewhere.
+/// Nothing is written if it does, and its error is returned.
+///
+/// @see hdk_graph_helpers::entries::update_entry_with
+///
+pub fn update_record_with<E, A, S, F>(
+    entry_type: S,
+    address: &A,
+    revision_id: Option<&Address>,
+    update: F,
+) -> GraphHelperResult<E>
+    where E: Clone + TryFrom<AppEntryValue> + Into<AppEntryValue>,
+        S: Into<AppEntryType> + Clone,
+        A: AsRef<Address>,
+        F: FnOnce(&E) -> GraphHelperResult<E>,
+{
+    // soft-deleted records must be restored before they can be modified
+    if is_record_deleted(address.as_ref())? {
+        return Err(GraphHelperError::EntryNotFound);
+    }
+
     // read base entry to determine dereferenced entry address
     let data_address: Addressable = get_key_index_address_as_type(address.as_ref())?;
 
+    // reject updates made against outdated data
+    if let Some(expected_revision) = revision_id {
+        let current_entry: E = get_as_type(data_address.as_ref().to_owned())?;
+        let current_revision = get_revision_id(entry_type.clone(), &current_entry)?;
+        if current_revision != *expected_revision {
+            return Err(GraphHelperError::RevisionConflict(current_revision));
+        }
+    }
+
     // perform regular entry update using internal address
-    let (_addr, updated_entry): (Address, E) = update_entry(entry_type, &data_address, update_payload)?;
+    let (_addr, updated_entry): (Address, E) = update_entry_with(entry_type, &data_address, update)?;
 
     Ok(updated_entry)
 }
 
+/// Returns a record to the content of `previous_entry`, as read before an update which
+/// must now be undone. A new revision is written, earlier revisions are left intact.
+///
+/// The restored content is written with the next revision number of the record, so that
+/// it does not collide with the earlier revision it was read from.
+///
+/// Used to compensate for updates made by operations which subsequently failed.
+/// @see hdk_graph_helpers::saga
+///
+pub fn revert_record<E, A, S>(
+    entry_type: S,
+    address: &A,
+    previous_entry: E,
+) -> GraphHelperResult<()>
+    where E: Clone + Revisioned + TryFrom<AppEntryValue> + Into<AppEntryValue>,
+        S: Into<AppEntryType> + Clone,
+        A: AsRef<Address>,
+{
+    let data_address: Addressable = get_key_index_address_as_type(address.as_ref())?;
+    let current_entry: E = get_as_type(data_address.as_ref().to_owned())?;
+    let revision = current_entry.get_revision();
+    let current_address = entry_address(&AppEntry(entry_type.clone().into(), current_entry.into()))?;
+
+    // :IMPORTANT: only write if data has changed, as with `update_entry`
+    let unchanged = AppEntry(entry_type.clone().into(), previous_entry.with_revision(revision).into());
+    if entry_address(&unchanged)? != current_address {
+        hdk_update_entry(AppEntry(entry_type.into(), previous_entry.with_revision(revision + 1).into()), &current_address)?;
+    }
+    Ok(())
+}
+
 /// Updates a record via references to its `anchor index`.
 ///
 /// The `update_payload` must contain all data necessary to determine both the existing
@@ -207,7 +504,7 @@ pub fn update_anchored_record<E, U, S>(
     id_link_type: &str,
     entry_type: S,
     update_payload: &U,
-) -> ZomeApiResult<(String, E)>
+) -> GraphHelperResult<(String, E)>
     where E: Clone + TryFrom<AppEntryValue> + Into<AppEntryValue> + Updateable<U>,
         S: Into<AppEntryType> + Clone,
         U: UpdateableIdentifier,
@@ -237,7 +534,7 @@ pub fn update_anchored_record<E, U, S>(
             // return updated record to caller
             Ok((final_id, new_entry))
         },
-        None => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.into())),
+        None => Err(GraphHelperError::EntryNotFound),
     }
 }
 
@@ -246,7 +543,7 @@ pub fn update_anchored_record<E, U, S>(
 /// Removes a record of the given `key index` from the DHT by marking it as deleted.
 /// Links are not affected so as to retain a link to the referencing information, which may now need to be updated.
 ///
-pub fn delete_record<T>(address: &dyn AsRef<Address>) -> ZomeApiResult<bool>
+pub fn delete_record<T>(address: &dyn AsRef<Address>) -> GraphHelperResult<bool>
     where T: TryFrom<AppEntryValue>
 {
     // read base entry to determine dereferenced entry address
@@ -262,6 +559,55 @@ pub fn delete_record<T>(address: &dyn AsRef<Address>) -> ZomeApiResult<bool>
     }
 }
 
+/// Removes a record created by an operation which subsequently failed, releasing the
+/// `idempotency_key` it was created with so that a retried request can create it again.
+/// Any indexes written for the record must be removed separately, beforehand.
+///
+/// @see hdk_graph_helpers::saga
+///
+pub fn discard_created_record<T>(
+    base_entry_type: &str,
+    address: &dyn AsRef<Address>,
+    idempotency_key: Option<&String>,
+) -> GraphHelperResult<()>
+    where T: TryFrom<AppEntryValue>
+{
+    if let Some(key) = idempotency_key {
+        delete_idempotency_key(base_entry_type, key)?;
+    }
+    delete_record::<T>(address)?;
+    Ok(())
+}
+
+/// Marks a record of the given `key index` as deleted by way of a `tombstone`, leaving all of
+/// its entries and links in place so that it can later be recovered via `restore_record`.
+///
+/// Returns `false` if the record does not exist or was already deleted.
+///
+/// @see hdk_graph_helpers::tombstones
+///
+pub fn soft_delete_record<T>(address: &dyn AsRef<Address>) -> GraphHelperResult<bool>
+    where T: TryFrom<AppEntryValue>
+{
+    // check the record exists and is of the expected type before marking it
+    let data_address = match get_key_index_address(address.as_ref()) {
+        Ok(addr) => addr,
+        Err(_) => return Ok(false),
+    };
+    match try_decode_entry::<T>(get_entry(&data_address)) {
+        Ok(_) => write_tombstone(address.as_ref(), true),
+        Err(_) => Err(GraphHelperError::ValidationFailed("incorrect record type specified for deletion".to_string())),
+    }
+}
+
+/// Reinstates a record of the given `key index` which was removed with `soft_delete_record`.
+///
+/// Returns `false` if the record was not deleted.
+///
+pub fn restore_record(address: &dyn AsRef<Address>) -> GraphHelperResult<bool> {
+    write_tombstone(address.as_ref(), false)
+}
+
 /// Removes a record via references to its `anchor index`.
 ///
 /// The index as well as the record's entry data will both be deleted; any failures
@@ -271,7 +617,7 @@ pub fn delete_anchored_record<E>(
     id_entry_type: &str,
     id_link_type: &str,
     entry_id: &String,
-) -> ZomeApiResult<bool>
+) -> GraphHelperResult<bool>
     where E: TryFrom<AppEntryValue>,
 {
     // determine entry address
@@ -288,6 +634,285 @@ pub fn delete_anchored_record<E>(
 
             entry_result
         },
-        None => Err(ZomeApiError::Internal(ERR_MSG_ENTRY_NOT_FOUND.into())),
+        None => Err(GraphHelperError::EntryNotFound),
+    }
+}
+
+#[cfg(all(test, feature = "mock-hdk"))]
+mod tests {
+    use super::*;
+    use hdk::holochain_json_api::{ json::JsonString, error::JsonError };
+    use holochain_json_derive::{ DefaultJson };
+    use crate::tombstones::is_record_deleted;
+    use crate::anchors::create_anchor_index;
+
+    const BASE_ENTRY_TYPE: &str = "test_base";
+    const ENTRY_TYPE: &str = "test_entry";
+    const INITIAL_ENTRY_LINK_TYPE: &str = "test_initial_entry";
+    const ID_ENTRY_TYPE: &str = "test_id";
+
+    #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+    struct TestEntry {
+        id: String,
+        note: String,
+        revision: u32,
+    }
+
+    impl Updateable<TestEntry> for TestEntry {
+        fn update_with(&self, e: &TestEntry) -> TestEntry {
+            TestEntry { id: self.id.to_owned(), note: e.note.to_owned(), revision: self.revision + 1 }
+        }
+    }
+
+    impl Revisioned for TestEntry {
+        fn get_revision(&self) -> u32 { self.revision }
+        fn with_revision(&self, revision: u32) -> TestEntry { TestEntry { revision, ..self.to_owned() } }
+    }
+
+    impl UniquelyIdentifiable for TestEntry {
+        fn get_anchor_key(&self) -> String { self.id.to_owned() }
+    }
+
+    fn test_entry(id: &str, note: &str) -> TestEntry {
+        TestEntry { id: id.to_string(), note: note.to_string(), revision: 0 }
+    }
+
+    fn create_test_record(note: &str) -> Addressable {
+        let (address, _entry): (Addressable, TestEntry) = create_record(
+            BASE_ENTRY_TYPE, ENTRY_TYPE, INITIAL_ENTRY_LINK_TYPE, None, test_entry("", note),
+        ).unwrap();
+        address
+    }
+
+    fn create_test_record_with_key(note: &str, key: &str) -> Addressable {
+        let (address, _entry): (Addressable, TestEntry) = create_record(
+            BASE_ENTRY_TYPE, ENTRY_TYPE, INITIAL_ENTRY_LINK_TYPE, Some(&key.to_string()), test_entry("", note),
+        ).unwrap();
+        address
+    }
+
+    #[test]
+    fn test_create_idempotent() {
+        let address = create_test_record_with_key("original", "request-1");
+        let retried = create_test_record_with_key("retried", "request-1");
+
+        assert_eq!(retried, address, "retried request resolves to the original record");
+        let read: TestEntry = read_record_entry(&address).unwrap();
+        assert_eq!(read.note, "original");
+        assert_eq!(
+            get_idempotent_record_address::<Addressable>(BASE_ENTRY_TYPE, Some(&"request-1".to_string())),
+            Ok(Some(address.to_owned())),
+        );
+
+        assert_ne!(create_test_record_with_key("retried", "request-2"), address, "other keys create new records");
+    }
+
+    #[test]
+    fn test_create_idempotent_deleted_record() {
+        let address = create_test_record_with_key("original", "request-1");
+        assert_eq!(soft_delete_record::<TestEntry>(&address), Ok(true));
+
+        let retried: GraphHelperResult<(Addressable, TestEntry)> = create_record(
+            BASE_ENTRY_TYPE, ENTRY_TYPE, INITIAL_ENTRY_LINK_TYPE, Some(&"request-1".to_string()), test_entry("", "retried"),
+        );
+        assert_eq!(retried, Err(GraphHelperError::IdempotentRecordDeleted(address.as_ref().to_owned())));
+        assert!(is_record_deleted(address.as_ref()).unwrap(), "deleted record is not recreated");
+
+        assert_eq!(restore_record(&address), Ok(true));
+        assert_eq!(create_test_record_with_key("retried", "request-1"), address, "key resolves again once restored");
+    }
+
+    #[test]
+    fn test_create_idempotent_hard_deleted_record() {
+        let address = create_test_record_with_key("original", "request-1");
+        assert_eq!(delete_record::<TestEntry>(&address), Ok(true));
+
+        let retried: GraphHelperResult<(Addressable, TestEntry)> = create_record(
+            BASE_ENTRY_TYPE, ENTRY_TYPE, INITIAL_ENTRY_LINK_TYPE, Some(&"request-1".to_string()), test_entry("", "retried"),
+        );
+        assert_eq!(retried, Err(GraphHelperError::IdempotentRecordDeleted(address.as_ref().to_owned())));
+        assert!(read_record_entry::<TestEntry, _>(&address).is_err(), "deleted record is not recreated");
+    }
+
+    #[test]
+    fn test_idempotency_keys_scoped_to_agent() {
+        let address = create_test_record_with_key("alice's", "request-1");
+
+        crate::mock_hdk::set_agent_address(&Address::from("bob"));
+        let other_address = create_test_record_with_key("bob's", "request-1");
+
+        assert_ne!(other_address, address);
+        let read: TestEntry = read_record_entry(&other_address).unwrap();
+        assert_eq!(read.note, "bob's");
+    }
+
+    #[test]
+    fn test_record_created_time() {
+        let first = create_test_record("first");
+        let second = create_test_record("second");
+        let first_created = get_record_created_time(&first).unwrap().unwrap();
+        let second_created = get_record_created_time(&second).unwrap().unwrap();
+        assert!(first_created < second_created);
+
+        let _updated: TestEntry = update_record(ENTRY_TYPE, &first, None, &test_entry("", "updated")).unwrap();
+        assert_eq!(get_record_created_time(&first), Ok(Some(first_created)), "creation time is unaffected by updates");
+
+        assert_eq!(get_record_created_time(&Addressable::from(Address::from("missing"))), Ok(None));
+    }
+
+    #[test]
+    fn test_record_lifecycle() {
+        let address = create_test_record("created");
+        let read: TestEntry = read_record_entry(&address).unwrap();
+        assert_eq!(read.note, "created");
+
+        let updated: TestEntry = update_record(ENTRY_TYPE, &address, None, &test_entry("", "updated")).unwrap();
+        assert_eq!(updated.note, "updated");
+        let read: TestEntry = read_record_entry(&address).unwrap();
+        assert_eq!(read.note, "updated", "key index address is stable across updates");
+
+        assert_eq!(delete_record::<TestEntry>(&address), Ok(true));
+        assert!(read_record_entry::<TestEntry, _>(&address).is_err());
+        assert_eq!(delete_record::<TestEntry>(&address), Ok(false), "deleting twice is a no-op");
+    }
+
+    #[test]
+    fn test_revert_to_earlier_revision() {
+        let address = create_test_record("before");
+        let before: TestEntry = read_record_entry(&address).unwrap();
+        let _: TestEntry = update_record(ENTRY_TYPE, &address, None, &test_entry("", "after")).unwrap();
+
+        revert_record(ENTRY_TYPE, &address, before.to_owned()).unwrap();
+        let read: TestEntry = read_record_entry(&address).unwrap();
+        assert_eq!(read.note, before.note);
+        assert_eq!(read.revision, 2, "restored content is written as a new revision");
+        assert_eq!(read_record_history::<TestEntry, _>(&address, INITIAL_ENTRY_LINK_TYPE).unwrap().len(), 3, "earlier revisions are left intact");
+
+        // record remains updateable after returning to an earlier revision
+        let updated: TestEntry = update_record(ENTRY_TYPE, &address, None, &test_entry("", "again")).unwrap();
+        assert_eq!(updated.note, "again");
+        revert_record(ENTRY_TYPE, &address, updated.to_owned()).unwrap();
+        let read: TestEntry = read_record_entry(&address).unwrap();
+        assert_eq!(read.note, "again", "reverting to the current revision is a no-op");
+    }
+
+    #[test]
+    fn test_rejected_update_not_written() {
+        let address = create_test_record("before");
+
+        let result: GraphHelperResult<TestEntry> = update_record_with(ENTRY_TYPE, &address, None, |_entry: &TestEntry| {
+            Err(GraphHelperError::ValidationFailed("rejected".to_string()))
+        });
+        assert_eq!(result, Err(GraphHelperError::ValidationFailed("rejected".to_string())));
+
+        let read: TestEntry = read_record_entry(&address).unwrap();
+        assert_eq!(read.note, "before");
+        assert_eq!(read_record_history::<TestEntry, _>(&address, INITIAL_ENTRY_LINK_TYPE).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_discarded_record_releases_idempotency_key() {
+        let address = create_test_record_with_key("failed", "request-1");
+        discard_created_record::<TestEntry>(BASE_ENTRY_TYPE, &address, Some(&"request-1".to_string())).unwrap();
+
+        assert!(read_record_entry::<TestEntry, _>(&address).is_err());
+        let retried = create_test_record_with_key("retried", "request-1");
+        let read: TestEntry = read_record_entry(&retried).unwrap();
+        assert_eq!(read.note, "retried", "retried request creates the record afresh");
+    }
+
+    #[test]
+    fn test_update_revision_conflict() {
+        let address = create_test_record("v1");
+        let v1: TestEntry = read_record_entry(&address).unwrap();
+        let v1_revision = get_revision_id(ENTRY_TYPE, &v1).unwrap();
+
+        let v2: TestEntry = update_record(ENTRY_TYPE, &address, Some(&v1_revision), &test_entry("", "v2")).unwrap();
+        let v2_revision = get_revision_id(ENTRY_TYPE, &v2).unwrap();
+        assert_ne!(v1_revision, v2_revision);
+
+        // a second writer still holding the first revision is rejected
+        let stale: GraphHelperResult<TestEntry> = update_record(ENTRY_TYPE, &address, Some(&v1_revision), &test_entry("", "stale"));
+        assert_eq!(stale, Err(GraphHelperError::RevisionConflict(v2_revision.to_owned())));
+        let read: TestEntry = read_record_entry(&address).unwrap();
+        assert_eq!(read.note, "v2");
+
+        let _: TestEntry = update_record(ENTRY_TYPE, &address, Some(&v2_revision), &test_entry("", "v3")).unwrap();
+    }
+
+    #[test]
+    fn test_read_record_entries() {
+        let first = create_test_record("first");
+        let deleted = create_test_record("deleted");
+        let second = create_test_record("second");
+        assert_eq!(soft_delete_record::<TestEntry>(&deleted), Ok(true));
+
+        let read: Vec<GraphHelperResult<TestEntry>> = read_record_entries(&[second.clone(), deleted, first.clone(), second]);
+        let notes: Vec<Option<String>> = read.into_iter().map(|r| r.ok().map(|e| e.note)).collect();
+        assert_eq!(notes, vec![Some("second".to_string()), None, Some("first".to_string()), Some("second".to_string())]);
+    }
+
+    #[test]
+    fn test_record_history() {
+        let address = create_test_record("v1");
+        let _: TestEntry = update_record(ENTRY_TYPE, &address, None, &test_entry("", "v2")).unwrap();
+
+        let history: Vec<RecordRevision<TestEntry>> = read_record_history(&address, INITIAL_ENTRY_LINK_TYPE).unwrap();
+        let notes: Vec<String> = history.iter().map(|r| r.entry.note.to_owned()).collect();
+        assert_eq!(notes, vec!["v1", "v2"]);
+        assert!(history.iter().all(|r| !r.deleted));
+    }
+
+    #[test]
+    fn test_soft_delete_and_restore() {
+        let address = create_test_record("soft");
+
+        assert_eq!(soft_delete_record::<TestEntry>(&address), Ok(true));
+        assert!(is_record_deleted(address.as_ref()).unwrap());
+        assert!(read_record_entry::<TestEntry, _>(&address).is_err());
+        assert!(update_record::<TestEntry, _, _, _>(ENTRY_TYPE, &address, None, &test_entry("", "nope")).is_err());
+        assert_eq!(soft_delete_record::<TestEntry>(&address), Ok(false));
+
+        assert_eq!(restore_record(&address), Ok(true));
+        let read: TestEntry = read_record_entry(&address).unwrap();
+        assert_eq!(read.note, "soft");
+        assert_eq!(restore_record(&address), Ok(false));
+
+        // tombstones can be reused for subsequent deletions
+        assert_eq!(soft_delete_record::<TestEntry>(&address), Ok(true));
+        assert!(read_record_entry::<TestEntry, _>(&address).is_err());
+    }
+
+    #[test]
+    fn test_anchored_record_collision() {
+        let (id, _): (String, TestEntry) = create_anchored_record(ID_ENTRY_TYPE, INITIAL_ENTRY_LINK_TYPE, ENTRY_TYPE, test_entry("kg", "first")).unwrap();
+        assert_eq!(id, "kg");
+
+        let conflict: GraphHelperResult<(String, TestEntry)> = create_anchored_record(ID_ENTRY_TYPE, INITIAL_ENTRY_LINK_TYPE, ENTRY_TYPE, test_entry("kg", "second"));
+        assert_eq!(conflict, Err(GraphHelperError::AnchorConflict("kg".to_string())));
+
+        let read: TestEntry = read_anchored_record_entry(&ID_ENTRY_TYPE.to_string(), INITIAL_ENTRY_LINK_TYPE, &id).unwrap();
+        assert_eq!(read.note, "first");
+
+        let _: (String, TestEntry) = upsert_anchored_record(ID_ENTRY_TYPE, INITIAL_ENTRY_LINK_TYPE, ENTRY_TYPE, test_entry("kg", "replaced")).unwrap();
+        let read: TestEntry = read_anchored_record_entry(&ID_ENTRY_TYPE.to_string(), INITIAL_ENTRY_LINK_TYPE, &id).unwrap();
+        assert_eq!(read.note, "replaced");
+    }
+
+    #[test]
+    fn test_concurrent_anchor_collision_resolved_by_upsert() {
+        let (id, _): (String, TestEntry) = create_anchored_record(ID_ENTRY_TYPE, INITIAL_ENTRY_LINK_TYPE, ENTRY_TYPE, test_entry("m", "mine")).unwrap();
+
+        // another agent's write, which could not see the first when checking for conflicts
+        let (theirs, _): (Address, TestEntry) = create_entry(ENTRY_TYPE, test_entry("m", "theirs")).unwrap();
+        let id_entry_type: AppEntryType = ID_ENTRY_TYPE.into();
+        create_anchor_index(&id_entry_type, INITIAL_ENTRY_LINK_TYPE, &id, &theirs).unwrap();
+
+        let conflicted: GraphHelperResult<TestEntry> = read_anchored_record_entry(&ID_ENTRY_TYPE.to_string(), INITIAL_ENTRY_LINK_TYPE, &id);
+        assert_eq!(conflicted, Err(GraphHelperError::AnchorConflict("m".to_string())));
+
+        let _: (String, TestEntry) = upsert_anchored_record(ID_ENTRY_TYPE, INITIAL_ENTRY_LINK_TYPE, ENTRY_TYPE, test_entry("m", "resolved")).unwrap();
+        let read: TestEntry = read_anchored_record_entry(&ID_ENTRY_TYPE.to_string(), INITIAL_ENTRY_LINK_TYPE, &id).unwrap();
+        assert_eq!(read.note, "resolved");
     }
 }
@@ -8,6 +8,10 @@
  * @since   2019-07-02
  */
 
+/// Derives `Updateable<UpdateRequest>` for entry structs with consistent null / undefined
+/// handling. See the `hdk_graph_helpers_derive` crate for available field attributes.
+pub use hdk_graph_helpers_derive::Updateable;
+
 /// Interface for Holochain entry structs that can be updated via some predefined logic.
 ///
 /// Defines a structured mechanism for generating new entries from previous entry data
@@ -23,6 +27,19 @@ pub trait Updateable<T> {
     fn update_with(&self, e: &T) -> Self;
 }
 
+/// Interface for entry structs which count their revisions, so that each revision is
+/// distinct from every other- even where a record is returned to some earlier state.
+///
+/// The DHT cannot distinguish entries with identical content, and so would otherwise
+/// treat such an update as re-publishing the earlier revision.
+///
+/// @see hdk_graph_helpers::record_helpers::revert_record
+///
+pub trait Revisioned {
+    fn get_revision(&self) -> u32;
+    fn with_revision(&self, revision: u32) -> Self;
+}
+
 /// Interface for obtaining identity information from any data type.
 /// Most commonly used for "anchored records" which are retrieved from
 /// unique well-known "anchor" entries.
@@ -42,3 +59,81 @@ pub trait UniquelyIdentifiable {
 pub trait UpdateableIdentifier: UniquelyIdentifiable {
     fn get_new_anchor_key(&self) -> Option<String>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MaybeUndefined;
+
+    #[derive(Debug, Clone, PartialEq, Updateable)]
+    #[updateable(request = "TestUpdate")]
+    struct TestEntry {
+        name: String,
+        note: Option<String>,
+        #[updateable(merge_set)]
+        tags: Option<Vec<String>>,
+        #[updateable(immutable)]
+        created: Option<String>,
+    }
+
+    #[derive(Default)]
+    struct TestUpdate {
+        name: MaybeUndefined<String>,
+        note: MaybeUndefined<String>,
+        tags: MaybeUndefined<Vec<String>>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Updateable)]
+    #[updateable(request = "TestUpdate")]
+    struct TestRevisionedEntry {
+        name: String,
+        #[updateable(revision)]
+        revision: u32,
+    }
+
+    fn entry() -> TestEntry {
+        TestEntry {
+            name: "thing".to_string(),
+            note: Some("a note".to_string()),
+            tags: Some(vec!["a".to_string()]),
+            created: Some("2020-06-13".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_undefined_fields_untouched() {
+        assert_eq!(entry().update_with(&TestUpdate::default()), entry());
+    }
+
+    #[test]
+    fn test_null_fields_erased() {
+        let updated = entry().update_with(&TestUpdate {
+            name: MaybeUndefined::None,
+            note: MaybeUndefined::None,
+            tags: MaybeUndefined::None,
+        });
+        // required fields cannot be erased
+        assert_eq!(updated, TestEntry { note: None, tags: None, ..entry() });
+    }
+
+    #[test]
+    fn test_values_merged() {
+        let updated = entry().update_with(&TestUpdate {
+            name: MaybeUndefined::Some("other".to_string()),
+            note: MaybeUndefined::Some("new note".to_string()),
+            tags: MaybeUndefined::Some(vec!["b".to_string()]),
+        });
+        assert_eq!(updated, TestEntry {
+            name: "other".to_string(),
+            note: Some("new note".to_string()),
+            tags: Some(vec!["a".to_string(), "b".to_string()]),
+            created: Some("2020-06-13".to_string()),
+        });
+    }
+
+    #[test]
+    fn test_revision_incremented() {
+        let entry = TestRevisionedEntry { name: "thing".to_string(), revision: 2 };
+        assert_eq!(entry.update_with(&TestUpdate::default()), TestRevisionedEntry { revision: 3, ..entry.to_owned() });
+    }
+}
@@ -0,0 +1,336 @@
+/**
+ * Declarative schema macro for defining records.
+ *
+ * Most record types are assembled from the same pieces: storage identifiers for the
+ * entry, `base entry` and `initial entry` link; entry definitions for each; link
+ * constants and a `BidirectionalIndex` for each field stored as an `index`; a method
+ * for loading those fields and a method for assembling the final API response.
+ * `record_schema!` generates all of these from a single declaration:
+ *
+ * ```ignore
+ * record_schema! {
+ *     record: Entry,
+ *     address: ProposalAddress,
+ *     description: "Published requests or offers, sometimes with what is expected in return.",
+ *     entry_type: PROPOSAL_ENTRY_TYPE = "vf_proposal",
+ *     base_entry_type: PROPOSAL_BASE_ENTRY_TYPE = "vf_proposal_baseurl",
+ *     initial_entry_link_type: PROPOSAL_INITIAL_ENTRY_LINK_TYPE = "vf_proposal_entry",
+ *     response: ResponseData { proposal: Response },
+ *     fields: [name, has_beginning, has_end, note],
+ *     local_links: [
+ *         publishes as "publishes": ProposedIntentAddress => PROPOSED_INTENT_BASE_ENTRY_TYPE,
+ *             link_type: PROPOSAL_PUBLISHES_LINK_TYPE = "vf_proposal_publishes",
+ *             link_tag: PROPOSAL_PUBLISHES_LINK_TAG = "publishes",
+ *             index: PROPOSAL_PUBLISHES_INDEX,
+ *             reverse_link_type: PROPOSED_INTENT_PUBLISHED_IN_LINK_TYPE,
+ *             reverse_link_tag: PROPOSED_INTENT_PUBLISHED_IN_LINK_TAG;
+ *     ],
+ *     remote_links: [],
+ *     validate: Entry::validate,
+ * }
+ * ```
+ *
+ * This expands to:
+ *
+ * - `pub const`s for each storage identifier given a value. Identifiers given without
+ *   a value must already be in scope, which allows them to be declared in a separate
+ *   `storage_consts` crate where other records' indexes refer to them.
+ * - a `BidirectionalIndex` named by `index` for each link field, from this record's
+ *   `base entry` to the `reverse_link_type` and `reverse_link_tag` of the target record
+ * - `entry_def()` and `base_entry_def()`, for registering with the zome. The entry
+ *   is validated with `validate` (a `fn(&Entry) -> Result<(), String>`) on create
+ *   and update, if provided. The base entry defines the `initial entry` link and one
+ *   link per declared link field.
+ * - `get_link_fields(&address, &fields)`, which loads the link fields (local links first,
+ *   then remote links, in declaration order) as a tuple. Fields whose response name (as
+ *   given after `as`) is not in the `FieldSelection` are returned as `None` without being read.
+ * - `construct_response(&address, &entry, link_fields)`, which copies each of the
+ *   listed `fields` from the entry into the response along with the link fields and
+ *   the `revision_id` of the entry
+ *
+ * Local links reference records in the same DNA, and are read directly. Remote links
+ * reference records in other DNAs via a local `base entry` holding the foreign
+ * address (as created by `remote_indexes::create_direct_remote_index`), and are
+ * dereferenced when read.
+ *
+ * Response fields which are neither copied from the entry nor loaded from links
+ * are not supported; records needing them should implement `construct_response` by hand.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-06-14
+ */
+
+#[macro_export]
+macro_rules! record_schema {
+    (
+        record: $entry:ty,
+        address: $address:ty,
+        description: $description:expr,
+        entry_type: $entry_type_const:ident $( = $entry_type:literal )?,
+        base_entry_type: $base_entry_type_const:ident $( = $base_entry_type:literal )?,
+        initial_entry_link_type: $initial_link_type_const:ident $( = $initial_link_type:literal )?,
+        response: $response_data:ident { $response_field:ident: $response:ident },
+        fields: [ $( $field:ident ),* $(,)? ],
+        local_links: [ $(
+            $local_field:ident as $local_name:literal: $local_type:ty => $local_target:expr,
+                link_type: $local_link_type_const:ident $( = $local_link_type:literal )?,
+                link_tag: $local_link_tag_const:ident $( = $local_link_tag:literal )?,
+                index: $local_index:ident,
+                reverse_link_type: $local_reverse_link_type:expr,
+                reverse_link_tag: $local_reverse_link_tag:expr;
+        )* ],
+        remote_links: [ $(
+            $remote_field:ident as $remote_name:literal: $remote_type:ty => $remote_target:expr,
+                link_type: $remote_link_type_const:ident $( = $remote_link_type:literal )?,
+                link_tag: $remote_link_tag_const:ident $( = $remote_link_tag:literal )?,
+                index: $remote_index:ident,
+                reverse_link_type: $remote_reverse_link_type:expr,
+                reverse_link_tag: $remote_reverse_link_tag:expr;
+        )* ],
+        $( validate: $validator:path, )?
+    ) => {
+        $( pub const $entry_type_const: &str = $entry_type; )?
+        $( pub const $base_entry_type_const: &str = $base_entry_type; )?
+        $( pub const $initial_link_type_const: &str = $initial_link_type; )?
+        $(
+            $( pub const $local_link_type_const: &str = $local_link_type; )?
+            $( pub const $local_link_tag_const: &str = $local_link_tag; )?
+            pub const $local_index: $crate::BidirectionalIndex = $crate::BidirectionalIndex {
+                forward: $crate::LinkSpec { base_entry_type: $base_entry_type_const, link_type: $local_link_type_const, link_tag: $local_link_tag_const },
+                reverse: $crate::LinkSpec { base_entry_type: $local_target, link_type: $local_reverse_link_type, link_tag: $local_reverse_link_tag },
+            };
+        )*
+        $(
+            $( pub const $remote_link_type_const: &str = $remote_link_type; )?
+            $( pub const $remote_link_tag_const: &str = $remote_link_tag; )?
+            pub const $remote_index: $crate::BidirectionalIndex = $crate::BidirectionalIndex {
+                forward: $crate::LinkSpec { base_entry_type: $base_entry_type_const, link_type: $remote_link_type_const, link_tag: $remote_link_tag_const },
+                reverse: $crate::LinkSpec { base_entry_type: $remote_target, link_type: $remote_reverse_link_type, link_tag: $remote_reverse_link_tag },
+            };
+        )*
+
+        pub fn entry_def() -> ::hdk::entry_definition::ValidatingEntryType {
+            ::hdk::entry!(
+                name: $entry_type_const,
+                description: $description,
+                sharing: ::hdk::holochain_core_types::dna::entry_types::Sharing::Public,
+                validation_package: || {
+                    ::hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |validation_data: hdk::EntryValidationData<$entry>| {
+                    let _ = &validation_data;
+                    $(
+                        match validation_data {
+                            ::hdk::EntryValidationData::Create { entry, .. } => return $validator(&entry),
+                            ::hdk::EntryValidationData::Modify { new_entry, .. } => return $validator(&new_entry),
+                            _ => (),
+                        }
+                    )?
+                    Ok(())
+                }
+            )
+        }
+
+        pub fn base_entry_def() -> ::hdk::entry_definition::ValidatingEntryType {
+            ::hdk::entry!(
+                name: $base_entry_type_const,
+                description: concat!("Base anchor for initial ", stringify!($response_field), " addresses to provide lookup functionality"),
+                sharing: ::hdk::holochain_core_types::dna::entry_types::Sharing::Public,
+                validation_package: || {
+                    ::hdk::ValidationPackageDefinition::Entry
+                },
+                validation: |_validation_data: hdk::EntryValidationData<::hdk::holochain_persistence_api::cas::content::Address>| {
+                    Ok(())
+                },
+                links: [
+                    $crate::record_schema!(@link $entry_type_const, $initial_link_type_const)
+                    $( , $crate::record_schema!(@link $local_target, $local_link_type_const) )*
+                    $( , $crate::record_schema!(@link $remote_target, $remote_link_type_const) )*
+                ]
+            )
+        }
+
+        /// Load the `index` fields of the record included in `fields`, for passing to `construct_response`
+        pub fn get_link_fields<'a>(address: &$address, fields: &$crate::field_selection::FieldSelection) -> ::hdk::error::ZomeApiResult<(
+            $( Option<::std::borrow::Cow<'a, Vec<$local_type>>>, )*
+            $( Option<::std::borrow::Cow<'a, Vec<$remote_type>>>, )*
+        )> {
+            let _ = (address, fields);
+            Ok((
+                $( if fields.includes($local_name) {
+                    Some($crate::links::get_linked_addresses_as_type(address, $local_link_type_const, $local_link_tag_const)?)
+                } else { None }, )*
+                $( if fields.includes($remote_name) {
+                    Some($crate::links::get_linked_addresses_with_foreign_key_as_type(address, $remote_link_type_const, $remote_link_tag_const)?)
+                } else { None }, )*
+            ))
+        }
+        /// Create response from input DHT primitives
+        pub fn construct_response<'a>(
+            address: &$address,
+            e: &$entry,
+            ( $( $local_field, )* $( $remote_field, )* ): (
+                $( Option<::std::borrow::Cow<'a, Vec<$local_type>>>, )*
+                $( Option<::std::borrow::Cow<'a, Vec<$remote_type>>>, )*
+            ),
+        ) -> ::hdk::error::ZomeApiResult<$response_data> {
+            Ok($response_data {
+                $response_field: $response {
+                    id: address.to_owned(),
+                    revision_id: $crate::records::get_revision_id($entry_type_const, e)?.into(),
+                    $( $field: e.$field.to_owned(), )*
+                    $( $local_field: $local_field.map(::std::borrow::Cow::into_owned), )*
+                    $( $remote_field: $remote_field.map(::std::borrow::Cow::into_owned), )*
+                },
+            })
+        }
+    };
+
+    // link definition from the base entry, with no additional validation
+    (@link $target:expr, $link_type:expr) => {
+        ::hdk::to!(
+            $target,
+            link_type: $link_type,
+            validation_package: || {
+                ::hdk::ValidationPackageDefinition::Entry
+            },
+            validation: |_validation_data: hdk::LinkValidationData| {
+                Ok(())
+            }
+        )
+    };
+}
+
+#[cfg(all(test, feature = "mock-hdk"))]
+mod tests {
+    use hdk::{
+        holochain_json_api::{ json::JsonString, error::JsonError },
+        holochain_persistence_api::cas::content::Address,
+    };
+    use holochain_json_derive::{ DefaultJson };
+    use crate::{
+        LinkSpec,
+        field_selection::FieldSelection,
+        type_wrappers::Addressable,
+        hdk_api::link_entries,
+        keys::create_key_index,
+        records::{ create_record, get_revision_id },
+    };
+
+    const OTHER_BASE_ENTRY_TYPE: &str = "other_baseurl";
+    const OTHER_THING_LINK_TYPE: &str = "other_thing";
+
+    #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+    pub struct Entry {
+        name: String,
+        note: Option<String>,
+    }
+
+    impl Entry {
+        fn validate(&self) -> Result<(), String> {
+            if self.name.is_empty() { return Err("name is required".into()); }
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct Response {
+        id: Addressable,
+        revision_id: Address,
+        name: String,
+        note: Option<String>,
+        related: Option<Vec<Addressable>>,
+        remote: Option<Vec<Addressable>>,
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct ResponseData {
+        thing: Response,
+    }
+
+    record_schema! {
+        record: Entry,
+        address: Addressable,
+        description: "A test record",
+        entry_type: THING_ENTRY_TYPE = "test_thing",
+        base_entry_type: THING_BASE_ENTRY_TYPE = "test_thing_baseurl",
+        initial_entry_link_type: THING_INITIAL_ENTRY_LINK_TYPE = "test_thing_entry",
+        response: ResponseData { thing: Response },
+        fields: [name, note],
+        local_links: [
+            related as "related": Addressable => THING_BASE_ENTRY_TYPE,
+                link_type: THING_RELATED_LINK_TYPE = "test_thing_related",
+                link_tag: THING_RELATED_LINK_TAG = "related",
+                index: THING_RELATED_INDEX,
+                reverse_link_type: "test_thing_related_by",
+                reverse_link_tag: "related_by";
+        ],
+        remote_links: [
+            remote as "remoteThings": Addressable => OTHER_BASE_ENTRY_TYPE,
+                link_type: THING_REMOTE_LINK_TYPE = "test_thing_remote",
+                link_tag: THING_REMOTE_LINK_TAG = "remote",
+                index: THING_REMOTE_INDEX,
+                reverse_link_type: OTHER_THING_LINK_TYPE,
+                reverse_link_tag: "thing";
+        ],
+        validate: Entry::validate,
+    }
+
+    fn create_thing(name: &str) -> (Addressable, Entry) {
+        create_record(THING_BASE_ENTRY_TYPE, THING_ENTRY_TYPE, THING_INITIAL_ENTRY_LINK_TYPE, None, Entry {
+            name: name.to_string(),
+            note: None,
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_entry_defs() {
+        assert_eq!(entry_def().links.len(), 0);
+        // initial entry link, plus one per link field
+        assert_eq!(base_entry_def().links.len(), 3);
+    }
+
+    #[test]
+    fn test_indexes_declared() {
+        assert_eq!(THING_RELATED_INDEX.forward, LinkSpec { base_entry_type: THING_BASE_ENTRY_TYPE, link_type: "test_thing_related", link_tag: "related" });
+        assert_eq!(THING_RELATED_INDEX.reverse, LinkSpec { base_entry_type: THING_BASE_ENTRY_TYPE, link_type: "test_thing_related_by", link_tag: "related_by" });
+        assert_eq!(THING_REMOTE_INDEX.reverse, LinkSpec { base_entry_type: OTHER_BASE_ENTRY_TYPE, link_type: OTHER_THING_LINK_TYPE, link_tag: "thing" });
+    }
+
+    #[test]
+    fn test_response_assembled() {
+        let (thing, entry) = create_thing("thing");
+        let (related, _) = create_thing("related");
+        link_entries(thing.as_ref(), related.as_ref(), THING_RELATED_LINK_TYPE, THING_RELATED_LINK_TAG).unwrap();
+
+        // remote records are referenced via a local base entry holding the foreign address
+        let foreign = Address::from("foreign_record");
+        let foreign_base = create_key_index(&OTHER_BASE_ENTRY_TYPE.into(), &foreign).unwrap();
+        link_entries(thing.as_ref(), &foreign_base, THING_REMOTE_LINK_TYPE, THING_REMOTE_LINK_TAG).unwrap();
+
+        let response = construct_response(&thing, &entry, get_link_fields(&thing, &FieldSelection::all()).unwrap()).unwrap();
+
+        assert_eq!(response, ResponseData { thing: Response {
+            id: thing.to_owned(),
+            revision_id: get_revision_id(THING_ENTRY_TYPE, &entry).unwrap(),
+            name: "thing".to_string(),
+            note: None,
+            related: Some(vec![related]),
+            remote: Some(vec![Addressable::from(foreign)]),
+        } });
+    }
+
+    #[test]
+    fn test_unselected_links_not_loaded() {
+        let (thing, entry) = create_thing("thing");
+        let (related, _) = create_thing("related");
+        link_entries(thing.as_ref(), related.as_ref(), THING_RELATED_LINK_TYPE, THING_RELATED_LINK_TAG).unwrap();
+
+        let fields = get_link_fields(&thing, &FieldSelection::only(&["remoteThings"])).unwrap();
+        let response = construct_response(&thing, &entry, fields).unwrap();
+
+        assert_eq!(response.thing.related, None);
+        assert_eq!(response.thing.remote, Some(vec![]));
+    }
+}
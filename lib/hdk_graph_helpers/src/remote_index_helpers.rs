@@ -17,12 +17,13 @@ use hdk::{
     holochain_core_types::{
         entry::entry_type::AppEntryType,
     },
-    error::{ ZomeApiResult, ZomeApiError },
+    error::{ ZomeApiError, ZomeApiResult },
 };
 use holochain_json_derive::{ DefaultJson };
 
 use super::{
     MaybeUndefined,
+    link_specs::BidirectionalIndex,
     links::{
         get_linked_addresses_as_type,
     },
@@ -33,6 +34,7 @@ use super::{
     },
     local_indexes::{
         create_direct_index,
+        delete_direct_index,
     },
     internals::{
         wipe_links_from_origin,
@@ -41,9 +43,9 @@ use super::{
     },
     rpc::{
         read_from_zome,
-        build_zome_req_error,
     },
-    identifiers::{ ERR_MSG_REMOTE_INDEXING_ERR },
+    errors::{ GraphHelperError, GraphHelperResult, throw_any_error, describe_zome_api_error },
+    remote_sync::{ queue_remote_index_sync, discard_pending_remote_index_syncs },
 };
 
 // Common request format (zome trait) for linking remote entries in cooperating DNAs
@@ -67,30 +69,29 @@ pub struct RemoteEntryLinkResponse {
 /// fetching the referenced remote IDs; the destination DNA will have a
 /// `destination query index` created for querying the referenced records in full.
 ///
+/// `index` links the local `source_base_address` to the remote records; its `reverse`
+/// side's base entry type is used for the local `key indexes` of the remote records.
+///
+/// If the destination DNA cannot be reached, the request to build its index is queued
+/// for retry in the `remote_sync` outbox. `remote_dna_id` is stored as given, and resolved
+/// against the DNA's bridge configuration whenever the request is sent.
+///
 pub fn create_direct_remote_index(
     remote_dna_id: &str,
     remote_zome_id: &str,
     remote_zome_method: &str,
     remote_request_cap_token: Address,
-    remote_base_entry_type: &str,
-    origin_relationship_link_type: &str,
-    origin_relationship_link_tag: &str,
-    destination_relationship_link_type: &str,
-    destination_relationship_link_tag: &str,
+    index: &BidirectionalIndex,
     source_base_address: &Address,
     target_base_addresses: Vec<Address>,
-) -> Vec<ZomeApiResult<Address>> {
+) -> Vec<GraphHelperResult<Address>> {
     let mut local_results = create_direct_remote_index_origin(
-        remote_base_entry_type,
-        origin_relationship_link_type,
-        origin_relationship_link_tag,
-        destination_relationship_link_type,
-        destination_relationship_link_tag,
+        index,
         source_base_address,
         target_base_addresses.clone(),
     );
 
-    let mut remote_results = request_sync_direct_remote_index_destination(
+    let mut remote_results = request_sync_or_queue_direct_remote_index_destination(
         remote_dna_id,
         remote_zome_id,
         remote_zome_method,
@@ -113,54 +114,41 @@ pub fn create_direct_remote_index(
 /// In the remote DNA, a corresponding remote query index is built via `create_direct_remote_index_destination`,
 /// which is presumed to be linked to the other end of the specified `remote_zome_method`.
 ///
-/// :TODO: return any errors encountered in internal link creation
+/// Returns the `key index` address of each referenced record, or the first error encountered
+/// in creating it and linking to it.
 ///
 fn create_direct_remote_index_origin(
-    remote_base_entry_type: &str,
-    origin_relationship_link_type: &str,
-    origin_relationship_link_tag: &str,
-    destination_relationship_link_type: &str,
-    destination_relationship_link_tag: &str,
+    index: &BidirectionalIndex,
     source_base_address: &Address,
     target_base_addresses: Vec<Address>,
-) -> Vec<ZomeApiResult<Address>> {
+) -> Vec<GraphHelperResult<Address>> {
     // abort if target_base_addresses are empty
     if target_base_addresses.len() == 0 { return vec![] }
 
     // Build local index first (for reading linked record IDs from the `source_base_address`)
-    let results: Vec<ZomeApiResult<Address>> = target_base_addresses.iter()
+    target_base_addresses.iter()
         .map(|base_entry_addr| {
             // create a base entry pointer for the referenced commitment
-            let base_entry_result = create_key_index(&(remote_base_entry_type.to_string().into()), base_entry_addr);
-
-            match &base_entry_result {
-                Ok(base_address) => {
-                    // link event to commitment by `fulfilled`/`fulfilledBy` edge
-                    create_direct_index(
-                        &source_base_address, base_address,
-                        origin_relationship_link_type, origin_relationship_link_tag,
-                        destination_relationship_link_type, destination_relationship_link_tag
-                    );
-                },
-                _ => (),
-            }
+            let base_address = create_key_index(&(index.reverse.base_entry_type.to_string().into()), base_entry_addr)?;
 
-            base_entry_result
-        })
-        .collect();
+            // link event to commitment by `fulfilled`/`fulfilledBy` edge
+            throw_any_error(create_direct_index(&source_base_address, &base_address, index))?;
 
-    results
+            Ok(base_address)
+        })
+        .collect()
 }
 
 /// Ask another bridged DNA or zome to build a 'remote query index' to match the
 /// one we have just created locally.
 /// When calling zomes within the same DNA, use `hdk::THIS_INSTANCE` as `remote_dna_id`.
 ///
+/// Any indexes which the remote DNA failed to write are returned as `RemoteIndexingFailed` errors.
+///
 /// :TODO: implement bridge genesis callbacks & private chain entry to wire up cross-DNA link calls
-/// :TODO: propagate errors from callee in error context, rather than masking them
 /// :TODO: return indexes_removed to the caller
 ///
-fn request_sync_direct_remote_index_destination(
+pub (crate) fn request_sync_direct_remote_index_destination(
     remote_dna_id: &str,
     remote_zome_id: &str,
     remote_zome_method: &str,
@@ -168,22 +156,56 @@ fn request_sync_direct_remote_index_destination(
     source_base_address: &Address,
     target_base_addresses: Vec<Address>,
     removed_base_addresses: Vec<Address>,
-) -> ZomeApiResult<Vec<ZomeApiResult<Address>>> {
+) -> GraphHelperResult<Vec<GraphHelperResult<Address>>> {
     // Call into remote DNA to enable target entries to setup data structures
     // for querying the associated remote entry records back out.
-    let response: ZomeApiResult<RemoteEntryLinkResponse> = read_from_zome(
+    let response: RemoteEntryLinkResponse = read_from_zome(
         remote_dna_id, remote_zome_id, remote_request_cap_token, remote_zome_method, RemoteEntryLinkRequest {
             base_entry: source_base_address.clone().into(),
             target_entries: target_base_addresses,
             removed_entries: removed_base_addresses,
         }.into()
+    )?;
+
+    // :TODO: how to treat deletion errors?
+    Ok(response.indexes_created.into_iter()
+        .map(|result| result.map_err(|e| {
+            GraphHelperError::remote_indexing_failed(remote_dna_id, remote_zome_id, remote_zome_method, &describe_zome_api_error(&e))
+        }))
+        .collect())
+}
+
+/// Same as `request_sync_direct_remote_index_destination`, except that if the remote
+/// DNA cannot be reached the request is stored in the pending sync outbox for later
+/// delivery (see `remote_sync::retry_pending_remote_index_syncs`), rather than failing.
+///
+/// Requests which reach the remote DNA but are rejected by it will not succeed on retry,
+/// and so are returned as errors rather than queued.
+///
+fn request_sync_or_queue_direct_remote_index_destination(
+    remote_dna_id: &str,
+    remote_zome_id: &str,
+    remote_zome_method: &str,
+    remote_request_cap_token: Address,
+    source_base_address: &Address,
+    target_base_addresses: Vec<Address>,
+    removed_base_addresses: Vec<Address>,
+) -> GraphHelperResult<Vec<GraphHelperResult<Address>>> {
+    let result = request_sync_direct_remote_index_destination(
+        remote_dna_id, remote_zome_id, remote_zome_method, remote_request_cap_token.clone(),
+        source_base_address, target_base_addresses.clone(), removed_base_addresses.clone(),
     );
 
-    match response {
-        Ok(RemoteEntryLinkResponse { indexes_created, .. }) => {
-            Ok(indexes_created) // :TODO: how to treat deletion errors?
+    match result {
+        Err(e @ GraphHelperError::RemoteUnreachable { .. }) => {
+            queue_remote_index_sync(
+                remote_dna_id, remote_zome_id, remote_zome_method, remote_request_cap_token,
+                source_base_address, target_base_addresses, removed_base_addresses,
+                1, e.to_string(),
+            )?;
+            Ok(vec![])
         },
-        Err(_) => Err(ZomeApiError::Internal(build_zome_req_error(ERR_MSG_REMOTE_INDEXING_ERR, remote_dna_id, remote_zome_id, remote_zome_method))),
+        response => response,
     }
 }
 
@@ -192,19 +214,17 @@ fn request_sync_direct_remote_index_destination(
 /// This essentially creates a base link for the `source_base_address` and then links it to every
 /// `target_base_addresses` found locally within this DNA.
 ///
+/// `index` should be the same as that passed to `create_direct_remote_index` in the foreign DNA.
+///
 /// The returned `RemoteEntryLinkResponse` provides an appropriate format for responding to indexing
 /// requests that originate with a call to `create_remote_index_pair` in a foreign DNA.
 ///
-pub fn handle_sync_direct_remote_index_destination<'a, A, B>(
-    remote_base_entry_type: &'a str,
-    origin_relationship_link_type: &str,
-    origin_relationship_link_tag: &str,
-    destination_relationship_link_type: &str,
-    destination_relationship_link_tag: &str,
+pub fn handle_sync_direct_remote_index_destination<A, B>(
+    index: &BidirectionalIndex,
     source_base_address: &A,
     target_base_addresses: Vec<B>,
     removed_base_addresses: Vec<B>,
-) -> ZomeApiResult<RemoteEntryLinkResponse>
+) -> GraphHelperResult<RemoteEntryLinkResponse>
     where A: AsRef<Address> + From<Address> + Clone,
         B: AsRef<Address> + From<Address> + Clone + PartialEq + Debug,
         Address: From<B>,
@@ -213,63 +233,48 @@ pub fn handle_sync_direct_remote_index_destination<'a, A, B>(
     let remove_resp = delete_direct_remote_index_destination(
         source_base_address,
         removed_base_addresses,
-        remote_base_entry_type,
-        origin_relationship_link_type, origin_relationship_link_tag,
-        destination_relationship_link_type, destination_relationship_link_tag,
+        index,
     );
 
     // create any new indexes
     let create_resp = create_direct_remote_index_destination(
-        remote_base_entry_type,
-        origin_relationship_link_type, origin_relationship_link_tag,
-        destination_relationship_link_type, destination_relationship_link_tag,
+        index,
         source_base_address,
         target_base_addresses,
-    );
-    if let Err(index_creation_failure) = create_resp {
-        return Err(index_creation_failure);
-    }
+    )?;
 
-    Ok(RemoteEntryLinkResponse { indexes_created: create_resp.unwrap(), indexes_removed: remove_resp })
+    // errors are sent back to the origin DNA in the zome API's own (serializable) format
+    Ok(RemoteEntryLinkResponse {
+        indexes_created: create_resp.into_iter().map(|r| r.map_err(ZomeApiError::from)).collect(),
+        indexes_removed: remove_resp.into_iter().map(|r| r.map_err(ZomeApiError::from)).collect(),
+    })
 }
 
 /// Creates a 'destination' query index used for following a link from some external record
 /// into records contained within the current DNA / zome.
 ///
-/// This basically consists of a `key index` for the remote content and bidirectional
-/// links between it and its `target_base_addresses`.
+/// This basically consists of a `key index` for the remote content (of the `forward` side's
+/// base entry type) and bidirectional links between it and its `target_base_addresses`.
 ///
-/// :TODO: return any errors encountered in internal link creation
+/// Returns the address of each target which was linked, or the first error encountered in linking it.
 ///
-pub fn create_direct_remote_index_destination<'a, A, B>(
-    remote_base_entry_type: &'a str,
-    origin_relationship_link_type: &str,
-    origin_relationship_link_tag: &str,
-    destination_relationship_link_type: &str,
-    destination_relationship_link_tag: &str,
+pub fn create_direct_remote_index_destination<A, B>(
+    index: &BidirectionalIndex,
     source_base_address: &A,
     target_base_addresses: Vec<B>,
-) -> ZomeApiResult<Vec<ZomeApiResult<Address>>>
+) -> GraphHelperResult<Vec<GraphHelperResult<Address>>>
     where A: AsRef<Address> + From<Address> + Clone,
         B: AsRef<Address> + From<Address> + Clone + PartialEq,
 {
     // create a base entry pointer for the referenced origin record
-    let base_entry: AppEntryType = remote_base_entry_type.to_string().into();
-    let base_resp = create_key_index(&base_entry, source_base_address.as_ref());
-    if let Err(base_creation_failure) = base_resp {
-        return Err(base_creation_failure);
-    }
-    let base_address = base_resp.unwrap();
+    let base_entry: AppEntryType = index.forward.base_entry_type.to_string().into();
+    let base_address = create_key_index(&base_entry, source_base_address.as_ref())?;
 
     // link all referenced records to our pointer to the remote origin record
     Ok(target_base_addresses.iter()
         .map(|target_address| {
             // link origin record to local records by specified edge
-            create_direct_index(
-                &base_address, target_address.as_ref(),
-                origin_relationship_link_type, origin_relationship_link_tag,
-                destination_relationship_link_type, destination_relationship_link_tag
-            );
+            throw_any_error(create_direct_index(&base_address, target_address.as_ref(), index))?;
 
             Ok(target_address.as_ref().clone())
         })
@@ -284,22 +289,17 @@ pub fn create_direct_remote_index_destination<'a, A, B>(
 ///
 /// :TODO: update to accept multiple targets for the replacement links
 ///
-pub fn update_direct_remote_index<A, B, S>(
+pub fn update_direct_remote_index<A, B>(
     remote_dna_id: &str,
     remote_zome_id: &str,
     remote_zome_method: &str,
     remote_request_cap_token: Address,
-    remote_base_entry_type: S,
-    origin_relationship_link_type: &str,
-    origin_relationship_link_tag: &str,
-    destination_relationship_link_type: &str,
-    destination_relationship_link_tag: &str,
+    index: &BidirectionalIndex,
     source_base_address: &A,
     target_base_address: &MaybeUndefined<B>,
-) -> ZomeApiResult<Vec<ZomeApiResult<Address>>>
+) -> GraphHelperResult<Vec<GraphHelperResult<Address>>>
     where A: AsRef<Address> + From<Address> + Clone,
         B: AsRef<Address> + From<Address> + Clone + PartialEq + Debug,
-        S: Into<AppEntryType>,
 {
     // no change, bail early
     if let MaybeUndefined::Undefined = target_base_address {
@@ -307,21 +307,14 @@ pub fn update_direct_remote_index<A, B, S>(
     }
 
     // process local index first and collect all removed link target address
-    // :TODO: error handling
-    let removed_links = replace_direct_remote_index_origin(
+    let removed_links = throw_any_error(replace_direct_remote_index_origin(
         source_base_address,
         target_base_address,
-        remote_base_entry_type,
-        origin_relationship_link_type,
-        origin_relationship_link_tag,
-        destination_relationship_link_type,
-        destination_relationship_link_tag,
-    )?.iter()
-        .filter_map(|r| { r.clone().ok() })
-        .collect();
+        index,
+    )?)?;
 
     // pass removed IDs and new IDs to remote DNA for re-indexing
-    request_sync_direct_remote_index_destination(
+    request_sync_or_queue_direct_remote_index_destination(
         remote_dna_id,
         remote_zome_id,
         remote_zome_method,
@@ -342,18 +335,13 @@ pub fn update_direct_remote_index<A, B, S>(
 ///
 /// :TODO: update to accept multiple targets for the replacement links
 ///
-pub fn replace_direct_remote_index_origin<A, B, S>(
+pub fn replace_direct_remote_index_origin<A, B>(
     source: &A,
     new_dest: &MaybeUndefined<B>,
-    base_entry_type: S,
-    link_type: &str,
-    link_name: &str,
-    link_type_reciprocal: &str,
-    link_name_reciprocal: &str,
-) -> ZomeApiResult<Vec<ZomeApiResult<Address>>>
+    index: &BidirectionalIndex,
+) -> GraphHelperResult<Vec<GraphHelperResult<Address>>>
     where A: AsRef<Address> + From<Address> + Clone,
         B: AsRef<Address> + From<Address> + Clone + PartialEq + Debug,
-        S: Into<AppEntryType>,
 {
     // if not updating, skip operation
     if let MaybeUndefined::Undefined = new_dest {
@@ -361,42 +349,28 @@ pub fn replace_direct_remote_index_origin<A, B, S>(
     }
 
     // load any existing links from the originating address
-    let existing_links: Vec<B> = get_linked_addresses_as_type(source, link_type, link_name).into_owned();
+    let existing_links: Vec<B> = get_linked_addresses_as_type(source, index.forward.link_type, index.forward.link_tag)?.into_owned();
 
     // determine links to erase
     let to_erase: Vec<B> = existing_links.iter()
         .filter(dereferenced_link_does_not_match(new_dest)).map(|x| { (*x).clone() }).collect();
 
     // wipe stale links. Note we don't remove the base addresses, dangling remnants do no harm.
-    // :TODO: propagate errors
-    let _erased: Vec<ZomeApiResult<()>> = to_erase.iter().flat_map(wipe_links_from_origin(
-        link_type, link_name,
-        link_type_reciprocal, link_name_reciprocal,
-        source,
-    )).collect();
+    to_erase.iter().flat_map(wipe_links_from_origin(index, source)).collect::<GraphHelperResult<Vec<()>>>()?;
 
     // get base addresses of erased items
-    let erased: Vec<ZomeApiResult<Address>> = to_erase.iter().map(|addr| { get_key_index_address(addr.as_ref()) }).collect();
+    let erased: Vec<GraphHelperResult<Address>> = to_erase.iter().map(|addr| { get_key_index_address(addr.as_ref()) }).collect();
 
     // run insert if needed
     match new_dest {
         MaybeUndefined::Some(new_link) => {
             let already_present = existing_links.iter().filter(dereferenced_link_matches(new_dest)).count() > 0;
 
-            if already_present {
-                Ok(erased)
-            } else {
-                let new_dest_pointer = create_key_index(&(base_entry_type.into()), new_link.as_ref());
-                if let Err(e) = new_dest_pointer {
-                    return Err(e);
-                }
-                create_direct_index(
-                    source.as_ref(), &(new_dest_pointer.unwrap()),
-                    link_type, link_name,
-                    link_type_reciprocal, link_name_reciprocal
-                );  // :TODO: error handling
-                Ok(erased)
+            if !already_present {
+                let new_dest_pointer = create_key_index(&(index.reverse.base_entry_type.to_string().into()), new_link.as_ref())?;
+                throw_any_error(create_direct_index(source.as_ref(), &new_dest_pointer, index))?;
             }
+            Ok(erased)
         },
         _ => Ok(erased),
     }
@@ -409,36 +383,28 @@ pub fn replace_direct_remote_index_origin<A, B, S>(
 ///
 /// :TODO: update to accept multiple targets for the replacement links
 ///
-pub fn remove_direct_remote_index<'a, A, B>(
+pub fn remove_direct_remote_index<A, B>(
     remote_dna_id: &str,
     remote_zome_id: &str,
     remote_zome_method: &str,
     remote_request_cap_token: Address,
-    remote_base_entry_type: &'a str,
-    origin_relationship_link_type: &str,
-    origin_relationship_link_tag: &str,
-    destination_relationship_link_type: &str,
-    destination_relationship_link_tag: &str,
+    index: &BidirectionalIndex,
     source_base_address: &A,
     remove_base_address: &B,
-) -> Vec<ZomeApiResult<()>>
+) -> Vec<GraphHelperResult<()>>
     where A: AsRef<Address> + From<Address> + Clone,
         B: AsRef<Address> + From<Address> + Clone + PartialEq + Debug,
         Address: From<B>,
 {
     // process local index first and collect any errors
-    let mut local_results = delete_direct_remote_index_destination(
+    let mut local_results = delete_direct_remote_index_origin(
         source_base_address,
-        vec![remove_base_address.clone().into()],
-        remote_base_entry_type,
-        origin_relationship_link_type,
-        origin_relationship_link_tag,
-        destination_relationship_link_type,
-        destination_relationship_link_tag,
+        remove_base_address,
+        index,
     );
 
     // pass removed IDs and new IDs to remote DNA for re-indexing
-    let remote_results = request_sync_direct_remote_index_destination(
+    let remote_results = request_sync_or_queue_direct_remote_index_destination(
         remote_dna_id,
         remote_zome_id,
         remote_zome_method,
@@ -450,7 +416,7 @@ pub fn remove_direct_remote_index<'a, A, B>(
 
     match remote_results {
         Ok(results) => {
-            let mut remote_errors: Vec<ZomeApiResult<()>> = results.iter()
+            let mut remote_errors: Vec<GraphHelperResult<()>> = results.iter()
                 .filter(|r| { r.is_err() })
                 .map(|e| { Err(e.clone().err().unwrap()) })
                 .collect();
@@ -463,6 +429,57 @@ pub fn remove_direct_remote_index<'a, A, B>(
     local_results
 }
 
+/// Undoes a `create_direct_remote_index` or `update_direct_remote_index` made earlier in
+/// the same operation, eg. as the compensating action of a `Saga` step.
+///
+/// If the request to build the destination index is still waiting in the outbox it is
+/// discarded, and only the origin index is removed. Otherwise the destination DNA is
+/// asked to remove its index as well, as with `remove_direct_remote_index`.
+///
+pub fn discard_direct_remote_index<A, B>(
+    remote_dna_id: &str,
+    remote_zome_id: &str,
+    remote_zome_method: &str,
+    remote_request_cap_token: Address,
+    index: &BidirectionalIndex,
+    source_base_address: &A,
+    remove_base_address: &B,
+) -> Vec<GraphHelperResult<()>>
+    where A: AsRef<Address> + From<Address> + Clone,
+        B: AsRef<Address> + From<Address> + Clone + PartialEq + Debug,
+        Address: From<B>,
+{
+    match discard_pending_remote_index_syncs(source_base_address.as_ref(), remove_base_address.as_ref()) {
+        Ok(0) => remove_direct_remote_index(
+            remote_dna_id, remote_zome_id, remote_zome_method, remote_request_cap_token,
+            index,
+            source_base_address, remove_base_address,
+        ),
+        Ok(_) => delete_direct_remote_index_origin(source_base_address, remove_base_address, index),
+        Err(e) => vec![Err(e)],
+    }
+}
+
+/// Deletes the links between a local record and the `key index` referencing
+/// a remote record.
+///
+/// The `key index` itself is left dangling, as with `delete_direct_remote_index_destination`.
+///
+fn delete_direct_remote_index_origin<A, B>(
+    source: &A,
+    remove_target: &B,
+    index: &BidirectionalIndex,
+) -> Vec<GraphHelperResult<()>>
+    where A: AsRef<Address>,
+        B: AsRef<Address>,
+{
+    let dereferenced_target: GraphHelperResult<Address> = determine_key_index_address(index.reverse.base_entry_type.to_string(), remove_target.as_ref());
+    match dereferenced_target {
+        Ok(target_index) => delete_direct_index(source.as_ref(), &target_index, index),
+        Err(e) => vec![Err(e)],
+    }
+}
+
 /// Deletes a set of links between a remote record reference and some set
 /// of local target addresses.
 ///
@@ -470,30 +487,22 @@ pub fn remove_direct_remote_index<'a, A, B>(
 /// affected in the removal, and is simply left dangling in the
 /// DHT space as an indicator of previously linked items.
 ///
-pub (crate) fn delete_direct_remote_index_destination<'a, A, B>(
+pub fn delete_direct_remote_index_destination<A, B>(
     source: &A,
     remove_targets: Vec<B>,
-    base_entry_type: &'a str,
-    link_type: &str,
-    link_name: &str,
-    link_type_reciprocal: &str,
-    link_name_reciprocal: &str,
-) -> Vec<ZomeApiResult<()>>
+    index: &BidirectionalIndex,
+) -> Vec<GraphHelperResult<()>>
     where A: AsRef<Address> + From<Address> + Clone,
         B: AsRef<Address> + From<Address> + Clone + PartialEq + Debug,
         Address: From<B>,
 {
-    let dereferenced_source: ZomeApiResult<A> = determine_key_index_address(base_entry_type.to_string(), source.as_ref());
+    let dereferenced_source: GraphHelperResult<A> = determine_key_index_address(index.forward.base_entry_type.to_string(), source.as_ref());
     if let Err(e) = dereferenced_source {
         return vec![Err(e)]
     }
 
     let index_address = dereferenced_source.unwrap();
     remove_targets.iter()
-        .flat_map(wipe_links_from_origin(
-            link_type, link_name,
-            link_type_reciprocal, link_name_reciprocal,
-            &index_address,
-        ))
+        .flat_map(wipe_links_from_origin(index, &index_address))
         .collect()
 }
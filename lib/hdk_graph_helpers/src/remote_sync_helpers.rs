@@ -0,0 +1,485 @@
+/**
+ * Helpers relating to the `remote index` sync outbox.
+ *
+ * When a request to update the `destination` side of a `remote index` cannot be delivered
+ * because the bridged DNA is unreachable, the `origin` side of the index has already
+ * been written. Rather than discarding the failed request and leaving the index
+ * one-sided, it is stored as a private "pending sync" entry on the agent's source chain
+ * so that it can be retried later via `retry_pending_remote_index_syncs`.
+ *
+ * Any zome which manages `remote indexes` must register `pending_sync_entry_def` as
+ * one of its entry types for this to work.
+ *
+ * :TODO: retry outstanding requests automatically once some scheduling mechanism is available
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-06-08
+ */
+use std::collections::HashSet;
+use hdk::{
+    holochain_json_api::{ json::JsonString, error::JsonError },
+    holochain_persistence_api::cas::content::Address,
+    holochain_core_types::{
+        entry::Entry::App as AppEntry,
+        dna::entry_types::Sharing,
+    },
+    entry_definition::ValidatingEntryType,
+};
+use holochain_json_derive::{ DefaultJson };
+
+use super::{
+    hdk_api::{
+        commit_entry,
+        get_entry,
+        remove_entry,
+        query,
+    },
+    identifiers::REMOTE_INDEX_PENDING_SYNC_ENTRY_TYPE,
+    errors::GraphHelperResult,
+    entries::try_decode_entry,
+    remote_indexes::request_sync_direct_remote_index_destination,
+};
+
+/// A `RemoteEntryLinkRequest` which could not be delivered, along with the
+/// information needed to deliver it again.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingRemoteIndexSync {
+    pub remote_dna_id: String,
+    pub remote_zome_id: String,
+    pub remote_zome_method: String,
+    pub remote_request_cap_token: Address,
+    pub base_entry: Address,
+    pub target_entries: Vec<Address>,
+    pub removed_entries: Vec<Address>,
+    pub attempts: u32,
+    pub last_error: String,
+}
+
+/// Outcome of a call to `retry_pending_remote_index_syncs`
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteIndexSyncReport {
+    pub retried: usize,
+    pub completed: usize,
+    pub pending: Vec<PendingRemoteIndexSync>,
+}
+
+/// Entry type definition for the pending sync outbox. Pending requests are
+/// private to the agent who made them.
+///
+pub fn pending_sync_entry_def() -> ValidatingEntryType {
+    hdk::entry!(
+        name: REMOTE_INDEX_PENDING_SYNC_ENTRY_TYPE,
+        description: "Remote index updates which could not be delivered to a bridged DNA, awaiting retry",
+        sharing: Sharing::Private,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<PendingRemoteIndexSync>| {
+            Ok(())
+        }
+    )
+}
+
+//-------------------------------[ CREATE ]-------------------------------------
+
+/// Stores a failed remote index request in the outbox, for later delivery.
+///
+pub (crate) fn queue_remote_index_sync(
+    remote_dna_id: &str,
+    remote_zome_id: &str,
+    remote_zome_method: &str,
+    remote_request_cap_token: Address,
+    base_entry: &Address,
+    target_entries: Vec<Address>,
+    removed_entries: Vec<Address>,
+    attempts: u32,
+    last_error: String,
+) -> GraphHelperResult<Address> {
+    commit_pending_sync(&PendingRemoteIndexSync {
+        remote_dna_id: remote_dna_id.to_string(),
+        remote_zome_id: remote_zome_id.to_string(),
+        remote_zome_method: remote_zome_method.to_string(),
+        remote_request_cap_token,
+        base_entry: base_entry.to_owned(),
+        target_entries,
+        removed_entries,
+        attempts,
+        last_error,
+    })
+}
+
+fn commit_pending_sync(pending: &PendingRemoteIndexSync) -> GraphHelperResult<Address> {
+    Ok(commit_entry(&AppEntry(REMOTE_INDEX_PENDING_SYNC_ENTRY_TYPE.into(), pending.to_owned().into()))?)
+}
+
+//-------------------------------[ READ ]--------------------------------------
+
+/// Reads all outstanding remote index requests from the agent's source chain,
+/// along with the address of the entry each is stored at.
+///
+pub fn get_pending_remote_index_syncs() -> GraphHelperResult<Vec<(Address, PendingRemoteIndexSync)>> {
+    let addresses = query(REMOTE_INDEX_PENDING_SYNC_ENTRY_TYPE.into(), 0, 0)?;
+
+    addresses.iter()
+        .filter_map(|address| {
+            // entries which have been delivered are removed, and so will no longer be found
+            match get_entry(address) {
+                Ok(None) => None,
+                entry => Some(try_decode_entry(entry).map(|pending| (address.to_owned(), pending.unwrap()))),
+            }
+        })
+        .collect()
+}
+
+//-------------------------------[ UPDATE ]-------------------------------------
+
+/// Attempts to deliver all outstanding remote index requests, in the order they were made.
+/// Delivered requests are removed from the outbox; those which fail again are kept, with
+/// their attempt count and last error updated. Where the remote DNA received a request but
+/// failed to index some of its targets, only those targets are kept for retry.
+///
+/// Once a request fails, later requests for the same `base_entry` are not attempted, since
+/// they may depend on it (eg. removing an index which has not yet been created). They are
+/// moved along with it in order to remain behind it in the outbox.
+///
+/// Returns a summary of the remaining undelivered requests.
+///
+pub fn retry_pending_remote_index_syncs() -> GraphHelperResult<RemoteIndexSyncReport> {
+    let outstanding = get_pending_remote_index_syncs()?;
+    let retried = outstanding.len();
+    let mut pending = vec![];
+    let mut blocked: HashSet<Address> = HashSet::new();
+
+    for (address, request) in outstanding {
+        let requeued = if blocked.contains(&request.base_entry) {
+            Some(request)
+        } else {
+            let result = request_sync_direct_remote_index_destination(
+                &request.remote_dna_id,
+                &request.remote_zome_id,
+                &request.remote_zome_method,
+                request.remote_request_cap_token.to_owned(),
+                &request.base_entry,
+                request.target_entries.to_owned(),
+                request.removed_entries.to_owned(),
+            );
+            undelivered_part(request, result)
+        };
+
+        if let Some(requeued) = requeued {
+            blocked.insert(requeued.base_entry.to_owned());
+            // commit the new attempt before erasing the old one, so that the request is never lost
+            commit_pending_sync(&requeued)?;
+            pending.push(requeued);
+        }
+        remove_entry(&address)?;
+    }
+
+    Ok(RemoteIndexSyncReport {
+        retried,
+        completed: retried - pending.len(),
+        pending,
+    })
+}
+
+/// Determine what remains to be delivered of a `request` after an attempt to deliver it,
+/// given the `result` of that attempt.
+///
+fn undelivered_part(
+    request: PendingRemoteIndexSync,
+    result: GraphHelperResult<Vec<GraphHelperResult<Address>>>,
+) -> Option<PendingRemoteIndexSync> {
+    let results = match result {
+        Ok(results) => results,
+        Err(e) => return Some(PendingRemoteIndexSync {
+            attempts: request.attempts + 1,
+            last_error: e.to_string(),
+            ..request
+        }),
+    };
+
+    // results correspond to `target_entries`; any not reported on are assumed undelivered
+    let mut last_error = None;
+    let failed_targets: Vec<Address> = request.target_entries.iter().enumerate()
+        .filter(|(i, _)| match results.get(*i) {
+            Some(Ok(_)) => false,
+            Some(Err(e)) => { last_error = Some(e.to_string()); true },
+            None => true,
+        })
+        .map(|(_, target)| target.to_owned())
+        .collect();
+
+    if failed_targets.is_empty() {
+        return None;
+    }
+    Some(PendingRemoteIndexSync {
+        target_entries: failed_targets,
+        // removals were processed by the remote DNA, and need not be repeated
+        removed_entries: vec![],
+        attempts: request.attempts + 1,
+        last_error: last_error.unwrap_or_else(|| "no response for some indexes".to_string()),
+        ..request
+    })
+}
+
+//-------------------------------[ DELETE ]-------------------------------------
+
+/// Removes any outstanding requests to index `target_entry` against `base_entry`
+/// from the outbox, so that they are never delivered.
+///
+/// For use when undoing an index written earlier in the same operation; see
+/// `remote_indexes::discard_direct_remote_index`. Returns the number of requests discarded.
+///
+pub fn discard_pending_remote_index_syncs(base_entry: &Address, target_entry: &Address) -> GraphHelperResult<usize> {
+    let discarded: Vec<Address> = get_pending_remote_index_syncs()?.into_iter()
+        .filter(|(_, pending)| &pending.base_entry == base_entry && pending.target_entries.contains(target_entry))
+        .map(|(address, _)| address)
+        .collect();
+
+    for address in discarded.iter() {
+        remove_entry(address)?;
+    }
+    Ok(discarded.len())
+}
+
+#[cfg(all(test, feature = "mock-hdk"))]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+    use hdk::error::ZomeApiError;
+    use crate::{
+        mock_hdk,
+        errors::GraphHelperError,
+        type_wrappers::Addressable,
+        link_specs::{ LinkSpec, BidirectionalIndex },
+        links::get_linked_addresses,
+        remote_indexes::{
+            create_direct_remote_index,
+            remove_direct_remote_index,
+            discard_direct_remote_index,
+            handle_sync_direct_remote_index_destination,
+        },
+    };
+
+    const INDEX: BidirectionalIndex = BidirectionalIndex {
+        forward: LinkSpec { base_entry_type: "origin_base", link_type: "origin_to_dest", link_tag: "dest" },
+        reverse: LinkSpec { base_entry_type: "dest_base", link_type: "dest_to_origin", link_tag: "origin" },
+    };
+
+    #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+    struct LinkRequest {
+        base_entry: Address,
+        target_entries: Vec<Address>,
+        removed_entries: Vec<Address>,
+    }
+
+    fn register_destination() {
+        mock_hdk::register_zome_fn("remote_dna", "remote_zome", "index_things", |args| {
+            let req = LinkRequest::try_from(args).unwrap();
+            let resp = handle_sync_direct_remote_index_destination(
+                &INDEX,
+                &Addressable::from(req.base_entry),
+                req.target_entries.into_iter().map(Addressable::from).collect(),
+                req.removed_entries.into_iter().map(Addressable::from).collect(),
+            ).map_err(ZomeApiError::from);
+            Ok(JsonString::from_json(&serde_json::to_string(&resp).unwrap()))
+        });
+    }
+
+    #[test]
+    fn test_undelivered_requests_retried() {
+        let origin = commit_entry(&AppEntry("origin_base".into(), JsonString::from_json("\"origin\""))).unwrap();
+        let target = commit_entry(&AppEntry("dest_base".into(), JsonString::from_json("\"target\""))).unwrap();
+
+        // no destination zome available; request is queued
+        let results = create_direct_remote_index(
+            "remote_dna", "remote_zome", "index_things", Address::from("token"),
+            &INDEX,
+            &origin, vec![target.to_owned()],
+        );
+        assert!(results.iter().all(Result::is_ok));
+
+        let pending = get_pending_remote_index_syncs().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].1.attempts, 1);
+
+        // still unavailable; attempt is recorded
+        let report = retry_pending_remote_index_syncs().unwrap();
+        assert_eq!((report.retried, report.completed), (1, 0));
+        assert_eq!(report.pending[0].attempts, 2);
+
+        register_destination();
+        let report = retry_pending_remote_index_syncs().unwrap();
+        assert_eq!((report.retried, report.completed), (1, 1));
+        assert_eq!(get_pending_remote_index_syncs().unwrap().len(), 0);
+
+        // destination index now built
+        let origin_key = crate::keys::determine_key_index_address::<Address, _>("origin_base", &origin).unwrap();
+        assert_eq!(get_linked_addresses(&origin_key, "origin_to_dest", "dest"), Ok(vec![target]));
+    }
+
+    #[test]
+    fn test_remote_errors_queued() {
+        mock_hdk::register_zome_fn("remote_dna", "remote_zome", "index_things", |_args| {
+            Err(ZomeApiError::Internal("unavailable".to_string()))
+        });
+        let origin = commit_entry(&AppEntry("origin_base".into(), JsonString::from_json("\"origin\""))).unwrap();
+
+        let _ = create_direct_remote_index(
+            "remote_dna", "remote_zome", "index_things", Address::from("token"),
+            &INDEX,
+            &origin, vec![origin.to_owned()],
+        );
+        let pending = get_pending_remote_index_syncs().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert!(pending[0].1.last_error.contains("unavailable"));
+    }
+
+    #[test]
+    fn test_failed_indexes_requeued() {
+        let origin = commit_entry(&AppEntry("origin_base".into(), JsonString::from_json("\"origin\""))).unwrap();
+        let good = commit_entry(&AppEntry("dest_base".into(), JsonString::from_json("\"good\""))).unwrap();
+        let bad = commit_entry(&AppEntry("dest_base".into(), JsonString::from_json("\"bad\""))).unwrap();
+
+        let _ = create_direct_remote_index(
+            "remote_dna", "remote_zome", "index_things", Address::from("token"),
+            &INDEX,
+            &origin, vec![good.to_owned(), bad.to_owned()],
+        );
+        assert_eq!(get_pending_remote_index_syncs().unwrap().len(), 1);
+
+        // destination receives the request, but fails to index one of the targets
+        let rejected = bad.to_owned();
+        mock_hdk::register_zome_fn("remote_dna", "remote_zome", "index_things", move |args| {
+            let req = LinkRequest::try_from(args).unwrap();
+            let created: Vec<Result<Address, ZomeApiError>> = req.target_entries.into_iter()
+                .map(|target| if target == rejected { Err(ZomeApiError::Internal("busy".to_string())) } else { Ok(target) })
+                .collect();
+            let resp = serde_json::json!({ "Ok": { "indexes_created": created, "indexes_removed": [] } });
+            Ok(JsonString::from_json(&resp.to_string()))
+        });
+
+        let report = retry_pending_remote_index_syncs().unwrap();
+        assert_eq!((report.retried, report.completed), (1, 0));
+        assert_eq!(report.pending[0].target_entries, vec![bad.to_owned()]);
+        assert_eq!(report.pending[0].attempts, 2);
+        assert!(report.pending[0].last_error.contains("busy"));
+
+        register_destination();
+        let report = retry_pending_remote_index_syncs().unwrap();
+        assert_eq!((report.retried, report.completed), (1, 1));
+        assert_eq!(get_pending_remote_index_syncs().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_later_requests_wait_for_failed_request() {
+        let origin = commit_entry(&AppEntry("origin_base".into(), JsonString::from_json("\"origin\""))).unwrap();
+        let target = commit_entry(&AppEntry("dest_base".into(), JsonString::from_json("\"target\""))).unwrap();
+
+        // both the creation and removal of the index are queued
+        let _ = create_direct_remote_index(
+            "remote_dna", "remote_zome", "index_things", Address::from("token"),
+            &INDEX,
+            &origin, vec![target.to_owned()],
+        );
+        let _ = remove_direct_remote_index(
+            "remote_dna", "remote_zome", "index_things", Address::from("token"),
+            &INDEX,
+            &origin, &target,
+        );
+
+        // removal is not attempted whilst the creation it follows is undelivered
+        let report = retry_pending_remote_index_syncs().unwrap();
+        assert_eq!((report.retried, report.completed), (2, 0));
+        assert_eq!(report.pending[0].target_entries, vec![target.to_owned()]);
+        assert_eq!(report.pending[0].attempts, 2);
+        assert_eq!(report.pending[1].removed_entries, vec![target.to_owned()]);
+        assert_eq!(report.pending[1].attempts, 1);
+        let queued: Vec<PendingRemoteIndexSync> = get_pending_remote_index_syncs().unwrap().into_iter().map(|(_, p)| p).collect();
+        assert_eq!(queued, report.pending, "requests remain in their original order");
+
+        register_destination();
+        let report = retry_pending_remote_index_syncs().unwrap();
+        assert_eq!((report.retried, report.completed), (2, 2));
+
+        let origin_key = crate::keys::determine_key_index_address::<Address, _>("origin_base", &origin).unwrap();
+        assert_eq!(get_linked_addresses(&origin_key, "origin_to_dest", "dest"), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_rejected_requests_not_queued() {
+        mock_hdk::register_zome_fn("remote_dna", "remote_zome", "index_things", |_args| {
+            let resp: Result<(), ZomeApiError> = Err(ZomeApiError::Internal("not permitted".to_string()));
+            Ok(JsonString::from_json(&serde_json::to_string(&resp).unwrap()))
+        });
+        let origin = commit_entry(&AppEntry("origin_base".into(), JsonString::from_json("\"origin\""))).unwrap();
+
+        let results = create_direct_remote_index(
+            "remote_dna", "remote_zome", "index_things", Address::from("token"),
+            &INDEX,
+            &origin, vec![origin.to_owned()],
+        );
+        assert!(results.iter().any(|r| match r {
+            Err(GraphHelperError::RemoteCallFailed { cause, .. }) => cause == "not permitted",
+            _ => false,
+        }));
+        assert_eq!(get_pending_remote_index_syncs().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_discarded_index_never_delivered() {
+        let origin = commit_entry(&AppEntry("origin_base".into(), JsonString::from_json("\"origin\""))).unwrap();
+        let target = commit_entry(&AppEntry("dest_base".into(), JsonString::from_json("\"target\""))).unwrap();
+
+        // destination unavailable; request is queued
+        let _ = create_direct_remote_index(
+            "remote_dna", "remote_zome", "index_things", Address::from("token"),
+            &INDEX,
+            &origin, vec![target.to_owned()],
+        );
+        assert_eq!(get_pending_remote_index_syncs().unwrap().len(), 1);
+
+        let results = discard_direct_remote_index(
+            "remote_dna", "remote_zome", "index_things", Address::from("token"),
+            &INDEX,
+            &origin, &target,
+        );
+        assert!(results.iter().all(Result::is_ok));
+
+        // neither the original request nor a removal request is left to deliver
+        assert_eq!(get_pending_remote_index_syncs().unwrap().len(), 0);
+        assert_eq!(get_linked_addresses(&origin, "origin_to_dest", "dest"), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_removal_clears_both_sides() {
+        register_destination();
+        let origin = commit_entry(&AppEntry("origin_base".into(), JsonString::from_json("\"origin\""))).unwrap();
+        let target = commit_entry(&AppEntry("dest_base".into(), JsonString::from_json("\"target\""))).unwrap();
+
+        let _ = create_direct_remote_index(
+            "remote_dna", "remote_zome", "index_things", Address::from("token"),
+            &INDEX,
+            &origin, vec![target.to_owned()],
+        );
+        let target_key = crate::keys::determine_key_index_address::<Address, _>("dest_base", &target).unwrap();
+        let origin_key = crate::keys::determine_key_index_address::<Address, _>("origin_base", &origin).unwrap();
+        assert_eq!(get_linked_addresses(&origin, "origin_to_dest", "dest"), Ok(vec![target_key.to_owned()]));
+        assert_eq!(get_linked_addresses(&origin_key, "origin_to_dest", "dest"), Ok(vec![target.to_owned()]));
+
+        let results = remove_direct_remote_index(
+            "remote_dna", "remote_zome", "index_things", Address::from("token"),
+            &INDEX,
+            &origin, &target,
+        );
+        assert!(results.iter().all(Result::is_ok));
+
+        assert_eq!(get_linked_addresses(&origin, "origin_to_dest", "dest"), Ok(vec![]));
+        assert_eq!(get_linked_addresses(&target_key, "dest_to_origin", "origin"), Ok(vec![]));
+        assert_eq!(get_linked_addresses(&origin_key, "origin_to_dest", "dest"), Ok(vec![]));
+        assert_eq!(get_linked_addresses(&target, "dest_to_origin", "origin"), Ok(vec![]));
+    }
+}
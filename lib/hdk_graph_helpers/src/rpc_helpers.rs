@@ -13,20 +13,30 @@ use std::convert::{ TryInto, TryFrom };
 use serde::{de::DeserializeOwned};
 use hdk::{
     holochain_json_api::{ json::JsonString, error::JsonError },
-    error::{ ZomeApiError, ZomeApiResult },
+    error::{ ZomeApiError },
     holochain_persistence_api::cas::content::Address,
-    call,
 };
 
 use super::{
-    identifiers::{ ERR_MSG_REMOTE_REQUEST_ERR, ERR_MSG_REMOTE_RESPONSE_FORMAT_ERR },
+    hdk_api::{
+        call,
+    },
+    bridges::resolve_bridge_id,
+    identifiers::HDK_ERR_MSG_CAPABILITY_CHECK_FAILED,
+    errors::{ GraphHelperError, GraphHelperResult, describe_zome_api_error },
 };
 
 /// Helper for reading data from other zomes or DNAs. Abstracts away the details of dealing with
 /// response decoding and type conversion.
-/// Simply use `ZomeApiResult<X>` as the return type, where X is the response struct format you wish to decode.
+/// Simply use `GraphHelperResult<X>` as the return type, where X is the response struct format you wish to decode.
 ///
-/// :TODO: propagate errors from callee in error context, rather than masking them
+/// `instance_handle` is resolved against the DNA's bridge configuration before calling.
+/// @see hdk_graph_helpers::bridges
+///
+/// Any error returned by the callee is reported as the `cause` of a `GraphHelperError::RemoteCallFailed`.
+/// Calls which the conductor could not deliver are reported as `GraphHelperError::RemoteUnreachable`,
+/// except for those refused by the callee's capability check, which are also `RemoteCallFailed`.
+/// Responses which cannot be decoded as `R` are reported as `GraphHelperError::RemoteResponseFormat`.
 ///
 pub fn read_from_zome<R, S>(
     instance_handle: S,
@@ -34,37 +44,33 @@ pub fn read_from_zome<R, S>(
     cap_token: Address,
     fn_name: S,
     fn_args: JsonString,
-) -> ZomeApiResult<R>
+) -> GraphHelperResult<R>
     where S: Clone + Into<String> + Deref<Target=str>,
         R: TryFrom<JsonString> + Into<JsonString> + DeserializeOwned,
 {
-    let rpc_response = call(instance_handle.clone(), zome_name.clone(), cap_token, fn_name.clone(), fn_args);
-    if let Err(bad_call) = rpc_response {
-        return Err(bad_call);
-    }
+    let instance_handle = resolve_bridge_id(&instance_handle[..])?;
+    let rpc_response = call(instance_handle.to_owned(), zome_name.to_string(), cap_token, fn_name.to_string(), fn_args);
+    let strng = match rpc_response {
+        Ok(response) => response,
+        Err(bad_call) => {
+            let cause = describe_zome_api_error(&bad_call);
+            return Err(if cause.contains(HDK_ERR_MSG_CAPABILITY_CHECK_FAILED) {
+                GraphHelperError::remote_call_failed(&instance_handle[..], &zome_name[..], &fn_name[..], &cause)
+            } else {
+                GraphHelperError::remote_unreachable(&instance_handle[..], &zome_name[..], &fn_name[..], &cause)
+            });
+        },
+    };
 
-    let strng = rpc_response.unwrap();
     let decoded: Result<Result<R, ZomeApiError>, JsonError> = strng.try_into();
 
     match decoded {
         Ok(Ok(response_data)) => Ok(response_data),
-        Ok(Err(_response_err)) => Err(ZomeApiError::Internal(build_zome_req_error(ERR_MSG_REMOTE_REQUEST_ERR, &instance_handle[..], &zome_name[..], &fn_name[..]))),
-        Err(_decoding_err) => Err(ZomeApiError::Internal(build_zome_req_error(ERR_MSG_REMOTE_RESPONSE_FORMAT_ERR, &instance_handle[..], &zome_name[..], &fn_name[..]))),
+        Ok(Err(response_err)) => Err(GraphHelperError::remote_call_failed(
+            &instance_handle[..], &zome_name[..], &fn_name[..], &describe_zome_api_error(&response_err),
+        )),
+        Err(decoding_err) => Err(GraphHelperError::remote_response_format(
+            &instance_handle[..], &zome_name[..], &fn_name[..], &format!("{:?}", decoding_err),
+        )),
     }
 }
-
-/// Error reporting for inter-zome RPC calls
-pub (crate) fn build_zome_req_error(
-    prefix_string: &str,
-    remote_dna_id: &str,
-    remote_zome_id: &str,
-    remote_zome_method: &str,
- ) -> String {
-    let mut err_string = prefix_string.to_string();
-    err_string.push_str(remote_dna_id);
-    err_string.push_str("/");
-    err_string.push_str(remote_zome_id);
-    err_string.push_str("/");
-    err_string.push_str(remote_zome_method);
-    err_string
-}
@@ -0,0 +1,261 @@
+/**
+ * Compensating actions for operations which write several entries & links.
+ *
+ * The DHT has no transactions, so an operation which fails partway through leaves
+ * behind whatever it had already written- eg. resource quantities altered by an event
+ * which was never saved. Operations run as a `Saga` instead register an action to undo
+ * each step once it completes. If a later step fails, the completed steps are undone
+ * in reverse order before the original error is returned.
+ *
+ * Compensation is best-effort: other agents may observe (and act upon) the intermediate
+ * state before it is undone, and compensating actions may themselves fail. Where they
+ * do, a `RollbackFailed` error names the steps which could not be undone.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-06-20
+ */
+use hdk::holochain_persistence_api::cas::content::Address;
+
+use super::{
+    hdk_api::remove_link,
+    link_specs::BidirectionalIndex,
+    local_indexes::create_direct_index,
+    errors::{ GraphHelperError, GraphHelperResult, throw_any_error },
+};
+
+type Compensation<'a> = Box<dyn FnOnce() -> GraphHelperResult<()> + 'a>;
+
+/// The steps of an operation completed so far, and how to undo each of them
+pub struct Saga<'a> {
+    completed: Vec<(String, Compensation<'a>)>,
+}
+
+impl<'a> Saga<'a> {
+    pub fn new() -> Self {
+        Saga { completed: vec![] }
+    }
+
+    /// Run `action`. If it succeeds, `compensate` is registered to undo it should any later
+    /// step fail, and is passed (a copy of) the result of the action to do so.
+    ///
+    pub fn step<T, F, C>(&mut self, name: &str, action: F, compensate: C) -> GraphHelperResult<T>
+        where T: Clone + 'a,
+            F: FnOnce() -> GraphHelperResult<T>,
+            C: FnOnce(T) -> GraphHelperResult<()> + 'a,
+    {
+        let result = action()?;
+        let undo_with = result.clone();
+        self.completed(name, move || compensate(undo_with));
+        Ok(result)
+    }
+
+    /// Register `compensate` to undo a step which has already been run.
+    ///
+    /// Compensating actions are run after the operation has returned, and so must own
+    /// any data they reference- capture copies of it with a `move` closure.
+    ///
+    pub fn completed<C>(&mut self, name: &str, compensate: C)
+        where C: FnOnce() -> GraphHelperResult<()> + 'a,
+    {
+        self.completed.push((name.to_string(), Box::new(compensate)));
+    }
+
+    /// Link `source` and `dest` by both sides of `index`, as with `create_direct_index`.
+    ///
+    /// Whichever of the two links were written are removed should any later step fail-
+    /// including this one, if only one of them could be written.
+    ///
+    pub fn create_direct_index(&mut self, name: &str, source: &Address, dest: &Address, index: &BidirectionalIndex) -> GraphHelperResult<()> {
+        let results = create_direct_index(source, dest, index);
+
+        let written: Vec<(Address, Address, &'static str, &'static str)> = vec![
+            (source.to_owned(), dest.to_owned(), index.forward.link_type, index.forward.link_tag),
+            (dest.to_owned(), source.to_owned(), index.reverse.link_type, index.reverse.link_tag),
+        ].into_iter()
+            .zip(results.iter())
+            .filter(|(_, result)| result.is_ok())
+            .map(|(link, _)| link)
+            .collect();
+
+        if !written.is_empty() {
+            self.completed(name, move || {
+                let removed: Vec<GraphHelperResult<()>> = written.iter()
+                    .map(|(base, target, link_type, link_tag)| remove_link(base, target, *link_type, *link_tag).map_err(GraphHelperError::from))
+                    .collect();
+                throw_any_error(removed).map(|_| ())
+            });
+        }
+
+        throw_any_error(results).map(|_| ())
+    }
+
+    /// Names of the steps completed so far, in the order they were run
+    pub fn completed_steps(&self) -> Vec<String> {
+        self.completed.iter().map(|(name, _)| name.to_owned()).collect()
+    }
+
+    /// Undo all completed steps, most recent first, in response to the failure `cause`.
+    ///
+    /// Returns `cause` if every step was undone, otherwise a `RollbackFailed` error
+    /// listing the steps whose compensating actions failed.
+    ///
+    pub fn rollback(self, cause: GraphHelperError) -> GraphHelperError {
+        let failed_steps: Vec<String> = self.completed.into_iter()
+            .rev()
+            .filter_map(|(name, compensate)| match compensate() {
+                Ok(()) => None,
+                Err(_) => Some(name),
+            })
+            .collect();
+
+        if failed_steps.is_empty() {
+            return cause;
+        }
+        GraphHelperError::RollbackFailed {
+            cause: cause.to_string(),
+            steps: failed_steps,
+        }
+    }
+}
+
+impl<'a> Default for Saga<'a> {
+    fn default() -> Self {
+        Saga::new()
+    }
+}
+
+/// Run `operation` as a `Saga`, rolling back all of its completed steps if it returns an error.
+///
+pub fn run_saga<'a, T, F>(operation: F) -> GraphHelperResult<T>
+    where F: FnOnce(&mut Saga<'a>) -> GraphHelperResult<T>,
+{
+    let mut saga = Saga::new();
+    match operation(&mut saga) {
+        Ok(result) => Ok(result),
+        Err(cause) => Err(saga.rollback(cause)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    fn failure(msg: &str) -> GraphHelperError {
+        GraphHelperError::ValidationFailed(msg.to_string())
+    }
+
+    #[test]
+    fn test_completed_steps_undone_in_reverse() {
+        let log = RefCell::new(vec![]);
+
+        let result: GraphHelperResult<()> = run_saga(|saga| {
+            let a = saga.step("a", || Ok(1), |n| { log.borrow_mut().push(format!("undo a {}", n)); Ok(()) })?;
+            saga.step("b", || Ok(a + 1), |n| { log.borrow_mut().push(format!("undo b {}", n)); Ok(()) })?;
+            saga.step("c", || -> GraphHelperResult<()> { Err(failure("c failed")) }, |_| { log.borrow_mut().push("undo c".to_string()); Ok(()) })?;
+            Ok(())
+        });
+
+        assert_eq!(result, Err(failure("c failed")), "original error returned after successful rollback");
+        assert_eq!(*log.borrow(), vec!["undo b 2".to_string(), "undo a 1".to_string()], "failed step is not undone");
+    }
+
+    #[test]
+    fn test_nothing_undone_on_success() {
+        let log = RefCell::new(vec![]);
+
+        let result = run_saga(|saga| {
+            saga.step("a", || Ok("done"), |_| { log.borrow_mut().push("undo a"); Ok(()) })
+        });
+
+        assert_eq!(result, Ok("done"));
+        assert!(log.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_failed_compensations_reported() {
+        let log = RefCell::new(vec![]);
+
+        let result: GraphHelperResult<()> = run_saga(|saga| {
+            saga.step("a", || Ok(()), |_| { log.borrow_mut().push("undo a"); Ok(()) })?;
+            saga.step("b", || Ok(()), |_| Err(failure("cannot undo b")))?;
+            saga.step("c", || Ok(()), |_| Err(failure("cannot undo c")))?;
+            Err(failure("d failed"))
+        });
+
+        assert_eq!(*log.borrow(), vec!["undo a"], "rollback continues past failed compensations");
+        assert_eq!(result, Err(GraphHelperError::RollbackFailed {
+            cause: "d failed".to_string(),
+            steps: vec!["c".to_string(), "b".to_string()],
+        }));
+    }
+
+    #[test]
+    fn test_steps_registered_after_running() {
+        let log = RefCell::new(vec![]);
+
+        let result: GraphHelperResult<()> = run_saga(|saga| {
+            let value = "a".to_string();
+            saga.completed("a", { let (log, value) = (&log, value.to_owned()); move || { log.borrow_mut().push(value); Ok(()) } });
+            Err(failure(&format!("{} failed", value)))
+        });
+
+        assert_eq!(result, Err(failure("a failed")));
+        assert_eq!(*log.borrow(), vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn test_completed_steps_listed() {
+        let mut saga = Saga::new();
+        saga.step("a", || Ok(()), |_| Ok(())).unwrap();
+        let _ = saga.step("b", || -> GraphHelperResult<()> { Err(failure("b failed")) }, |_| Ok(()));
+
+        assert_eq!(saga.completed_steps(), vec!["a".to_string()]);
+    }
+}
+
+#[cfg(all(test, feature = "mock-hdk"))]
+mod mock_tests {
+    use super::*;
+    use hdk::{
+        holochain_json_api::json::JsonString,
+        holochain_core_types::entry::Entry::App as AppEntry,
+    };
+    use crate::{
+        hdk_api::commit_entry,
+        link_specs::LinkSpec,
+        links::get_linked_addresses,
+    };
+
+    const INDEX: BidirectionalIndex = BidirectionalIndex {
+        forward: LinkSpec { base_entry_type: "thing", link_type: "thing_to_other", link_tag: "other" },
+        reverse: LinkSpec { base_entry_type: "other", link_type: "other_to_thing", link_tag: "thing" },
+    };
+
+    #[test]
+    fn test_index_removed_on_rollback() {
+        let source = commit_entry(&AppEntry("thing".into(), JsonString::from_json("\"source\""))).unwrap();
+        let dest = commit_entry(&AppEntry("other".into(), JsonString::from_json("\"dest\""))).unwrap();
+
+        let result: GraphHelperResult<()> = run_saga(|saga| {
+            saga.create_direct_index("index", &source, &dest, &INDEX)?;
+            assert_eq!(get_linked_addresses(&source, "thing_to_other", "other"), Ok(vec![dest.to_owned()]));
+            Err(GraphHelperError::ValidationFailed("later step failed".to_string()))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(get_linked_addresses(&source, "thing_to_other", "other"), Ok(vec![]));
+        assert_eq!(get_linked_addresses(&dest, "other_to_thing", "thing"), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_failed_index_not_compensated() {
+        let source = commit_entry(&AppEntry("thing".into(), JsonString::from_json("\"source\""))).unwrap();
+        let missing = Address::from("missing");
+
+        let mut saga = Saga::new();
+        assert!(saga.create_direct_index("index", &source, &missing, &INDEX).is_err());
+        assert!(saga.completed_steps().is_empty(), "no links written, so nothing to undo");
+    }
+}
@@ -0,0 +1,677 @@
+/**
+ * Helper methods related to `time indexes`.
+ *
+ * Time indexes order records chronologically by way of a tree of anchors, bucketed
+ * by year, month and day. Each level links to the next via a link tagged with the
+ * bucket's date prefix; day buckets link to the `key index` of each record, tagged
+ * with the record's full timestamp:
+ *
+ *     root -> "2020" -> "2020-06" -> "2020-06-02" -> (record, "2020-06-02T10:00:00Z")
+ *
+ * This avoids a single "hot" anchor which every record must link to, and allows
+ * range reads to skip over buckets which fall outside the requested period.
+ *
+ * Timestamps may be given in any ISO8601 date or date-time format, with or without a UTC
+ * offset (timestamps without an offset are taken to be UTC). They are normalised to UTC with
+ * millisecond precision before indexing, such that records land in the UTC year, month & day
+ * buckets for the instant they describe, and so that normalised timestamps can be compared
+ * lexicographically.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-06-04
+ */
+use std::convert::{ TryFrom };
+use std::collections::HashSet;
+use hdk::{
+    holochain_persistence_api::cas::content::Address,
+    holochain_core_types::{
+        entry::Entry::App as AppEntry,
+        entry::entry_type::AppEntryType,
+        entry::AppEntryValue,
+    },
+};
+
+use super::{
+    hdk_api::{
+        commit_entry,
+        link_entries,
+        remove_link,
+    },
+    errors::{ GraphHelperError, GraphHelperResult },
+    anchors::determine_anchor_index_address,
+    entries::get_entries_by_key_index,
+    tombstones::is_record_deleted,
+    pagination::{ PageRequest, Page, encode_cursor_value, decode_cursor_value },
+    links::{
+        get_linked_addresses,
+        get_linked_addresses_with_tags,
+    },
+};
+
+/// Anchor ID of the topmost bucket in every time index
+const TIME_INDEX_ROOT_ID: &str = "root";
+
+/// Number of bucket levels between the root and the indexed records (year, month, day)
+const TIME_INDEX_DEPTH: usize = 3;
+
+/// Order in which to read records from a time index
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TimeOrder {
+    OldestFirst,
+    NewestFirst,
+}
+
+impl Default for TimeOrder {
+    fn default() -> Self { TimeOrder::OldestFirst }
+}
+
+/// Period to read records from a time index for. Both bounds are inclusive and optional.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct TimeRange {
+    pub from: Option<String>,
+    pub until: Option<String>,
+}
+
+impl TimeRange {
+    /// Normalise the bounds of this range to UTC. Date-only bounds include the whole day.
+    fn normalised(&self) -> GraphHelperResult<TimeRange> {
+        Ok(TimeRange {
+            from: match &self.from {
+                Some(from) if from.len() == 10 => Some(normalise_timestamp(&format!("{}T00:00:00.000Z", from))?),
+                Some(from) => Some(normalise_timestamp(from)?),
+                None => None,
+            },
+            until: match &self.until {
+                Some(until) if until.len() == 10 => Some(normalise_timestamp(&format!("{}T23:59:59.999Z", until))?),
+                Some(until) => Some(normalise_timestamp(until)?),
+                None => None,
+            },
+        })
+    }
+
+    /// Determine whether the given bucket key (a partial or complete normalised timestamp) overlaps this range
+    fn overlaps(&self, key: &str) -> bool {
+        let after_start = match &self.from {
+            Some(from) => key >= prefix_of(from, key.len()),
+            None => true,
+        };
+        let before_end = match &self.until {
+            Some(until) => key <= prefix_of(until, key.len()),
+            None => true,
+        };
+        after_start && before_end
+    }
+}
+
+fn prefix_of(timestamp: &str, len: usize) -> &str {
+    timestamp.get(..len).unwrap_or(timestamp)
+}
+
+/// Read `len` ASCII digits from `input` at `pos`
+fn read_digits(input: &[u8], pos: usize, len: usize) -> Option<u32> {
+    let digits = input.get(pos..pos + len)?;
+    if !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    Some(digits.iter().fold(0, |acc, d| acc * 10 + (d - b'0') as u32))
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of the given date in the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Inverse of `days_from_civil`
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (if month <= 2 { yoe + era * 400 + 1 } else { yoe + era * 400 }, month, day)
+}
+
+/// Normalise an ISO8601 date or date-time to UTC, in the form `YYYY-MM-DDThh:mm:ss.sssZ`.
+///
+/// Seconds and fractional seconds are optional, as is the UTC offset. Fractions of a
+/// second beyond millisecond precision are truncated.
+///
+pub fn normalise_timestamp(timestamp: &str) -> GraphHelperResult<String> {
+    parse_timestamp(timestamp.as_bytes())
+        .ok_or(GraphHelperError::InvalidTimestamp)
+}
+
+fn parse_timestamp(input: &[u8]) -> Option<String> {
+    // date
+    let year = read_digits(input, 0, 4)? as i64;
+    let month = read_digits(input, 5, 2)?;
+    let day = read_digits(input, 8, 2)?;
+    if input.get(4) != Some(&b'-') || input.get(7) != Some(&b'-')
+        || !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+
+    // time
+    let (mut hours, mut minutes, mut seconds, mut millis) = (0, 0, 0, 0);
+    let mut pos = 10;
+    if pos < input.len() {
+        if input[pos] != b'T' && input[pos] != b't' {
+            return None;
+        }
+        hours = read_digits(input, pos + 1, 2)?;
+        if input.get(pos + 3) != Some(&b':') { return None; }
+        minutes = read_digits(input, pos + 4, 2)?;
+        pos += 6;
+        if input.get(pos) == Some(&b':') {
+            seconds = read_digits(input, pos + 1, 2)?;
+            pos += 3;
+            if input.get(pos) == Some(&b'.') || input.get(pos) == Some(&b',') {
+                pos += 1;
+                let fraction_start = pos;
+                while matches!(input.get(pos), Some(c) if c.is_ascii_digit()) {
+                    if pos - fraction_start < 3 {
+                        millis = millis * 10 + (input[pos] - b'0') as u32;
+                    }
+                    pos += 1;
+                }
+                if pos == fraction_start { return None; }
+                for _ in (pos - fraction_start)..3 {
+                    millis *= 10;
+                }
+            }
+        }
+        if hours > 23 || minutes > 59 || seconds > 59 {
+            return None;
+        }
+    }
+
+    // offset
+    let offset_minutes: i64 = match input.get(pos) {
+        None => 0,
+        Some(b'Z') | Some(b'z') if pos + 1 == input.len() => 0,
+        Some(sign) if *sign == b'+' || *sign == b'-' => {
+            let offset_hours = read_digits(input, pos + 1, 2)? as i64;
+            let offset_mins = match input.len() - pos {
+                3 => 0,
+                5 => read_digits(input, pos + 3, 2)? as i64,
+                6 if input[pos + 3] == b':' => read_digits(input, pos + 4, 2)? as i64,
+                _ => return None,
+            };
+            if offset_hours > 23 || offset_mins > 59 { return None; }
+            let offset = offset_hours * 60 + offset_mins;
+            if *sign == b'+' { offset } else { -offset }
+        },
+        _ => return None,
+    };
+
+    let total_minutes = days_from_civil(year, month, day) * 1440 + (hours * 60 + minutes) as i64 - offset_minutes;
+    let (year, month, day) = civil_from_days(total_minutes.div_euclid(1440));
+    let minute_of_day = total_minutes.rem_euclid(1440);
+    if !(0..=9999).contains(&year) {
+        return None;
+    }
+
+    Some(format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day, minute_of_day / 60, minute_of_day % 60, seconds, millis,
+    ))
+}
+
+/// Determine the year, month and day bucket keys for a normalised timestamp
+fn time_bucket_keys(normalised_timestamp: &str) -> Vec<String> {
+    let date = &normalised_timestamp[..10];
+    vec![date[..4].to_string(), date[..7].to_string(), date.to_string()]
+}
+
+//-------------------------------[ CREATE ]-------------------------------------
+
+/// Appends the `key index` at `record_address` to the time index identified by `index_entry_type`,
+/// creating any year, month and day buckets which do not yet exist.
+///
+/// Returns the address of the day bucket the record was linked from.
+///
+/// The record is linked from its day bucket with the normalised form of `timestamp`
+/// (see `normalise_timestamp`) as the link tag.
+///
+pub fn append_to_time_index<E>(
+    index_entry_type: &E,
+    index_link_type: &str,
+    timestamp: &str,
+    record_address: &Address,
+) -> GraphHelperResult<Address>
+    where E: Into<AppEntryType> + Clone,
+{
+    let timestamp = normalise_timestamp(timestamp)?;
+    let bucket_keys = time_bucket_keys(&timestamp);
+
+    let mut parent_address = commit_time_bucket(index_entry_type, TIME_INDEX_ROOT_ID)?;
+    for bucket_key in bucket_keys.iter() {
+        let bucket_address = commit_time_bucket(index_entry_type, bucket_key)?;
+
+        // only link buckets once, to avoid duplicate traversal on read
+        let existing = get_linked_addresses(&parent_address, index_link_type, bucket_key)?;
+        if !existing.contains(&bucket_address) {
+            link_entries(&parent_address, &bucket_address, index_link_type, bucket_key)?;
+        }
+
+        parent_address = bucket_address;
+    }
+
+    link_entries(&parent_address, record_address, index_link_type, &timestamp)?;
+
+    Ok(parent_address)
+}
+
+fn commit_time_bucket<E>(
+    index_entry_type: &E,
+    bucket_key: &str,
+) -> GraphHelperResult<Address>
+    where E: Into<AppEntryType> + Clone,
+{
+    let bucket_entry = AppEntry(index_entry_type.to_owned().into(), Some(bucket_key.to_string()).into());
+    Ok(commit_entry(&bucket_entry)?)
+}
+
+//-------------------------------[ READ ]--------------------------------------
+
+/// Reads the `key index` addresses of records in a time index which fall within `range`,
+/// in the requested `order`. At most `limit` results are returned, if provided.
+///
+/// Buckets are traversed lazily, such that only the buckets needed to satisfy the
+/// range and limit are read. Soft-deleted records are skipped during traversal unless
+/// `include_deleted` is set, so that they do not count towards the `limit`.
+///
+pub fn get_time_indexed_addresses<E>(
+    index_entry_type: &E,
+    index_link_type: &str,
+    range: &TimeRange,
+    order: TimeOrder,
+    limit: Option<usize>,
+    include_deleted: bool,
+) -> GraphHelperResult<Vec<Address>>
+    where E: Into<AppEntryType> + Clone,
+{
+    let mut traversal = TimeTraversal::new(order, limit, None, !include_deleted);
+    traversal.collect_index(index_entry_type, index_link_type, &range.normalised()?)?;
+
+    Ok(traversal.results.into_iter().map(|(_timestamp, address)| address).collect())
+}
+
+/// Reads the entry data of records in a time index which fall within `range`, in the requested `order`.
+///
+/// Soft-deleted records are omitted from the results unless `include_deleted` is set. Record types
+/// which are never soft-deleted should set it, to avoid needlessly checking each record's tombstone.
+///
+pub fn read_time_indexed_record_entries<R, E, A>(
+    index_entry_type: &E,
+    index_link_type: &str,
+    range: &TimeRange,
+    order: TimeOrder,
+    limit: Option<usize>,
+    include_deleted: bool,
+) -> GraphHelperResult<Vec<(A, Option<R>)>>
+    where E: Into<AppEntryType> + Clone,
+        A: From<Address>,
+        R: Clone + TryFrom<AppEntryValue>,
+{
+    let addresses = get_time_indexed_addresses(index_entry_type, index_link_type, range, order, limit, include_deleted)?;
+    get_entries_by_key_index(addresses, true)
+}
+
+/// Paginated version of `read_time_indexed_record_entries`, for reading every record in a
+/// time index one page at a time.
+///
+/// @see get_time_indexed_page
+/// @see hdk_graph_helpers::pagination
+///
+pub fn read_time_indexed_record_entries_paginated<R, E, A>(
+    index_entry_type: &E,
+    index_link_type: &str,
+    range: &TimeRange,
+    order: TimeOrder,
+    page: &PageRequest,
+    include_deleted: bool,
+) -> GraphHelperResult<Page<(A, Option<R>)>>
+    where E: Into<AppEntryType> + Clone,
+        A: From<Address>,
+        R: Clone + TryFrom<AppEntryValue>,
+{
+    let (addresses, next_cursor) = get_time_indexed_page(index_entry_type, index_link_type, range, order, page, include_deleted)?;
+    Ok(Page {
+        results: get_entries_by_key_index(addresses, true)?,
+        next_cursor,
+    })
+}
+
+/// Reads the `key index` addresses of a single page of records in a time index, along with
+/// the cursor needed to read the next page. Soft-deleted records are skipped whilst filling
+/// the page unless `include_deleted` is set.
+///
+/// Cursors record the timestamp and address of the last record in each page, such that the
+/// next page resumes traversal from that record's time bucket. Only the buckets needed to
+/// fill the page (plus one record, to determine whether another page follows) are read.
+/// Records sharing a timestamp are ordered by address.
+///
+pub fn get_time_indexed_page<E>(
+    index_entry_type: &E,
+    index_link_type: &str,
+    range: &TimeRange,
+    order: TimeOrder,
+    page: &PageRequest,
+    include_deleted: bool,
+) -> GraphHelperResult<(Vec<Address>, Option<String>)>
+    where E: Into<AppEntryType> + Clone,
+{
+    if page.limit == 0 {
+        return Err(GraphHelperError::InvalidPageSize);
+    }
+
+    let after = match &page.cursor {
+        Some(cursor) => Some(decode_time_cursor(cursor)?),
+        None => None,
+    };
+
+    // narrow the range to begin from the cursor's bucket
+    let mut range = range.normalised()?;
+    if let Some((timestamp, _)) = &after {
+        match order {
+            TimeOrder::OldestFirst => if range.from.as_ref().map_or(true, |from| from < timestamp) {
+                range.from = Some(timestamp.to_owned());
+            },
+            TimeOrder::NewestFirst => if range.until.as_ref().map_or(true, |until| until > timestamp) {
+                range.until = Some(timestamp.to_owned());
+            },
+        }
+    }
+
+    let mut traversal = TimeTraversal::new(order, Some(page.limit + 1), after, !include_deleted);
+    traversal.collect_index(index_entry_type, index_link_type, &range)?;
+
+    let mut results = traversal.results;
+    let next_cursor = if results.len() > page.limit {
+        results.truncate(page.limit);
+        results.last().map(encode_time_cursor)
+    } else {
+        None
+    };
+
+    Ok((results.into_iter().map(|(_timestamp, address)| address).collect(), next_cursor))
+}
+
+fn encode_time_cursor(position: &(String, Address)) -> String {
+    encode_cursor_value(&format!("{} {}", position.0, position.1))
+}
+
+fn decode_time_cursor(cursor: &str) -> GraphHelperResult<(String, Address)> {
+    let value = decode_cursor_value(cursor)?;
+    let mut parts = value.splitn(2, ' ');
+    match (parts.next(), parts.next()) {
+        (Some(timestamp), Some(address)) if !address.is_empty() => {
+            let timestamp = normalise_timestamp(timestamp).map_err(|_| GraphHelperError::InvalidCursor)?;
+            Ok((timestamp, Address::from(address)))
+        },
+        _ => Err(GraphHelperError::InvalidCursor),
+    }
+}
+
+/// State of a depth-first read through the buckets of a time index
+struct TimeTraversal {
+    order: TimeOrder,
+    limit: Option<usize>,
+    /// position of the last record already read, which results must follow
+    after: Option<(String, Address)>,
+    exclude_deleted: bool,
+    seen: HashSet<Address>,
+    results: Vec<(String, Address)>,
+}
+
+impl TimeTraversal {
+    fn new(order: TimeOrder, limit: Option<usize>, after: Option<(String, Address)>, exclude_deleted: bool) -> Self {
+        TimeTraversal {
+            order, limit, after, exclude_deleted,
+            seen: HashSet::new(),
+            results: vec![],
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.limit.map_or(false, |max| self.results.len() >= max)
+    }
+
+    fn is_after_cursor(&self, position: &(String, Address)) -> bool {
+        match &self.after {
+            None => true,
+            Some(after) => match self.order {
+                TimeOrder::OldestFirst => position > after,
+                TimeOrder::NewestFirst => position < after,
+            },
+        }
+    }
+
+    fn collect_index<E>(&mut self, index_entry_type: &E, index_link_type: &str, range: &TimeRange) -> GraphHelperResult<()>
+        where E: Into<AppEntryType> + Clone,
+    {
+        let root_address = determine_anchor_index_address(index_entry_type, &TIME_INDEX_ROOT_ID.to_string())?;
+        self.collect_bucket(&root_address, index_link_type, range, 0)
+    }
+
+    fn collect_bucket(
+        &mut self,
+        bucket_address: &Address,
+        index_link_type: &str,
+        range: &TimeRange,
+        depth: usize,
+    ) -> GraphHelperResult<()> {
+        let mut children: Vec<(String, Address)> = get_linked_addresses_with_tags(bucket_address, index_link_type)?
+            .into_iter()
+            .filter(|(tag, _)| range.overlaps(tag))
+            .collect();
+
+        children.sort();
+        if self.order == TimeOrder::NewestFirst {
+            children.reverse();
+        }
+
+        for child in children {
+            if self.is_full() { break; }
+
+            if depth < TIME_INDEX_DEPTH {
+                self.collect_bucket(&child.1, index_link_type, range, depth + 1)?;
+            } else if self.is_after_cursor(&child) && !self.seen.contains(&child.1) {
+                if self.exclude_deleted && is_record_deleted(&child.1)? {
+                    continue;
+                }
+                self.seen.insert(child.1.to_owned());
+                self.results.push(child);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//-------------------------------[ DELETE ]-------------------------------------
+
+/// Removes the `key index` at `record_address` from the time index identified by `index_entry_type`.
+/// `timestamp` must describe the same instant the record was originally indexed with.
+///
+/// :NOTE: emptied buckets are left in place, since other agents may be concurrently appending to them.
+///
+pub fn remove_from_time_index<E>(
+    index_entry_type: &E,
+    index_link_type: &str,
+    timestamp: &str,
+    record_address: &Address,
+) -> GraphHelperResult<()>
+    where E: Into<AppEntryType> + Clone,
+{
+    let timestamp = normalise_timestamp(timestamp)?;
+    let bucket_keys = time_bucket_keys(&timestamp);
+    let day_address = determine_anchor_index_address(index_entry_type, &bucket_keys[TIME_INDEX_DEPTH - 1])?;
+
+    Ok(remove_link(&day_address, record_address, index_link_type, &timestamp)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_keys() {
+        assert_eq!(time_bucket_keys("2020-06-02T10:00:00.000Z"), vec!["2020", "2020-06", "2020-06-02"]);
+    }
+
+    #[test]
+    fn test_timestamps_normalised_to_utc() {
+        assert_eq!(normalise_timestamp("2020-06-02T10:00:00Z").unwrap(), "2020-06-02T10:00:00.000Z");
+        assert_eq!(normalise_timestamp("2020-06-02T10:00Z").unwrap(), "2020-06-02T10:00:00.000Z");
+        assert_eq!(normalise_timestamp("2020-06-02T10:00:00.5Z").unwrap(), "2020-06-02T10:00:00.500Z");
+        assert_eq!(normalise_timestamp("2020-06-02T10:00:00.123456Z").unwrap(), "2020-06-02T10:00:00.123Z");
+        assert_eq!(normalise_timestamp("2020-06-02T10:00:00").unwrap(), "2020-06-02T10:00:00.000Z");
+        assert_eq!(normalise_timestamp("2020-06-02").unwrap(), "2020-06-02T00:00:00.000Z");
+        assert_eq!(normalise_timestamp("2020-06-02T10:00:00+02:00").unwrap(), "2020-06-02T08:00:00.000Z");
+        assert_eq!(normalise_timestamp("2020-06-02T10:00:00-0530").unwrap(), "2020-06-02T15:30:00.000Z");
+    }
+
+    #[test]
+    fn test_offsets_move_timestamps_between_buckets() {
+        assert_eq!(normalise_timestamp("2020-06-02T01:00:00+10:00").unwrap(), "2020-06-01T15:00:00.000Z");
+        assert_eq!(normalise_timestamp("2020-01-01T05:00:00+08").unwrap(), "2019-12-31T21:00:00.000Z");
+        assert_eq!(normalise_timestamp("2020-02-28T20:00:00-05:00").unwrap(), "2020-02-29T01:00:00.000Z");
+        assert_eq!(normalise_timestamp("2019-12-31T23:30:00-01:00").unwrap(), "2020-01-01T00:30:00.000Z");
+    }
+
+    #[test]
+    fn test_invalid_timestamps_rejected() {
+        assert!(normalise_timestamp("2020-6-2").is_err());
+        assert!(normalise_timestamp("not a date").is_err());
+        assert!(normalise_timestamp("2019-02-29").is_err());
+        assert!(normalise_timestamp("2020-06-02T25:00:00Z").is_err());
+        assert!(normalise_timestamp("2020-06-02T10:00:00+1").is_err());
+        assert!(normalise_timestamp("2020-06-02T10:00:00Zjunk").is_err());
+    }
+
+    #[test]
+    fn test_range_overlaps_buckets() {
+        let range = TimeRange {
+            from: Some("2020-03-15T00:00:00Z".to_string()),
+            until: Some("2020-06-02T12:00:00Z".to_string()),
+        }.normalised().unwrap();
+
+        assert!(range.overlaps("2020"));
+        assert!(range.overlaps("2020-03"));
+        assert!(!range.overlaps("2020-02"));
+        assert!(!range.overlaps("2020-03-14"));
+        assert!(range.overlaps("2020-06-02"));
+        assert!(range.overlaps("2020-06-02T12:00:00.000Z"));
+        assert!(!range.overlaps("2020-06-02T12:00:01.000Z"));
+        assert!(!range.overlaps("2021"));
+    }
+
+    #[test]
+    fn test_range_bounds_normalised() {
+        let range = TimeRange {
+            from: Some("2020-06-02T10:00:00+10:00".to_string()),
+            until: Some("2020-06-03".to_string()),
+        }.normalised().unwrap();
+
+        assert!(range.overlaps("2020-06-02T00:00:00.000Z"));
+        assert!(!range.overlaps("2020-06-01T23:59:59.999Z"));
+        assert!(range.overlaps("2020-06-03T23:59:59.999Z"), "date-only bounds include the whole day");
+    }
+
+    #[test]
+    fn test_open_range() {
+        assert!(TimeRange::default().overlaps("1970-01-01T00:00:00.000Z"));
+    }
+}
+
+#[cfg(all(test, feature = "mock-hdk"))]
+mod mock_tests {
+    use super::*;
+
+    const INDEX_ENTRY_TYPE: &str = "test_time_index";
+    const INDEX_LINK_TYPE: &str = "test_time_indexed";
+
+    fn append(timestamp: &str, id: &str) -> Address {
+        let record = Address::from(id);
+        append_to_time_index(&INDEX_ENTRY_TYPE.to_string(), INDEX_LINK_TYPE, timestamp, &record).unwrap();
+        record
+    }
+
+    fn read_page(order: TimeOrder, limit: usize, cursor: Option<String>) -> (Vec<Address>, Option<String>) {
+        get_time_indexed_page(
+            &INDEX_ENTRY_TYPE.to_string(), INDEX_LINK_TYPE,
+            &TimeRange::default(), order, &PageRequest::new(Some(limit), cursor), false,
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_pages_resume_from_cursor() {
+        let first = append("2020-06-01T10:00:00Z", "page_first");
+        let second_a = append("2020-06-02T10:00:00Z", "page_second_a");
+        let second_b = append("2020-06-02T10:00:00Z", "page_second_b");
+        let last = append("2021-01-01T00:00:00Z", "page_last");
+
+        let (page, cursor) = read_page(TimeOrder::OldestFirst, 2, None);
+        assert_eq!(page, vec![first.to_owned(), second_a.to_owned()]);
+
+        // resumes between records sharing the cursor's timestamp
+        let (page, cursor) = read_page(TimeOrder::OldestFirst, 2, cursor);
+        assert_eq!(page, vec![second_b.to_owned(), last.to_owned()]);
+        assert_eq!(cursor, None);
+
+        let (page, cursor) = read_page(TimeOrder::NewestFirst, 3, None);
+        assert_eq!(page, vec![last, second_b, second_a]);
+        let (page, cursor) = read_page(TimeOrder::NewestFirst, 3, cursor);
+        assert_eq!(page, vec![first]);
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn test_deleted_records_not_counted_towards_limit() {
+        let deleted = append("2020-06-01T10:00:00Z", "limit_deleted");
+        let second = append("2020-06-02T10:00:00Z", "limit_second");
+        let third = append("2020-06-03T10:00:00Z", "limit_third");
+        crate::tombstones::write_tombstone(&deleted, true).unwrap();
+
+        let read = |include_deleted| get_time_indexed_addresses(
+            &INDEX_ENTRY_TYPE.to_string(), INDEX_LINK_TYPE,
+            &TimeRange::default(), TimeOrder::OldestFirst, Some(2), include_deleted,
+        ).unwrap();
+        assert_eq!(read(false), vec![second.to_owned(), third]);
+        assert_eq!(read(true), vec![deleted, second]);
+    }
+
+    #[test]
+    fn test_invalid_time_cursor() {
+        let cursor = encode_cursor_value("not a timestamp");
+        assert_eq!(
+            get_time_indexed_page(
+                &INDEX_ENTRY_TYPE.to_string(), INDEX_LINK_TYPE,
+                &TimeRange::default(), TimeOrder::OldestFirst, &PageRequest::new(Some(2), Some(cursor)), false,
+            ),
+            Err(GraphHelperError::InvalidCursor),
+        );
+    }
+}
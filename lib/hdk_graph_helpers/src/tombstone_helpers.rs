@@ -0,0 +1,228 @@
+/**
+ * Helpers related to record `tombstones`.
+ *
+ * A `tombstone` marks a record as deleted without removing any of its entries or
+ * links, such that it can later be restored. Each record has at most one tombstone,
+ * which lives at a deterministic address derived from the record's `key index`;
+ * deleting and restoring the record updates the tombstone in place.
+ *
+ * Records with a tombstone marked as deleted are hidden from `read_record_entry`
+ * and all index queries, unless explicitly requested.
+ *
+ * Any zome which soft-deletes its records must register `tombstone_entry_def` as
+ * one of its entry types for this to work. Only the agreement, commitment and intent
+ * zomes currently do so; records of all other types are deleted outright.
+ *
+ * Only the author of a record may delete or restore it. Since entries are content-addressed,
+ * any agent may commit a copy of a record's `key index`; so the record's author is taken to be
+ * the provenance of the earliest header published for its `key index`, and the tombstone's
+ * own header must be signed by them.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-06-11
+ */
+use hdk::{
+    holochain_json_api::{ json::JsonString, error::JsonError },
+    holochain_persistence_api::cas::content::Address,
+    holochain_core_types::{
+        entry::Entry,
+        entry::Entry::App as AppEntry,
+        chain_header::ChainHeader,
+        dna::entry_types::Sharing,
+        validation::{ EntryValidationData, ValidationData },
+    },
+    entry_definition::ValidatingEntryType,
+};
+use holochain_json_derive::{ DefaultJson };
+
+use super::{
+    errors::GraphHelperResult,
+    hdk_api::{
+        entry_address,
+        commit_entry,
+        get_entry,
+        update_entry,
+    },
+    identifiers::{
+        RECORD_TOMBSTONE_ENTRY_TYPE,
+        ERR_MSG_TOMBSTONE_NOT_AUTHOR,
+        ERR_MSG_TOMBSTONE_RECORD_CHANGED,
+        ERR_MSG_TOMBSTONE_REVISION,
+        ERR_MSG_TOMBSTONE_REMOVED,
+    },
+    entries::try_decode_entry,
+    keys::{ get_key_index_headers, earliest_header },
+};
+
+/// Deletion marker for the record identified by the `key index` at `record`
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordTombstone {
+    pub record: Address,
+    pub deleted: bool,
+    /// incremented on each change of state, so that every revision of the tombstone has a unique address
+    pub revision: u32,
+}
+
+/// Entry type definition for record tombstones. Tombstones must be public so that
+/// all agents agree on which records have been deleted.
+///
+pub fn tombstone_entry_def() -> ValidatingEntryType {
+    hdk::entry!(
+        name: RECORD_TOMBSTONE_ENTRY_TYPE,
+        description: "Marks a record as deleted, such that it can later be restored",
+        sharing: Sharing::Public,
+        validation_package: || {
+            // authorship is checked against the tombstone's own header, so no chain data is needed
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |validation_data: hdk::EntryValidationData<RecordTombstone>| {
+            validate_tombstone(validation_data)
+        }
+    )
+}
+
+fn validate_tombstone(validation_data: EntryValidationData<RecordTombstone>) -> Result<(), String> {
+    match validation_data {
+        EntryValidationData::Create{ entry, validation_data } => {
+            if entry.revision != 0 {
+                return Err(ERR_MSG_TOMBSTONE_REVISION.to_string());
+            }
+            validate_tombstone_author(&entry, &validation_data)
+        },
+        EntryValidationData::Modify{ new_entry, old_entry, old_entry_header: _, validation_data } => {
+            if new_entry.record != old_entry.record {
+                return Err(ERR_MSG_TOMBSTONE_RECORD_CHANGED.to_string());
+            }
+            if new_entry.revision != old_entry.revision + 1 {
+                return Err(ERR_MSG_TOMBSTONE_REVISION.to_string());
+            }
+            validate_tombstone_author(&new_entry, &validation_data)
+        },
+        EntryValidationData::Delete{ .. } => Err(ERR_MSG_TOMBSTONE_REMOVED.to_string()),
+    }
+}
+
+/// The record's `key index` is written by its author at creation, so the author of its
+/// earliest header must have signed the tombstone.
+fn validate_tombstone_author(tombstone: &RecordTombstone, validation_data: &ValidationData) -> Result<(), String> {
+    let headers = get_key_index_headers(&tombstone.record).map_err(|e| e.to_string())?;
+
+    if is_original_author(&headers, &validation_data.sources()) {
+        Ok(())
+    } else {
+        Err(ERR_MSG_TOMBSTONE_NOT_AUTHOR.to_string())
+    }
+}
+
+/// Determines whether any of `sources` authored the earliest of `headers`
+fn is_original_author(headers: &[ChainHeader], sources: &[Address]) -> bool {
+    earliest_header(headers)
+        .and_then(|(_, header)| header.provenances().first().map(|p| p.source()))
+        .map_or(false, |author| sources.contains(&author))
+}
+
+/// The first revision of a record's tombstone, whose address is used to locate all later revisions
+fn initial_tombstone(record: &Address) -> RecordTombstone {
+    RecordTombstone {
+        record: record.to_owned(),
+        deleted: true,
+        revision: 0,
+    }
+}
+
+fn tombstone_entry(tombstone: &RecordTombstone) -> Entry {
+    AppEntry(RECORD_TOMBSTONE_ENTRY_TYPE.into(), tombstone.to_owned().into())
+}
+
+//--------------------------------[ READ ]--------------------------------------
+
+/// Reads the most recent revision of the tombstone for the record with the given `key index`, if any
+///
+pub fn get_record_tombstone(record: &Address) -> GraphHelperResult<Option<RecordTombstone>> {
+    let initial_address = entry_address(&tombstone_entry(&initial_tombstone(record)))?;
+
+    // `get_entry` follows the update chain, so this resolves to the latest revision
+    match get_entry(&initial_address)? {
+        None => Ok(None),
+        entry => try_decode_entry(Ok(entry)),
+    }
+}
+
+/// Determines whether the record with the given `key index` has been soft-deleted
+///
+pub fn is_record_deleted(record: &Address) -> GraphHelperResult<bool> {
+    Ok(get_record_tombstone(record)?.map_or(false, |t| t.deleted))
+}
+
+/// Filters any soft-deleted records out of a list of `key index` addresses, preserving order
+///
+pub (crate) fn exclude_deleted_records(addresses: Vec<Address>) -> GraphHelperResult<Vec<Address>> {
+    let mut results = vec![];
+    for address in addresses {
+        if !is_record_deleted(&address)? {
+            results.push(address);
+        }
+    }
+    Ok(results)
+}
+
+//-------------------------------[ UPDATE ]-------------------------------------
+
+/// Marks the record with the given `key index` as `deleted` or not, creating its tombstone
+/// if necessary. Returns `false` if the record was already in the requested state.
+///
+pub (crate) fn write_tombstone(record: &Address, deleted: bool) -> GraphHelperResult<bool> {
+    match get_record_tombstone(record)? {
+        None => {
+            // records without a tombstone have never been deleted
+            if !deleted { return Ok(false); }
+            commit_entry(&tombstone_entry(&initial_tombstone(record)))?;
+        },
+        Some(ref current) if current.deleted == deleted => {
+            return Ok(false);
+        },
+        Some(current) => {
+            // :NOTE: update checks need the exact address of the most recent revision
+            let current_address = entry_address(&tombstone_entry(&current))?;
+            update_entry(tombstone_entry(&RecordTombstone {
+                deleted,
+                revision: current.revision + 1,
+                ..current
+            }), &current_address)?;
+        },
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use hdk::holochain_core_types::{
+        entry::entry_type::EntryType,
+        signature::{ Provenance, Signature },
+        time::Iso8601,
+    };
+
+    fn key_index_header(author: &str, secs: i64) -> ChainHeader {
+        ChainHeader::new(
+            &EntryType::App("thing_baseurl".into()),
+            &Address::from("QmThing"),
+            &[Provenance::new(Address::from(author), Signature::from("sig"))],
+            &None, &None, &None,
+            &Iso8601::new(secs, 0),
+        )
+    }
+
+    #[test]
+    fn test_tombstone_author_must_have_created_record() {
+        let original = key_index_header("alice", 1_000);
+        let copy = key_index_header("mallory", 2_000);
+
+        assert!(is_original_author(&[copy.to_owned(), original.to_owned()], &[Address::from("alice")]));
+        assert!(!is_original_author(&[copy.to_owned(), original.to_owned()], &[Address::from("mallory")]), "later commits of the key index do not confer authorship");
+        assert!(!is_original_author(&[], &[Address::from("alice")]));
+    }
+}
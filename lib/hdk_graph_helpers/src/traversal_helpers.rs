@@ -0,0 +1,268 @@
+/**
+ * Breadth-first traversal of the graph formed by record `indexes`.
+ *
+ * Starting from a single record, each of the `indexes` declared for the record's type
+ * is followed to find neighbouring records, and so on outward until the depth or node
+ * limits for the traversal are reached. The visited subgraph is returned as a flat list
+ * of nodes and the edges between them, so that answering questions like "everything
+ * upstream of this resource" takes a single zome call rather than one per hop.
+ *
+ * Each record is visited only once, so cycles do not cause runaway traversal. Edges
+ * which close a cycle are still returned, so that callers can detect them.
+ *
+ * :TODO: traversal is limited to the DNA it runs in. Where an `index` references
+ * records in another DNA, the node returned is the local `key index` of the foreign record.
+ *
+ * @see     ../README.md
+ * @package HDK Graph Helpers
+ * @since   2020-06-17
+ */
+use std::collections::{ HashSet, VecDeque };
+use hdk::{
+    holochain_json_api::{ json::JsonString, error::JsonError },
+    holochain_persistence_api::cas::content::Address,
+};
+use holochain_json_derive::{ DefaultJson };
+
+use super::{
+    errors::GraphHelperResult,
+    link_specs::BidirectionalIndex,
+    links::get_linked_addresses,
+    tombstones::is_record_deleted,
+};
+
+/// Number of hops followed from the starting record where no `max_depth` is requested
+pub const DEFAULT_TRAVERSAL_DEPTH: usize = 10;
+/// Number of records returned where no `max_nodes` is requested
+pub const DEFAULT_TRAVERSAL_NODES: usize = 500;
+
+/// Bounds for a single traversal
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TraversalLimits {
+    pub max_depth: usize,
+    pub max_nodes: usize,
+}
+
+impl TraversalLimits {
+    pub fn new(max_depth: Option<usize>, max_nodes: Option<usize>) -> Self {
+        TraversalLimits {
+            max_depth: max_depth.unwrap_or(DEFAULT_TRAVERSAL_DEPTH),
+            max_nodes: max_nodes.unwrap_or(DEFAULT_TRAVERSAL_NODES),
+        }
+    }
+}
+
+/// A record visited during traversal. `depth` is the number of hops from the starting record.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphNode {
+    pub address: Address,
+    pub entry_type: String,
+    pub depth: usize,
+}
+
+/// A link followed between two visited records
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphEdge {
+    pub source: Address,
+    pub target: Address,
+    pub link_type: String,
+    pub link_tag: String,
+}
+
+/// The result of a traversal. Nodes are listed in the order they were visited, starting
+/// with the record traversal began from. `truncated` is set if `max_nodes` was reached
+/// before all reachable records were visited.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Subgraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    pub truncated: bool,
+}
+
+//--------------------------------[ READ ]--------------------------------------
+
+/// Walk the graph breadth-first from the `key index` at `start_address`, whose base entry
+/// type is `start_entry_type`.
+///
+/// Each of the `indexes` is followed in its `forward` direction from any record whose type
+/// matches `forward.base_entry_type`; pass `reversed()` indexes to follow them the other way.
+/// Records which have been soft-deleted are not visited.
+///
+pub fn traverse_graph<A: AsRef<Address>>(
+    start_address: &A,
+    start_entry_type: &str,
+    indexes: &[BidirectionalIndex],
+    limits: &TraversalLimits,
+) -> GraphHelperResult<Subgraph> {
+    let mut result = Subgraph { nodes: vec![], edges: vec![], truncated: false };
+    if limits.max_nodes == 0 {
+        result.truncated = true;
+        return Ok(result);
+    }
+
+    // addresses which have been considered for inclusion, and those which were included
+    let mut seen: HashSet<Address> = HashSet::new();
+    let mut included: HashSet<Address> = HashSet::new();
+    let mut queue: VecDeque<GraphNode> = VecDeque::new();
+
+    let start = GraphNode {
+        address: start_address.as_ref().to_owned(),
+        entry_type: start_entry_type.to_string(),
+        depth: 0,
+    };
+    seen.insert(start.address.to_owned());
+    included.insert(start.address.to_owned());
+    queue.push_back(start.to_owned());
+    result.nodes.push(start);
+
+    while let Some(node) = queue.pop_front() {
+        if node.depth >= limits.max_depth {
+            continue;
+        }
+
+        for index in indexes.iter().filter(|i| i.forward.base_entry_type == node.entry_type) {
+            let link = &index.forward;
+
+            for target in get_linked_addresses(&node.address, link.link_type, link.link_tag)? {
+                if !seen.contains(&target) {
+                    if result.nodes.len() >= limits.max_nodes {
+                        result.truncated = true;
+                        continue;
+                    }
+                    seen.insert(target.to_owned());
+                    if is_record_deleted(&target)? {
+                        continue;
+                    }
+
+                    let next = GraphNode {
+                        address: target.to_owned(),
+                        entry_type: index.reverse.base_entry_type.to_string(),
+                        depth: node.depth + 1,
+                    };
+                    included.insert(target.to_owned());
+                    queue.push_back(next.to_owned());
+                    result.nodes.push(next);
+                }
+
+                // :NOTE: edges to already-visited records are kept, this is how cycles are reported
+                if included.contains(&target) {
+                    result.edges.push(GraphEdge {
+                        source: node.address.to_owned(),
+                        target,
+                        link_type: link.link_type.to_string(),
+                        link_tag: link.link_tag.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(all(test, feature = "mock-hdk"))]
+mod tests {
+    use super::*;
+    use crate::{
+        LinkSpec,
+        hdk_api::link_entries,
+        keys::create_key_index,
+    };
+
+    const THING_BASE_ENTRY_TYPE: &str = "test_thing_baseurl";
+    const OTHER_BASE_ENTRY_TYPE: &str = "test_other_baseurl";
+
+    const THING_NEXT_INDEX: BidirectionalIndex = BidirectionalIndex {
+        forward: LinkSpec { base_entry_type: THING_BASE_ENTRY_TYPE, link_type: "test_thing_next", link_tag: "next" },
+        reverse: LinkSpec { base_entry_type: THING_BASE_ENTRY_TYPE, link_type: "test_thing_previous", link_tag: "previous" },
+    };
+    const THING_OTHER_INDEX: BidirectionalIndex = BidirectionalIndex {
+        forward: LinkSpec { base_entry_type: THING_BASE_ENTRY_TYPE, link_type: "test_thing_other", link_tag: "other" },
+        reverse: LinkSpec { base_entry_type: OTHER_BASE_ENTRY_TYPE, link_type: "test_other_thing", link_tag: "thing" },
+    };
+
+    fn create_node(base_entry_type: &str, id: &str) -> Address {
+        create_key_index(&base_entry_type.into(), &Address::from(id)).unwrap()
+    }
+
+    fn link(source: &Address, target: &Address, index: &BidirectionalIndex) {
+        link_entries(source, target, index.forward.link_type, index.forward.link_tag).unwrap();
+        link_entries(target, source, index.reverse.link_type, index.reverse.link_tag).unwrap();
+    }
+
+    fn addresses(graph: &Subgraph) -> Vec<Address> {
+        graph.nodes.iter().map(|n| n.address.to_owned()).collect()
+    }
+
+    #[test]
+    fn test_cycles_visited_once() {
+        let a = create_node(THING_BASE_ENTRY_TYPE, "a");
+        let b = create_node(THING_BASE_ENTRY_TYPE, "b");
+        let c = create_node(THING_BASE_ENTRY_TYPE, "c");
+        link(&a, &b, &THING_NEXT_INDEX);
+        link(&b, &c, &THING_NEXT_INDEX);
+        link(&c, &a, &THING_NEXT_INDEX);
+
+        let graph = traverse_graph(&a, THING_BASE_ENTRY_TYPE, &[THING_NEXT_INDEX], &TraversalLimits::new(None, None)).unwrap();
+
+        assert_eq!(addresses(&graph), vec![a.to_owned(), b.to_owned(), c.to_owned()]);
+        assert_eq!(graph.nodes.iter().map(|n| n.depth).collect::<Vec<usize>>(), vec![0, 1, 2]);
+        assert_eq!(graph.edges.len(), 3, "edge closing the cycle is reported");
+        assert_eq!(graph.edges[2], GraphEdge {
+            source: c,
+            target: a,
+            link_type: "test_thing_next".to_string(),
+            link_tag: "next".to_string(),
+        });
+        assert!(!graph.truncated);
+    }
+
+    #[test]
+    fn test_indexes_followed_by_entry_type() {
+        let thing = create_node(THING_BASE_ENTRY_TYPE, "thing");
+        let other = create_node(OTHER_BASE_ENTRY_TYPE, "other");
+        let next = create_node(THING_BASE_ENTRY_TYPE, "next");
+        link(&thing, &other, &THING_OTHER_INDEX);
+        link(&thing, &next, &THING_NEXT_INDEX);
+
+        let indexes = [THING_OTHER_INDEX, THING_OTHER_INDEX.reversed()];
+        let graph = traverse_graph(&other, OTHER_BASE_ENTRY_TYPE, &indexes, &TraversalLimits::new(None, None)).unwrap();
+
+        // `next` is not reachable via the given indexes
+        assert_eq!(addresses(&graph), vec![other.to_owned(), thing.to_owned()]);
+        assert_eq!(graph.nodes[1].entry_type, THING_BASE_ENTRY_TYPE);
+        assert_eq!(graph.edges.len(), 2, "both directions of the index are reported");
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let a = create_node(THING_BASE_ENTRY_TYPE, "depth_a");
+        let b = create_node(THING_BASE_ENTRY_TYPE, "depth_b");
+        let c = create_node(THING_BASE_ENTRY_TYPE, "depth_c");
+        link(&a, &b, &THING_NEXT_INDEX);
+        link(&b, &c, &THING_NEXT_INDEX);
+
+        let graph = traverse_graph(&a, THING_BASE_ENTRY_TYPE, &[THING_NEXT_INDEX], &TraversalLimits::new(Some(1), None)).unwrap();
+
+        assert_eq!(addresses(&graph), vec![a, b]);
+        assert_eq!(graph.edges.len(), 1);
+    }
+
+    #[test]
+    fn test_node_limit() {
+        let hub = create_node(THING_BASE_ENTRY_TYPE, "hub");
+        for id in ["spoke_1", "spoke_2", "spoke_3"].iter() {
+            link(&hub, &create_node(THING_BASE_ENTRY_TYPE, id), &THING_NEXT_INDEX);
+        }
+
+        let graph = traverse_graph(&hub, THING_BASE_ENTRY_TYPE, &[THING_NEXT_INDEX], &TraversalLimits::new(None, Some(2))).unwrap();
+
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.edges.len(), 1, "edges to records beyond the limit are omitted");
+        assert!(graph.truncated);
+    }
+}
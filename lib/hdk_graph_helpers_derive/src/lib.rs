@@ -0,0 +1,157 @@
+/**
+ * Derive macros for `hdk_graph_helpers` record interfaces.
+ *
+ * `#[derive(Updateable)]` generates an `Updateable<UpdateRequest>` implementation for
+ * an entry struct, by merging each field of the entry with the `MaybeUndefined` field
+ * of the same name in the update request:
+ *
+ * - an undefined field in the request leaves the entry's value untouched
+ * - a null field in the request erases `Option` fields of the entry. Required
+ *   (non-`Option`) fields cannot be erased, and so are also left untouched.
+ * - any other value replaces the entry's value
+ *
+ * Individual fields may opt out of this behaviour via field attributes:
+ *
+ * - `#[updateable(immutable)]` fields are never modified by updates, and need not
+ *   be present in the update request
+ * - `#[updateable(merge_set)]` fields (which must be `Option<Vec<T>>`) have any
+ *   provided values appended to the existing set, rather than replacing it
+ * - `#[updateable(revision)]` fields (which must be integers) are incremented by every
+ *   update, and need not be present in the update request. See `record_interface::Revisioned`.
+ *
+ * The update request type defaults to `UpdateRequest` (as found in scope where the
+ * macro is used), and can be overridden with the container attribute
+ * `#[updateable(request = "MyUpdateRequest")]`.
+ *
+ * @see     ../../hdk_graph_helpers/README.md
+ * @package HDK Graph Helpers
+ * @since   2020-06-13
+ */
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Fields, Field, Ident, Lit, Meta, NestedMeta, Type,
+    Error, Result, Path,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+enum FieldBehaviour {
+    Default,
+    Immutable,
+    MergeSet,
+    Revision,
+}
+
+#[proc_macro_derive(Updateable, attributes(updateable))]
+pub fn derive_updateable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand_updateable(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand_updateable(input: &DeriveInput) -> Result<proc_macro2::TokenStream> {
+    let entry_type = &input.ident;
+    let request_type = get_request_type(input)?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(&input.ident, "Updateable can only be derived for structs with named fields")),
+        },
+        _ => return Err(Error::new_spanned(&input.ident, "Updateable can only be derived for structs")),
+    };
+
+    let field_updates = fields.iter()
+        .map(|field| {
+            let name = field.ident.as_ref().unwrap();
+            Ok(match get_field_behaviour(field)? {
+                FieldBehaviour::Immutable => quote! {
+                    #name: self.#name.to_owned()
+                },
+                FieldBehaviour::MergeSet => quote! {
+                    #name: e.#name.merge_set(&self.#name)
+                },
+                FieldBehaviour::Revision => quote! {
+                    #name: self.#name + 1
+                },
+                FieldBehaviour::Default => if is_option(&field.ty) {
+                    quote! { #name: e.#name.update_optional(&self.#name) }
+                } else {
+                    quote! { #name: e.#name.update_required(&self.#name) }
+                },
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        impl ::hdk_graph_helpers::record_interface::Updateable<#request_type> for #entry_type {
+            fn update_with(&self, e: &#request_type) -> #entry_type {
+                #entry_type {
+                    #(#field_updates,)*
+                }
+            }
+        }
+    })
+}
+
+/// Reads the update request type from `#[updateable(request = "...")]`, if present
+fn get_request_type(input: &DeriveInput) -> Result<Path> {
+    let mut request_type = None;
+    for meta in get_updateable_meta(&input.attrs)? {
+        match meta {
+            NestedMeta::Meta(Meta::NameValue(ref nv)) if nv.path.is_ident("request") => {
+                if request_type.is_some() {
+                    return Err(Error::new_spanned(nv, "duplicate updateable request type"));
+                }
+                request_type = match nv.lit {
+                    Lit::Str(ref s) => Some(s.parse()?),
+                    _ => return Err(Error::new_spanned(&nv.lit, "expected request type as a string")),
+                };
+            },
+            other => return Err(Error::new_spanned(other, "unknown updateable container attribute")),
+        }
+    }
+    Ok(request_type.unwrap_or_else(|| Ident::new("UpdateRequest", Span::call_site()).into()))
+}
+
+fn get_field_behaviour(field: &Field) -> Result<FieldBehaviour> {
+    let mut behaviour = FieldBehaviour::Default;
+    for meta in get_updateable_meta(&field.attrs)? {
+        let next = match meta {
+            NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("immutable") => FieldBehaviour::Immutable,
+            NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("merge_set") => FieldBehaviour::MergeSet,
+            NestedMeta::Meta(Meta::Path(ref path)) if path.is_ident("revision") => FieldBehaviour::Revision,
+            other => return Err(Error::new_spanned(other, "unknown updateable field attribute")),
+        };
+        if behaviour != FieldBehaviour::Default {
+            return Err(Error::new_spanned(field, "only one updateable behaviour may be specified per field"));
+        }
+        behaviour = next;
+    }
+    Ok(behaviour)
+}
+
+fn get_updateable_meta(attrs: &[syn::Attribute]) -> Result<Vec<NestedMeta>> {
+    let mut results = vec![];
+    for attr in attrs.iter().filter(|a| a.path.is_ident("updateable")) {
+        match attr.parse_meta()? {
+            Meta::List(list) => results.extend(list.nested),
+            other => return Err(Error::new_spanned(other, "expected #[updateable(...)]")),
+        }
+    }
+    Ok(results)
+}
+
+/// :NOTE: determined syntactically, so type aliases of `Option` will be treated as required fields
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) => type_path.path.segments.last()
+            .map(|segment| segment.ident == "Option").unwrap_or(false),
+        _ => false,
+    }
+}
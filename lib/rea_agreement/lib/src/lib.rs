@@ -16,12 +16,22 @@ use hdk_graph_helpers::{
     records::{
         create_record,
         read_record_entry,
+        read_record_entries,
+        read_record_history,
         update_record,
-        delete_record,
+        get_revision_id,
+        RecordRevision,
+        soft_delete_record,
+        restore_record,
     },
     links::{
         get_linked_addresses_with_foreign_key_as_type,
     },
+    integrity::{
+        RecordIndexDefinition,
+        IndexIntegrityReport,
+        check_index_integrity,
+    },
 };
 
 use vf_core::type_aliases::{
@@ -34,6 +44,36 @@ use hc_zome_rea_agreement_storage_consts::*;
 use hc_zome_rea_agreement_storage::*;
 use hc_zome_rea_agreement_rpc::*;
 
+use hc_zome_rea_commitment_storage_consts::{COMMITMENT_BASE_ENTRY_TYPE, COMMITMENT_CLAUSE_OF_INDEX};
+use hc_zome_rea_economic_event_storage_consts::{EVENT_BASE_ENTRY_TYPE, EVENT_REALIZATION_OF_INDEX};
+
+/// Indexes managed by the agreement DNA, as checked by `receive_check_index_integrity`
+const AGREEMENT_INDEXES: &[RecordIndexDefinition] = &[
+    RecordIndexDefinition {
+        base_entry_type: AGREEMENT_BASE_ENTRY_TYPE,
+        indexes: &[
+            COMMITMENT_CLAUSE_OF_INDEX.reversed(),
+            EVENT_REALIZATION_OF_INDEX.reversed(),
+        ],
+        remote: false,
+    },
+    // destinations of remote indexes from the planning & observation DNAs
+    RecordIndexDefinition {
+        base_entry_type: COMMITMENT_BASE_ENTRY_TYPE,
+        indexes: &[
+            COMMITMENT_CLAUSE_OF_INDEX,
+        ],
+        remote: true,
+    },
+    RecordIndexDefinition {
+        base_entry_type: EVENT_BASE_ENTRY_TYPE,
+        indexes: &[
+            EVENT_REALIZATION_OF_INDEX,
+        ],
+        remote: true,
+    },
+];
+
 pub fn receive_create_agreement(agreement: CreateRequest) -> ZomeApiResult<ResponseData> {
     handle_create_agreement(&agreement)
 }
@@ -42,31 +82,68 @@ pub fn receive_get_agreement(address: AgreementAddress) -> ZomeApiResult<Respons
     handle_get_agreement(&address)
 }
 
+pub fn receive_get_agreements_by_ids(addresses: Vec<AgreementAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+    Ok(handle_get_agreements_by_ids(&addresses))
+}
+
+pub fn receive_get_agreement_revisions(address: AgreementAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_agreement_revisions(&address)
+}
+
 pub fn receive_update_agreement(agreement: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_agreement(&agreement)
 }
 
+/// Agreements are only ever the destination of indexes managed by the commitments and events
+/// which reference them, so their links are left in place whilst soft-deleted.
 pub fn receive_delete_agreement(address: AgreementAddress) -> ZomeApiResult<bool> {
-    delete_record::<Entry>(&address)
+    Ok(soft_delete_record::<Entry>(&address)?)
+}
+
+/// Unlike commitments, no indexes are removed upon deletion and so there are none to re-create.
+pub fn receive_restore_agreement(address: AgreementAddress) -> ZomeApiResult<bool> {
+    Ok(restore_record(&address)?)
+}
+
+pub fn receive_check_index_integrity(repair: bool) -> ZomeApiResult<IndexIntegrityReport> {
+    Ok(check_index_integrity(AGREEMENT_INDEXES, repair)?)
 }
 
 fn handle_get_agreement(address: &AgreementAddress) -> ZomeApiResult<ResponseData> {
-    Ok(construct_response(address, &read_record_entry(address)?, get_link_fields(&address)))
+    construct_response(address, &read_record_entry(address)?, get_link_fields(&address)?)
+}
+
+fn handle_get_agreements_by_ids(addresses: &[AgreementAddress]) -> Vec<ZomeApiResult<ResponseData>> {
+    addresses.iter()
+        .zip(read_record_entries(addresses))
+        .map(|(address, entry)| construct_response(address, &entry?, get_link_fields(address)?))
+        .collect()
+}
+
+fn handle_get_agreement_revisions(address: &AgreementAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    read_record_history(address, AGREEMENT_INITIAL_ENTRY_LINK_TYPE)?
+        .into_iter()
+        .map(|revision: RecordRevision<Entry>| {
+            let response = construct_response(address, &revision.entry, (None, None))?;
+            Ok(revision.map(|_| response))
+        })
+        .collect()
 }
 
 fn handle_create_agreement(agreement: &CreateRequest) -> ZomeApiResult<ResponseData> {
     let (base_address, entry_resp): (AgreementAddress, Entry) = create_record(
         AGREEMENT_BASE_ENTRY_TYPE, AGREEMENT_ENTRY_TYPE,
         AGREEMENT_INITIAL_ENTRY_LINK_TYPE,
+        agreement.get_idempotency_key(),
         agreement.to_owned(),
     )?;
-    Ok(construct_response(&base_address, &entry_resp, get_link_fields(&base_address)))
+    construct_response(&base_address, &entry_resp, get_link_fields(&base_address)?)
 }
 
 fn handle_update_agreement(agreement: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let base_address = agreement.get_id();
-    let new_entry = update_record(AGREEMENT_ENTRY_TYPE, base_address, agreement)?;
-    Ok(construct_response(&base_address, &new_entry, get_link_fields(&base_address)))
+    let new_entry = update_record(AGREEMENT_ENTRY_TYPE, base_address, agreement.get_revision_id(), agreement)?;
+    construct_response(&base_address, &new_entry, get_link_fields(&base_address)?)
 }
 
 /// Create response from input DHT primitives
@@ -78,28 +155,29 @@ pub fn construct_response<'a>(
         Option<Cow<'a, Vec<CommitmentAddress>>>,
         Option<Cow<'a, Vec<EventAddress>>>,
     ),
-) -> ResponseData {
-    ResponseData {
+) -> ZomeApiResult<ResponseData> {
+    Ok(ResponseData {
         agreement: Response {
             id: address.to_owned(),
+            revision_id: get_revision_id(AGREEMENT_ENTRY_TYPE, e)?.into(),
             name: e.name.to_owned(),
             created: e.created.to_owned(),
             note: e.note.to_owned(),
             commitments: commitments.map(Cow::into_owned),
             economic_events: economic_events.map(Cow::into_owned),
         }
-    }
+    })
 }
 
 //---------------- READ ----------------
 
 // @see construct_response
-pub fn get_link_fields<'a>(agreement: &AgreementAddress) -> (
+pub fn get_link_fields<'a>(agreement: &AgreementAddress) -> ZomeApiResult<(
         Option<Cow<'a, Vec<CommitmentAddress>>>,
         Option<Cow<'a, Vec<EventAddress>>>,
-) {
-    (
-        Some(get_linked_addresses_with_foreign_key_as_type(agreement, AGREEMENT_COMMITMENTS_LINK_TYPE, AGREEMENT_COMMITMENTS_LINK_TAG)),
-        Some(get_linked_addresses_with_foreign_key_as_type(agreement, AGREEMENT_EVENTS_LINK_TYPE, AGREEMENT_EVENTS_LINK_TAG)),
-    )
+)> {
+    Ok((
+        Some(get_linked_addresses_with_foreign_key_as_type(agreement, AGREEMENT_COMMITMENTS_LINK_TYPE, AGREEMENT_COMMITMENTS_LINK_TAG)?),
+        Some(get_linked_addresses_with_foreign_key_as_type(agreement, AGREEMENT_EVENTS_LINK_TYPE, AGREEMENT_EVENTS_LINK_TAG)?),
+    ))
 }
@@ -14,8 +14,13 @@ extern crate serde_json;
 use holochain_json_api::{ json::JsonString, error::JsonError };
 use holochain_json_derive::{ DefaultJson };
 
-use hdk_graph_helpers::MaybeUndefined;
+use hdk_graph_helpers::{
+    MaybeUndefined,
+    records::RecordRevision,
+};
 use vf_core::type_aliases::{
+    Address,
+    RevisionId,
     Timestamp,
     CommitmentAddress,
     EventAddress,
@@ -32,6 +37,7 @@ pub use vf_core::type_aliases::{ AgreementAddress };
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub id: AgreementAddress,
+    pub revision_id: RevisionId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -54,6 +60,9 @@ pub struct ResponseData {
     pub agreement: Response,
 }
 
+/// I/O struct to describe a past revision of a record, as returned by `get_*_revisions`
+pub type RevisionResponse = RecordRevision<ResponseData>;
+
 //---------------- CREATE REQUEST ----------------
 
 /// I/O struct to describe the complete input record, including all managed links
@@ -67,10 +76,18 @@ pub struct CreateRequest {
     pub created: MaybeUndefined<Timestamp>,
     #[serde(default)]
     pub note: MaybeUndefined<String>,
+
+    // key supplied by the client to detect retried requests
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 impl<'a> CreateRequest {
     // :TODO: accessors for field data
+
+    pub fn get_idempotency_key(&'a self) -> Option<&String> {
+        self.idempotency_key.as_ref()
+    }
 }
 
 //---------------- UPDATE REQUEST ----------------
@@ -82,6 +99,8 @@ impl<'a> CreateRequest {
 pub struct UpdateRequest {
     pub id: AgreementAddress,
     #[serde(default)]
+    pub revision_id: Option<RevisionId>,
+    #[serde(default)]
     pub name: MaybeUndefined<String>,
     #[serde(default)]
     pub created: MaybeUndefined<Timestamp>,
@@ -94,6 +113,10 @@ impl<'a> UpdateRequest {
         &self.id
     }
 
+    pub fn get_revision_id(&'a self) -> Option<&Address> {
+        self.revision_id.as_ref().map(AsRef::as_ref)
+    }
+
     // :TODO: accessors for other field data
 }
 
@@ -26,7 +26,7 @@ use hc_zome_rea_agreement_rpc::{ CreateRequest, UpdateRequest };
 
 //---------------- RECORD INTERNALS & VALIDATION ----------------
 
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, Updateable)]
 pub struct Entry {
     pub name: Option<String>,
     pub created: Option<Timestamp>,
@@ -45,16 +45,3 @@ impl From<CreateRequest> for Entry {
         }
     }
 }
-
-//---------------- UPDATE ----------------
-
-/// Handles update operations by merging any newly provided fields
-impl Updateable<UpdateRequest> for Entry {
-    fn update_with(&self, e: &UpdateRequest) -> Entry {
-        Entry {
-            name: if !e.name.is_some() { self.name.to_owned() } else { e.name.to_owned().into() },
-            created: if !e.created.is_some() { self.created.to_owned() } else { e.created.to_owned().into() },
-            note: if !e.note.is_some() { self.note.to_owned() } else { e.note.to_owned().into() },
-        }
-    }
-}
@@ -10,7 +10,7 @@ use std::borrow::Cow;
 use hdk::{
     PUBLIC_TOKEN,
     prelude::Address,
-    error::{ ZomeApiResult, ZomeApiError },
+    error::{ ZomeApiResult },
 };
 
 use hdk_graph_helpers::{
@@ -18,21 +18,35 @@ use hdk_graph_helpers::{
     records::{
         create_record,
         read_record_entry,
+        read_record_entries,
+        read_record_history,
         update_record,
-        delete_record,
+        get_revision_id,
+        RecordRevision,
+        soft_delete_record,
+        restore_record,
     },
+    idempotency::get_idempotent_record_address,
     links::{
         get_linked_addresses_as_type,
     },
     local_indexes::{
-        query_direct_index_with_foreign_key,
-        query_direct_remote_index_with_foreign_key,
+        IndexQuery,
+        query_indexes_with_foreign_key,
+        query_indexes_with_foreign_key_paginated,
     },
+    pagination::Page,
     remote_indexes::{
         create_direct_remote_index,
         update_direct_remote_index,
         remove_direct_remote_index,
     },
+    errors::{ GraphHelperError, GraphHelperResult, throw_any_error },
+    integrity::{
+        RecordIndexDefinition,
+        IndexIntegrityReport,
+        check_index_integrity,
+    },
 };
 
 use vf_core::type_aliases::{
@@ -45,18 +59,85 @@ use hc_zome_rea_commitment_storage_consts::*;
 use hc_zome_rea_commitment_storage::*;
 use hc_zome_rea_commitment_rpc::*;
 
-use hc_zome_rea_process_storage_consts::{
-    PROCESS_BASE_ENTRY_TYPE,
-    PROCESS_COMMITMENT_INPUTS_LINK_TYPE, PROCESS_COMMITMENT_INPUTS_LINK_TAG,
-    PROCESS_COMMITMENT_OUTPUTS_LINK_TYPE, PROCESS_COMMITMENT_OUTPUTS_LINK_TAG,
+use hc_zome_rea_fulfillment_storage_consts::{FULFILLMENT_BASE_ENTRY_TYPE, FULFILLMENT_FULFILLS_INDEX};
+use hc_zome_rea_satisfaction_storage_consts::{
+    SATISFACTION_BASE_ENTRY_TYPE,
+    SATISFACTION_SATISFIEDBY_COMMITMENT_INDEX,
+    SATISFACTION_SATISFIES_INDEX,
 };
-use hc_zome_rea_fulfillment_storage_consts::{FULFILLMENT_FULFILLS_LINK_TYPE, FULFILLMENT_FULFILLS_LINK_TAG};
-use hc_zome_rea_satisfaction_storage_consts::{SATISFACTION_SATISFIEDBY_LINK_TYPE, SATISFACTION_SATISFIEDBY_LINK_TAG};
-use hc_zome_rea_agreement_storage_consts::{
-    AGREEMENT_BASE_ENTRY_TYPE,
-    AGREEMENT_COMMITMENTS_LINK_TYPE,
-    AGREEMENT_COMMITMENTS_LINK_TAG,
+use hc_zome_rea_intent_storage_consts::{
+    INTENT_BASE_ENTRY_TYPE,
+    INTENT_INPUT_OF_INDEX,
+    INTENT_OUTPUT_OF_INDEX,
 };
+use hc_zome_rea_proposed_intent_storage_consts::{PROPOSED_INTENT_BASE_ENTRY_TYPE, PROPOSED_INTENT_PUBLISHES_INDEX};
+use hc_zome_rea_process_storage_consts::{PROCESS_BASE_ENTRY_TYPE};
+use hc_zome_rea_agreement_storage_consts::{AGREEMENT_BASE_ENTRY_TYPE};
+
+/// Indexes managed by the planning DNA, as checked by `receive_check_index_integrity`
+const PLANNING_INDEXES: &[RecordIndexDefinition] = &[
+    RecordIndexDefinition {
+        base_entry_type: COMMITMENT_BASE_ENTRY_TYPE,
+        indexes: &[
+            COMMITMENT_INPUT_OF_INDEX,
+            COMMITMENT_OUTPUT_OF_INDEX,
+            COMMITMENT_CLAUSE_OF_INDEX,
+            FULFILLMENT_FULFILLS_INDEX.reversed(),
+            SATISFACTION_SATISFIEDBY_COMMITMENT_INDEX.reversed(),
+        ],
+        remote: false,
+    },
+    RecordIndexDefinition {
+        base_entry_type: INTENT_BASE_ENTRY_TYPE,
+        indexes: &[
+            INTENT_INPUT_OF_INDEX,
+            INTENT_OUTPUT_OF_INDEX,
+            SATISFACTION_SATISFIES_INDEX.reversed(),
+            PROPOSED_INTENT_PUBLISHES_INDEX.reversed(),
+        ],
+        remote: false,
+    },
+    RecordIndexDefinition {
+        base_entry_type: FULFILLMENT_BASE_ENTRY_TYPE,
+        indexes: &[
+            FULFILLMENT_FULFILLS_INDEX,
+        ],
+        remote: false,
+    },
+    RecordIndexDefinition {
+        base_entry_type: SATISFACTION_BASE_ENTRY_TYPE,
+        indexes: &[
+            SATISFACTION_SATISFIES_INDEX,
+            SATISFACTION_SATISFIEDBY_COMMITMENT_INDEX,
+        ],
+        remote: false,
+    },
+    RecordIndexDefinition {
+        base_entry_type: PROPOSED_INTENT_BASE_ENTRY_TYPE,
+        indexes: &[
+            PROPOSED_INTENT_PUBLISHES_INDEX,
+        ],
+        remote: false,
+    },
+    // destinations of remote indexes into the observation & agreement DNAs
+    RecordIndexDefinition {
+        base_entry_type: PROCESS_BASE_ENTRY_TYPE,
+        indexes: &[
+            COMMITMENT_INPUT_OF_INDEX.reversed(),
+            COMMITMENT_OUTPUT_OF_INDEX.reversed(),
+            INTENT_INPUT_OF_INDEX.reversed(),
+            INTENT_OUTPUT_OF_INDEX.reversed(),
+        ],
+        remote: true,
+    },
+    RecordIndexDefinition {
+        base_entry_type: AGREEMENT_BASE_ENTRY_TYPE,
+        indexes: &[
+            COMMITMENT_CLAUSE_OF_INDEX.reversed(),
+        ],
+        remote: true,
+    },
+];
 
 pub fn receive_create_commitment(commitment: CreateRequest) -> ZomeApiResult<ResponseData> {
     handle_create_commitment(&commitment)
@@ -66,6 +147,14 @@ pub fn receive_get_commitment(address: CommitmentAddress) -> ZomeApiResult<Respo
     handle_get_commitment(&address)
 }
 
+pub fn receive_get_commitments_by_ids(addresses: Vec<CommitmentAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+    Ok(handle_get_commitments_by_ids(&addresses))
+}
+
+pub fn receive_get_commitment_revisions(address: CommitmentAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_commitment_revisions(&address)
+}
+
 pub fn receive_update_commitment(commitment: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_commitment(&commitment)
 }
@@ -74,93 +163,116 @@ pub fn receive_delete_commitment(address: CommitmentAddress) -> ZomeApiResult<bo
     handle_delete_commitment(&address)
 }
 
+pub fn receive_restore_commitment(address: CommitmentAddress) -> ZomeApiResult<bool> {
+    handle_restore_commitment(&address)
+}
+
 pub fn receive_query_commitments(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
     handle_query_commitments(&params)
 }
 
+pub fn receive_query_commitments_paginated(params: QueryParams) -> ZomeApiResult<ResponsePage> {
+    handle_query_commitments_paginated(&params)
+}
+
+pub fn receive_check_index_integrity(repair: bool) -> ZomeApiResult<IndexIntegrityReport> {
+    Ok(check_index_integrity(PLANNING_INDEXES, repair)?)
+}
+
 fn handle_get_commitment(address: &CommitmentAddress) -> ZomeApiResult<ResponseData> {
     let entry = read_record_entry(&address)?;
-    Ok(construct_response(&address, &entry, get_link_fields(&address)))
+    construct_response(&address, &entry, get_link_fields(&address)?)
+}
+
+fn handle_get_commitments_by_ids(addresses: &[CommitmentAddress]) -> Vec<ZomeApiResult<ResponseData>> {
+    addresses.iter()
+        .zip(read_record_entries(addresses))
+        .map(|(address, entry)| construct_response(address, &entry?, get_link_fields(address)?))
+        .collect()
+}
+
+fn handle_get_commitment_revisions(address: &CommitmentAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    read_record_history(address, COMMITMENT_INITIAL_ENTRY_LINK_TYPE)?
+        .into_iter()
+        .map(|revision: RecordRevision<Entry>| {
+            let response = construct_response(address, &revision.entry, (None, None, None))?;
+            Ok(revision.map(|_| response))
+        })
+        .collect()
 }
 
 fn handle_create_commitment(commitment: &CreateRequest) -> ZomeApiResult<ResponseData> {
+    // retried requests resolve to the record created by the original, whose indexes have already been written
+    if let Some(existing) = get_idempotent_record_address(COMMITMENT_BASE_ENTRY_TYPE, commitment.get_idempotency_key())? {
+        return handle_get_commitment(&existing);
+    }
+
     let (base_address, entry_resp): (CommitmentAddress, Entry) = create_record(
         COMMITMENT_BASE_ENTRY_TYPE, COMMITMENT_ENTRY_TYPE,
         COMMITMENT_INITIAL_ENTRY_LINK_TYPE,
+        commitment.get_idempotency_key(),
         commitment.to_owned()
     )?;
 
     // handle link fields
     if let CreateRequest { input_of: MaybeUndefined::Some(input_of), .. } = commitment {
-        let _results = create_direct_remote_index(
+        throw_any_error(create_direct_remote_index(
             BRIDGED_OBSERVATION_DHT, "process", "index_committed_inputs", Address::from(PUBLIC_TOKEN.to_string()),
-            PROCESS_BASE_ENTRY_TYPE,
-            COMMITMENT_INPUT_OF_LINK_TYPE, COMMITMENT_INPUT_OF_LINK_TAG,
-            PROCESS_COMMITMENT_INPUTS_LINK_TYPE, PROCESS_COMMITMENT_INPUTS_LINK_TAG,
+            &COMMITMENT_INPUT_OF_INDEX,
             base_address.as_ref(),
             vec![(input_of.as_ref()).clone()],
-        );
+        ))?;
     };
     if let CreateRequest { output_of: MaybeUndefined::Some(output_of), .. } = commitment {
-        let _results = create_direct_remote_index(
+        throw_any_error(create_direct_remote_index(
             BRIDGED_OBSERVATION_DHT, "process", "index_committed_outputs", Address::from(PUBLIC_TOKEN.to_string()),
-            PROCESS_BASE_ENTRY_TYPE,
-            COMMITMENT_OUTPUT_OF_LINK_TYPE, COMMITMENT_OUTPUT_OF_LINK_TAG,
-            PROCESS_COMMITMENT_OUTPUTS_LINK_TYPE, PROCESS_COMMITMENT_OUTPUTS_LINK_TAG,
+            &COMMITMENT_OUTPUT_OF_INDEX,
             base_address.as_ref(),
             vec![(output_of.as_ref()).clone()],
-        );
+        ))?;
     };
     if let CreateRequest { clause_of: MaybeUndefined::Some(clause_of), .. } = commitment {
-        let _results = create_direct_remote_index(
+        throw_any_error(create_direct_remote_index(
             BRIDGED_AGREEMENT_DHT, "commitment_idx", "index_commitments", Address::from(PUBLIC_TOKEN.to_string()),
-            AGREEMENT_BASE_ENTRY_TYPE,
-            COMMITMENT_CLAUSE_OF_LINK_TYPE, COMMITMENT_CLAUSE_OF_LINK_TAG,
-            AGREEMENT_COMMITMENTS_LINK_TYPE, AGREEMENT_COMMITMENTS_LINK_TAG,
+            &COMMITMENT_CLAUSE_OF_INDEX,
             base_address.as_ref(),
             vec![(clause_of.as_ref()).clone()],
-        );
+        ))?;
     };
 
     // :TODO: pass results from link creation rather than re-reading
-    Ok(construct_response(&base_address, &entry_resp, get_link_fields(&base_address)))
+    construct_response(&base_address, &entry_resp, get_link_fields(&base_address)?)
 }
 
 fn handle_update_commitment(commitment: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let address = commitment.get_id();
-    let new_entry = update_record(COMMITMENT_ENTRY_TYPE, &address, commitment)?;
+    let new_entry = update_record(COMMITMENT_ENTRY_TYPE, &address, commitment.get_revision_id(), commitment)?;
 
     // handle link fields
     if MaybeUndefined::Undefined != commitment.input_of {
-        let _results = update_direct_remote_index(
+        throw_any_error(update_direct_remote_index(
             BRIDGED_OBSERVATION_DHT, "process", "index_committed_inputs", Address::from(PUBLIC_TOKEN.to_string()),
-            PROCESS_BASE_ENTRY_TYPE,
-            COMMITMENT_INPUT_OF_LINK_TYPE, COMMITMENT_INPUT_OF_LINK_TAG,
-            PROCESS_COMMITMENT_INPUTS_LINK_TYPE, PROCESS_COMMITMENT_INPUTS_LINK_TAG,
+            &COMMITMENT_INPUT_OF_INDEX,
             address, &commitment.input_of,
-        );
+        )?)?;
     }
     if MaybeUndefined::Undefined != commitment.output_of {
-        let _results = update_direct_remote_index(
+        throw_any_error(update_direct_remote_index(
             BRIDGED_OBSERVATION_DHT, "process", "index_committed_outputs", Address::from(PUBLIC_TOKEN.to_string()),
-            PROCESS_BASE_ENTRY_TYPE,
-            COMMITMENT_OUTPUT_OF_LINK_TYPE, COMMITMENT_OUTPUT_OF_LINK_TAG,
-            PROCESS_COMMITMENT_OUTPUTS_LINK_TYPE, PROCESS_COMMITMENT_OUTPUTS_LINK_TAG,
+            &COMMITMENT_OUTPUT_OF_INDEX,
             address, &commitment.output_of,
-        );
+        )?)?;
     }
     if MaybeUndefined::Undefined != commitment.clause_of {
-        let _results = update_direct_remote_index(
+        throw_any_error(update_direct_remote_index(
             BRIDGED_AGREEMENT_DHT, "commitment_idx", "index_commitments", Address::from(PUBLIC_TOKEN.to_string()),
-            AGREEMENT_BASE_ENTRY_TYPE,
-            COMMITMENT_CLAUSE_OF_LINK_TYPE, COMMITMENT_CLAUSE_OF_LINK_TAG,
-            AGREEMENT_COMMITMENTS_LINK_TYPE, AGREEMENT_COMMITMENTS_LINK_TAG,
+            &COMMITMENT_CLAUSE_OF_INDEX,
             address, &commitment.clause_of,
-        );
+        )?)?;
     }
 
     // :TODO: optimise this- should pass results from `replace_direct_index` instead of retrieving from `get_link_fields` where updates
-    Ok(construct_response(address, &new_entry, get_link_fields(address)))
+    construct_response(address, &new_entry, get_link_fields(address)?)
 }
 
 fn handle_delete_commitment(address: &CommitmentAddress) -> ZomeApiResult<bool> {
@@ -168,103 +280,125 @@ fn handle_delete_commitment(address: &CommitmentAddress) -> ZomeApiResult<bool>
     let entry: Entry = read_record_entry(&address)?;
 
     // handle link fields
+    // :NOTE: indexes into other DNAs are removed so that they no longer reference the record, and are re-created
+    // upon restore. Other indexes are retained, and deleted records are filtered out of query results on read.
     if let Some(process_address) = entry.input_of {
-        let _results = remove_direct_remote_index(
+        throw_any_error(remove_direct_remote_index(
             BRIDGED_OBSERVATION_DHT, "process", "index_committed_inputs", Address::from(PUBLIC_TOKEN.to_string()),
-            PROCESS_BASE_ENTRY_TYPE,
-            COMMITMENT_INPUT_OF_LINK_TYPE, COMMITMENT_INPUT_OF_LINK_TAG,
-            PROCESS_COMMITMENT_INPUTS_LINK_TYPE, PROCESS_COMMITMENT_INPUTS_LINK_TAG,
+            &COMMITMENT_INPUT_OF_INDEX,
             address, &process_address,
-        );
+        ))?;
     }
     if let Some(process_address) = entry.output_of {
-        let _results = remove_direct_remote_index(
+        throw_any_error(remove_direct_remote_index(
             BRIDGED_OBSERVATION_DHT, "process", "index_committed_outputs", Address::from(PUBLIC_TOKEN.to_string()),
-            PROCESS_BASE_ENTRY_TYPE,
-            COMMITMENT_OUTPUT_OF_LINK_TYPE, COMMITMENT_OUTPUT_OF_LINK_TAG,
-            PROCESS_COMMITMENT_OUTPUTS_LINK_TYPE, PROCESS_COMMITMENT_OUTPUTS_LINK_TAG,
+            &COMMITMENT_OUTPUT_OF_INDEX,
             address, &process_address,
-        );
+        ))?;
     }
     if let Some(agreement_address) = entry.clause_of {
-        let _results = remove_direct_remote_index(
+        throw_any_error(remove_direct_remote_index(
             BRIDGED_AGREEMENT_DHT, "commitment_idx", "index_commitments", Address::from(PUBLIC_TOKEN.to_string()),
-            AGREEMENT_BASE_ENTRY_TYPE,
-            COMMITMENT_CLAUSE_OF_LINK_TYPE, COMMITMENT_CLAUSE_OF_LINK_TAG,
-            AGREEMENT_COMMITMENTS_LINK_TYPE, AGREEMENT_COMMITMENTS_LINK_TAG,
+            &COMMITMENT_CLAUSE_OF_INDEX,
             address, &agreement_address,
-        );
+        ))?;
     }
 
-    // delete entry last as it must be present in order for links to be removed
-    delete_record::<Entry>(&address)
+    // commitments are soft-deleted, so that they can be restored later
+    Ok(soft_delete_record::<Entry>(&address)?)
+}
+
+fn handle_restore_commitment(address: &CommitmentAddress) -> ZomeApiResult<bool> {
+    if !restore_record(&address)? {
+        return Ok(false);
+    }
+
+    // re-create remote indexes removed upon deletion
+    let entry: Entry = read_record_entry(&address)?;
+    if let Some(process_address) = entry.input_of {
+        throw_any_error(create_direct_remote_index(
+            BRIDGED_OBSERVATION_DHT, "process", "index_committed_inputs", Address::from(PUBLIC_TOKEN.to_string()),
+            &COMMITMENT_INPUT_OF_INDEX,
+            address.as_ref(),
+            vec![process_address.as_ref().clone()],
+        ))?;
+    }
+    if let Some(process_address) = entry.output_of {
+        throw_any_error(create_direct_remote_index(
+            BRIDGED_OBSERVATION_DHT, "process", "index_committed_outputs", Address::from(PUBLIC_TOKEN.to_string()),
+            &COMMITMENT_OUTPUT_OF_INDEX,
+            address.as_ref(),
+            vec![process_address.as_ref().clone()],
+        ))?;
+    }
+    if let Some(agreement_address) = entry.clause_of {
+        throw_any_error(create_direct_remote_index(
+            BRIDGED_AGREEMENT_DHT, "commitment_idx", "index_commitments", Address::from(PUBLIC_TOKEN.to_string()),
+            &COMMITMENT_CLAUSE_OF_INDEX,
+            address.as_ref(),
+            vec![agreement_address.as_ref().clone()],
+        ))?;
+    }
+
+    Ok(true)
 }
 
 fn handle_query_commitments(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(CommitmentAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
-
-    // :TODO: implement proper AND search rather than exclusive operations
-    match &params.fulfilled_by {
-        Some(fulfilled_by) => {
-            entries_result = query_direct_index_with_foreign_key(
-                fulfilled_by, FULFILLMENT_FULFILLS_LINK_TYPE, FULFILLMENT_FULFILLS_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
-    match &params.satisfies {
-        Some(satisfies) => {
-            entries_result = query_direct_index_with_foreign_key(
-                satisfies, SATISFACTION_SATISFIEDBY_LINK_TYPE, SATISFACTION_SATISFIEDBY_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
-    match &params.input_of {
-        Some(input_of) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
-                input_of, PROCESS_BASE_ENTRY_TYPE,
-                PROCESS_COMMITMENT_INPUTS_LINK_TYPE, PROCESS_COMMITMENT_INPUTS_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
-    match &params.output_of {
-        Some(output_of) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
-                output_of, PROCESS_BASE_ENTRY_TYPE,
-                PROCESS_COMMITMENT_OUTPUTS_LINK_TYPE, PROCESS_COMMITMENT_OUTPUTS_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
-    match &params.clause_of {
-        Some(clause_of) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
-                clause_of, AGREEMENT_BASE_ENTRY_TYPE,
-                AGREEMENT_COMMITMENTS_LINK_TYPE, AGREEMENT_COMMITMENTS_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
+    if params.is_page_request() {
+        return Err(GraphHelperError::PaginationUnsupported.into());
+    }
 
+    let entries_result: GraphHelperResult<Vec<(CommitmentAddress, Option<Entry>)>> = query_indexes_with_foreign_key(&build_query(params), params.include_deleted.unwrap_or(false));
+
+    handle_list_output(entries_result)
+}
+
+fn handle_query_commitments_paginated(params: &QueryParams) -> ZomeApiResult<ResponsePage> {
+    let page: Page<(CommitmentAddress, Option<Entry>)> = query_indexes_with_foreign_key_paginated(&build_query(params), &params.get_page_request(), params.include_deleted.unwrap_or(false))?;
+
+    Ok(ResponsePage {
+        results: handle_list_output(Ok(page.results))?,
+        next_cursor: page.next_cursor,
+    })
+}
+
+fn build_query(params: &QueryParams) -> IndexQuery {
+    let mut filters: Vec<IndexQuery> = vec![];
+
+    if let Some(fulfilled_by) = &params.fulfilled_by {
+        filters.push(IndexQuery::local(fulfilled_by, &FULFILLMENT_FULFILLS_INDEX.forward));
+    }
+    if let Some(satisfies) = &params.satisfies {
+        filters.push(IndexQuery::local(satisfies, &SATISFACTION_SATISFIEDBY_COMMITMENT_INDEX.forward));
+    }
+    if let Some(input_of) = &params.input_of {
+        filters.push(IndexQuery::remote(input_of, &COMMITMENT_INPUT_OF_INDEX.reverse));
+    }
+    if let Some(output_of) = &params.output_of {
+        filters.push(IndexQuery::remote(output_of, &COMMITMENT_OUTPUT_OF_INDEX.reverse));
+    }
+    if let Some(clause_of) = &params.clause_of {
+        filters.push(IndexQuery::remote(clause_of, &COMMITMENT_CLAUSE_OF_INDEX.reverse));
+    }
+
+    IndexQuery::And(filters)
+}
+
+fn handle_list_output(entries_result: GraphHelperResult<Vec<(CommitmentAddress, Option<Entry>)>>) -> ZomeApiResult<Vec<ResponseData>> {
     match entries_result {
-        Ok(entries) => Ok(
-            entries.iter()
-                .map(|(entry_base_address, maybe_entry)| {
-                    match maybe_entry {
-                        Some(entry) => Ok(construct_response(
-                            entry_base_address,
-                            &entry,
-                            get_link_fields(entry_base_address),
-                        )),
-                        None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
-                    }
+        Ok(entries) => entries.iter()
+            // skip any referenced entries which could not be located
+            .filter_map(|(entry_base_address, maybe_entry)| {
+                maybe_entry.as_ref().map(|entry| -> ZomeApiResult<ResponseData> {
+                    construct_response(
+                        entry_base_address,
+                        &entry,
+                        get_link_fields(entry_base_address)?,
+                    )
                 })
-                .filter_map(Result::ok)
-                .collect()
-        ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
+            })
+            .collect(),
+        Err(e) => Err(e.into()),
     }
 }
 
@@ -279,10 +413,11 @@ pub fn construct_response<'a>(
         Option<Cow<'a, Vec<SatisfactionAddress>>>,
         Option<Cow<'a, Vec<AgentAddress>>>,
     )
-) -> ResponseData {
-    ResponseData {
+) -> ZomeApiResult<ResponseData> {
+    Ok(ResponseData {
         commitment: Response {
             id: address.to_owned(),
+            revision_id: get_revision_id(COMMITMENT_ENTRY_TYPE, e)?.into(),
             action: e.action.to_owned(),
             note: e.note.to_owned(),
             input_of: e.input_of.to_owned(),
@@ -309,20 +444,20 @@ pub fn construct_response<'a>(
             satisfies: satisfactions.map(Cow::into_owned),
             involved_agents: involved_agents.map(Cow::into_owned),
         }
-    }
+    })
 }
 
 //---------------- READ ----------------
 
 // @see construct_response
-pub fn get_link_fields<'a>(commitment: &CommitmentAddress) -> (
+pub fn get_link_fields<'a>(commitment: &CommitmentAddress) -> ZomeApiResult<(
     Option<Cow<'a, Vec<FulfillmentAddress>>>,
     Option<Cow<'a, Vec<SatisfactionAddress>>>,
     Option<Cow<'a, Vec<AgentAddress>>>,
-) {
-    (
-        Some(get_linked_addresses_as_type(commitment, COMMITMENT_FULFILLEDBY_LINK_TYPE, COMMITMENT_FULFILLEDBY_LINK_TAG)),
-        Some(get_linked_addresses_as_type(commitment, COMMITMENT_SATISFIES_LINK_TYPE, COMMITMENT_SATISFIES_LINK_TAG)),
+)> {
+    Ok((
+        Some(get_linked_addresses_as_type(commitment, COMMITMENT_FULFILLEDBY_LINK_TYPE, COMMITMENT_FULFILLEDBY_LINK_TAG)?),
+        Some(get_linked_addresses_as_type(commitment, COMMITMENT_SATISFIES_LINK_TYPE, COMMITMENT_SATISFIES_LINK_TAG)?),
         None,   // :TODO:
-    )
+    ))
 }
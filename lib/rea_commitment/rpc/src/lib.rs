@@ -4,6 +4,9 @@
  * Required by packages wishing to interact with the zome via its standard RPC interface,
  * and by the zome API handlers accepting these parameters.
  *
+ * Non-integer `hasNumericalValue`s of committed quantities are returned as decimal strings.
+ * @see `vf_core::measurement::QuantityValue`
+ *
  * @package Holo-REA
  */
 extern crate serde;
@@ -17,10 +20,14 @@ use holochain_json_derive::{ DefaultJson };
 use hdk_graph_helpers::{
     MaybeUndefined,
     maybe_undefined::default_false,
+    pagination::{ PageRequest, is_page_request },
+    records::RecordRevision,
 };
 use vf_core::{
     measurement::QuantityValue,
     type_aliases::{
+        Address,
+        RevisionId,
         ActionId,
         Timestamp,
         ExternalURL,
@@ -47,6 +54,7 @@ pub use vf_core::type_aliases::{ CommitmentAddress };
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub id: CommitmentAddress,
+    pub revision_id: RevisionId,
     pub action: ActionId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
@@ -108,6 +116,9 @@ pub struct ResponseData {
     pub commitment: Response,
 }
 
+/// I/O struct to describe a past revision of a record, as returned by `get_*_revisions`
+pub type RevisionResponse = RecordRevision<ResponseData>;
+
 //---------------- CREATE REQUEST ----------------
 
 /// I/O struct to describe the complete input record, including all managed links
@@ -156,10 +167,18 @@ pub struct CreateRequest {
     pub finished: MaybeUndefined<bool>,
     #[serde(default)]
     pub in_scope_of: MaybeUndefined<Vec<String>>,
+
+    // key supplied by the client to detect retried requests
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 impl<'a> CreateRequest {
     // :TODO: accessors for field data
+
+    pub fn get_idempotency_key(&'a self) -> Option<&String> {
+        self.idempotency_key.as_ref()
+    }
 }
 
 //---------------- UPDATE REQUEST ----------------
@@ -171,6 +190,8 @@ impl<'a> CreateRequest {
 pub struct UpdateRequest {
     pub id: CommitmentAddress,
     #[serde(default)]
+    pub revision_id: Option<RevisionId>,
+    #[serde(default)]
     pub action: MaybeUndefined<ActionId>,
     #[serde(default)]
     pub note: MaybeUndefined<String>,
@@ -221,6 +242,10 @@ impl<'a> UpdateRequest {
         &self.id
     }
 
+    pub fn get_revision_id(&'a self) -> Option<&Address> {
+        self.revision_id.as_ref().map(AsRef::as_ref)
+    }
+
     // :TODO: accessors for other field data
 }
 
@@ -234,4 +259,30 @@ pub struct QueryParams {
     pub fulfilled_by: Option<FulfillmentAddress>,
     pub satisfies: Option<SatisfactionAddress>,
     pub clause_of: Option<AgreementAddress>,
+
+    // set to also return records which have been deleted
+    pub include_deleted: Option<bool>,
+
+    // pagination parameters, used by paginated query endpoints only
+    pub limit: Option<usize>,
+    pub cursor: Option<String>,
+}
+
+impl<'a> QueryParams {
+    pub fn get_page_request(&'a self) -> PageRequest {
+        PageRequest::new(self.limit, self.cursor.to_owned())
+    }
+
+    pub fn is_page_request(&'a self) -> bool {
+        is_page_request(&self.limit, &self.cursor)
+    }
+}
+
+/// I/O struct to describe a single page of query results, along with the cursor to request the next page
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponsePage {
+    pub results: Vec<ResponseData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
@@ -5,6 +5,17 @@
  *
  * @package Holo-REA
  */
+use hdk_graph_helpers::{ LinkSpec, BidirectionalIndex };
+use hc_zome_rea_process_storage_consts::{
+    PROCESS_BASE_ENTRY_TYPE,
+    PROCESS_COMMITMENT_INPUTS_LINK_TYPE, PROCESS_COMMITMENT_INPUTS_LINK_TAG,
+    PROCESS_COMMITMENT_OUTPUTS_LINK_TYPE, PROCESS_COMMITMENT_OUTPUTS_LINK_TAG,
+};
+use hc_zome_rea_agreement_storage_consts::{
+    AGREEMENT_BASE_ENTRY_TYPE,
+    AGREEMENT_COMMITMENTS_LINK_TYPE, AGREEMENT_COMMITMENTS_LINK_TAG,
+};
+
 pub const COMMITMENT_BASE_ENTRY_TYPE: &str = "vf_commitment_baseurl";
 pub const COMMITMENT_INITIAL_ENTRY_LINK_TYPE: &str = "vf_commitment_entry";
 pub const COMMITMENT_ENTRY_TYPE: &str = "vf_commitment";
@@ -19,5 +30,21 @@ pub const COMMITMENT_OUTPUT_OF_LINK_TAG: &str = "output_of";
 pub const COMMITMENT_CLAUSE_OF_LINK_TYPE: &str = "vf_commitment_clause_of";
 pub const COMMITMENT_CLAUSE_OF_LINK_TAG: &str = "clause_of";
 
+/// Commitments consuming or using resources in a process
+pub const COMMITMENT_INPUT_OF_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: COMMITMENT_BASE_ENTRY_TYPE, link_type: COMMITMENT_INPUT_OF_LINK_TYPE, link_tag: COMMITMENT_INPUT_OF_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: PROCESS_BASE_ENTRY_TYPE, link_type: PROCESS_COMMITMENT_INPUTS_LINK_TYPE, link_tag: PROCESS_COMMITMENT_INPUTS_LINK_TAG },
+};
+/// Commitments producing resources from a process
+pub const COMMITMENT_OUTPUT_OF_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: COMMITMENT_BASE_ENTRY_TYPE, link_type: COMMITMENT_OUTPUT_OF_LINK_TYPE, link_tag: COMMITMENT_OUTPUT_OF_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: PROCESS_BASE_ENTRY_TYPE, link_type: PROCESS_COMMITMENT_OUTPUTS_LINK_TYPE, link_tag: PROCESS_COMMITMENT_OUTPUTS_LINK_TAG },
+};
+/// Commitments forming part of an agreement
+pub const COMMITMENT_CLAUSE_OF_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: COMMITMENT_BASE_ENTRY_TYPE, link_type: COMMITMENT_CLAUSE_OF_LINK_TYPE, link_tag: COMMITMENT_CLAUSE_OF_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: AGREEMENT_BASE_ENTRY_TYPE, link_type: AGREEMENT_COMMITMENTS_LINK_TYPE, link_tag: AGREEMENT_COMMITMENTS_LINK_TAG },
+};
+
 pub const BRIDGED_OBSERVATION_DHT: &str = "vf_observation";
 pub const BRIDGED_AGREEMENT_DHT: &str = "vf_agreement";
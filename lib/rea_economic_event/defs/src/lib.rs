@@ -12,6 +12,7 @@ use hdk::prelude::*;
 use hc_zome_rea_fulfillment_storage_consts::FULFILLMENT_BASE_ENTRY_TYPE;
 use hc_zome_rea_process_storage_consts::PROCESS_BASE_ENTRY_TYPE;
 use hc_zome_rea_satisfaction_storage_consts::SATISFACTION_BASE_ENTRY_TYPE;
+use hc_zome_rea_economic_resource_storage_consts::RESOURCE_BASE_ENTRY_TYPE;
 use hc_zome_rea_economic_event_storage_consts::*;
 use hc_zome_rea_economic_event_storage::{
     Entry as EconomicEventEntry,
@@ -31,7 +32,8 @@ pub fn entry_def() -> ValidatingEntryType {
                 let record: EconomicEventEntry = entry;
                 let result = record.validate_or_fields();
                 if result.is_ok() {
-                    return record.validate_action();
+                    record.validate_action()?;
+                    return record.validate_quantity_dimensions();
                 }
                 return result;
             }
@@ -41,7 +43,8 @@ pub fn entry_def() -> ValidatingEntryType {
                 let record: EconomicEventEntry = new_entry;
                 let result = record.validate_or_fields();
                 if result.is_ok() {
-                    return record.validate_action();
+                    record.validate_action()?;
+                    return record.validate_quantity_dimensions();
                 }
                 return result;
             }
@@ -121,11 +124,22 @@ pub fn base_entry_def() -> ValidatingEntryType {
                 validation: | _validation_data: hdk::LinkValidationData| {
                     Ok(())
                 }
+            ),
+            to!(
+                RESOURCE_BASE_ENTRY_TYPE,
+                link_type: EVENT_AFFECTS_RESOURCE_LINK_TYPE,
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
             )
         ]
     )
 }
 
+/// Root anchor of the unordered index used before the time index, read only by the legacy index migration
 pub fn root_entry_def() -> ValidatingEntryType {
     entry!(
         name: EVENT_INDEX_ROOT_ENTRY_TYPE,
@@ -153,3 +167,43 @@ pub fn root_entry_def() -> ValidatingEntryType {
         ]
     )
 }
+
+pub fn time_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: EVENT_TIME_INDEX_ENTRY_TYPE,
+        description: "Year, month & day anchors which order all Economic Events stored in this zome chronologically.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                EVENT_TIME_INDEX_ENTRY_TYPE,
+                link_type: EVENT_TIME_INDEX_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            ),
+            to!(
+                EVENT_BASE_ENTRY_TYPE,
+                link_type: EVENT_TIME_INDEX_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
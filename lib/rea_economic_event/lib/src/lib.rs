@@ -7,9 +7,11 @@
  * @package Holo-REA
  */
 use std::borrow::Cow;
+use std::convert::TryFrom;
 use hdk::{
     PUBLIC_TOKEN,
     prelude::Address,
+    holochain_core_types::entry::AppEntryValue,
     error::{ ZomeApiResult, ZomeApiError },
 };
 
@@ -18,31 +20,59 @@ use hdk_graph_helpers::{
     records::{
         create_record,
         read_record_entry,
+        read_record_entries,
+        read_record_history,
         update_record,
+        update_record_with,
+        get_revision_id,
+        RecordRevision,
         delete_record,
-    },
-    links::{
-        link_entries,
-        get_linked_addresses_as_type,
+        discard_created_record,
+        get_record_created_time,
     },
     anchors::{
-        create_anchor_index,
         read_anchored_record_entries,
+        remove_from_anchor_index,
+    },
+    idempotency::get_idempotent_record_address,
+    saga::{ Saga, run_saga },
+    links::{
+        get_linked_addresses_as_type,
     },
     local_indexes::{
-        create_direct_index,
+        IndexQuery,
         delete_direct_index,
-        query_direct_index_with_foreign_key,
-        query_direct_remote_index_with_foreign_key,
+        query_indexes_with_foreign_key,
+        query_indexes_with_foreign_key_paginated,
+    },
+    pagination::Page,
+    field_selection::FieldSelection,
+    time_indexes::{
+        TimeRange,
+        TimeOrder,
+        append_to_time_index,
+        remove_from_time_index,
+        read_time_indexed_record_entries,
+        read_time_indexed_record_entries_paginated,
     },
     remote_indexes::{
         create_direct_remote_index,
         update_direct_remote_index,
         remove_direct_remote_index,
+        discard_direct_remote_index,
         create_direct_remote_index_destination,
+        delete_direct_remote_index_destination,
+    },
+    errors::{ GraphHelperError, GraphHelperResult, throw_any_error },
+    integrity::{
+        RecordIndexDefinition,
+        IndexIntegrityReport,
+        check_index_integrity,
     },
 };
 
+use vf_core::measurement::{ QuantityValue, UnitConversions };
+use vf_actions::check_action_dimension;
 use vf_core::type_aliases::{
     EventAddress,
     ResourceAddress,
@@ -52,12 +82,8 @@ use vf_core::type_aliases::{
     ProcessSpecificationAddress,
 };
 
-use hc_zome_rea_fulfillment_storage_consts::{FULFILLMENT_FULFILLEDBY_LINK_TYPE, FULFILLMENT_FULFILLEDBY_LINK_TAG};
-use hc_zome_rea_satisfaction_storage_consts::{SATISFACTION_SATISFIEDBY_LINK_TYPE, SATISFACTION_SATISFIEDBY_LINK_TAG};
-use hc_zome_rea_resource_specification_storage_consts::{
-    ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE,
-    RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TYPE, RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TAG,
-};
+use hc_zome_rea_fulfillment_storage_consts::{FULFILLMENT_BASE_ENTRY_TYPE, FULFILLMENT_FULFILLEDBY_INDEX};
+use hc_zome_rea_satisfaction_storage_consts::{SATISFACTION_BASE_ENTRY_TYPE, SATISFACTION_SATISFIEDBY_EVENT_INDEX};
 
 use hc_zome_rea_economic_event_storage_consts::*;
 use hc_zome_rea_economic_event_storage::{
@@ -66,15 +92,19 @@ use hc_zome_rea_economic_event_storage::{
 use hc_zome_rea_economic_event_rpc::{
     ResourceInventoryType,
     QueryParams,
+    TimeQueryParams,
     CreateRequest as EconomicEventCreateRequest,
     UpdateRequest as EconomicEventUpdateRequest,
     Response,
     ResponseData,
+    ResponsePage,
+    RevisionResponse,
 };
 
 use hc_zome_rea_economic_resource_storage_consts::*;
 use hc_zome_rea_economic_resource_storage::{
     Entry as EconomicResourceEntry,
+    SpecificationUnitConversions,
 };
 use hc_zome_rea_economic_resource_rpc::{
     CreateRequest as EconomicResourceCreateRequest,
@@ -87,83 +117,193 @@ use hc_zome_rea_economic_resource_lib::{
 };
 
 use hc_zome_rea_process_storage_consts::*;
-use hc_zome_rea_agreement_storage_consts::{
-    AGREEMENT_BASE_ENTRY_TYPE,
-    AGREEMENT_EVENTS_LINK_TYPE,
-    AGREEMENT_EVENTS_LINK_TAG,
-};
+use hc_zome_rea_agreement_storage_consts::{AGREEMENT_BASE_ENTRY_TYPE};
+use hc_zome_rea_resource_specification_storage_consts::{ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE};
+use hc_zome_rea_commitment_storage_consts::{COMMITMENT_BASE_ENTRY_TYPE, COMMITMENT_INPUT_OF_INDEX, COMMITMENT_OUTPUT_OF_INDEX};
+use hc_zome_rea_intent_storage_consts::{INTENT_BASE_ENTRY_TYPE, INTENT_INPUT_OF_INDEX, INTENT_OUTPUT_OF_INDEX};
+
+/// Events are only ever deleted outright, never soft-deleted, so reads of their indexes skip
+/// checking for tombstones by passing this as `include_deleted`.
+const SKIP_TOMBSTONE_CHECKS: bool = true;
+
+/// Indexes managed by the observation DNA, as checked by `receive_check_index_integrity`
+const OBSERVATION_INDEXES: &[RecordIndexDefinition] = &[
+    RecordIndexDefinition {
+        base_entry_type: EVENT_BASE_ENTRY_TYPE,
+        indexes: &[
+            EVENT_INPUT_OF_INDEX,
+            EVENT_OUTPUT_OF_INDEX,
+            EVENT_REALIZATION_OF_INDEX,
+            EVENT_AFFECTS_RESOURCE_INDEX,
+            FULFILLMENT_FULFILLEDBY_INDEX.reversed(),
+            SATISFACTION_SATISFIEDBY_EVENT_INDEX.reversed(),
+        ],
+        remote: false,
+    },
+    RecordIndexDefinition {
+        base_entry_type: RESOURCE_BASE_ENTRY_TYPE,
+        indexes: &[
+            RESOURCE_CONTAINED_IN_INDEX,
+            RESOURCE_CONFORMS_TO_INDEX,
+            EVENT_AFFECTS_RESOURCE_INDEX.reversed(),
+        ],
+        remote: false,
+    },
+    RecordIndexDefinition {
+        base_entry_type: PROCESS_BASE_ENTRY_TYPE,
+        indexes: &[
+            EVENT_INPUT_OF_INDEX.reversed(),
+            EVENT_OUTPUT_OF_INDEX.reversed(),
+            COMMITMENT_INPUT_OF_INDEX.reversed(),
+            COMMITMENT_OUTPUT_OF_INDEX.reversed(),
+            INTENT_INPUT_OF_INDEX.reversed(),
+            INTENT_OUTPUT_OF_INDEX.reversed(),
+        ],
+        remote: false,
+    },
+    RecordIndexDefinition {
+        base_entry_type: FULFILLMENT_BASE_ENTRY_TYPE,
+        indexes: &[
+            FULFILLMENT_FULFILLEDBY_INDEX,
+        ],
+        remote: false,
+    },
+    RecordIndexDefinition {
+        base_entry_type: SATISFACTION_BASE_ENTRY_TYPE,
+        indexes: &[
+            SATISFACTION_SATISFIEDBY_EVENT_INDEX,
+        ],
+        remote: false,
+    },
+    // destinations of remote indexes from the planning, agreement & specification DNAs
+    RecordIndexDefinition {
+        base_entry_type: AGREEMENT_BASE_ENTRY_TYPE,
+        indexes: &[
+            EVENT_REALIZATION_OF_INDEX.reversed(),
+        ],
+        remote: true,
+    },
+    RecordIndexDefinition {
+        base_entry_type: ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE,
+        indexes: &[
+            RESOURCE_CONFORMS_TO_INDEX.reversed(),
+        ],
+        remote: true,
+    },
+    RecordIndexDefinition {
+        base_entry_type: COMMITMENT_BASE_ENTRY_TYPE,
+        indexes: &[
+            COMMITMENT_INPUT_OF_INDEX,
+            COMMITMENT_OUTPUT_OF_INDEX,
+        ],
+        remote: true,
+    },
+    RecordIndexDefinition {
+        base_entry_type: INTENT_BASE_ENTRY_TYPE,
+        indexes: &[
+            INTENT_INPUT_OF_INDEX,
+            INTENT_OUTPUT_OF_INDEX,
+        ],
+        remote: true,
+    },
+];
 
 // API gateway entrypoints. All methods must accept parameters by value.
 
 pub fn receive_create_economic_event(event: EconomicEventCreateRequest, new_inventoried_resource: Option<EconomicResourceCreateRequest>) -> ZomeApiResult<ResponseData> {
-    let mut resources_affected: Vec<(ResourceAddress, EconomicResourceEntry)> = vec![];
-    let mut resource_created: Option<(ResourceAddress, EconomicResourceEntry)> = None;
-
-    // if the event observes a new resource, create that resource & return it in the response
-    if let Some(economic_resource) = new_inventoried_resource {
-        let new_resource = handle_create_economic_resource(&economic_resource, &event)?;
-        resource_created = Some(new_resource.clone());
-        resources_affected.push(new_resource);
+    // retried requests resolve to the event created by the original, and must not affect any resources a second time
+    if let Some(existing) = get_idempotent_record_address(EVENT_BASE_ENTRY_TYPE, event.get_idempotency_key())? {
+        return handle_get_created_economic_event(&existing, new_inventoried_resource.is_some());
     }
 
-    // if the event is a transfer-like event, run the receiver's update first
-    if let MaybeUndefined::Some(receiver_inventory) = event.to_resource_inventoried_as.to_owned() {
-        resources_affected.push(handle_update_economic_resource(&receiver_inventory, ResourceInventoryType::ReceivingInventory, &event)?);
-    }
-    // after receiver, run provider. This entry data will be returned in the response.
-    if let MaybeUndefined::Some(provider_inventory) = event.resource_inventoried_as.to_owned() {
-        resources_affected.push(handle_update_economic_resource(&provider_inventory, ResourceInventoryType::ProvidingInventory, &event)?);
-    }
+    // reject events whose quantities measure the wrong kind of thing for their action
+    let units = SpecificationUnitConversions::default();
+    validate_quantity_dimensions(&event, &units)?;
 
-    // now that the resource updates have succeeded, write the event
-    // :TODO: rethinking this, it's probably the event that should be written first, and the resource
-    // validation should eventually depend on an event already having been authored.
-    let (event_address, event_entry) = handle_create_economic_event(&event, match resource_created.clone() {
-        Some(data) => Some(data.0.to_owned()),
-        None => None,
-    })?;
+    // every write is undone if a later one fails, so that resources are not left altered by an event which was never saved
+    let (event_address, event_entry, resource_created) = run_saga(|saga| {
+        let mut resources_affected: Vec<(ResourceAddress, EconomicResourceEntry)> = vec![];
+        let mut resource_created: Option<(ResourceAddress, EconomicResourceEntry)> = None;
 
-    // :IMPORTANT: we don't create indexes until after the event has saved since the event storage may fail validation and
-    // we don't want dangling resources without events to be visible to callers.
-    // This is dirty and introduces some non-obvious complexity, but works around transactionality limitations for now.
-
-    // Index the event for retrieval via `get_all` API endpoints
-    // :TODO: change to use DAG indexes for time ordering & pagination
-    create_anchor_index(&EVENT_INDEX_ROOT_ENTRY_TYPE.to_string(), EVENT_INDEX_ENTRY_LINK_TYPE, &EVENT_INDEX_ROOT_ENTRY_ID.to_string(), &event_address.as_ref())?;
-    // Index any new resource for retrieval via `get_all` API endpoints
-    if let Some(resource_data) = &resource_created {
-        let resource_addr = resource_data.0.to_owned();
-        create_anchor_index(&RESOURCE_INDEX_ROOT_ENTRY_TYPE.to_string(), RESOURCE_INDEX_ENTRY_LINK_TYPE, &RESOURCE_INDEX_ROOT_ENTRY_ID.to_string(), &resource_addr.as_ref())?;
-    }
-    // Link any affected resources to this event so that we can pull all the events which affect any resource
-    // :TODO: error handling
-    for resource_data in resources_affected.iter() {
-        let _ = link_entries(
-            resource_data.0.as_ref(),
-            event_address.as_ref(),
-            RESOURCE_AFFECTED_BY_EVENT_LINK_TYPE, RESOURCE_AFFECTED_BY_EVENT_LINK_TAG,
-        );
-    }
+        // if the event observes a new resource, create that resource & return it in the response
+        if let Some(economic_resource) = &new_inventoried_resource {
+            let new_resource = handle_create_economic_resource(saga, economic_resource, &event)?;
+            resource_created = Some(new_resource.clone());
+            resources_affected.push(new_resource);
+        }
+
+        // if the event is a transfer-like event, run the receiver's update first
+        if let MaybeUndefined::Some(receiver_inventory) = event.to_resource_inventoried_as.to_owned() {
+            resources_affected.push(handle_update_economic_resource(saga, "update receiving resource", &receiver_inventory, ResourceInventoryType::ReceivingInventory, &event, &units)?);
+        }
+        // after receiver, run provider. This entry data will be returned in the response.
+        if let MaybeUndefined::Some(provider_inventory) = event.resource_inventoried_as.to_owned() {
+            resources_affected.push(handle_update_economic_resource(saga, "update providing resource", &provider_inventory, ResourceInventoryType::ProvidingInventory, &event, &units)?);
+        }
+
+        // now that the resource updates have succeeded, write the event
+        // :TODO: rethinking this, it's probably the event that should be written first, and the resource
+        // validation should eventually depend on an event already having been authored.
+        let (event_address, event_entry) = handle_create_economic_event(saga, &event, match resource_created.clone() {
+            Some(data) => Some(data.0.to_owned()),
+            None => None,
+        })?;
+
+        // :IMPORTANT: we don't create indexes until after the event has saved since the event storage may fail validation and
+        // we don't want dangling resources without events to be visible to callers.
+
+        // Index the event chronologically, for retrieval via `get_all` API endpoints and time range queries
+        if let Some(timestamp) = get_time_index_key(&event_address, &event_entry)? {
+            append_to_time_index(&EVENT_TIME_INDEX_ENTRY_TYPE.to_string(), EVENT_TIME_INDEX_LINK_TYPE, &timestamp, event_address.as_ref())?;
+            saga.completed("index event time", { let (address, timestamp) = (event_address.to_owned(), timestamp.to_owned()); move || {
+                remove_from_time_index(&EVENT_TIME_INDEX_ENTRY_TYPE.to_string(), EVENT_TIME_INDEX_LINK_TYPE, &timestamp, address.as_ref())
+            } });
+
+            // Index any new resource likewise. Resources are ordered by the time of the event which created them.
+            if let Some(resource_data) = &resource_created {
+                let resource_addr = resource_data.0.to_owned();
+                append_to_time_index(&RESOURCE_TIME_INDEX_ENTRY_TYPE.to_string(), RESOURCE_TIME_INDEX_LINK_TYPE, &timestamp, resource_addr.as_ref())?;
+                saga.completed("index resource time", { let (address, timestamp) = (resource_addr.to_owned(), timestamp.to_owned()); move || {
+                    remove_from_time_index(&RESOURCE_TIME_INDEX_ENTRY_TYPE.to_string(), RESOURCE_TIME_INDEX_LINK_TYPE, &timestamp, address.as_ref())
+                } });
+            }
+        }
+        // Index any affected resources against this event, so that we can pull all the events which affect any resource
+        // and trace resources back through the events which affected them
+        for resource_data in resources_affected.iter() {
+            saga.create_direct_index("index affected resource", event_address.as_ref(), resource_data.0.as_ref(), &EVENT_AFFECTS_RESOURCE_INDEX)?;
+        }
+
+        Ok((event_address, event_entry, resource_created))
+    })?;
 
     match resource_created {
         Some(resource_data) => {
             let resource_addr = resource_data.0.to_owned();
             let resource_entry = resource_data.1;
 
-            Ok(construct_response_with_resource(
-                &event_address, &event_entry, get_link_fields(&event_address),
-                Some(resource_addr.clone()), Some(resource_entry), get_resource_link_fields(&resource_addr)
-            ))
+            construct_response_with_resource(
+                &event_address, &event_entry, get_link_fields(&event_address, &FieldSelection::all())?,
+                Some(resource_addr.clone()), Some(resource_entry), get_resource_link_fields(&resource_addr, &FieldSelection::all())?
+            )
         },
         None => {
             // :TODO: pass results from link creation rather than re-reading
-            Ok(construct_response(&event_address, &event_entry, get_link_fields(&event_address)))
+            construct_response(&event_address, &event_entry, get_link_fields(&event_address, &FieldSelection::all())?)
         },
     }
 }
 
-pub fn receive_get_economic_event(address: EventAddress) -> ZomeApiResult<ResponseData> {
-    handle_get_economic_event(&address)
+pub fn receive_get_economic_event(address: EventAddress, fields: Option<Vec<String>>) -> ZomeApiResult<ResponseData> {
+    handle_get_economic_event(&address, &FieldSelection::from(fields))
+}
+
+pub fn receive_get_economic_events_by_ids(addresses: Vec<EventAddress>, fields: Option<Vec<String>>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+    Ok(handle_get_economic_events_by_ids(&addresses, &FieldSelection::from(fields)))
+}
+
+pub fn receive_get_economic_event_revisions(address: EventAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    handle_get_economic_event_revisions(&address)
 }
 
 pub fn receive_update_economic_event(event: EconomicEventUpdateRequest) -> ZomeApiResult<ResponseData> {
@@ -174,53 +314,85 @@ pub fn receive_delete_economic_event(address: EventAddress) -> ZomeApiResult<boo
     handle_delete_economic_event(&address)
 }
 
-pub fn receive_get_all_economic_events() -> ZomeApiResult<Vec<ResponseData>> {
-    handle_get_all_economic_events()
+pub fn receive_get_all_economic_events(fields: Option<Vec<String>>) -> ZomeApiResult<Vec<ResponseData>> {
+    handle_get_all_economic_events(&FieldSelection::from(fields))
 }
 
 pub fn receive_query_events(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
     handle_query_events(&params)
 }
 
+pub fn receive_query_events_paginated(params: QueryParams) -> ZomeApiResult<ResponsePage> {
+    handle_query_events_paginated(&params)
+}
+
+pub fn receive_query_events_by_time(params: TimeQueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+    handle_query_events_by_time(&params)
+}
+
+pub fn receive_check_index_integrity(repair: bool) -> ZomeApiResult<IndexIntegrityReport> {
+    Ok(check_index_integrity(OBSERVATION_INDEXES, repair)?)
+}
+
+/// Moves events and resources indexed only by the unordered root anchors used prior to the time
+/// indexes into the time indexes, returning the number of records moved. Records which have since
+/// been deleted are only unlinked. Has no effect once all records have been migrated.
+///
+pub fn receive_migrate_legacy_indexes() -> ZomeApiResult<usize> {
+    let events_migrated = migrate_legacy_anchor_index(
+        EVENT_INDEX_ROOT_ENTRY_TYPE, EVENT_INDEX_ENTRY_LINK_TYPE, EVENT_INDEX_ROOT_ENTRY_ID,
+        EVENT_TIME_INDEX_ENTRY_TYPE, EVENT_TIME_INDEX_LINK_TYPE,
+        get_time_index_key,
+    )?;
+    // resources were created alongside the event which observed them, so are ordered by their own creation time
+    let resources_migrated = migrate_legacy_anchor_index(
+        RESOURCE_INDEX_ROOT_ENTRY_TYPE, RESOURCE_INDEX_ENTRY_LINK_TYPE, RESOURCE_INDEX_ROOT_ENTRY_ID,
+        RESOURCE_TIME_INDEX_ENTRY_TYPE, RESOURCE_TIME_INDEX_LINK_TYPE,
+        |address: &ResourceAddress, _entry: &EconomicResourceEntry| get_record_created_time(address),
+    )?;
+    Ok(events_migrated + resources_migrated)
+}
+
 // API logic handlers
 
-fn handle_create_economic_event(event: &EconomicEventCreateRequest, resource_address: Option<ResourceAddress>) -> ZomeApiResult<(EventAddress, Entry)> {
-    let (base_address, entry_resp): (EventAddress, Entry) = create_record(
-        EVENT_BASE_ENTRY_TYPE, EVENT_ENTRY_TYPE,
-        EVENT_INITIAL_ENTRY_LINK_TYPE,
-        match resource_address {
-            Some(addr) => event.with_inventoried_resource(&addr),
-            None => event.to_owned(),
-        }
+fn handle_create_economic_event(saga: &mut Saga, event: &EconomicEventCreateRequest, resource_address: Option<ResourceAddress>) -> GraphHelperResult<(EventAddress, Entry)> {
+    let idempotency_key = event.get_idempotency_key().cloned();
+    let (base_address, entry_resp): (EventAddress, Entry) = saga.step("create event",
+        || create_record(
+            EVENT_BASE_ENTRY_TYPE, EVENT_ENTRY_TYPE,
+            EVENT_INITIAL_ENTRY_LINK_TYPE,
+            event.get_idempotency_key(),
+            match resource_address {
+                Some(addr) => event.with_inventoried_resource(&addr),
+                None => event.to_owned(),
+            }
+        ),
+        move |(address, _): (EventAddress, Entry)| discard_created_record::<Entry>(EVENT_BASE_ENTRY_TYPE, &address, idempotency_key.as_ref()),
     )?;
 
     // handle link fields
-    // :TODO: propagate errors
     if let EconomicEventCreateRequest { input_of: MaybeUndefined::Some(input_of), .. } = event {
-        let _results = create_direct_index(
-            base_address.as_ref(),
-            input_of.as_ref(),
-            EVENT_INPUT_OF_LINK_TYPE, EVENT_INPUT_OF_LINK_TAG,
-            PROCESS_EVENT_INPUTS_LINK_TYPE, PROCESS_EVENT_INPUTS_LINK_TAG,
-        );
+        saga.create_direct_index("index event input", base_address.as_ref(), input_of.as_ref(), &EVENT_INPUT_OF_INDEX)?;
     };
     if let EconomicEventCreateRequest { output_of: MaybeUndefined::Some(output_of), .. } = event {
-        let _results = create_direct_index(
-            base_address.as_ref(),
-            output_of.as_ref(),
-            EVENT_OUTPUT_OF_LINK_TYPE, EVENT_OUTPUT_OF_LINK_TAG,
-            PROCESS_EVENT_OUTPUTS_LINK_TYPE, PROCESS_EVENT_OUTPUTS_LINK_TAG,
-        );
+        saga.create_direct_index("index event output", base_address.as_ref(), output_of.as_ref(), &EVENT_OUTPUT_OF_INDEX)?;
     };
     if let EconomicEventCreateRequest { realization_of: MaybeUndefined::Some(realization_of), .. } = event {
-        let _results = create_direct_remote_index(
+        let results = create_direct_remote_index(
             BRIDGED_AGREEMENT_DHT, "economic_event_idx", "index_events", Address::from(PUBLIC_TOKEN.to_string()),
-            AGREEMENT_BASE_ENTRY_TYPE,
-            EVENT_REALIZATION_OF_LINK_TYPE, EVENT_REALIZATION_OF_LINK_TAG,
-            AGREEMENT_EVENTS_LINK_TYPE, AGREEMENT_EVENTS_LINK_TAG,
+            &EVENT_REALIZATION_OF_INDEX,
             base_address.as_ref(),
             vec![(realization_of.as_ref()).clone()],
         );
+        // registered before checking the results, so that partially written indexes are also undone
+        saga.completed("index event agreement", { let (address, realization_of) = (base_address.to_owned(), realization_of.to_owned()); move || {
+            throw_any_error(discard_direct_remote_index(
+                BRIDGED_AGREEMENT_DHT, "economic_event_idx", "index_events", Address::from(PUBLIC_TOKEN.to_string()),
+                &EVENT_REALIZATION_OF_INDEX,
+                &address, &realization_of,
+            )).map(|_| ())
+        } });
+        throw_any_error(results)?;
     };
 
     Ok((base_address, entry_resp))
@@ -228,20 +400,28 @@ fn handle_create_economic_event(event: &EconomicEventCreateRequest, resource_add
 
 /// Handle creation of new resources via events + resource metadata
 ///
-fn handle_create_economic_resource(economic_resource: &EconomicResourceCreateRequest, event: &EconomicEventCreateRequest) -> ZomeApiResult<(ResourceAddress, EconomicResourceEntry)> {
+fn handle_create_economic_resource(saga: &mut Saga, economic_resource: &EconomicResourceCreateRequest, event: &EconomicEventCreateRequest) -> GraphHelperResult<(ResourceAddress, EconomicResourceEntry)> {
     // :TODO: move this assertion to validation callback
     if let MaybeUndefined::Some(_sent_inventory_id) = &event.resource_inventoried_as {
-        panic!("cannot create a new EconomicResource and specify an inventoried resource ID in the same event");
+        return Err(GraphHelperError::ValidationFailed("cannot create a new EconomicResource and specify an inventoried resource ID in the same event".to_string()));
     }
 
     let params: ResourceCreationPayload = resource_creation(
         &event.with_inventory_type(ResourceInventoryType::ProvidingInventory),
         &economic_resource
     );
+    let new_entry = match EconomicResourceEntry::try_from(params.clone()) {
+        Ok(entry) => entry,
+        Err(e) => return Err(ZomeApiError::Internal(e.to_string()).into()),
+    };
 
-    let (base_address, entry_resp): (ResourceAddress, EconomicResourceEntry) = create_record(
-        RESOURCE_BASE_ENTRY_TYPE, RESOURCE_ENTRY_TYPE, RESOURCE_INITIAL_ENTRY_LINK_TYPE,
-        EconomicResourceEntry::from(params.clone())
+    let (base_address, entry_resp): (ResourceAddress, EconomicResourceEntry) = saga.step("create resource",
+        || create_record(
+            RESOURCE_BASE_ENTRY_TYPE, RESOURCE_ENTRY_TYPE, RESOURCE_INITIAL_ENTRY_LINK_TYPE,
+            None,
+            new_entry,
+        ),
+        |(address, _): (ResourceAddress, EconomicResourceEntry)| discard_created_record::<EconomicResourceEntry>(RESOURCE_BASE_ENTRY_TYPE, &address, None),
     )?;
 
     let resource_params = params.get_resource_params();
@@ -249,54 +429,121 @@ fn handle_create_economic_resource(economic_resource: &EconomicResourceCreateReq
 
     // :NOTE: this will always run- resource without a specification ID would fail entry validation (implicit in the above)
     if let Some(conforms_to) = params.get_resource_specification_id() {
-        let _results = create_direct_remote_index_destination(
-            ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE,
-            RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TYPE, RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TAG,
-            RESOURCE_CONFORMS_TO_LINK_TYPE, RESOURCE_CONFORMS_TO_LINK_TAG,
+        throw_any_error(create_direct_remote_index_destination(
+            &RESOURCE_CONFORMS_TO_INDEX.reversed(),
             &conforms_to,
             vec![base_address.clone()],
-        );
+        )?)?;
+        saga.completed("index resource specification", { let address = base_address.to_owned(); move || {
+            throw_any_error(delete_direct_remote_index_destination(
+                &conforms_to,
+                vec![address],
+                &RESOURCE_CONFORMS_TO_INDEX.reversed(),
+            )).map(|_| ())
+        } });
     }
     if let Some(contained_in) = resource_params.get_contained_in() {
-        let _results = create_direct_index(
-            base_address.as_ref(),
-            contained_in.as_ref(),
-            RESOURCE_CONTAINED_IN_LINK_TYPE, RESOURCE_CONTAINED_IN_LINK_TAG,
-            RESOURCE_CONTAINS_LINK_TYPE, RESOURCE_CONTAINS_LINK_TAG,
-        );
+        saga.create_direct_index("index resource container", base_address.as_ref(), contained_in.as_ref(), &RESOURCE_CONTAINED_IN_INDEX)?;
     };
     if let MaybeUndefined::Some(realization_of) = event_params.get_realization_of() {
-        let _results = update_direct_remote_index(
+        let results = update_direct_remote_index(
             BRIDGED_AGREEMENT_DHT, "economic_event_idx", "index_events", Address::from(PUBLIC_TOKEN.to_string()),
-            AGREEMENT_BASE_ENTRY_TYPE,
-            EVENT_REALIZATION_OF_LINK_TYPE, EVENT_REALIZATION_OF_LINK_TAG,
-            AGREEMENT_EVENTS_LINK_TYPE, AGREEMENT_EVENTS_LINK_TAG,
-            &base_address, &MaybeUndefined::Some(realization_of),
+            &EVENT_REALIZATION_OF_INDEX,
+            &base_address, &MaybeUndefined::Some(realization_of.to_owned()),
         );
+        // registered before checking the results, so that partially written indexes are also undone
+        saga.completed("index resource agreement", { let (address, realization_of) = (base_address.to_owned(), realization_of.to_owned()); move || {
+            throw_any_error(discard_direct_remote_index(
+                BRIDGED_AGREEMENT_DHT, "economic_event_idx", "index_events", Address::from(PUBLIC_TOKEN.to_string()),
+                &EVENT_REALIZATION_OF_INDEX,
+                &address, &realization_of,
+            )).map(|_| ())
+        } });
+        throw_any_error(results?)?;
     };
 
     Ok((base_address, entry_resp))
 }
 
-fn handle_get_economic_event(address: &EventAddress) -> ZomeApiResult<ResponseData> {
+fn handle_get_economic_event(address: &EventAddress, fields: &FieldSelection) -> ZomeApiResult<ResponseData> {
     let entry = read_record_entry(&address)?;
-    Ok(construct_response(address, &entry, get_link_fields(address)))
+    construct_response(address, &entry, get_link_fields(address, fields)?)
+}
+
+/// Read back a previously created event in the same format as `receive_create_economic_event`,
+/// including the resource it created if there was one.
+///
+fn handle_get_created_economic_event(address: &EventAddress, created_resource: bool) -> ZomeApiResult<ResponseData> {
+    let entry: Entry = read_record_entry(address)?;
+
+    match (created_resource, &entry.resource_inventoried_as) {
+        (true, Some(resource_addr)) => construct_response_with_resource(
+            address, &entry, get_link_fields(address, &FieldSelection::all())?,
+            Some(resource_addr.to_owned()), Some(read_record_entry(resource_addr)?), get_resource_link_fields(resource_addr, &FieldSelection::all())?,
+        ),
+        _ => construct_response(address, &entry, get_link_fields(address, &FieldSelection::all())?),
+    }
+}
+
+fn handle_get_economic_events_by_ids(addresses: &[EventAddress], fields: &FieldSelection) -> Vec<ZomeApiResult<ResponseData>> {
+    addresses.iter()
+        .zip(read_record_entries(addresses))
+        .map(|(address, entry)| construct_response(address, &entry?, get_link_fields(address, fields)?))
+        .collect()
+}
+
+fn handle_get_economic_event_revisions(address: &EventAddress) -> ZomeApiResult<Vec<RevisionResponse>> {
+    read_record_history(address, EVENT_INITIAL_ENTRY_LINK_TYPE)?
+        .into_iter()
+        .map(|revision: RecordRevision<Entry>| {
+            let response = construct_response(address, &revision.entry, (None, None))?;
+            Ok(revision.map(|_| response))
+        })
+        .collect()
 }
 
 fn handle_update_economic_event(event: &EconomicEventUpdateRequest) -> ZomeApiResult<ResponseData> {
     let address = event.get_id();
-    let new_entry = update_record(EVENT_ENTRY_TYPE, &address, event)?;
+    let new_entry = update_record(EVENT_ENTRY_TYPE, &address, event.get_revision_id(), event)?;
 
     // :TODO: optimise this- should pass results from `replace_direct_index` instead of retrieving from `get_link_fields` where updates
-    Ok(construct_response(address, &new_entry, get_link_fields(address)))
+    construct_response(address, &new_entry, get_link_fields(address, &FieldSelection::all())?)
+}
+
+/// Check that the quantities of `event` measure the dimension required by its action, if any.
+///
+/// Unlike the check made by entry validation, this also covers units defined in the specification DNA.
+/// @see hc_zome_rea_economic_event_storage::Entry::validate_quantity_dimensions
+///
+fn validate_quantity_dimensions(event: &EconomicEventCreateRequest, units: &dyn UnitConversions) -> ZomeApiResult<()> {
+    let quantities: Vec<QuantityValue> = vec![event.resource_quantity.to_owned(), event.effort_quantity.to_owned()]
+        .into_iter()
+        .filter_map(|q| q.to_option())
+        .collect();
+
+    check_action_dimension(event.get_action(), &quantities.iter().collect::<Vec<_>>(), units)
+        .map_err(|e| ZomeApiError::Internal(e.to_string()))
 }
 
-/// Handle alteration of existing resources via events
+/// Handle alteration of existing resources via events. The event's changes are undone if the `saga`
+/// the update is made in subsequently fails, retaining any changes made by other events since.
 ///
-fn handle_update_economic_resource(resource_addr: &ResourceAddress, inventory_type: ResourceInventoryType, event: &EconomicEventCreateRequest) -> ZomeApiResult<(ResourceAddress, EconomicResourceEntry)> {
+fn handle_update_economic_resource<'a>(saga: &mut Saga<'a>, step_name: &str, resource_addr: &ResourceAddress, inventory_type: ResourceInventoryType, event: &EconomicEventCreateRequest, units: &'a dyn UnitConversions) -> GraphHelperResult<(ResourceAddress, EconomicResourceEntry)> {
     let context_event = event.with_inventory_type(inventory_type);
 
-    let new_resource = update_record(RESOURCE_ENTRY_TYPE, &resource_addr.to_owned(), &context_event)?;
+    let prev_resource: EconomicResourceEntry = read_record_entry(resource_addr)?;
+
+    // reject events whose quantities measure a different dimension to the resource, or cannot be expressed in its units
+    let new_resource = update_record_with(RESOURCE_ENTRY_TYPE, resource_addr, None, |resource: &EconomicResourceEntry| {
+        resource.update_with_event(&context_event, units)
+            .map_err(|e| ZomeApiError::Internal(e.to_string()).into())
+    })?;
+    saga.completed(step_name, { let (address, applied) = (resource_addr.to_owned(), new_resource.to_owned()); move || {
+        update_record_with(RESOURCE_ENTRY_TYPE, &address, None, |resource: &EconomicResourceEntry| {
+            resource.revert_update(&prev_resource, &applied, units)
+                .map_err(|e| ZomeApiError::Internal(e.to_string()).into())
+        }).map(|_: EconomicResourceEntry| ())
+    } });
 
     Ok((resource_addr.to_owned(), new_resource))
 }
@@ -305,108 +552,164 @@ fn handle_delete_economic_event(address: &EventAddress) -> ZomeApiResult<bool> {
     // read any referencing indexes
     let entry: Entry = read_record_entry(&address)?;
 
+    if let Some(timestamp) = get_time_index_key(address, &entry)? {
+        remove_from_time_index(&EVENT_TIME_INDEX_ENTRY_TYPE.to_string(), EVENT_TIME_INDEX_LINK_TYPE, &timestamp, address.as_ref())?;
+    }
+
     // handle link fields
     if let Some(process_address) = entry.input_of {
-        let _results = delete_direct_index(
+        throw_any_error(delete_direct_index(
             address.as_ref(), process_address.as_ref(),
-            EVENT_INPUT_OF_LINK_TYPE, EVENT_INPUT_OF_LINK_TAG,
-            PROCESS_EVENT_INPUTS_LINK_TYPE, PROCESS_EVENT_INPUTS_LINK_TAG,
-        );
+            &EVENT_INPUT_OF_INDEX,
+        ))?;
     }
     if let Some(process_address) = entry.output_of {
-        let _results = delete_direct_index(
+        throw_any_error(delete_direct_index(
             address.as_ref(), process_address.as_ref(),
-            EVENT_OUTPUT_OF_LINK_TYPE, EVENT_OUTPUT_OF_LINK_TAG,
-            PROCESS_EVENT_OUTPUTS_LINK_TYPE, PROCESS_EVENT_OUTPUTS_LINK_TAG,
-        );
+            &EVENT_OUTPUT_OF_INDEX,
+        ))?;
     }
     if let Some(agreement_address) = entry.realization_of {
-        let _results = remove_direct_remote_index(
+        throw_any_error(remove_direct_remote_index(
             BRIDGED_AGREEMENT_DHT, "event_idx", "index_events", Address::from(PUBLIC_TOKEN.to_string()),
-            AGREEMENT_BASE_ENTRY_TYPE,
-            EVENT_REALIZATION_OF_LINK_TYPE, EVENT_REALIZATION_OF_LINK_TAG,
-            AGREEMENT_EVENTS_LINK_TYPE, AGREEMENT_EVENTS_LINK_TAG,
+            &EVENT_REALIZATION_OF_INDEX,
             address, &agreement_address,
-        );
+        ))?;
     }
 
     // delete entry last as it must be present in order for links to be removed
-    delete_record::<Entry>(&address)
+    Ok(delete_record::<Entry>(&address)?)
 }
 
-fn handle_get_all_economic_events() -> ZomeApiResult<Vec<ResponseData>> {
-    let entries_result: ZomeApiResult<Vec<(EventAddress, Option<Entry>)>> = read_anchored_record_entries(
-        &EVENT_INDEX_ROOT_ENTRY_TYPE.to_string(), EVENT_INDEX_ENTRY_LINK_TYPE, &EVENT_INDEX_ROOT_ENTRY_ID.to_string(),
+fn handle_get_all_economic_events(fields: &FieldSelection) -> ZomeApiResult<Vec<ResponseData>> {
+    let entries_result: GraphHelperResult<Vec<(EventAddress, Option<Entry>)>> = read_time_indexed_record_entries(
+        &EVENT_TIME_INDEX_ENTRY_TYPE.to_string(), EVENT_TIME_INDEX_LINK_TYPE,
+        &TimeRange::default(), TimeOrder::OldestFirst, None, SKIP_TOMBSTONE_CHECKS,
     );
 
-    handle_list_output(entries_result)
+    handle_list_output(entries_result, fields)
 }
 
 fn handle_query_events(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(EventAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
-
-    // :TODO: implement proper AND search rather than exclusive operations
-    match &params.satisfies {
-        Some(satisfies) => {
-            entries_result = query_direct_index_with_foreign_key(
-                satisfies, SATISFACTION_SATISFIEDBY_LINK_TYPE, SATISFACTION_SATISFIEDBY_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
-    match &params.fulfills {
-        Some(fulfills) => {
-            entries_result = query_direct_index_with_foreign_key(
-                fulfills, FULFILLMENT_FULFILLEDBY_LINK_TYPE, FULFILLMENT_FULFILLEDBY_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
-    match &params.input_of {
-        Some(input_of) => {
-            entries_result = query_direct_index_with_foreign_key(
-                input_of, PROCESS_EVENT_INPUTS_LINK_TYPE, PROCESS_EVENT_INPUTS_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
-    match &params.output_of {
-        Some(output_of) => {
-            entries_result = query_direct_index_with_foreign_key(
-                output_of, PROCESS_EVENT_OUTPUTS_LINK_TYPE, PROCESS_EVENT_OUTPUTS_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
-    match &params.realization_of {
-        Some(realization_of) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
-                realization_of, AGREEMENT_BASE_ENTRY_TYPE,
-                AGREEMENT_EVENTS_LINK_TYPE, AGREEMENT_EVENTS_LINK_TAG,
-            );
-        },
-        _ => (),
+    if params.is_page_request() {
+        return Err(GraphHelperError::PaginationUnsupported.into());
+    }
+
+    let entries_result: GraphHelperResult<Vec<(EventAddress, Option<Entry>)>> = query_indexes_with_foreign_key(&build_query(params), SKIP_TOMBSTONE_CHECKS);
+
+    handle_list_output(entries_result, &params.get_field_selection())
+}
+
+fn handle_query_events_paginated(params: &QueryParams) -> ZomeApiResult<ResponsePage> {
+    let query = build_query(params);
+    let page_result: GraphHelperResult<Page<(EventAddress, Option<Entry>)>> = if query.is_empty() {
+        // no filters given, page through all events
+        read_time_indexed_record_entries_paginated(
+            &EVENT_TIME_INDEX_ENTRY_TYPE.to_string(), EVENT_TIME_INDEX_LINK_TYPE,
+            &TimeRange::default(), TimeOrder::OldestFirst, &params.get_page_request(), SKIP_TOMBSTONE_CHECKS,
+        )
+    } else {
+        query_indexes_with_foreign_key_paginated(&query, &params.get_page_request(), SKIP_TOMBSTONE_CHECKS)
     };
 
-    handle_list_output(entries_result)
+    let page = page_result?;
+    Ok(ResponsePage {
+        results: handle_list_output(Ok(page.results), &params.get_field_selection())?,
+        next_cursor: page.next_cursor,
+    })
+}
+
+fn handle_query_events_by_time(params: &TimeQueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+    let entries_result: GraphHelperResult<Vec<(EventAddress, Option<Entry>)>> = read_time_indexed_record_entries(
+        &EVENT_TIME_INDEX_ENTRY_TYPE.to_string(), EVENT_TIME_INDEX_LINK_TYPE,
+        &params.get_time_range(), params.get_time_order(), params.limit, SKIP_TOMBSTONE_CHECKS,
+    );
+
+    handle_list_output(entries_result, &params.get_field_selection())
+}
+
+/// Determine the timestamp to order an event by in time indexes- its point in time
+/// if provided, otherwise the beginning or end of the period it occurred over. Events
+/// recording none of these are ordered by the time they were created.
+///
+fn get_time_index_key(address: &EventAddress, entry: &Entry) -> GraphHelperResult<Option<String>> {
+    match entry.has_point_in_time.as_ref()
+        .or(entry.has_beginning.as_ref())
+        .or(entry.has_end.as_ref())
+    {
+        Some(t) => Ok(Some(t.as_ref().to_string())),
+        None => get_record_created_time(address),
+    }
+}
+
+/// Move the records linked from a legacy root anchor into a time index, ordering each by `time_index_key`.
+/// Records whose timestamp cannot yet be determined are left in place, so that they are retried on the next run.
+///
+fn migrate_legacy_anchor_index<A, E, F>(
+    root_entry_type: &str,
+    root_link_type: &str,
+    root_id: &str,
+    time_index_entry_type: &str,
+    time_index_link_type: &str,
+    time_index_key: F,
+) -> GraphHelperResult<usize>
+    where A: From<Address> + AsRef<Address>,
+        E: Clone + TryFrom<AppEntryValue>,
+        F: Fn(&A, &E) -> GraphHelperResult<Option<String>>,
+{
+    let records: Vec<(A, Option<E>)> = read_anchored_record_entries(&root_entry_type.to_string(), root_link_type, &root_id.to_string())?;
+    let mut migrated = 0;
+
+    for (address, entry) in records {
+        // records deleted outright need only be unlinked
+        if let Some(entry) = entry {
+            match time_index_key(&address, &entry)? {
+                Some(timestamp) => append_to_time_index(&time_index_entry_type.to_string(), time_index_link_type, &timestamp, address.as_ref())?,
+                None => continue,
+            };
+            migrated += 1;
+        }
+        remove_from_anchor_index(&root_entry_type.to_string(), root_link_type, &root_id.to_string(), address.as_ref())?;
+    }
+
+    Ok(migrated)
 }
 
-fn handle_list_output(entries_result: ZomeApiResult<Vec<(EventAddress, Option<Entry>)>>) -> ZomeApiResult<Vec<ResponseData>> {
+fn build_query(params: &QueryParams) -> IndexQuery {
+    let mut filters: Vec<IndexQuery> = vec![];
+
+    if let Some(satisfies) = &params.satisfies {
+        filters.push(IndexQuery::local(satisfies, &SATISFACTION_SATISFIEDBY_EVENT_INDEX.forward));
+    }
+    if let Some(fulfills) = &params.fulfills {
+        filters.push(IndexQuery::local(fulfills, &FULFILLMENT_FULFILLEDBY_INDEX.forward));
+    }
+    if let Some(input_of) = &params.input_of {
+        filters.push(IndexQuery::local(input_of, &EVENT_INPUT_OF_INDEX.reverse));
+    }
+    if let Some(output_of) = &params.output_of {
+        filters.push(IndexQuery::local(output_of, &EVENT_OUTPUT_OF_INDEX.reverse));
+    }
+    if let Some(realization_of) = &params.realization_of {
+        filters.push(IndexQuery::remote(realization_of, &EVENT_REALIZATION_OF_INDEX.reverse));
+    }
+
+    IndexQuery::And(filters)
+}
+
+fn handle_list_output(entries_result: GraphHelperResult<Vec<(EventAddress, Option<Entry>)>>, fields: &FieldSelection) -> ZomeApiResult<Vec<ResponseData>> {
     match entries_result {
-        Ok(entries) => Ok(
-            entries.iter()
-                .map(|(entry_base_address, maybe_entry)| {
-                    match maybe_entry {
-                        Some(entry) => Ok(construct_response(
-                            entry_base_address, &entry, get_link_fields(entry_base_address),
-                        )),
-                        None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
-                    }
+        Ok(entries) => entries.iter()
+            // skip any referenced entries which could not be located
+            .filter_map(|(entry_base_address, maybe_entry)| {
+                maybe_entry.as_ref().map(|entry| -> ZomeApiResult<ResponseData> {
+                    construct_response(
+                        entry_base_address, &entry, get_link_fields(entry_base_address, fields)?,
+                    )
                 })
-                .filter_map(Result::ok)
-                .collect()
-        ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
+            })
+            .collect(),
+        Err(e) => Err(e.into()),
     }
 }
 
@@ -436,10 +739,11 @@ pub fn construct_response_with_resource<'a>(
         Option<ActionId>,
         Option<Cow<'a, Vec<ResourceAddress>>>,
     ),
-) -> ResponseData {
-    ResponseData {
+) -> ZomeApiResult<ResponseData> {
+    Ok(ResponseData {
         economic_event: Response {
             id: event_address.to_owned(),
+            revision_id: get_revision_id(EVENT_ENTRY_TYPE, event)?.into(),
             action: event.action.to_owned(),
             note: event.note.to_owned(),
             input_of: event.input_of.to_owned(),
@@ -464,10 +768,10 @@ pub fn construct_response_with_resource<'a>(
             satisfies: satisfactions.map(Cow::into_owned),
         },
         economic_resource: match resource_address {
-            Some(addr) => Some(construct_resource_response(&addr, &(resource.unwrap()), (contained_in, stage, state, contains))),
+            Some(addr) => Some(construct_resource_response(&addr, &(resource.unwrap()), (contained_in, stage, state, contains))?),
             None => None,
         },
-    }
+    })
 }
 
 // Same as above, but omits EconomicResource object
@@ -479,10 +783,11 @@ pub fn construct_response<'a>(
         Option<Cow<'a, Vec<FulfillmentAddress>>>,
         Option<Cow<'a, Vec<SatisfactionAddress>>>,
     )
-) -> ResponseData {
-    ResponseData {
+) -> ZomeApiResult<ResponseData> {
+    Ok(ResponseData {
         economic_event: Response {
             id: address.to_owned().into(),
+            revision_id: get_revision_id(EVENT_ENTRY_TYPE, e)?.into(),
             action: e.action.to_owned(),
             note: e.note.to_owned(),
             input_of: e.input_of.to_owned(),
@@ -507,18 +812,23 @@ pub fn construct_response<'a>(
             satisfies: satisfactions.map(Cow::into_owned),
         },
         economic_resource: None,
-    }
+    })
 }
 
+// Fields not present in `fields` are returned as `None` without being read.
 // @see construct_response
-pub fn get_link_fields<'a>(event: &EventAddress) -> (
+pub fn get_link_fields<'a>(event: &EventAddress, fields: &FieldSelection) -> ZomeApiResult<(
     Option<Cow<'a, Vec<FulfillmentAddress>>>,
     Option<Cow<'a, Vec<SatisfactionAddress>>>,
-) {
-    (
-        Some(get_linked_addresses_as_type(event, EVENT_FULFILLS_LINK_TYPE, EVENT_FULFILLS_LINK_TAG)),
-        Some(get_linked_addresses_as_type(event, EVENT_SATISFIES_LINK_TYPE, EVENT_SATISFIES_LINK_TAG)),
-    )
+)> {
+    Ok((
+        if fields.includes("fulfills") {
+            Some(get_linked_addresses_as_type(event, EVENT_FULFILLS_LINK_TYPE, EVENT_FULFILLS_LINK_TAG)?)
+        } else { None },
+        if fields.includes("satisfies") {
+            Some(get_linked_addresses_as_type(event, EVENT_SATISFIES_LINK_TYPE, EVENT_SATISFIES_LINK_TAG)?)
+        } else { None },
+    ))
 }
 
 // #[cfg(test)]
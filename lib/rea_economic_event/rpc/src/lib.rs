@@ -4,6 +4,9 @@
  * Required by packages wishing to interact with the zome via its standard RPC interface,
  * and by the zome API handlers accepting these parameters.
  *
+ * Non-integer `hasNumericalValue`s of quantities, including those of the affected resources
+ * in responses, are returned as decimal strings. @see `vf_core::measurement::QuantityValue`
+ *
  * @package Holo-REA
  */
 extern crate serde;
@@ -14,9 +17,17 @@ extern crate serde_json;
 use holochain_json_api::{ json::JsonString, error::JsonError };
 use holochain_json_derive::{ DefaultJson };
 
-use hdk_graph_helpers::MaybeUndefined;
+use hdk_graph_helpers::{
+    MaybeUndefined,
+    pagination::{ PageRequest, is_page_request },
+    field_selection::FieldSelection,
+    time_indexes::{ TimeRange, TimeOrder },
+    records::RecordRevision,
+};
 use vf_core::measurement::QuantityValue;
 use vf_core::type_aliases::{
+    Address,
+    RevisionId,
     ActionId,
     Timestamp,
     ExternalURL,
@@ -44,6 +55,7 @@ pub use vf_core::type_aliases::{ EventAddress, ResourceAddress };
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub id: EventAddress,
+    pub revision_id: RevisionId,
     pub action: ActionId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
@@ -96,6 +108,7 @@ pub struct Response {
 #[serde(rename_all = "camelCase")]
 pub struct ResourceResponse {
     pub id: ResourceAddress,
+    pub revision_id: RevisionId,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub conforms_to: Option<ResourceSpecificationAddress>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -141,6 +154,9 @@ pub struct ResponseData {
     pub economic_resource: Option<ResourceResponse>,
 }
 
+/// I/O struct to describe a past revision of a record, as returned by `get_*_revisions`
+pub type RevisionResponse = RecordRevision<ResponseData>;
+
 /// I/O struct to describe what is returned outside the gateway
 #[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -198,6 +214,10 @@ pub struct CreateRequest {
     #[serde(default)]
     pub in_scope_of: MaybeUndefined<Vec<String>>,
 
+    // key supplied by the client to detect retried requests
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+
     // :SHONK: internal field used in updating linked resource quantities
     #[serde(default)]
     pub target_inventory_type: Option<ResourceInventoryType>,
@@ -231,6 +251,10 @@ impl<'a> CreateRequest {
     pub fn get_realization_of(&'a self) -> MaybeUndefined<AgreementAddress> {
         self.realization_of.to_owned()
     }
+
+    pub fn get_idempotency_key(&'a self) -> Option<&String> {
+        self.idempotency_key.as_ref()
+    }
 }
 
 //---------------- UPDATE REQUEST ----------------
@@ -241,6 +265,8 @@ impl<'a> CreateRequest {
 pub struct UpdateRequest {
     pub id: EventAddress,
     #[serde(default)]
+    pub revision_id: Option<RevisionId>,
+    #[serde(default)]
     pub note: MaybeUndefined<String>,
     #[serde(default)]
     pub agreed_in: MaybeUndefined<ExternalURL>,
@@ -257,6 +283,10 @@ impl<'a> UpdateRequest {
         &self.id
     }
 
+    pub fn get_revision_id(&'a self) -> Option<&Address> {
+        self.revision_id.as_ref().map(AsRef::as_ref)
+    }
+
     // :TODO: accessors for other field data
 }
 
@@ -270,4 +300,66 @@ pub struct QueryParams {
     pub satisfies: Option<IntentAddress>,
     pub fulfills: Option<CommitmentAddress>,
     pub realization_of: Option<AgreementAddress>,
+
+    // pagination parameters, used by paginated query endpoints only
+    pub limit: Option<usize>,
+    pub cursor: Option<String>,
+
+    // response fields to load, all fields are returned if omitted
+    pub fields: Option<Vec<String>>,
+}
+
+impl<'a> QueryParams {
+    pub fn get_page_request(&'a self) -> PageRequest {
+        PageRequest::new(self.limit, self.cursor.to_owned())
+    }
+
+    pub fn is_page_request(&'a self) -> bool {
+        is_page_request(&self.limit, &self.cursor)
+    }
+
+    pub fn get_field_selection(&'a self) -> FieldSelection {
+        FieldSelection::from(self.fields.to_owned())
+    }
+}
+
+/// Query parameters for reading records in chronological order.
+/// Used for both economic events and economic resources.
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeQueryParams {
+    pub from: Option<Timestamp>,
+    pub until: Option<Timestamp>,
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub newest_first: bool,
+
+    // response fields to load, all fields are returned if omitted
+    pub fields: Option<Vec<String>>,
+}
+
+impl<'a> TimeQueryParams {
+    pub fn get_time_range(&'a self) -> TimeRange {
+        TimeRange {
+            from: self.from.to_owned().map(|t| t.as_ref().to_string()),
+            until: self.until.to_owned().map(|t| t.as_ref().to_string()),
+        }
+    }
+
+    pub fn get_time_order(&'a self) -> TimeOrder {
+        if self.newest_first { TimeOrder::NewestFirst } else { TimeOrder::OldestFirst }
+    }
+
+    pub fn get_field_selection(&'a self) -> FieldSelection {
+        FieldSelection::from(self.fields.to_owned())
+    }
+}
+
+/// I/O struct to describe a single page of query results, along with the cursor to request the next page
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponsePage {
+    pub results: Vec<ResponseData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
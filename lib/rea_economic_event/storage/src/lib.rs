@@ -15,11 +15,11 @@ use holochain_json_api::{ json::JsonString, error::JsonError };
 use holochain_json_derive::{ DefaultJson };
 
 use hdk_graph_helpers::{
-    MaybeUndefined,
     record_interface::Updateable,
 };
 
 use vf_core::measurement::QuantityValue;
+use vf_core::builtin_units::BuiltinUnits;
 use vf_core::type_aliases::{
     EventAddress,
     ActionId,
@@ -32,30 +32,48 @@ use vf_core::type_aliases::{
     ResourceSpecificationAddress,
     AgreementAddress,
 };
-use vf_actions::{ validate_flow_action, validate_move_inventories };
+use vf_actions::{ validate_flow_action, validate_move_inventories, check_action_dimension };
 use hc_zome_rea_economic_event_rpc::*;
 
 //---------------- RECORD INTERNALS & VALIDATION ----------------
 
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, Updateable)]
 pub struct Entry {
+    #[updateable(immutable)]
     pub action: ActionId,
+    #[updateable(immutable)]
     pub provider: AgentAddress,
+    #[updateable(immutable)]
     pub receiver: AgentAddress,
+    #[updateable(immutable)]
     pub input_of: Option<ProcessAddress>,   // :NOTE: shadows link, see https://github.com/holo-rea/holo-rea/issues/60#issuecomment-553756873
+    #[updateable(immutable)]
     pub output_of: Option<ProcessAddress>,
+    #[updateable(immutable)]
     pub resource_inventoried_as: Option<ResourceAddress>,
+    #[updateable(immutable)]
     pub to_resource_inventoried_as: Option<ResourceAddress>,
+    #[updateable(immutable)]
     pub resource_classified_as: Option<Vec<ExternalURL>>,
+    #[updateable(immutable)]
     pub resource_conforms_to: Option<ResourceSpecificationAddress>,
+    #[updateable(immutable)]
     pub resource_quantity: Option<QuantityValue>,
+    #[updateable(immutable)]
     pub effort_quantity: Option<QuantityValue>,
+    #[updateable(immutable)]
     pub has_beginning: Option<Timestamp>,
+    #[updateable(immutable)]
     pub has_end: Option<Timestamp>,
+    #[updateable(immutable)]
     pub has_point_in_time: Option<Timestamp>,
+    #[updateable(immutable)]
     pub at_location: Option<LocationAddress>,
+    #[updateable(immutable)]
     pub agreed_in: Option<ExternalURL>,
+    #[updateable(immutable)]
     pub realization_of: Option<AgreementAddress>,
+    #[updateable(immutable)]
     pub triggered_by: Option<EventAddress>,
     pub in_scope_of: Option<Vec<String>>,
     pub note: Option<String>,
@@ -70,6 +88,17 @@ impl Entry {
         return result;
     }
 
+    /// Check that the event's quantities measure the dimension required by its action, if any.
+    ///
+    /// :NOTE: only quantities in built-in units can be checked here, since other units are defined
+    /// in the specification DNA which validation callbacks cannot read from. Quantities in other
+    /// units are checked when the event is created via the zome API, but are not enforced on the network.
+    ///
+    pub fn validate_quantity_dimensions(&self) -> Result<(), String> {
+        let quantities: Vec<&QuantityValue> = self.resource_quantity.iter().chain(self.effort_quantity.iter()).collect();
+        check_action_dimension(self.action.as_ref(), &quantities, &BuiltinUnits).map_err(|e| e.to_string())
+    }
+
     pub fn validate_or_fields(&self) -> Result<(), String> {
         if !(self.resource_inventoried_as.is_some() || self.resource_classified_as.is_some() || self.resource_conforms_to.is_some()) {
             return Err("EconomicEvent must reference an inventoried resource, resource specification or resource classification".into());
@@ -115,33 +144,3 @@ impl From<CreateRequest> for Entry {
         }
     }
 }
-
-//---------------- UPDATE ----------------
-
-/// Handles update operations by merging any newly provided fields into
-impl Updateable<UpdateRequest> for Entry {
-    fn update_with(&self, e: &UpdateRequest) -> Entry {
-        Entry {
-            action: self.action.to_owned(),
-            provider: self.provider.to_owned(),
-            receiver: self.receiver.to_owned(),
-            input_of: self.input_of.to_owned(),
-            output_of: self.output_of.to_owned(),
-            resource_inventoried_as: self.resource_inventoried_as.to_owned(),
-            to_resource_inventoried_as: self.to_resource_inventoried_as.to_owned(),
-            resource_classified_as: self.resource_classified_as.to_owned(),
-            resource_conforms_to: self.resource_conforms_to.to_owned(),
-            resource_quantity: self.resource_quantity.to_owned(),
-            effort_quantity: self.effort_quantity.to_owned(),
-            has_beginning: self.has_beginning.to_owned(),
-            has_end: self.has_end.to_owned(),
-            has_point_in_time: self.has_point_in_time.to_owned(),
-            agreed_in: self.agreed_in.to_owned(),
-            triggered_by: self.triggered_by.to_owned(),
-            realization_of: self.realization_of.to_owned(),
-            at_location: self.at_location.to_owned(),
-            in_scope_of: if e.in_scope_of== MaybeUndefined::Undefined { self.in_scope_of.to_owned() } else { e.in_scope_of.to_owned().into() },
-            note: if e.note== MaybeUndefined::Undefined { self.note.to_owned() } else { e.note.to_owned().into() },
-        }
-    }
-}
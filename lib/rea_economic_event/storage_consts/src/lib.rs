@@ -5,6 +5,21 @@
  *
  * @package Holo-REA
  */
+use hdk_graph_helpers::{ LinkSpec, BidirectionalIndex };
+use hc_zome_rea_process_storage_consts::{
+    PROCESS_BASE_ENTRY_TYPE,
+    PROCESS_EVENT_INPUTS_LINK_TYPE, PROCESS_EVENT_INPUTS_LINK_TAG,
+    PROCESS_EVENT_OUTPUTS_LINK_TYPE, PROCESS_EVENT_OUTPUTS_LINK_TAG,
+};
+use hc_zome_rea_agreement_storage_consts::{
+    AGREEMENT_BASE_ENTRY_TYPE,
+    AGREEMENT_EVENTS_LINK_TYPE, AGREEMENT_EVENTS_LINK_TAG,
+};
+use hc_zome_rea_economic_resource_storage_consts::{
+    RESOURCE_BASE_ENTRY_TYPE,
+    RESOURCE_AFFECTED_BY_EVENT_LINK_TYPE, RESOURCE_AFFECTED_BY_EVENT_LINK_TAG,
+};
+
 pub const EVENT_BASE_ENTRY_TYPE: &str = "vf_economic_event_baseurl";
 pub const EVENT_INITIAL_ENTRY_LINK_TYPE: &str = "vf_economic_event_entry";
 pub const EVENT_ENTRY_TYPE: &str = "vf_economic_event";
@@ -18,10 +33,38 @@ pub const EVENT_OUTPUT_OF_LINK_TYPE: &str = "vf_economic_event_output_of";
 pub const EVENT_OUTPUT_OF_LINK_TAG: &str = "output_of";
 pub const EVENT_REALIZATION_OF_LINK_TYPE: &str = "vf_economic_event_realization_of";
 pub const EVENT_REALIZATION_OF_LINK_TAG: &str = "realization_of";
+pub const EVENT_AFFECTS_RESOURCE_LINK_TYPE: &str = "vf_economic_event_affects";
+pub const EVENT_AFFECTS_RESOURCE_LINK_TAG: &str = "affects";
 
-// :TODO: replace with a DAG
+// :DEPRECATED: unordered index of all records, superseded by the time index below.
+// Retained only so that records indexed before the time index existed can be migrated to it.
 pub const EVENT_INDEX_ROOT_ENTRY_TYPE: &str = "vf_economic_events_root";
 pub const EVENT_INDEX_ROOT_ENTRY_ID: &str = "all_vf_economic_events";
 pub const EVENT_INDEX_ENTRY_LINK_TYPE: &str = "vf_economic_event_root_index";
 
+// chronological index of all records, used by `get_all` API endpoints and time range queries
+pub const EVENT_TIME_INDEX_ENTRY_TYPE: &str = "vf_economic_event_time_index";
+pub const EVENT_TIME_INDEX_LINK_TYPE: &str = "vf_economic_event_time_indexed";
+
+/// Events consuming or using resources in a process
+pub const EVENT_INPUT_OF_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: EVENT_BASE_ENTRY_TYPE, link_type: EVENT_INPUT_OF_LINK_TYPE, link_tag: EVENT_INPUT_OF_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: PROCESS_BASE_ENTRY_TYPE, link_type: PROCESS_EVENT_INPUTS_LINK_TYPE, link_tag: PROCESS_EVENT_INPUTS_LINK_TAG },
+};
+/// Events producing resources from a process
+pub const EVENT_OUTPUT_OF_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: EVENT_BASE_ENTRY_TYPE, link_type: EVENT_OUTPUT_OF_LINK_TYPE, link_tag: EVENT_OUTPUT_OF_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: PROCESS_BASE_ENTRY_TYPE, link_type: PROCESS_EVENT_OUTPUTS_LINK_TYPE, link_tag: PROCESS_EVENT_OUTPUTS_LINK_TAG },
+};
+/// Events realizing an agreement
+pub const EVENT_REALIZATION_OF_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: EVENT_BASE_ENTRY_TYPE, link_type: EVENT_REALIZATION_OF_LINK_TYPE, link_tag: EVENT_REALIZATION_OF_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: AGREEMENT_BASE_ENTRY_TYPE, link_type: AGREEMENT_EVENTS_LINK_TYPE, link_tag: AGREEMENT_EVENTS_LINK_TAG },
+};
+/// Events altering the quantities or other state of resources
+pub const EVENT_AFFECTS_RESOURCE_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: EVENT_BASE_ENTRY_TYPE, link_type: EVENT_AFFECTS_RESOURCE_LINK_TYPE, link_tag: EVENT_AFFECTS_RESOURCE_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: RESOURCE_BASE_ENTRY_TYPE, link_type: RESOURCE_AFFECTED_BY_EVENT_LINK_TYPE, link_tag: RESOURCE_AFFECTED_BY_EVENT_LINK_TAG },
+};
+
 pub const BRIDGED_AGREEMENT_DHT: &str = "vf_agreement";
@@ -111,6 +111,7 @@ pub fn base_entry_def() -> ValidatingEntryType {
     )
 }
 
+/// Root anchor of the unordered index used before the time index, read only by the legacy index migration
 pub fn root_entry_def() -> ValidatingEntryType {
     entry!(
         name: RESOURCE_INDEX_ROOT_ENTRY_TYPE,
@@ -138,3 +139,43 @@ pub fn root_entry_def() -> ValidatingEntryType {
         ]
     )
 }
+
+pub fn time_index_entry_def() -> ValidatingEntryType {
+    entry!(
+        name: RESOURCE_TIME_INDEX_ENTRY_TYPE,
+        description: "Year, month & day anchors which order all Economic Resources stored in this zome chronologically.",
+        sharing: Sharing::Public,
+        validation_package: || {
+            hdk::ValidationPackageDefinition::Entry
+        },
+        validation: |_validation_data: hdk::EntryValidationData<Address>| {
+            Ok(())
+        },
+        links: [
+            to!(
+                RESOURCE_TIME_INDEX_ENTRY_TYPE,
+                link_type: RESOURCE_TIME_INDEX_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            ),
+            to!(
+                RESOURCE_BASE_ENTRY_TYPE,
+                link_type: RESOURCE_TIME_INDEX_LINK_TYPE,
+
+                validation_package: || {
+                    hdk::ValidationPackageDefinition::Entry
+                },
+
+                validation: | _validation_data: hdk::LinkValidationData| {
+                    Ok(())
+                }
+            )
+        ]
+    )
+}
@@ -8,27 +8,38 @@
  */
 use std::borrow::Cow;
 use hdk::{
-    error::{ ZomeApiResult, ZomeApiError },
+    error::{ ZomeApiResult },
 };
 
 use hdk_graph_helpers::{
+    BidirectionalIndex,
     records::{
         read_record_entry,
+        read_record_entries,
         update_record,
+        get_revision_id,
     },
     links::get_linked_addresses_as_type,
-    anchors::read_anchored_record_entries,
     local_indexes::{
+        IndexQuery,
         replace_direct_index,
-        query_direct_index_with_foreign_key,
-        query_direct_remote_index_with_foreign_key,
+        query_indexes_with_foreign_key,
+        query_indexes_with_foreign_key_paginated,
+    },
+    pagination::Page,
+    errors::{ GraphHelperError, GraphHelperResult },
+    field_selection::FieldSelection,
+    time_indexes::{
+        TimeRange,
+        TimeOrder,
+        read_time_indexed_record_entries,
+        read_time_indexed_record_entries_paginated,
+    },
+    traversal::{
+        TraversalLimits,
+        Subgraph,
+        traverse_graph,
     },
-};
-
-use hc_zome_rea_resource_specification_storage_consts::{
-    ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE,
-    RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TYPE,
-    RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TAG,
 };
 
 use vf_core::type_aliases::{
@@ -42,103 +53,173 @@ use hc_zome_rea_process_storage::Entry as ProcessEntry;
 use hc_zome_rea_economic_resource_storage::*;
 use hc_zome_rea_economic_resource_storage_consts::*;
 use hc_zome_rea_economic_resource_rpc::*;
+use hc_zome_rea_economic_event_storage_consts::{
+    EVENT_INPUT_OF_INDEX,
+    EVENT_OUTPUT_OF_INDEX,
+    EVENT_AFFECTS_RESOURCE_INDEX,
+};
 use hc_zome_rea_economic_event_storage::Entry as EventEntry;
 use hc_zome_rea_economic_event_rpc::{
     CreateRequest as EventCreateRequest,
     ResourceResponse as Response,
     ResourceResponseData as ResponseData,
+    TimeQueryParams,
 };
 
-pub fn receive_get_economic_resource(address: ResourceAddress) -> ZomeApiResult<ResponseData> {
-    handle_get_economic_resource(&address)
+/// Resources are only ever deleted outright, never soft-deleted, so reads of their indexes skip
+/// checking for tombstones by passing this as `include_deleted`.
+const SKIP_TOMBSTONE_CHECKS: bool = true;
+
+/// Indexes followed when tracing a resource upstream: to the events which affected it, the
+/// processes those events were output from, the events input to those processes and the
+/// resources those inputs affected, and so on.
+///
+/// :NOTE: events are linked to every resource they affect, so edges from each event back to
+/// the resource it was reached from are also returned.
+const RESOURCE_UPSTREAM_INDEXES: &[BidirectionalIndex] = &[
+    EVENT_AFFECTS_RESOURCE_INDEX.reversed(),
+    EVENT_OUTPUT_OF_INDEX,
+    EVENT_INPUT_OF_INDEX.reversed(),
+    EVENT_AFFECTS_RESOURCE_INDEX,
+];
+
+pub fn receive_get_economic_resource(address: ResourceAddress, fields: Option<Vec<String>>) -> ZomeApiResult<ResponseData> {
+    handle_get_economic_resource(&address, &FieldSelection::from(fields))
+}
+
+pub fn receive_get_economic_resources_by_ids(addresses: Vec<ResourceAddress>, fields: Option<Vec<String>>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+    Ok(handle_get_economic_resources_by_ids(&addresses, &FieldSelection::from(fields)))
 }
 
 pub fn receive_update_economic_resource(resource: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_economic_resource(&resource)
 }
 
-pub fn receive_get_all_economic_resources() -> ZomeApiResult<Vec<ResponseData>> {
-    handle_get_all_economic_resources()
+pub fn receive_get_all_economic_resources(fields: Option<Vec<String>>) -> ZomeApiResult<Vec<ResponseData>> {
+    handle_get_all_economic_resources(&FieldSelection::from(fields))
 }
 
 pub fn receive_query_economic_resources(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
     handle_query_economic_resources(&params)
 }
 
-fn handle_get_economic_resource(address: &ResourceAddress) -> ZomeApiResult<ResponseData> {
+pub fn receive_query_economic_resources_paginated(params: QueryParams) -> ZomeApiResult<ResponsePage> {
+    handle_query_economic_resources_paginated(&params)
+}
+
+pub fn receive_trace_economic_resource_upstream(address: ResourceAddress, max_depth: Option<usize>, max_nodes: Option<usize>) -> ZomeApiResult<Subgraph> {
+    handle_trace_economic_resource_upstream(&address, &TraversalLimits::new(max_depth, max_nodes))
+}
+
+pub fn receive_query_economic_resources_by_time(params: TimeQueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+    handle_query_economic_resources_by_time(&params)
+}
+
+fn handle_get_economic_resource(address: &ResourceAddress, fields: &FieldSelection) -> ZomeApiResult<ResponseData> {
     let entry = read_record_entry(&address)?;
-    Ok(construct_response(&address, &entry, get_link_fields(&address)))
+    construct_response(&address, &entry, get_link_fields(&address, fields)?)
+}
+
+fn handle_trace_economic_resource_upstream(address: &ResourceAddress, limits: &TraversalLimits) -> ZomeApiResult<Subgraph> {
+    // ensure the resource exists, rather than returning a single-node graph for any address
+    let _entry: Entry = read_record_entry(address)?;
+    Ok(traverse_graph(address, RESOURCE_BASE_ENTRY_TYPE, RESOURCE_UPSTREAM_INDEXES, limits)?)
+}
+
+fn handle_get_economic_resources_by_ids(addresses: &[ResourceAddress], fields: &FieldSelection) -> Vec<ZomeApiResult<ResponseData>> {
+    addresses.iter()
+        .zip(read_record_entries(addresses))
+        .map(|(address, entry)| construct_response(address, &entry?, get_link_fields(address, fields)?))
+        .collect()
 }
 
 fn handle_update_economic_resource(resource: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let address = resource.get_id();
-    let new_entry = update_record(RESOURCE_ENTRY_TYPE, &address, resource)?;
+    let new_entry = update_record(RESOURCE_ENTRY_TYPE, &address, resource.get_revision_id(), resource)?;
 
     // :TODO: handle link fields
-    replace_direct_index(address, &resource.get_contained_in(),
-        RESOURCE_CONTAINED_IN_LINK_TYPE, RESOURCE_CONTAINED_IN_LINK_TAG,
-        RESOURCE_CONTAINS_LINK_TYPE, RESOURCE_CONTAINS_LINK_TAG,
-    )?;
+    replace_direct_index(address, &resource.get_contained_in(), &RESOURCE_CONTAINED_IN_INDEX)?;
 
     // :TODO: optimise this- should pass results from `replace_direct_index` instead of retrieving from `get_link_fields` where updates
-    Ok(construct_response(address, &new_entry, get_link_fields(address)))
+    construct_response(address, &new_entry, get_link_fields(address, &FieldSelection::all())?)
 }
 
-fn handle_get_all_economic_resources() -> ZomeApiResult<Vec<ResponseData>> {
-    let entries_result: ZomeApiResult<Vec<(ResourceAddress, Option<Entry>)>> = read_anchored_record_entries(
-        &RESOURCE_INDEX_ROOT_ENTRY_TYPE.to_string(), RESOURCE_INDEX_ENTRY_LINK_TYPE, &RESOURCE_INDEX_ROOT_ENTRY_ID.to_string(),
+fn handle_get_all_economic_resources(fields: &FieldSelection) -> ZomeApiResult<Vec<ResponseData>> {
+    let entries_result: GraphHelperResult<Vec<(ResourceAddress, Option<Entry>)>> = read_time_indexed_record_entries(
+        &RESOURCE_TIME_INDEX_ENTRY_TYPE.to_string(), RESOURCE_TIME_INDEX_LINK_TYPE,
+        &TimeRange::default(), TimeOrder::OldestFirst, None, SKIP_TOMBSTONE_CHECKS,
     );
 
-    handle_list_output(entries_result)
+    handle_list_output(entries_result, fields)
 }
 
 fn handle_query_economic_resources(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(ResourceAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
-
-    match &params.contains {
-        Some(contains) => {
-            entries_result = query_direct_index_with_foreign_key(
-                &contains, RESOURCE_CONTAINED_IN_LINK_TYPE, RESOURCE_CONTAINED_IN_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
-    match &params.contained_in {
-        Some(contained_in) => {
-            entries_result = query_direct_index_with_foreign_key(
-                contained_in, RESOURCE_CONTAINS_LINK_TYPE, RESOURCE_CONTAINS_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
-    match &params.conforms_to {
-        Some(conforms_to) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
-                conforms_to, ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE, RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TYPE, RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TAG,
-            );
-        },
-        _ => (),
+    if params.is_page_request() {
+        return Err(GraphHelperError::PaginationUnsupported.into());
+    }
+
+    let entries_result: GraphHelperResult<Vec<(ResourceAddress, Option<Entry>)>> = query_indexes_with_foreign_key(&build_query(params), SKIP_TOMBSTONE_CHECKS);
+
+    handle_list_output(entries_result, &params.get_field_selection())
+}
+
+fn handle_query_economic_resources_paginated(params: &QueryParams) -> ZomeApiResult<ResponsePage> {
+    let query = build_query(params);
+    let page_result: GraphHelperResult<Page<(ResourceAddress, Option<Entry>)>> = if query.is_empty() {
+        // no filters given, page through all resources
+        read_time_indexed_record_entries_paginated(
+            &RESOURCE_TIME_INDEX_ENTRY_TYPE.to_string(), RESOURCE_TIME_INDEX_LINK_TYPE,
+            &TimeRange::default(), TimeOrder::OldestFirst, &params.get_page_request(), SKIP_TOMBSTONE_CHECKS,
+        )
+    } else {
+        query_indexes_with_foreign_key_paginated(&query, &params.get_page_request(), SKIP_TOMBSTONE_CHECKS)
     };
 
-    handle_list_output(entries_result)
+    let page = page_result?;
+    Ok(ResponsePage {
+        results: handle_list_output(Ok(page.results), &params.get_field_selection())?,
+        next_cursor: page.next_cursor,
+    })
+}
+
+fn handle_query_economic_resources_by_time(params: &TimeQueryParams) -> ZomeApiResult<Vec<ResponseData>> {
+    let entries_result: GraphHelperResult<Vec<(ResourceAddress, Option<Entry>)>> = read_time_indexed_record_entries(
+        &RESOURCE_TIME_INDEX_ENTRY_TYPE.to_string(), RESOURCE_TIME_INDEX_LINK_TYPE,
+        &params.get_time_range(), params.get_time_order(), params.limit, SKIP_TOMBSTONE_CHECKS,
+    );
+
+    handle_list_output(entries_result, &params.get_field_selection())
 }
 
-fn handle_list_output(entries_result: ZomeApiResult<Vec<(ResourceAddress, Option<Entry>)>>) -> ZomeApiResult<Vec<ResponseData>> {
+fn build_query(params: &QueryParams) -> IndexQuery {
+    let mut filters: Vec<IndexQuery> = vec![];
+
+    if let Some(contains) = &params.contains {
+        filters.push(IndexQuery::local(contains, &RESOURCE_CONTAINED_IN_INDEX.forward));
+    }
+    if let Some(contained_in) = &params.contained_in {
+        filters.push(IndexQuery::local(contained_in, &RESOURCE_CONTAINED_IN_INDEX.reverse));
+    }
+    if let Some(conforms_to) = &params.conforms_to {
+        filters.push(IndexQuery::remote(conforms_to, &RESOURCE_CONFORMS_TO_INDEX.reverse));
+    }
+
+    IndexQuery::And(filters)
+}
+
+fn handle_list_output(entries_result: GraphHelperResult<Vec<(ResourceAddress, Option<Entry>)>>, fields: &FieldSelection) -> ZomeApiResult<Vec<ResponseData>> {
     match entries_result {
-        Ok(entries) => Ok(
-            entries.iter()
-                .map(|(entry_base_address, maybe_entry)| {
-                    match maybe_entry {
-                        Some(entry) => Ok(construct_response(
-                            entry_base_address, &entry, get_link_fields(entry_base_address)
-                        )),
-                        None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
-                    }
+        Ok(entries) => entries.iter()
+            // skip any referenced entries which could not be located
+            .filter_map(|(entry_base_address, maybe_entry)| {
+                maybe_entry.as_ref().map(|entry| -> ZomeApiResult<ResponseData> {
+                    construct_response(
+                        entry_base_address, &entry, get_link_fields(entry_base_address, fields)?
+                    )
                 })
-                .filter_map(Result::ok)
-                .collect()
-        ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
+            })
+            .collect(),
+        Err(e) => Err(e.into()),
     }
 }
 
@@ -162,10 +243,10 @@ pub fn construct_response<'a>(
         Option<ActionId>,
         Option<Cow<'a, Vec<ResourceAddress>>>,
     ),
-) -> ResponseData {
-    ResponseData {
-        economic_resource: construct_response_record(address, e, (contained_in, stage, state, contains))
-    }
+) -> ZomeApiResult<ResponseData> {
+    Ok(ResponseData {
+        economic_resource: construct_response_record(address, e, (contained_in, stage, state, contains))?
+    })
 }
 
 /// Create response from input DHT primitives
@@ -181,10 +262,11 @@ pub fn construct_response_record<'a>(
         Option<ActionId>,
         Option<Cow<'a, Vec<ResourceAddress>>>,
     ),
-) -> Response {
-    Response {
+) -> ZomeApiResult<Response> {
+    Ok(Response {
         // entry fields
         id: address.to_owned(),
+        revision_id: get_revision_id(RESOURCE_ENTRY_TYPE, e)?.into(),
         conforms_to: e.conforms_to.to_owned(),
         classified_as: e.classified_as.to_owned(),
         tracking_identifier: e.tracking_identifier.to_owned(),
@@ -201,30 +283,36 @@ pub fn construct_response_record<'a>(
         // link fields
         contained_in: contained_in.to_owned(),
         contains: contains.map(Cow::into_owned),
-    }
+    })
 }
 
 // field list retrieval internals
+// Fields not present in `fields` are returned as `None` without being read.
 // @see construct_response
-pub fn get_link_fields<'a>(resource: &ResourceAddress) -> (
+pub fn get_link_fields<'a>(resource: &ResourceAddress, fields: &FieldSelection) -> ZomeApiResult<(
     Option<ResourceAddress>,
     Option<ProcessSpecificationAddress>,
     Option<ActionId>,
     Option<Cow<'a, Vec<ResourceAddress>>>,
-) {
-    (
-        get_linked_addresses_as_type(resource, RESOURCE_CONTAINED_IN_LINK_TYPE, RESOURCE_CONTAINED_IN_LINK_TAG).into_owned().pop(),
-        get_resource_stage(resource),
-        get_resource_state(resource),
-        Some(get_linked_addresses_as_type(resource, RESOURCE_CONTAINS_LINK_TYPE, RESOURCE_CONTAINS_LINK_TAG)),
-    )
+)> {
+    Ok((
+        if fields.includes("containedIn") {
+            get_linked_addresses_as_type(resource, RESOURCE_CONTAINED_IN_LINK_TYPE, RESOURCE_CONTAINED_IN_LINK_TAG)?.into_owned().pop()
+        } else { None },
+        // stage & state are derived by scanning all affecting events, so are skipped wherever possible
+        if fields.includes("stage") { get_resource_stage(resource)? } else { None },
+        if fields.includes("state") { get_resource_state(resource)? } else { None },
+        if fields.includes("contains") {
+            Some(get_linked_addresses_as_type(resource, RESOURCE_CONTAINS_LINK_TYPE, RESOURCE_CONTAINS_LINK_TAG)?)
+        } else { None },
+    ))
 }
 
-fn get_resource_state(resource: &ResourceAddress) -> Option<ActionId> {
-    let events: Vec<EventAddress> = get_affecting_events(resource);
+fn get_resource_state(resource: &ResourceAddress) -> ZomeApiResult<Option<ActionId>> {
+    let events: Vec<EventAddress> = get_affecting_events(resource)?;
 
     // grab the most recent "pass" or "fail" action
-    events.iter()
+    Ok(events.iter()
         .rev()
         .fold(None, move |result, event| {
             // already found it, just fall through
@@ -233,7 +321,7 @@ fn get_resource_state(resource: &ResourceAddress) -> Option<ActionId> {
                 return result;
             }
 
-            let entry: ZomeApiResult<EventEntry> = read_record_entry(event);
+            let entry: GraphHelperResult<EventEntry> = read_record_entry(event);
             match entry {
                 Err(_) => result, // :TODO: this indicates some data integrity error
                 Ok(entry) => {
@@ -243,14 +331,14 @@ fn get_resource_state(resource: &ResourceAddress) -> Option<ActionId> {
                     }
                 },
             }
-        })
+        }))
 }
 
-fn get_resource_stage(resource: &ResourceAddress) -> Option<ProcessSpecificationAddress> {
-    let events: Vec<EventAddress> = get_affecting_events(resource);
+fn get_resource_stage(resource: &ResourceAddress) -> ZomeApiResult<Option<ProcessSpecificationAddress>> {
+    let events: Vec<EventAddress> = get_affecting_events(resource)?;
 
     // grab the most recent event with a process output association
-    events.iter()
+    Ok(events.iter()
         .rev()
         .fold(None, move |result, event| {
             // already found it, just fall through
@@ -259,14 +347,14 @@ fn get_resource_stage(resource: &ResourceAddress) -> Option<ProcessSpecification
                 return result;
             }
 
-            let entry: ZomeApiResult<EventEntry> = read_record_entry(event);
+            let entry: GraphHelperResult<EventEntry> = read_record_entry(event);
             match entry {
                 Err(_) => result, // :TODO: this indicates some data integrity error
                 Ok(entry) => {
                     match &entry.output_of {
                         Some(output_of) => {
                             // get the associated process
-                            let maybe_process_entry: ZomeApiResult<ProcessEntry> = read_record_entry(output_of);
+                            let maybe_process_entry: GraphHelperResult<ProcessEntry> = read_record_entry(output_of);
                             // check to see if it has an associated specification
                             match &maybe_process_entry {
                                 Ok(process_entry) => match &process_entry.based_on {
@@ -280,14 +368,14 @@ fn get_resource_stage(resource: &ResourceAddress) -> Option<ProcessSpecification
                     }
                 },
             }
-        })
+        }))
 }
 
 /// Read all the EconomicEvents affecting a given EconomicResource
-fn get_affecting_events(resource: &ResourceAddress) -> Vec<EventAddress> {
-    get_linked_addresses_as_type(
+fn get_affecting_events(resource: &ResourceAddress) -> ZomeApiResult<Vec<EventAddress>> {
+    Ok(get_linked_addresses_as_type(
         resource,
         RESOURCE_AFFECTED_BY_EVENT_LINK_TYPE,
         RESOURCE_AFFECTED_BY_EVENT_LINK_TAG,
-    ).into_owned()
+    )?.into_owned())
 }
@@ -14,8 +14,14 @@ extern crate serde_json;
 use holochain_json_api::{ json::JsonString, error::JsonError };
 use holochain_json_derive::{ DefaultJson };
 
-use hdk_graph_helpers::MaybeUndefined;
+use hdk_graph_helpers::{
+    MaybeUndefined,
+    pagination::{ PageRequest, is_page_request },
+    field_selection::FieldSelection,
+};
 use vf_core::type_aliases::{
+    Address,
+    RevisionId,
     ExternalURL,
     LocationAddress,
     ResourceSpecificationAddress,
@@ -23,7 +29,10 @@ use vf_core::type_aliases::{
     ProductBatchAddress,
 };
 
-use hc_zome_rea_economic_event_rpc::CreateRequest as EventCreateRequest;
+use hc_zome_rea_economic_event_rpc::{
+    CreateRequest as EventCreateRequest,
+    ResourceResponseData,
+};
 
 // Export external type interface to allow consuming zomes to easily import & define zome API
 pub use vf_core::type_aliases::{ ResourceAddress };
@@ -83,6 +92,8 @@ impl<'a> CreationPayload {
 pub struct UpdateRequest {
     pub id: ResourceAddress,
     #[serde(default)]
+    pub revision_id: Option<RevisionId>,
+    #[serde(default)]
     pub classified_as: MaybeUndefined<Vec<ExternalURL>>,
     #[serde(default)]
     pub image: MaybeUndefined<ExternalURL>,
@@ -99,6 +110,10 @@ impl<'a> UpdateRequest {
         &self.id
     }
 
+    pub fn get_revision_id(&'a self) -> Option<&Address> {
+        self.revision_id.as_ref().map(AsRef::as_ref)
+    }
+
     pub fn get_contained_in(&'a self) -> MaybeUndefined<ResourceAddress> {
         self.contained_in.to_owned()
     }
@@ -112,4 +127,34 @@ pub struct QueryParams {
     pub contains: Option<ResourceAddress>,
     pub contained_in: Option<ResourceAddress>,
     pub conforms_to: Option<ResourceSpecificationAddress>,
+
+    // pagination parameters, used by paginated query endpoints only
+    pub limit: Option<usize>,
+    pub cursor: Option<String>,
+
+    // response fields to load, all fields are returned if omitted
+    pub fields: Option<Vec<String>>,
+}
+
+impl<'a> QueryParams {
+    pub fn get_page_request(&'a self) -> PageRequest {
+        PageRequest::new(self.limit, self.cursor.to_owned())
+    }
+
+    pub fn is_page_request(&'a self) -> bool {
+        is_page_request(&self.limit, &self.cursor)
+    }
+
+    pub fn get_field_selection(&'a self) -> FieldSelection {
+        FieldSelection::from(self.fields.to_owned())
+    }
+}
+
+/// I/O struct to describe a single page of query results, along with the cursor to request the next page
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponsePage {
+    pub results: Vec<ResourceResponseData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
@@ -11,9 +11,12 @@ extern crate serde;
 extern crate serde_derive;
 extern crate serde_json;
 
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
 use hdk::{
     PUBLIC_TOKEN,
-    error::{ZomeApiResult},
     holochain_persistence_api::cas::content::Address,
 };
 use holochain_json_api::{ json::JsonString, error::JsonError };
@@ -21,11 +24,16 @@ use holochain_json_derive::{ DefaultJson };
 
 use hdk_graph_helpers::{
     MaybeUndefined,
-    record_interface::Updateable,
+    GraphHelperResult,
+    GraphHelperError,
+    record_interface::{ Updateable, Revisioned },
     rpc::read_from_zome,
+    identifiers::ERR_MSG_ENTRY_NOT_FOUND,
 };
 
 use vf_core::measurement::*;
+use vf_core::decimal::Decimal;
+use vf_core::builtin_units::get_builtin_unit;
 use vf_core::type_aliases::{
     ExternalURL,
     LocationAddress,
@@ -36,6 +44,7 @@ use vf_core::type_aliases::{
 };
 use vf_actions::{ ActionEffect, ActionInventoryEffect, get_builtin_action };
 use hc_zome_rea_resource_specification_rpc::{ResponseData as ResourceSpecificationResponse};
+use hc_zome_rea_unit_rpc::{ResponseData as UnitResponse};
 
 use hc_zome_rea_economic_resource_storage_consts::BRIDGED_SPECIFICATION_DHT;
 use hc_zome_rea_economic_resource_rpc::*;
@@ -46,18 +55,38 @@ use hc_zome_rea_economic_event_rpc::{
 
 //---------------- RECORD INTERNALS & VALIDATION ----------------
 
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, Updateable)]
 pub struct Entry {
+    #[updateable(immutable)]
     pub conforms_to: Option<ResourceSpecificationAddress>,
     pub classified_as: Option<Vec<ExternalURL>>,
+    #[updateable(immutable)]
     pub tracking_identifier: Option<String>,
+    #[updateable(immutable)]
     pub lot: Option<ProductBatchAddress>,
     pub image: Option<ExternalURL>,
+    #[updateable(immutable)]
     pub accounting_quantity: Option<QuantityValue>,
+    #[updateable(immutable)]
     pub onhand_quantity: Option<QuantityValue>,
     pub unit_of_effort: Option<UnitId>,
+    #[updateable(immutable)]
     pub current_location: Option<LocationAddress>,
     pub note: Option<String>,
+    // distinguishes revisions with identical quantities, eg. once a failed event's changes are undone
+    #[serde(default)]
+    #[updateable(revision)]
+    pub revision: u32,
+}
+
+impl Revisioned for Entry {
+    fn get_revision(&self) -> u32 {
+        self.revision
+    }
+
+    fn with_revision(&self, revision: u32) -> Entry {
+        Entry { revision, ..self.to_owned() }
+    }
 }
 
 impl Entry {
@@ -73,13 +102,18 @@ impl Entry {
 
 /// Handles create operations via observed event resource inspection parameter
 /// @see https://github.com/holo-rea/holo-rea/issues/65
-impl From<CreationPayload> for Entry
+///
+/// Fails if the event's quantity cannot be represented as the initial quantity of the resource.
+///
+impl TryFrom<CreationPayload> for Entry
 {
-    fn from(t: CreationPayload) -> Entry {
+    type Error = MeasurementError;
+
+    fn try_from(t: CreationPayload) -> Result<Entry, MeasurementError> {
         let conforming = t.get_resource_specification_id();
         let r = t.resource;
         let e = t.event;
-        Entry {
+        Ok(Entry {
             conforms_to: conforming.clone(),
             classified_as: if e.resource_classified_as == MaybeUndefined::Undefined { None } else { e.resource_classified_as.to_owned().to_option() },
             tracking_identifier: if r.tracking_identifier == MaybeUndefined::Undefined { None } else { r.tracking_identifier.to_owned().to_option() },
@@ -87,7 +121,7 @@ impl From<CreationPayload> for Entry
             image: if r.image == MaybeUndefined::Undefined { None } else { r.image.to_owned().to_option() },
             accounting_quantity: match e.resource_quantity.to_owned() {
                 MaybeUndefined::Some(resource_quantity) => update_quantity(
-                    Some(QuantityValue::new(0.0, resource_quantity.get_unit())), // :TODO: pull from e.resource_conforms_to.unit_of_effort if present
+                    Some(QuantityValue::new(Decimal::zero(), resource_quantity.get_unit())), // :TODO: pull from e.resource_conforms_to.unit_of_effort if present
                     e.resource_quantity.to_owned(),
                     &e.action,
                     ResourceValueType::AccountingValue,
@@ -95,12 +129,13 @@ impl From<CreationPayload> for Entry
                         Some(inventory_type) => inventory_type.to_owned(),
                         None => panic!("Developer error: EconomicEvent inventory type must be provided when creating EconomicResource!"),
                     },
-                ),
+                    &NoConversions, // initial quantity is in the unit of the event
+                )?,
                 _ => None,
             },
             onhand_quantity: match e.resource_quantity.to_owned() {
                 MaybeUndefined::Some(resource_quantity) => update_quantity(
-                    Some(QuantityValue::new(0.0, resource_quantity.get_unit())), // :TODO: pull from e.resource_conforms_to.unit_of_effort if present
+                    Some(QuantityValue::new(Decimal::zero(), resource_quantity.get_unit())), // :TODO: pull from e.resource_conforms_to.unit_of_effort if present
                     e.resource_quantity.to_owned(),
                     &e.action,
                     ResourceValueType::OnhandValue,
@@ -108,7 +143,8 @@ impl From<CreationPayload> for Entry
                         Some(inventory_type) => inventory_type.to_owned(),
                         None => panic!("Developer error: EconomicEvent inventory type must be provided when updating EconomicResource!"),
                     },
-                ),
+                    &NoConversions, // initial quantity is in the unit of the event
+                )?,
                 _ => None,
             },
             unit_of_effort: match conforming {
@@ -117,7 +153,8 @@ impl From<CreationPayload> for Entry
             },
             current_location: if r.current_location == MaybeUndefined::Undefined { None } else { r.current_location.to_owned().to_option() },
             note: if r.note == MaybeUndefined::Undefined { None } else { r.note.clone().into() },
-        }
+            revision: 0,
+        })
     }
 }
 
@@ -129,7 +166,7 @@ pub struct GetSpecificationRequest {
 }
 
 fn get_default_unit_for_specification(specification_id: ResourceSpecificationAddress) -> Option<UnitId> {
-    let spec_data: ZomeApiResult<ResourceSpecificationResponse> = read_from_zome(
+    let spec_data: GraphHelperResult<ResourceSpecificationResponse> = read_from_zome(
         BRIDGED_SPECIFICATION_DHT,
         "resource_specification",
         Address::from(PUBLIC_TOKEN.to_string()),    // :TODO:
@@ -143,37 +180,97 @@ fn get_default_unit_for_specification(specification_id: ResourceSpecificationAdd
     }
 }
 
-//---------------- UPDATE ----------------
+/// I/O struct for reading units from the specification DNA
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUnitRequest {
+    pub id: UnitId,
+}
 
-/// Handles update operations for correcting data entry errors
-impl Updateable<UpdateRequest> for Entry {
-    fn update_with(&self, e: &UpdateRequest) -> Entry {
-        Entry {
-            conforms_to: self.conforms_to.to_owned(),
-            classified_as: if e.classified_as == MaybeUndefined::Undefined { self.classified_as.to_owned() } else { e.classified_as.to_owned().to_option() },
-            tracking_identifier: self.tracking_identifier.to_owned(),
-            lot: self.lot.to_owned(),
-            image: if e.image == MaybeUndefined::Undefined { self.image.to_owned() } else { e.image.to_owned().to_option() },
-            accounting_quantity: self.accounting_quantity.to_owned(),
-            onhand_quantity: self.onhand_quantity.to_owned(),
-            unit_of_effort: if e.unit_of_effort == MaybeUndefined::Undefined { self.unit_of_effort.to_owned() } else { e.unit_of_effort.to_owned().to_option() },
-            current_location: self.current_location.to_owned(),
-            note: if e.note == MaybeUndefined::Undefined { self.note.to_owned() } else { e.note.to_owned().to_option() },
+/// Reads the conversions, precision & dimensions declared for units from the `unit` zome
+/// of the specification DNA. Each unit is read at most once, and built-in units are
+/// resolved without reading from the zome at all.
+///
+/// Units which the zome reports do not exist declare nothing. Any other failure to read a
+/// unit is returned as `MeasurementError::UnitUnavailable`, and the unit is read again on
+/// next use rather than being remembered as unknown.
+///
+#[derive(Default)]
+pub struct SpecificationUnitConversions {
+    units: RefCell<HashMap<String, Option<Unit>>>,
+}
+
+impl SpecificationUnitConversions {
+    fn read_unit(&self, unit: &UnitId) -> Result<Option<Unit>, MeasurementError> {
+        if let Some(builtin) = get_builtin_unit(unit.as_ref()) {
+            return Ok(Some(builtin));
         }
+        if let Some(cached) = self.units.borrow().get(unit.as_ref()) {
+            return Ok(cached.to_owned());
+        }
+
+        let unit_data: GraphHelperResult<UnitResponse> = read_from_zome(
+            BRIDGED_SPECIFICATION_DHT,
+            "unit",
+            Address::from(PUBLIC_TOKEN.to_string()),    // :TODO:
+            "get_unit",
+            GetUnitRequest { id: unit.to_owned() }.into(),
+        );
+
+        let result = match unit_data {
+            Ok(unit_response) => Some(Unit {
+                id: unit_response.unit.id,
+                label: unit_response.unit.label,
+                symbol: unit_response.unit.symbol,
+                dimension: unit_response.unit.dimension,
+                conversion: unit_response.unit.conversion,
+                precision: unit_response.unit.precision,
+            }),
+            // unknown units cannot be converted
+            Err(GraphHelperError::RemoteCallFailed { ref cause, .. }) if cause == ERR_MSG_ENTRY_NOT_FOUND => None,
+            Err(e) => return Err(MeasurementError::UnitUnavailable { unit: unit.to_owned(), cause: e.to_string() }),
+        };
+        self.units.borrow_mut().insert(unit.as_ref().to_owned(), result.to_owned());
+        Ok(result)
     }
 }
 
-/// Handle update operations by observed events
-///
-/// :WARNING: we presume the event has already been determined to relate to the
-/// resource, and this method will panic if that is not the case.
-///
-/// Currently it is only called within `hdk_graph_helpers::record_helpers::update_record`,
-/// where this check is already implicitly performed.
-///
-impl Updateable<EventCreateRequest> for Entry {
-    fn update_with(&self, e: &EventCreateRequest) -> Entry {
-        Entry {
+impl UnitConversions for SpecificationUnitConversions {
+    fn get_conversion(&self, unit: &UnitId) -> Result<Option<UnitConversion>, MeasurementError> {
+        Ok(self.read_unit(unit)?.and_then(|u| u.conversion))
+    }
+
+    fn get_precision(&self, unit: &UnitId) -> Result<Option<UnitPrecision>, MeasurementError> {
+        Ok(self.read_unit(unit)?.and_then(|u| u.precision))
+    }
+
+    fn get_dimension(&self, unit: &UnitId) -> Result<Option<Dimension>, MeasurementError> {
+        Ok(self.read_unit(unit)?.and_then(|u| u.dimension))
+    }
+}
+
+//---------------- UPDATE ----------------
+
+impl Entry {
+    /// Determine the new state of the resource after applying event `e`, converting the event's
+    /// quantity to the units of the resource with `conversions` where they differ- usually
+    /// a `SpecificationUnitConversions` shared with any other checks made of the event.
+    ///
+    /// :WARNING: we presume the event has already been determined to relate to the
+    /// resource, and this method will panic if its inventory type has not been set.
+    ///
+    /// Fails if the event's quantity measures a different dimension than the resource's,
+    /// regardless of whether the event alters the resource's quantities, or if it cannot
+    /// be expressed in the resource's units.
+    ///
+    pub fn update_with_event(&self, e: &EventCreateRequest, conversions: &dyn UnitConversions) -> Result<Entry, MeasurementError> {
+        if let MaybeUndefined::Some(event_qty) = &e.resource_quantity {
+            for resource_qty in self.accounting_quantity.iter().chain(self.onhand_quantity.iter()) {
+                check_same_dimension(resource_qty, event_qty, conversions)?;
+            }
+        }
+
+        Ok(Entry {
             conforms_to: self.conforms_to.to_owned(),
             classified_as: {
                 if let MaybeUndefined::Some(classified_as) = e.resource_classified_as.to_owned() {
@@ -204,14 +301,16 @@ impl Updateable<EventCreateRequest> for Entry {
                     Some(inventory_type) => inventory_type.to_owned(),
                     None => panic!("Developer error: EconomicEvent inventory type must be provided when updating EconomicResource!"),
                 },
-            ),
+                conversions,
+            )?,
             onhand_quantity: update_quantity(
                 self.onhand_quantity.to_owned(), e.resource_quantity.to_owned(),
                 &e.action, ResourceValueType::OnhandValue, match &e.target_inventory_type {
                     Some(inventory_type) => inventory_type.to_owned(),
                     None => panic!("Developer error: EconomicEvent inventory type must be provided when updating EconomicResource!"),
                 },
-            ),
+                conversions,
+            )?,
             unit_of_effort: self.unit_of_effort.to_owned(), // :TODO: pull from e.resource_conforms_to.unit_of_effort
             current_location: if e.get_action() == "move" {
                 if let MaybeUndefined::Some(at_location) = e.get_location() {
@@ -221,7 +320,54 @@ impl Updateable<EventCreateRequest> for Entry {
                 }
             } else { self.current_location.to_owned() },
             note: self.note.to_owned(),
-        }
+            revision: self.revision + 1,
+        })
+    }
+
+    /// Undo the changes made by `update_with_event` in moving a resource from `prior` to `updated`,
+    /// applying their inverse to this, the resource's current state.
+    ///
+    /// Unlike restoring `prior` outright, any changes made to the resource by other events
+    /// in the meantime are retained.
+    ///
+    pub fn revert_update(&self, prior: &Entry, updated: &Entry, conversions: &dyn UnitConversions) -> Result<Entry, MeasurementError> {
+        Ok(Entry {
+            classified_as: match &self.classified_as {
+                // remove only the classifications which the update added
+                Some(classified_as) => Some(classified_as.iter()
+                    .filter(|url| {
+                        !updated.classified_as.iter().flatten().any(|added| added == *url)
+                        || prior.classified_as.iter().flatten().any(|existing| existing == *url)
+                    })
+                    .cloned()
+                    .collect()),
+                None => None,
+            },
+            accounting_quantity: revert_quantity(&self.accounting_quantity, &prior.accounting_quantity, &updated.accounting_quantity, conversions)?,
+            onhand_quantity: revert_quantity(&self.onhand_quantity, &prior.onhand_quantity, &updated.onhand_quantity, conversions)?,
+            current_location: if self.current_location == updated.current_location {
+                prior.current_location.to_owned()
+            } else {
+                self.current_location.to_owned()
+            },
+            revision: self.revision + 1,
+            ..self.to_owned()
+        })
+    }
+}
+
+/// Subtract the change from `prior` to `updated` from the `current` value of a resource quantity
+fn revert_quantity(
+    current: &Option<QuantityValue>,
+    prior: &Option<QuantityValue>,
+    updated: &Option<QuantityValue>,
+    conversions: &dyn UnitConversions,
+) -> Result<Option<QuantityValue>, MeasurementError> {
+    match (current, prior, updated) {
+        (Some(current), Some(prior), Some(updated)) => {
+            Ok(Some(current.checked_sub(&updated.checked_sub(prior, conversions)?, conversions)?))
+        },
+        _ => Ok(current.to_owned()),
     }
 }
 
@@ -232,23 +378,27 @@ fn update_quantity(
     action: &ActionId,
     which_qty_type: ResourceValueType,
     which_inventory_type: ResourceInventoryType,
-) -> Option<QuantityValue> {
+    conversions: &dyn UnitConversions,
+) -> Result<Option<QuantityValue>, MeasurementError> {
     if None == current_val {
-        return None;
+        return Ok(None);
     }
     if MaybeUndefined::None == event_val || MaybeUndefined::Undefined == event_val {
-        return current_val;
+        return Ok(current_val);
     }
     let current = current_val.unwrap();
     let event_qty = event_val.unwrap();
 
     let action_to_perform = get_event_action(action, which_qty_type, which_inventory_type);
 
-    match action_to_perform {
-        ActionInventoryEffect::NoEffect => Some(current),
-        ActionInventoryEffect::Increment => Some(add(current, event_qty)),
-        ActionInventoryEffect::Decrement => Some(subtract(current, event_qty)),
-    }
+    let updated = match action_to_perform {
+        ActionInventoryEffect::NoEffect => return Ok(Some(current)),
+        ActionInventoryEffect::Increment => add(current, event_qty, conversions)?,
+        ActionInventoryEffect::Decrement => subtract(current, event_qty, conversions)?,
+    };
+
+    // event quantities may be given to more places than the resource's unit holds
+    Ok(Some(updated.round_to_precision(conversions)?))
 }
 
 enum ResourceValueType {
@@ -314,3 +464,246 @@ fn get_event_action(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vf_core::decimal::RoundingMode;
+
+    fn dec(value: f64) -> Decimal {
+        Decimal::try_from(value).unwrap()
+    }
+
+    fn event_in(unit: &str, action: &str, quantity: f64, inventory_type: ResourceInventoryType) -> EventCreateRequest {
+        let event: EventCreateRequest = serde_json::from_str(&format!(r#"{{
+            "action": "{}",
+            "provider": "agent1",
+            "receiver": "agent2",
+            "resourceQuantity": {{ "hasNumericalValue": {}, "hasUnit": "{}" }}
+        }}"#, action, quantity, unit)).unwrap();
+        event.with_inventory_type(inventory_type)
+    }
+
+    fn event(action: &str, quantity: f64, inventory_type: ResourceInventoryType) -> EventCreateRequest {
+        event_in("kg", action, quantity, inventory_type)
+    }
+
+    fn resource(accounting: f64, onhand: f64) -> Entry {
+        Entry {
+            conforms_to: None,
+            classified_as: Some(vec![ExternalURL::from("http://example.com/apples".to_string())]),
+            tracking_identifier: None,
+            lot: None,
+            image: None,
+            accounting_quantity: kg(accounting),
+            onhand_quantity: kg(onhand),
+            unit_of_effort: None,
+            current_location: None,
+            note: None,
+            revision: 0,
+        }
+    }
+
+    fn kg(value: f64) -> Option<QuantityValue> {
+        Some(QuantityValue::new(dec(value), Some(UnitId::from("kg".to_string()))))
+    }
+
+    #[test]
+    fn test_normal_action_effects() {
+        let updated = resource(10.0, 10.0).update_with_event(&event("produce", 2.0, ResourceInventoryType::ReceivingInventory), &NoConversions).unwrap();
+        assert_eq!(updated.accounting_quantity, kg(12.0));
+        assert_eq!(updated.onhand_quantity, kg(12.0));
+
+        let updated = resource(10.0, 10.0).update_with_event(&event("consume", 2.0, ResourceInventoryType::ProvidingInventory), &NoConversions).unwrap();
+        assert_eq!(updated.accounting_quantity, kg(8.0));
+        assert_eq!(updated.onhand_quantity, kg(8.0));
+
+        let updated = resource(10.0, 10.0).update_with_event(&event("use", 2.0, ResourceInventoryType::ProvidingInventory), &NoConversions).unwrap();
+        assert_eq!(updated.accounting_quantity, kg(10.0));
+        assert_eq!(updated.onhand_quantity, kg(10.0));
+    }
+
+    #[test]
+    fn test_transfer_effects() {
+        let provider = resource(10.0, 10.0).update_with_event(&event("transfer", 3.0, ResourceInventoryType::ProvidingInventory), &NoConversions).unwrap();
+        let receiver = resource(0.0, 0.0).update_with_event(&event("transfer", 3.0, ResourceInventoryType::ReceivingInventory), &NoConversions).unwrap();
+        assert_eq!((provider.accounting_quantity, provider.onhand_quantity), (kg(7.0), kg(7.0)));
+        assert_eq!((receiver.accounting_quantity, receiver.onhand_quantity), (kg(3.0), kg(3.0)));
+
+        // custody changes affect only the on-hand quantity; rights only the accounting quantity
+        let custody = resource(10.0, 10.0).update_with_event(&event("transfer-custody", 3.0, ResourceInventoryType::ProvidingInventory), &NoConversions).unwrap();
+        assert_eq!((custody.accounting_quantity, custody.onhand_quantity), (kg(10.0), kg(7.0)));
+
+        let rights = resource(10.0, 10.0).update_with_event(&event("transfer-all-rights", 3.0, ResourceInventoryType::ProvidingInventory), &NoConversions).unwrap();
+        assert_eq!((rights.accounting_quantity, rights.onhand_quantity), (kg(7.0), kg(10.0)));
+    }
+
+    #[test]
+    fn test_missing_quantities_unchanged() {
+        let mut e = event("consume", 2.0, ResourceInventoryType::ProvidingInventory);
+        e.resource_quantity = MaybeUndefined::Undefined;
+        let updated = resource(10.0, 5.0).update_with_event(&e, &NoConversions).unwrap();
+        assert_eq!((updated.accounting_quantity, updated.onhand_quantity), (kg(10.0), kg(5.0)));
+
+        let mut r = resource(10.0, 5.0);
+        r.accounting_quantity = None;
+        let updated = r.update_with_event(&event("consume", 2.0, ResourceInventoryType::ProvidingInventory), &NoConversions).unwrap();
+        assert_eq!(updated.accounting_quantity, None);
+    }
+
+    #[test]
+    fn test_quantities_do_not_drift() {
+        let mut r = resource(0.0, 0.0);
+        for _ in 0..1000 {
+            r = r.update_with_event(&event("produce", 0.1, ResourceInventoryType::ReceivingInventory), &NoConversions).unwrap();
+        }
+        assert_eq!((r.accounting_quantity, r.onhand_quantity), (kg(100.0), kg(100.0)));
+    }
+
+    #[test]
+    fn test_reverting_update_keeps_later_changes() {
+        let prior = resource(10.0, 10.0);
+        let updated = prior.update_with_event(&event("consume", 2.0, ResourceInventoryType::ProvidingInventory), &NoConversions).unwrap();
+        // another event altered the resource in the meantime
+        let current = updated.update_with_event(&event("produce", 5.0, ResourceInventoryType::ReceivingInventory), &NoConversions).unwrap();
+
+        let reverted = current.revert_update(&prior, &updated, &NoConversions).unwrap();
+        assert_eq!((reverted.accounting_quantity, reverted.onhand_quantity), (kg(15.0), kg(15.0)));
+        assert_eq!(reverted.revision, current.revision + 1);
+    }
+
+    #[test]
+    fn test_event_quantities_converted() {
+        let mut conversions = HashMap::new();
+        conversions.insert("g".to_string(), UnitConversion { reference_unit: UnitId::from("kg".to_string()), factor: dec(0.001) });
+
+        let updated = resource(10.0, 10.0).update_with_event(&event_in("g", "produce", 500.0, ResourceInventoryType::ReceivingInventory), &conversions).unwrap();
+        assert_eq!((updated.accounting_quantity, updated.onhand_quantity), (kg(10.5), kg(10.5)), "resource quantities keep their unit");
+
+        let failed = resource(10.0, 10.0).update_with_event(&event_in("h", "produce", 2.0, ResourceInventoryType::ReceivingInventory), &conversions);
+        assert_eq!(failed.unwrap_err(), MeasurementError::NoConversion {
+            from: Some(UnitId::from("h".to_string())),
+            to: Some(UnitId::from("kg".to_string())),
+        });
+    }
+
+    #[test]
+    fn test_quantities_rounded_to_unit_precision() {
+        let mut units = HashMap::new();
+        units.insert("kg".to_string(), Unit {
+            id: UnitId::from("kg".to_string()), label: "kilograms".to_string(), symbol: "kg".to_string(),
+            dimension: Some(Dimension::Mass), conversion: None,
+            precision: Some(UnitPrecision { decimal_places: 2, rounding: RoundingMode::HalfUp }),
+        });
+
+        let updated = resource(10.0, 10.0).update_with_event(&event("produce", 0.125, ResourceInventoryType::ReceivingInventory), &units).unwrap();
+        assert_eq!((updated.accounting_quantity, updated.onhand_quantity), (kg(10.13), kg(10.13)), "same-unit events are rounded");
+
+        let updated = resource(10.0, 10.0).update_with_event(&event("consume", 0.125, ResourceInventoryType::ProvidingInventory), &units).unwrap();
+        assert_eq!(updated.accounting_quantity, kg(9.88));
+    }
+
+    #[test]
+    fn test_event_dimensions_checked() {
+        let mut units = HashMap::new();
+        for (id, dimension) in [("kg", Dimension::Mass), ("h", Dimension::Time)].iter() {
+            units.insert(id.to_string(), Unit {
+                id: UnitId::from(id.to_string()), label: id.to_string(), symbol: id.to_string(),
+                dimension: Some(*dimension), conversion: None, precision: None,
+            });
+        }
+
+        let failed = resource(10.0, 10.0).update_with_event(&event_in("h", "use", 2.0, ResourceInventoryType::ProvidingInventory), &units);
+        assert_eq!(
+            failed.unwrap_err(), MeasurementError::DimensionMismatch { expected: Dimension::Mass, found: Dimension::Time },
+            "checked even where the resource quantity is unaffected",
+        );
+        assert!(resource(10.0, 10.0).update_with_event(&event("transfer", 2.0, ResourceInventoryType::ProvidingInventory), &units).is_ok());
+    }
+
+    #[cfg(feature = "mock-hdk")]
+    #[test]
+    fn test_conversions_read_from_unit_zome() {
+        use hdk_graph_helpers::mock_hdk;
+
+        mock_hdk::register_zome_fn(BRIDGED_SPECIFICATION_DHT, "unit", "get_unit", |_args| {
+            Ok(JsonString::from_json(r#"{"Ok":{"unit":{"id":"jin","label":"catties","symbol":"jin","dimension":"mass","conversion":{"referenceUnit":"kg","factor":0.5}}}}"#))
+        });
+
+        let updated = resource(10.0, 10.0).update_with_event(&event_in("jin", "consume", 3.0, ResourceInventoryType::ProvidingInventory), &SpecificationUnitConversions::default()).unwrap();
+        assert_eq!(updated.accounting_quantity, kg(8.5));
+    }
+
+    #[cfg(feature = "mock-hdk")]
+    #[test]
+    fn test_unreadable_units_not_remembered() {
+        use hdk_graph_helpers::mock_hdk;
+        use hdk::error::ZomeApiError;
+
+        let units = SpecificationUnitConversions::default();
+        let jin = UnitId::from("jin".to_string());
+
+        mock_hdk::register_zome_fn(BRIDGED_SPECIFICATION_DHT, "unit", "get_unit", |_args| {
+            Err(ZomeApiError::Internal("unavailable".to_string()))
+        });
+        match units.get_dimension(&jin) {
+            Err(MeasurementError::UnitUnavailable { unit, .. }) => assert_eq!(unit, jin),
+            other => panic!("expected unit to be unavailable, got {:?}", other),
+        }
+
+        mock_hdk::register_zome_fn(BRIDGED_SPECIFICATION_DHT, "unit", "get_unit", |_args| {
+            Ok(JsonString::from_json(r#"{"Ok":{"unit":{"id":"jin","label":"catties","symbol":"jin","dimension":"mass"}}}"#))
+        });
+        assert_eq!(units.get_dimension(&jin), Ok(Some(Dimension::Mass)), "read again once available");
+    }
+
+    #[cfg(feature = "mock-hdk")]
+    #[test]
+    fn test_missing_units_remembered() {
+        use hdk_graph_helpers::mock_hdk;
+
+        let units = SpecificationUnitConversions::default();
+        let jin = UnitId::from("jin".to_string());
+
+        mock_hdk::register_zome_fn(BRIDGED_SPECIFICATION_DHT, "unit", "get_unit", |_args| {
+            Ok(JsonString::from_json(&format!(r#"{{"Err":{{"Internal":"{}"}}}}"#, ERR_MSG_ENTRY_NOT_FOUND)))
+        });
+        assert_eq!(units.get_dimension(&jin), Ok(None));
+
+        mock_hdk::register_zome_fn(BRIDGED_SPECIFICATION_DHT, "unit", "get_unit", |_args| {
+            Err(hdk::error::ZomeApiError::Internal("should not be called again".to_string()))
+        });
+        assert_eq!(units.get_dimension(&jin), Ok(None));
+    }
+
+    #[test]
+    fn test_builtin_units_resolved_locally() {
+        // no `unit` zome is available to read from outside of the mock environment
+        let builtin_kg = |value| Some(QuantityValue::new(dec(value), Some(UnitId::from("om2:kg".to_string()))));
+        let stock = Entry { accounting_quantity: builtin_kg(10.0), onhand_quantity: builtin_kg(10.0), ..resource(10.0, 10.0) };
+
+        let updated = stock.update_with_event(&event_in("om2:g", "consume", 250.0, ResourceInventoryType::ProvidingInventory), &SpecificationUnitConversions::default()).unwrap();
+        assert_eq!(updated.accounting_quantity, builtin_kg(9.75));
+    }
+
+    #[cfg(feature = "mock-hdk")]
+    #[test]
+    fn test_create_from_event() {
+        use hdk_graph_helpers::mock_hdk;
+
+        mock_hdk::register_zome_fn(BRIDGED_SPECIFICATION_DHT, "resource_specification", "get_resource_specification", |_args| {
+            Ok(JsonString::from_json(r#"{"Ok":{"resourceSpecification":{"id":"spec1","name":"apples","defaultUnitOfEffort":"hour"}}}"#))
+        });
+
+        let resource_params: CreateRequest = serde_json::from_str(r#"{ "conformsTo": "spec1", "note": "fresh" }"#).unwrap();
+        let created = Entry::try_from(CreationPayload {
+            event: event("produce", 4.0, ResourceInventoryType::ReceivingInventory),
+            resource: resource_params,
+        }).unwrap();
+
+        assert_eq!(created.accounting_quantity, kg(4.0));
+        assert_eq!(created.onhand_quantity, kg(4.0));
+        assert_eq!(created.unit_of_effort, Some(UnitId::from("hour".to_string())));
+        assert_eq!(created.note, Some("fresh".to_string()));
+    }
+}
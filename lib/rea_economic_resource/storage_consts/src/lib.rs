@@ -5,6 +5,12 @@
  *
  * @package Holo-REA
  */
+use hdk_graph_helpers::{ LinkSpec, BidirectionalIndex };
+use hc_zome_rea_resource_specification_storage_consts::{
+    ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE,
+    RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TYPE, RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TAG,
+};
+
 pub const RESOURCE_BASE_ENTRY_TYPE: &str = "vf_economic_resource_baseurl";
 pub const RESOURCE_INITIAL_ENTRY_LINK_TYPE: &str = "vf_economic_resource_entry";
 pub const RESOURCE_ENTRY_TYPE: &str = "vf_economic_resource";
@@ -17,9 +23,25 @@ pub const RESOURCE_AFFECTED_BY_EVENT_LINK_TAG: &str = "affected_by";
 pub const RESOURCE_CONFORMS_TO_LINK_TYPE: &str = "vf_economic_resource_conforms_to";
 pub const RESOURCE_CONFORMS_TO_LINK_TAG: &str = "conforms_to";
 
-// :TODO: replace with a DAG
+// :DEPRECATED: unordered index of all records, superseded by the time index below.
+// Retained only so that records indexed before the time index existed can be migrated to it.
 pub const RESOURCE_INDEX_ROOT_ENTRY_TYPE: &str = "vf_economic_resources_root";
 pub const RESOURCE_INDEX_ROOT_ENTRY_ID: &str = "all_vf_economic_resources";
 pub const RESOURCE_INDEX_ENTRY_LINK_TYPE: &str = "vf_economic_resource_root_index";
 
+// chronological index of all records, used by `get_all` API endpoints and time range queries
+pub const RESOURCE_TIME_INDEX_ENTRY_TYPE: &str = "vf_economic_resource_time_index";
+pub const RESOURCE_TIME_INDEX_LINK_TYPE: &str = "vf_economic_resource_time_indexed";
+
+/// Resources contained within another resource
+pub const RESOURCE_CONTAINED_IN_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: RESOURCE_BASE_ENTRY_TYPE, link_type: RESOURCE_CONTAINED_IN_LINK_TYPE, link_tag: RESOURCE_CONTAINED_IN_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: RESOURCE_BASE_ENTRY_TYPE, link_type: RESOURCE_CONTAINS_LINK_TYPE, link_tag: RESOURCE_CONTAINS_LINK_TAG },
+};
+/// Resources conforming to a resource specification
+pub const RESOURCE_CONFORMS_TO_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: RESOURCE_BASE_ENTRY_TYPE, link_type: RESOURCE_CONFORMS_TO_LINK_TYPE, link_tag: RESOURCE_CONFORMS_TO_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE, link_type: RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TYPE, link_tag: RESOURCE_SPECIFICATION_CONFORMING_RESOURCE_LINK_TAG },
+};
+
 pub const BRIDGED_SPECIFICATION_DHT: &str = "vf_specification";
@@ -9,20 +9,25 @@
  *
  * @package Holo-REA
  */
+use hdk::error::ZomeApiResult;
+
+use hdk_graph_helpers::records::get_revision_id;
 use vf_core::type_aliases::FulfillmentAddress;
+use hc_zome_rea_fulfillment_storage_consts::FULFILLMENT_ENTRY_TYPE;
 use hc_zome_rea_fulfillment_storage::Entry;
 use hc_zome_rea_fulfillment_rpc::*;
 
 /// Create response from input DHT primitives
-pub fn construct_response(address: &FulfillmentAddress, e: &Entry) -> ResponseData {
-    ResponseData {
+pub fn construct_response(address: &FulfillmentAddress, e: &Entry) -> ZomeApiResult<ResponseData> {
+    Ok(ResponseData {
         fulfillment: Response {
             id: address.to_owned(),
+            revision_id: get_revision_id(FULFILLMENT_ENTRY_TYPE, e)?.into(),
             fulfilled_by: e.fulfilled_by.to_owned(),
             fulfills: e.fulfills.to_owned(),
             resource_quantity: e.resource_quantity.to_owned(),
             effort_quantity: e.effort_quantity.to_owned(),
             note: e.note.to_owned(),
         }
-    }
+    })
 }
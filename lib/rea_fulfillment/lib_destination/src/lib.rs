@@ -15,16 +15,20 @@ use hdk_graph_helpers::{
     records::{
         create_record,
         read_record_entry,
+        read_record_entries,
         update_record,
         delete_record,
+        discard_created_record,
     },
+    idempotency::get_idempotent_record_address,
+    saga::run_saga,
     local_indexes::{
-        create_direct_index,
-        query_direct_index_with_foreign_key,
+        IndexQuery,
+        query_indexes_with_foreign_key,
     },
+    errors::GraphHelperResult,
 };
 
-use hc_zome_rea_economic_event_storage_consts::{EVENT_FULFILLS_LINK_TYPE, EVENT_FULFILLS_LINK_TAG};
 use hc_zome_rea_fulfillment_storage_consts::*;
 use hc_zome_rea_fulfillment_storage::Entry;
 use hc_zome_rea_fulfillment_rpc::*;
@@ -38,12 +42,16 @@ pub fn receive_get_fulfillment(address: FulfillmentAddress) -> ZomeApiResult<Res
     handle_get_fulfillment(&address)
 }
 
+pub fn receive_get_fulfillments_by_ids(addresses: Vec<FulfillmentAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+    Ok(handle_get_fulfillments_by_ids(&addresses))
+}
+
 pub fn receive_update_fulfillment(fulfillment: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_fulfillment(&fulfillment)
 }
 
 pub fn receive_delete_fulfillment(address: FulfillmentAddress) -> ZomeApiResult<bool> {
-    delete_record::<Entry>(&address)
+    Ok(delete_record::<Entry>(&address)?)
 }
 
 pub fn receive_query_fulfillments(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
@@ -51,19 +59,28 @@ pub fn receive_query_fulfillments(params: QueryParams) -> ZomeApiResult<Vec<Resp
 }
 
 fn handle_create_fulfillment(fulfillment: &CreateRequest) -> ZomeApiResult<ResponseData> {
-    let (fulfillment_address, entry_resp): (FulfillmentAddress, Entry) = create_record(
-        FULFILLMENT_BASE_ENTRY_TYPE, FULFILLMENT_ENTRY_TYPE,
-        FULFILLMENT_INITIAL_ENTRY_LINK_TYPE,
-        fulfillment.to_owned()
-    )?;
-
-    // link entries in the local DNA
-    let _results = create_direct_index(
-        fulfillment_address.as_ref(),
-        fulfillment.get_fulfilled_by().as_ref(),
-        FULFILLMENT_FULFILLEDBY_LINK_TYPE, FULFILLMENT_FULFILLEDBY_LINK_TAG,
-        EVENT_FULFILLS_LINK_TYPE, EVENT_FULFILLS_LINK_TAG,
-    );
+    // retried requests resolve to the record created by the original, whose indexes have already been written
+    if let Some(existing) = get_idempotent_record_address(FULFILLMENT_BASE_ENTRY_TYPE, fulfillment.get_idempotency_key())? {
+        return handle_get_fulfillment(&existing);
+    }
+
+    // the record is removed again if it cannot be indexed, so that the origin DNA's request can be retried
+    let (fulfillment_address, entry_resp) = run_saga(|saga| {
+        let (fulfillment_address, entry_resp): (FulfillmentAddress, Entry) = saga.step("create fulfillment",
+            || create_record(
+                FULFILLMENT_BASE_ENTRY_TYPE, FULFILLMENT_ENTRY_TYPE,
+                FULFILLMENT_INITIAL_ENTRY_LINK_TYPE,
+                fulfillment.get_idempotency_key(),
+                fulfillment.to_owned()
+            ),
+            |(address, _): (FulfillmentAddress, Entry)| discard_created_record::<Entry>(FULFILLMENT_BASE_ENTRY_TYPE, &address, fulfillment.get_idempotency_key()),
+        )?;
+
+        // link entries in the local DNA
+        saga.create_direct_index("index fulfilling event", fulfillment_address.as_ref(), fulfillment.get_fulfilled_by().as_ref(), &FULFILLMENT_FULFILLEDBY_INDEX)?;
+
+        Ok((fulfillment_address, entry_resp))
+    })?;
 
     // register in the associated foreign DNA as well
     // :TODO: probably need to remove this, can't do bridging bidirectionally
@@ -75,30 +92,37 @@ fn handle_create_fulfillment(fulfillment: &CreateRequest) -> ZomeApiResult<Respo
     //     fulfillment.into(),
     // );
 
-    Ok(construct_response(&fulfillment_address, &entry_resp))
+    construct_response(&fulfillment_address, &entry_resp)
 }
 
 fn handle_update_fulfillment(fulfillment: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let base_address = fulfillment.get_id();
-    let new_entry = update_record(FULFILLMENT_ENTRY_TYPE, base_address, fulfillment)?;
-    Ok(construct_response(&base_address, &new_entry))
+    // :NOTE: revisions are checked by the origin DNA prior to forwarding, this copy only mirrors it
+    let new_entry = update_record(FULFILLMENT_ENTRY_TYPE, base_address, None, fulfillment)?;
+    construct_response(&base_address, &new_entry)
 }
 
 /// Read an individual fulfillment's details
 fn handle_get_fulfillment(base_address: &FulfillmentAddress) -> ZomeApiResult<ResponseData> {
     let entry = read_record_entry(base_address)?;
-    Ok(construct_response(base_address, &entry))
+    construct_response(base_address, &entry)
+}
+
+fn handle_get_fulfillments_by_ids(addresses: &[FulfillmentAddress]) -> Vec<ZomeApiResult<ResponseData>> {
+    addresses.iter()
+        .zip(read_record_entries(addresses))
+        .map(|(address, entry)| construct_response(address, &entry?))
+        .collect()
 }
 
 fn handle_query_fulfillments(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(FulfillmentAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
+    let mut filters: Vec<IndexQuery> = vec![];
+
+    if let Some(fulfilled_by) = &params.fulfilled_by {
+        filters.push(IndexQuery::local(fulfilled_by, &FULFILLMENT_FULFILLEDBY_INDEX.reverse));
+    }
 
-    match &params.fulfilled_by {
-        Some(fulfilled_by) => {
-            entries_result = query_direct_index_with_foreign_key(fulfilled_by, EVENT_FULFILLS_LINK_TYPE, EVENT_FULFILLS_LINK_TAG);
-        },
-        _ => (),
-    };
+    let entries_result: GraphHelperResult<Vec<(FulfillmentAddress, Option<Entry>)>> = query_indexes_with_foreign_key(&IndexQuery::And(filters), false);
 
     match entries_result {
         Ok(entries) => Ok(
@@ -106,13 +130,13 @@ fn handle_query_fulfillments(params: &QueryParams) -> ZomeApiResult<Vec<Response
                 .map(|(entry_base_address, maybe_entry)| {
                     // :TODO: avoid cloning entry
                     match maybe_entry {
-                        Some(entry) => Ok(construct_response(entry_base_address, &entry)),
+                        Some(entry) => construct_response(entry_base_address, &entry),
                         None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
                     }
                 })
                 .filter_map(Result::ok)
                 .collect()
         ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
+        Err(e) => Err(e.into()),
     }
 }
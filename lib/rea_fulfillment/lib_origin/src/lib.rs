@@ -10,25 +10,28 @@
  * @package Holo-REA
  */
 use hdk::prelude::*;
-use hdk::{
-    PUBLIC_TOKEN,
-    call,
-};
+use hdk::PUBLIC_TOKEN;
 
 use hdk_graph_helpers::{
     records::{
         create_record,
         read_record_entry,
+        read_record_entries,
         update_record,
+        revert_record,
         delete_record,
+        discard_created_record,
     },
+    idempotency::get_idempotent_record_address,
+    saga::run_saga,
     local_indexes::{
-        create_direct_index,
-        query_direct_index_with_foreign_key,
+        IndexQuery,
+        query_indexes_with_foreign_key,
     },
+    rpc::read_from_zome,
+    errors::GraphHelperResult,
 };
 
-use hc_zome_rea_commitment_storage_consts::{COMMITMENT_FULFILLEDBY_LINK_TYPE, COMMITMENT_FULFILLEDBY_LINK_TAG};
 use hc_zome_rea_fulfillment_storage_consts::*;
 use hc_zome_rea_fulfillment_storage::Entry;
 use hc_zome_rea_fulfillment_rpc::*;
@@ -42,6 +45,10 @@ pub fn receive_get_fulfillment(address: FulfillmentAddress) -> ZomeApiResult<Res
     handle_get_fulfillment(&address)
 }
 
+pub fn receive_get_fulfillments_by_ids(addresses: Vec<FulfillmentAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+    Ok(handle_get_fulfillments_by_ids(&addresses))
+}
+
 pub fn receive_update_fulfillment(fulfillment: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_fulfillment(&fulfillment)
 }
@@ -55,86 +62,103 @@ pub fn receive_query_fulfillments(params: QueryParams) -> ZomeApiResult<Vec<Resp
 }
 
 fn handle_create_fulfillment(fulfillment: &CreateRequest) -> ZomeApiResult<ResponseData> {
-    let (fulfillment_address, entry_resp): (FulfillmentAddress, Entry) = create_record(
-        FULFILLMENT_BASE_ENTRY_TYPE, FULFILLMENT_ENTRY_TYPE,
-        FULFILLMENT_INITIAL_ENTRY_LINK_TYPE,
-        fulfillment.to_owned(),
-    )?;
+    // retried requests resolve to the record created by the original, whose indexes have already been written
+    if let Some(existing) = get_idempotent_record_address(FULFILLMENT_BASE_ENTRY_TYPE, fulfillment.get_idempotency_key())? {
+        return handle_get_fulfillment(&existing);
+    }
 
-    // link entries in the local DNA
-    let _results = create_direct_index(
-        fulfillment_address.as_ref(),
-        fulfillment.get_fulfills().as_ref(),
-        FULFILLMENT_FULFILLS_LINK_TYPE, FULFILLMENT_FULFILLS_LINK_TAG,
-        COMMITMENT_FULFILLEDBY_LINK_TYPE, COMMITMENT_FULFILLEDBY_LINK_TAG,
-    );
+    // the local record is removed again if it cannot be registered in the foreign DNA
+    let (fulfillment_address, entry_resp) = run_saga(|saga| {
+        let (fulfillment_address, entry_resp): (FulfillmentAddress, Entry) = saga.step("create fulfillment",
+            || create_record(
+                FULFILLMENT_BASE_ENTRY_TYPE, FULFILLMENT_ENTRY_TYPE,
+                FULFILLMENT_INITIAL_ENTRY_LINK_TYPE,
+                fulfillment.get_idempotency_key(),
+                fulfillment.to_owned(),
+            ),
+            |(address, _): (FulfillmentAddress, Entry)| discard_created_record::<Entry>(FULFILLMENT_BASE_ENTRY_TYPE, &address, fulfillment.get_idempotency_key()),
+        )?;
 
-    // update in the associated foreign DNA as well
-    let _pingback = call(
-        BRIDGED_OBSERVATION_DHT,
-        "fulfillment",
-        Address::from(PUBLIC_TOKEN.to_string()),
-        "fulfillment_created",
-        FwdCreateRequest { fulfillment: fulfillment.to_owned() }.into()
-    );
+        // link entries in the local DNA
+        saga.create_direct_index("index fulfilled commitment", fulfillment_address.as_ref(), fulfillment.get_fulfills().as_ref(), &FULFILLMENT_FULFILLS_INDEX)?;
+
+        // update in the associated foreign DNA as well
+        let _pingback: ResponseData = read_from_zome(
+            BRIDGED_OBSERVATION_DHT,
+            "fulfillment",
+            Address::from(PUBLIC_TOKEN.to_string()),
+            "fulfillment_created",
+            FwdCreateRequest { fulfillment: fulfillment.to_owned() }.into()
+        )?;
+
+        Ok((fulfillment_address, entry_resp))
+    })?;
 
-    Ok(construct_response(&fulfillment_address, &entry_resp))
+    construct_response(&fulfillment_address, &entry_resp)
 }
 
 /// Read an individual fulfillment's details
 fn handle_get_fulfillment(base_address: &FulfillmentAddress) -> ZomeApiResult<ResponseData> {
     let entry = read_record_entry(base_address)?;
-    Ok(construct_response(&base_address, &entry))
+    construct_response(&base_address, &entry)
+}
+
+fn handle_get_fulfillments_by_ids(addresses: &[FulfillmentAddress]) -> Vec<ZomeApiResult<ResponseData>> {
+    addresses.iter()
+        .zip(read_record_entries(addresses))
+        .map(|(address, entry)| construct_response(address, &entry?))
+        .collect()
 }
 
 fn handle_update_fulfillment(fulfillment: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let base_address = fulfillment.get_id();
-    let new_entry = update_record(FULFILLMENT_ENTRY_TYPE, &base_address, fulfillment)?;
 
-    // update in the associated foreign DNA as well
-    let _pingback = call(
-        BRIDGED_OBSERVATION_DHT,
-        "fulfillment",
-        Address::from(PUBLIC_TOKEN.to_string()),
-        "fulfillment_updated",
-        FwdUpdateRequest { fulfillment: fulfillment.clone() }.into()
-    );
+    // the local changes are undone again if they cannot be applied in the foreign DNA
+    let new_entry = run_saga(|saga| {
+        let prev_entry: Entry = read_record_entry(base_address)?;
+        let new_entry: Entry = update_record(FULFILLMENT_ENTRY_TYPE, base_address, fulfillment.get_revision_id(), fulfillment)?;
+        saga.completed("update fulfillment", { let address = base_address.to_owned(); move || {
+            revert_record(FULFILLMENT_ENTRY_TYPE, &address, prev_entry)
+        }});
 
-    Ok(construct_response(base_address, &new_entry))
+        // update in the associated foreign DNA as well
+        let _pingback: ResponseData = read_from_zome(
+            BRIDGED_OBSERVATION_DHT,
+            "fulfillment",
+            Address::from(PUBLIC_TOKEN.to_string()),
+            "fulfillment_updated",
+            FwdUpdateRequest { fulfillment: fulfillment.clone() }.into()
+        )?;
+
+        Ok(new_entry)
+    })?;
+
+    construct_response(base_address, &new_entry)
 }
 
 fn handle_delete_fulfillment(address: &FulfillmentAddress) -> ZomeApiResult<bool> {
-    let result = delete_record::<Entry>(address);
-
-    // update in the associated foreign DNA as well
-    let _pingback = call(
+    // Removed from the foreign DNA first, so that a failure leaves both sides intact. Should the
+    // local delete then fail, retrying is safe since the foreign DNA ignores records already removed.
+    let _pingback: bool = read_from_zome(
         BRIDGED_OBSERVATION_DHT,
         "fulfillment",
         Address::from(PUBLIC_TOKEN.to_string()),
         "fulfillment_deleted",
         address.into(),
-    );
+    )?;
 
-    result
+    Ok(delete_record::<Entry>(address)?)
 }
 
 fn handle_query_fulfillments(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(FulfillmentAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
-
-    // :TODO: proper search logic, not mutually exclusive ID filters
-    match &params.fulfills {
-        Some(fulfills) => {
-            entries_result = query_direct_index_with_foreign_key(fulfills, COMMITMENT_FULFILLEDBY_LINK_TYPE, COMMITMENT_FULFILLEDBY_LINK_TAG);
-        },
-        _ => (),
-    };
-    // :TODO: observation DNA handles this. Should queries be possible in planning DNA, too?
-    // match &params.fulfilled_by {
-    //     Some(fulfilled_by) => {
-    //         entries_result = query_direct_index_with_foreign_key(fulfilled_by, EVENT_FULFILLS_LINK_TYPE, EVENT_FULFILLS_LINK_TAG);
-    //     },
-    //     _ => (),
-    // };
+    let mut filters: Vec<IndexQuery> = vec![];
+
+    if let Some(fulfills) = &params.fulfills {
+        filters.push(IndexQuery::local(fulfills, &FULFILLMENT_FULFILLS_INDEX.reverse));
+    }
+    // :TODO: observation DNA handles `fulfilled_by`. Should queries be possible in planning DNA, too?
+
+    let entries_result: GraphHelperResult<Vec<(FulfillmentAddress, Option<Entry>)>> = query_indexes_with_foreign_key(&IndexQuery::And(filters), false);
 
     match entries_result {
         Ok(entries) => Ok(
@@ -142,13 +166,92 @@ fn handle_query_fulfillments(params: &QueryParams) -> ZomeApiResult<Vec<Response
                 .map(|(entry_base_address, maybe_entry)| {
                     // :TODO: avoid cloning entry
                     match maybe_entry {
-                        Some(entry) => Ok(construct_response(entry_base_address, entry)),
+                        Some(entry) => construct_response(entry_base_address, entry),
                         None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
                     }
                 })
                 .filter_map(Result::ok)
                 .collect()
         ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(all(test, feature = "mock-hdk"))]
+mod tests {
+    use super::*;
+    use std::{ rc::Rc, cell::Cell, convert::TryFrom };
+    use hdk::holochain_core_types::entry::Entry::App as AppEntry;
+    use hdk_graph_helpers::mock_hdk;
+    use hc_zome_rea_commitment_storage_consts::COMMITMENT_BASE_ENTRY_TYPE;
+
+    fn create_request() -> (Address, CreateRequest) {
+        let commitment = mock_hdk::commit_entry(&AppEntry(COMMITMENT_BASE_ENTRY_TYPE.into(), JsonString::from_json("\"commitment\""))).unwrap();
+        let request = CreateRequest::try_from(JsonString::from_json(&format!(
+            "{{\"fulfilledBy\":\"event\",\"fulfills\":\"{}\"}}", commitment,
+        ))).unwrap();
+        (commitment, request)
+    }
+
+    fn count_fulfillment_links(commitment: &Address) -> usize {
+        let reverse = &FULFILLMENT_FULFILLS_INDEX.reverse;
+        mock_hdk::count_links(commitment, reverse.link_type, reverse.link_tag)
+    }
+
+    #[test]
+    fn test_create_rolled_back_when_observation_dna_fails() {
+        mock_hdk::register_zome_fn(BRIDGED_OBSERVATION_DHT, "fulfillment", "fulfillment_created", |_args| {
+            Err(ZomeApiError::Internal("unavailable".to_string()))
+        });
+        let (commitment, request) = create_request();
+
+        assert!(receive_create_fulfillment(request).is_err());
+        assert_eq!(count_fulfillment_links(&commitment), 0, "local index removed");
+    }
+
+    #[test]
+    fn test_update_reverted_when_observation_dna_fails() {
+        mock_hdk::register_zome_fn(BRIDGED_OBSERVATION_DHT, "fulfillment", "fulfillment_updated", |_args| {
+            Err(ZomeApiError::Internal("unavailable".to_string()))
+        });
+        let (_commitment, request) = create_request();
+        let (address, _): (FulfillmentAddress, Entry) = create_record(
+            FULFILLMENT_BASE_ENTRY_TYPE, FULFILLMENT_ENTRY_TYPE, FULFILLMENT_INITIAL_ENTRY_LINK_TYPE, None, request,
+        ).unwrap();
+        let update = UpdateRequest::try_from(JsonString::from_json(&format!(
+            "{{\"id\":\"{}\",\"note\":\"changed\"}}", address.as_ref(),
+        ))).unwrap();
+
+        assert!(receive_update_fulfillment(update).is_err());
+        let entry: Entry = read_record_entry(&address).unwrap();
+        assert_eq!(entry.note, None, "local changes undone");
+    }
+
+    #[test]
+    fn test_delete_forwarded_to_observation_dna() {
+        let forwarded = Rc::new(Cell::new(false));
+        let called = forwarded.clone();
+        mock_hdk::register_zome_fn(BRIDGED_OBSERVATION_DHT, "fulfillment", "fulfillment_deleted", move |_args| {
+            called.set(true);
+            Ok(JsonString::from_json("{\"Ok\":true}"))
+        });
+        let (_commitment, request) = create_request();
+        let (address, _): (FulfillmentAddress, Entry) = create_record(
+            FULFILLMENT_BASE_ENTRY_TYPE, FULFILLMENT_ENTRY_TYPE, FULFILLMENT_INITIAL_ENTRY_LINK_TYPE, None, request,
+        ).unwrap();
+
+        assert_eq!(receive_delete_fulfillment(address), Ok(true));
+        assert!(forwarded.get(), "observation DNA notified");
+    }
+
+    #[test]
+    fn test_delete_fails_when_observation_dna_unreachable() {
+        let (_commitment, request) = create_request();
+        let (address, _): (FulfillmentAddress, Entry) = create_record(
+            FULFILLMENT_BASE_ENTRY_TYPE, FULFILLMENT_ENTRY_TYPE, FULFILLMENT_INITIAL_ENTRY_LINK_TYPE, None, request,
+        ).unwrap();
+
+        // no handler registered for the bridged DNA
+        assert!(receive_delete_fulfillment(address).is_err());
     }
 }
@@ -4,6 +4,9 @@
  * Required by packages wishing to interact with the zome via its standard RPC interface,
  * and by the zome API handlers accepting these parameters.
  *
+ * Non-integer `hasNumericalValue`s of fulfilled quantities are returned as decimal strings.
+ * @see `vf_core::measurement::QuantityValue`
+ *
  * @package Holo-REA
  */
 extern crate serde;
@@ -18,6 +21,8 @@ use hdk_graph_helpers::MaybeUndefined;
 use vf_core::{
     measurement::QuantityValue,
     type_aliases::{
+        Address,
+        RevisionId,
         EventAddress,
         CommitmentAddress,
     },
@@ -34,6 +39,7 @@ pub use vf_core::type_aliases::{ FulfillmentAddress };
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub id: FulfillmentAddress,
+    pub revision_id: RevisionId,
     pub fulfilled_by: EventAddress,
     pub fulfills: CommitmentAddress,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -72,6 +78,11 @@ pub struct CreateRequest {
     #[serde(default)]
     #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
     pub note: MaybeUndefined<String>,
+
+    // key supplied by the client to detect retried requests
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
 }
 
 impl<'a> CreateRequest {
@@ -84,6 +95,10 @@ impl<'a> CreateRequest {
     pub fn get_fulfills(&'a self) -> &CommitmentAddress {
         &self.fulfills
     }
+
+    pub fn get_idempotency_key(&'a self) -> Option<&String> {
+        self.idempotency_key.as_ref()
+    }
 }
 
 /// I/O struct for forwarding records to other DNAs via zome API
@@ -102,6 +117,9 @@ pub struct FwdCreateRequest {
 pub struct UpdateRequest {
     pub id: FulfillmentAddress,
     #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision_id: Option<RevisionId>,
+    #[serde(default)]
     #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
     pub fulfilled_by: MaybeUndefined<EventAddress>, // note this setup allows None to be passed but `update_with` ignores it
     #[serde(default)]
@@ -123,6 +141,10 @@ impl<'a> UpdateRequest {
         &self.id
     }
 
+    pub fn get_revision_id(&'a self) -> Option<&Address> {
+        self.revision_id.as_ref().map(AsRef::as_ref)
+    }
+
     // :TODO: accessors for other field data
 }
 
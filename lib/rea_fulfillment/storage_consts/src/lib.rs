@@ -5,6 +5,16 @@
  *
  * @package Holo-REA
  */
+use hdk_graph_helpers::{ LinkSpec, BidirectionalIndex };
+use hc_zome_rea_commitment_storage_consts::{
+    COMMITMENT_BASE_ENTRY_TYPE,
+    COMMITMENT_FULFILLEDBY_LINK_TYPE, COMMITMENT_FULFILLEDBY_LINK_TAG,
+};
+use hc_zome_rea_economic_event_storage_consts::{
+    EVENT_BASE_ENTRY_TYPE,
+    EVENT_FULFILLS_LINK_TYPE, EVENT_FULFILLS_LINK_TAG,
+};
+
 pub const FULFILLMENT_BASE_ENTRY_TYPE: &str = "vf_fulfillment_baseurl";
 pub const FULFILLMENT_INITIAL_ENTRY_LINK_TYPE: &str = "vf_fulfillment_entry";
 pub const FULFILLMENT_ENTRY_TYPE: &str = "vf_fulfillment";
@@ -13,4 +23,15 @@ pub const FULFILLMENT_FULFILLS_LINK_TAG: &str = "fulfills";
 pub const FULFILLMENT_FULFILLEDBY_LINK_TYPE: &str = "vf_fulfillment_fulfilled_by";
 pub const FULFILLMENT_FULFILLEDBY_LINK_TAG: &str = "fulfilled_by";
 
+/// Fulfillments of a commitment
+pub const FULFILLMENT_FULFILLS_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: FULFILLMENT_BASE_ENTRY_TYPE, link_type: FULFILLMENT_FULFILLS_LINK_TYPE, link_tag: FULFILLMENT_FULFILLS_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: COMMITMENT_BASE_ENTRY_TYPE, link_type: COMMITMENT_FULFILLEDBY_LINK_TYPE, link_tag: COMMITMENT_FULFILLEDBY_LINK_TAG },
+};
+/// Fulfillments by an economic event
+pub const FULFILLMENT_FULFILLEDBY_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: FULFILLMENT_BASE_ENTRY_TYPE, link_type: FULFILLMENT_FULFILLEDBY_LINK_TYPE, link_tag: FULFILLMENT_FULFILLEDBY_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: EVENT_BASE_ENTRY_TYPE, link_type: EVENT_FULFILLS_LINK_TYPE, link_tag: EVENT_FULFILLS_LINK_TAG },
+};
+
 pub const BRIDGED_OBSERVATION_DHT: &str = "vf_observation";
@@ -10,7 +10,7 @@ use std::borrow::Cow;
 use hdk::{
     PUBLIC_TOKEN,
     prelude::Address,
-    error::{ ZomeApiResult, ZomeApiError },
+    error::{ ZomeApiResult },
 };
 
 use hdk_graph_helpers::{
@@ -18,21 +18,28 @@ use hdk_graph_helpers::{
     records::{
         create_record,
         read_record_entry,
+        read_record_entries,
         update_record,
-        delete_record,
+        get_revision_id,
+        soft_delete_record,
+        restore_record,
     },
+    idempotency::get_idempotent_record_address,
     links::{
         get_linked_addresses_as_type,
     },
     local_indexes::{
-        query_direct_index_with_foreign_key,
-        query_direct_remote_index_with_foreign_key,
+        IndexQuery,
+        query_indexes_with_foreign_key,
+        query_indexes_with_foreign_key_paginated,
     },
+    pagination::Page,
     remote_indexes::{
         create_direct_remote_index,
         update_direct_remote_index,
         remove_direct_remote_index,
     },
+    errors::{ GraphHelperError, GraphHelperResult, throw_any_error },
 };
 
 use vf_core::type_aliases::{
@@ -43,15 +50,7 @@ use hc_zome_rea_intent_storage_consts::*;
 use hc_zome_rea_intent_storage::*;
 use hc_zome_rea_intent_rpc::*;
 
-use hc_zome_rea_process_storage_consts::{
-    PROCESS_BASE_ENTRY_TYPE,
-    PROCESS_INTENT_INPUTS_LINK_TYPE, PROCESS_INTENT_INPUTS_LINK_TAG,
-    PROCESS_INTENT_OUTPUTS_LINK_TYPE, PROCESS_INTENT_OUTPUTS_LINK_TAG,
-};
-
-use hc_zome_rea_satisfaction_storage_consts::{
-    SATISFACTION_SATISFIES_LINK_TYPE, SATISFACTION_SATISFIES_LINK_TAG,
-};
+use hc_zome_rea_satisfaction_storage_consts::{SATISFACTION_SATISFIES_INDEX};
 
 pub fn receive_create_intent(intent: CreateRequest) -> ZomeApiResult<ResponseData> {
     handle_create_intent(&intent)
@@ -61,6 +60,10 @@ pub fn receive_get_intent(address: IntentAddress) -> ZomeApiResult<ResponseData>
     handle_get_intent(&address)
 }
 
+pub fn receive_get_intents_by_ids(addresses: Vec<IntentAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+    Ok(handle_get_intents_by_ids(&addresses))
+}
+
 pub fn receive_update_intent(intent: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_intent(&intent)
 }
@@ -69,76 +72,89 @@ pub fn receive_delete_intent(address: IntentAddress) -> ZomeApiResult<bool> {
     handle_delete_intent(&address)
 }
 
+pub fn receive_restore_intent(address: IntentAddress) -> ZomeApiResult<bool> {
+    handle_restore_intent(&address)
+}
+
 pub fn receive_query_intents(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
     handle_query_intents(&params)
 }
 
+pub fn receive_query_intents_paginated(params: QueryParams) -> ZomeApiResult<ResponsePage> {
+    handle_query_intents_paginated(&params)
+}
+
 // :TODO: move to hdk_graph_helpers module
 
 fn handle_get_intent(address: &IntentAddress) -> ZomeApiResult<ResponseData> {
     let entry = read_record_entry(&address)?;
-    Ok(construct_response(&address, &entry, get_link_fields(&address)))
+    construct_response(&address, &entry, get_link_fields(&address)?)
+}
+
+fn handle_get_intents_by_ids(addresses: &[IntentAddress]) -> Vec<ZomeApiResult<ResponseData>> {
+    addresses.iter()
+        .zip(read_record_entries(addresses))
+        .map(|(address, entry)| construct_response(address, &entry?, get_link_fields(address)?))
+        .collect()
 }
 
 fn handle_create_intent(intent: &CreateRequest) -> ZomeApiResult<ResponseData> {
+    // retried requests resolve to the record created by the original, whose indexes have already been written
+    if let Some(existing) = get_idempotent_record_address(INTENT_BASE_ENTRY_TYPE, intent.get_idempotency_key())? {
+        return handle_get_intent(&existing);
+    }
+
     let (base_address, entry_resp): (IntentAddress, Entry) = create_record(
         INTENT_BASE_ENTRY_TYPE, INTENT_ENTRY_TYPE,
         INTENT_INITIAL_ENTRY_LINK_TYPE,
+        intent.get_idempotency_key(),
         intent.to_owned(),
     )?;
 
     // handle link fields
     if let CreateRequest { input_of: MaybeUndefined::Some(input_of), .. } = intent {
-        let _results = create_direct_remote_index(
+        throw_any_error(create_direct_remote_index(
             BRIDGED_OBSERVATION_DHT, "process", "index_intended_inputs", Address::from(PUBLIC_TOKEN.to_string()),
-            PROCESS_BASE_ENTRY_TYPE,
-            INTENT_INPUT_OF_LINK_TYPE, INTENT_INPUT_OF_LINK_TAG,
-            PROCESS_INTENT_INPUTS_LINK_TYPE, PROCESS_INTENT_INPUTS_LINK_TAG,
+            &INTENT_INPUT_OF_INDEX,
             base_address.as_ref(),
             vec![(input_of.as_ref()).clone()],
-        );
+        ))?;
     };
     if let CreateRequest { output_of: MaybeUndefined::Some(output_of), .. } = intent {
-        let _results = create_direct_remote_index(
+        throw_any_error(create_direct_remote_index(
             BRIDGED_OBSERVATION_DHT, "process", "index_intended_outputs", Address::from(PUBLIC_TOKEN.to_string()),
-            PROCESS_BASE_ENTRY_TYPE,
-            INTENT_OUTPUT_OF_LINK_TYPE, INTENT_OUTPUT_OF_LINK_TAG,
-            PROCESS_INTENT_OUTPUTS_LINK_TYPE, PROCESS_INTENT_OUTPUTS_LINK_TAG,
+            &INTENT_OUTPUT_OF_INDEX,
             base_address.as_ref(),
             vec![(output_of.as_ref()).clone()],
-        );
+        ))?;
     };
 
     // return entire record structure
-    Ok(construct_response(&base_address, &entry_resp, get_link_fields(&base_address)))
+    construct_response(&base_address, &entry_resp, get_link_fields(&base_address)?)
 }
 
 fn handle_update_intent(intent: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let address = intent.get_id();
-    let new_entry = update_record(INTENT_ENTRY_TYPE, address, intent)?;
+    let new_entry = update_record(INTENT_ENTRY_TYPE, address, intent.get_revision_id(), intent)?;
 
     // handle link fields
     if MaybeUndefined::Undefined != intent.input_of {
-        let _results = update_direct_remote_index(
+        throw_any_error(update_direct_remote_index(
             BRIDGED_OBSERVATION_DHT, "process", "index_intended_inputs", Address::from(PUBLIC_TOKEN.to_string()),
-            PROCESS_BASE_ENTRY_TYPE,
-            INTENT_INPUT_OF_LINK_TYPE, INTENT_INPUT_OF_LINK_TAG,
-            PROCESS_INTENT_INPUTS_LINK_TYPE, PROCESS_INTENT_INPUTS_LINK_TAG,
+            &INTENT_INPUT_OF_INDEX,
             address, &intent.input_of,
-        );
+        )?)?;
     }
     if MaybeUndefined::Undefined != intent.output_of {
-        let _results = update_direct_remote_index(
+        throw_any_error(update_direct_remote_index(
             BRIDGED_OBSERVATION_DHT, "process", "index_intended_outputs", Address::from(PUBLIC_TOKEN.to_string()),
-            PROCESS_BASE_ENTRY_TYPE,
-            INTENT_OUTPUT_OF_LINK_TYPE, INTENT_OUTPUT_OF_LINK_TAG,
-            PROCESS_INTENT_OUTPUTS_LINK_TYPE, PROCESS_INTENT_OUTPUTS_LINK_TAG,
+            &INTENT_OUTPUT_OF_INDEX,
             address, &intent.output_of,
-        );
+        )?)?;
     }
 
     // :TODO: optimise this- should pass results from `replace_direct_index` instead of retrieving from `get_link_fields` where updates
-    Ok(construct_response(address, &new_entry, get_link_fields(address)))
+    construct_response(address, &new_entry, get_link_fields(address)?)
 }
 
 fn handle_delete_intent(address: &IntentAddress) -> ZomeApiResult<bool> {
@@ -146,74 +162,102 @@ fn handle_delete_intent(address: &IntentAddress) -> ZomeApiResult<bool> {
     let entry: Entry = read_record_entry(&address)?;
 
     // handle link fields
+    // :NOTE: indexes into other DNAs are removed so that they no longer reference the record, and are re-created
+    // upon restore. Other indexes are retained, and deleted records are filtered out of query results on read.
     if let Some(process_address) = entry.input_of {
-        let _results = remove_direct_remote_index(
+        throw_any_error(remove_direct_remote_index(
             BRIDGED_OBSERVATION_DHT, "process", "index_intended_inputs", Address::from(PUBLIC_TOKEN.to_string()),
-            PROCESS_BASE_ENTRY_TYPE,
-            INTENT_INPUT_OF_LINK_TYPE, INTENT_INPUT_OF_LINK_TAG,
-            PROCESS_INTENT_INPUTS_LINK_TYPE, PROCESS_INTENT_INPUTS_LINK_TAG,
+            &INTENT_INPUT_OF_INDEX,
             address, &process_address,
-        );
+        ))?;
     }
     if let Some(process_address) = entry.output_of {
-        let _results = remove_direct_remote_index(
+        throw_any_error(remove_direct_remote_index(
             BRIDGED_OBSERVATION_DHT, "process", "index_intended_outputs", Address::from(PUBLIC_TOKEN.to_string()),
-            PROCESS_BASE_ENTRY_TYPE,
-            INTENT_OUTPUT_OF_LINK_TYPE, INTENT_OUTPUT_OF_LINK_TAG,
-            PROCESS_INTENT_OUTPUTS_LINK_TYPE, PROCESS_INTENT_OUTPUTS_LINK_TAG,
+            &INTENT_OUTPUT_OF_INDEX,
             address, &process_address,
-        );
+        ))?;
+    }
+
+    // intents are soft-deleted, so that they can be restored later
+    Ok(soft_delete_record::<Entry>(&address)?)
+}
+
+fn handle_restore_intent(address: &IntentAddress) -> ZomeApiResult<bool> {
+    if !restore_record(&address)? {
+        return Ok(false);
+    }
+
+    // re-create remote indexes removed upon deletion
+    let entry: Entry = read_record_entry(&address)?;
+    if let Some(process_address) = entry.input_of {
+        throw_any_error(create_direct_remote_index(
+            BRIDGED_OBSERVATION_DHT, "process", "index_intended_inputs", Address::from(PUBLIC_TOKEN.to_string()),
+            &INTENT_INPUT_OF_INDEX,
+            address.as_ref(),
+            vec![process_address.as_ref().clone()],
+        ))?;
+    }
+    if let Some(process_address) = entry.output_of {
+        throw_any_error(create_direct_remote_index(
+            BRIDGED_OBSERVATION_DHT, "process", "index_intended_outputs", Address::from(PUBLIC_TOKEN.to_string()),
+            &INTENT_OUTPUT_OF_INDEX,
+            address.as_ref(),
+            vec![process_address.as_ref().clone()],
+        ))?;
     }
 
-    // delete entry last as it must be present in order for links to be removed
-    delete_record::<Entry>(&address)
+    Ok(true)
 }
 
 fn handle_query_intents(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(IntentAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
-
-    match &params.satisfied_by {
-        Some(satisfied_by) => {
-            entries_result = query_direct_index_with_foreign_key(
-                &satisfied_by, SATISFACTION_SATISFIES_LINK_TYPE, SATISFACTION_SATISFIES_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
-    match &params.input_of {
-        Some(input_of) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
-                input_of, PROCESS_BASE_ENTRY_TYPE,
-                PROCESS_INTENT_INPUTS_LINK_TYPE, PROCESS_INTENT_INPUTS_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
-    match &params.output_of {
-        Some(output_of) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
-                output_of, PROCESS_BASE_ENTRY_TYPE,
-                PROCESS_INTENT_OUTPUTS_LINK_TYPE, PROCESS_INTENT_OUTPUTS_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
+    if params.is_page_request() {
+        return Err(GraphHelperError::PaginationUnsupported.into());
+    }
+
+    let entries_result: GraphHelperResult<Vec<(IntentAddress, Option<Entry>)>> = query_indexes_with_foreign_key(&build_query(params), params.include_deleted.unwrap_or(false));
+
+    handle_list_output(entries_result)
+}
+
+fn handle_query_intents_paginated(params: &QueryParams) -> ZomeApiResult<ResponsePage> {
+    let page: Page<(IntentAddress, Option<Entry>)> = query_indexes_with_foreign_key_paginated(&build_query(params), &params.get_page_request(), params.include_deleted.unwrap_or(false))?;
 
+    Ok(ResponsePage {
+        results: handle_list_output(Ok(page.results))?,
+        next_cursor: page.next_cursor,
+    })
+}
+
+fn build_query(params: &QueryParams) -> IndexQuery {
+    let mut filters: Vec<IndexQuery> = vec![];
+
+    if let Some(satisfied_by) = &params.satisfied_by {
+        filters.push(IndexQuery::local(satisfied_by, &SATISFACTION_SATISFIES_INDEX.forward));
+    }
+    if let Some(input_of) = &params.input_of {
+        filters.push(IndexQuery::remote(input_of, &INTENT_INPUT_OF_INDEX.reverse));
+    }
+    if let Some(output_of) = &params.output_of {
+        filters.push(IndexQuery::remote(output_of, &INTENT_OUTPUT_OF_INDEX.reverse));
+    }
+
+    IndexQuery::And(filters)
+}
+
+fn handle_list_output(entries_result: GraphHelperResult<Vec<(IntentAddress, Option<Entry>)>>) -> ZomeApiResult<Vec<ResponseData>> {
     match entries_result {
-        Ok(entries) => Ok(
-            entries.iter()
-                .map(|(entry_base_address, maybe_entry)| {
-                    match maybe_entry {
-                        Some(entry) => Ok(construct_response(
-                            entry_base_address, &entry, get_link_fields(entry_base_address)
-                        )),
-                        None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
-                    }
+        Ok(entries) => entries.iter()
+            // skip any referenced entries which could not be located
+            .filter_map(|(entry_base_address, maybe_entry)| {
+                maybe_entry.as_ref().map(|entry| -> ZomeApiResult<ResponseData> {
+                    construct_response(
+                        entry_base_address, &entry, get_link_fields(entry_base_address)?
+                    )
                 })
-                .filter_map(Result::ok)
-                .collect()
-        ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
+            })
+            .collect(),
+        Err(e) => Err(e.into()),
     }
 }
 
@@ -226,10 +270,11 @@ pub fn construct_response<'a>(
         Option<Cow<'a, Vec<SatisfactionAddress>>>,
         // Option<Cow<'a, Vec<ProposedIntentAddress>>>
     )
-) -> ResponseData {
-    ResponseData {
+) -> ZomeApiResult<ResponseData> {
+    Ok(ResponseData {
         intent: Response {
             id: address.to_owned(),
+            revision_id: get_revision_id(INTENT_ENTRY_TYPE, e)?.into(),
             action: e.action.to_owned(),
             note: e.note.to_owned(),
             image: e.image.to_owned(),
@@ -254,16 +299,16 @@ pub fn construct_response<'a>(
             satisfied_by: satisfactions.map(Cow::into_owned),
             // published_in: published_in.map(Cow::into_owned),
         }
-    }
+    })
 }
 
 //---------------- READ ----------------
 
 // @see construct_response
-pub fn get_link_fields<'a>(intent: &IntentAddress) -> (
+pub fn get_link_fields<'a>(intent: &IntentAddress) -> ZomeApiResult<(
     Option<Cow<'a, Vec<SatisfactionAddress>>>,
-) {
-    (
-        Some(get_linked_addresses_as_type(intent, INTENT_SATISFIEDBY_LINK_TYPE, INTENT_SATISFIEDBY_LINK_TAG)),
-    )
+)> {
+    Ok((
+        Some(get_linked_addresses_as_type(intent, INTENT_SATISFIEDBY_LINK_TYPE, INTENT_SATISFIEDBY_LINK_TAG)?),
+    ))
 }
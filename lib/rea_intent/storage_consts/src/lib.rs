@@ -5,6 +5,13 @@
  *
  * @package Holo-REA
  */
+use hdk_graph_helpers::{ LinkSpec, BidirectionalIndex };
+use hc_zome_rea_process_storage_consts::{
+    PROCESS_BASE_ENTRY_TYPE,
+    PROCESS_INTENT_INPUTS_LINK_TYPE, PROCESS_INTENT_INPUTS_LINK_TAG,
+    PROCESS_INTENT_OUTPUTS_LINK_TYPE, PROCESS_INTENT_OUTPUTS_LINK_TAG,
+};
+
 pub const INTENT_BASE_ENTRY_TYPE: &str = "vf_intent_baseurl";
 pub const INTENT_INITIAL_ENTRY_LINK_TYPE: &str = "vf_intent_entry";
 pub const INTENT_ENTRY_TYPE: &str = "vf_intent";
@@ -18,4 +25,15 @@ pub const INTENT_OUTPUT_OF_LINK_TAG: &str = "output_of";
 pub const INTENT_PUBLISHED_IN_LINK_TYPE: &str = "vf_intent_published_in";
 pub const INTENT_PUBLISHED_IN_LINK_TAG: &str = "published_in";
 
+/// Intents to consume or use resources in a process
+pub const INTENT_INPUT_OF_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: INTENT_BASE_ENTRY_TYPE, link_type: INTENT_INPUT_OF_LINK_TYPE, link_tag: INTENT_INPUT_OF_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: PROCESS_BASE_ENTRY_TYPE, link_type: PROCESS_INTENT_INPUTS_LINK_TYPE, link_tag: PROCESS_INTENT_INPUTS_LINK_TAG },
+};
+/// Intents to produce resources from a process
+pub const INTENT_OUTPUT_OF_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: INTENT_BASE_ENTRY_TYPE, link_type: INTENT_OUTPUT_OF_LINK_TYPE, link_tag: INTENT_OUTPUT_OF_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: PROCESS_BASE_ENTRY_TYPE, link_type: PROCESS_INTENT_OUTPUTS_LINK_TYPE, link_tag: PROCESS_INTENT_OUTPUTS_LINK_TAG },
+};
+
 pub const BRIDGED_OBSERVATION_DHT: &str = "vf_observation";
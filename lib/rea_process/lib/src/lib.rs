@@ -8,14 +8,16 @@
  */
 use std::borrow::Cow;
 use hdk::{
-    error::{ ZomeApiResult, ZomeApiError },
+    error::{ ZomeApiResult },
 };
 
 use hdk_graph_helpers::{
     records::{
         create_record,
         read_record_entry,
+        read_record_entries,
         update_record,
+        get_revision_id,
         delete_record,
     },
     links::{
@@ -23,13 +25,14 @@ use hdk_graph_helpers::{
         get_linked_addresses_with_foreign_key_as_type,
     },
     local_indexes::{
-        query_direct_index_with_foreign_key,
-        query_direct_remote_index_with_foreign_key,
+        IndexQuery,
+        query_indexes_with_foreign_key,
     },
     remote_indexes::{
         RemoteEntryLinkResponse,
         handle_sync_direct_remote_index_destination,
     },
+    GraphHelperResult,
 };
 
 use vf_core::type_aliases::{
@@ -44,20 +47,9 @@ use hc_zome_rea_process_storage_consts::*;
 use hc_zome_rea_process_storage::*;
 use hc_zome_rea_process_rpc::*;
 
-use hc_zome_rea_economic_event_storage_consts::{
-    EVENT_INPUT_OF_LINK_TYPE, EVENT_INPUT_OF_LINK_TAG,
-    EVENT_OUTPUT_OF_LINK_TYPE, EVENT_OUTPUT_OF_LINK_TAG,
-};
-use hc_zome_rea_commitment_storage_consts::{
-    COMMITMENT_BASE_ENTRY_TYPE,
-    COMMITMENT_INPUT_OF_LINK_TYPE, COMMITMENT_INPUT_OF_LINK_TAG,
-    COMMITMENT_OUTPUT_OF_LINK_TYPE, COMMITMENT_OUTPUT_OF_LINK_TAG,
-};
-use hc_zome_rea_intent_storage_consts::{
-    INTENT_BASE_ENTRY_TYPE,
-    INTENT_INPUT_OF_LINK_TYPE, INTENT_INPUT_OF_LINK_TAG,
-    INTENT_OUTPUT_OF_LINK_TYPE, INTENT_OUTPUT_OF_LINK_TAG,
-};
+use hc_zome_rea_economic_event_storage_consts::{EVENT_INPUT_OF_INDEX, EVENT_OUTPUT_OF_INDEX};
+use hc_zome_rea_commitment_storage_consts::{COMMITMENT_INPUT_OF_INDEX, COMMITMENT_OUTPUT_OF_INDEX};
+use hc_zome_rea_intent_storage_consts::{INTENT_INPUT_OF_INDEX, INTENT_OUTPUT_OF_INDEX};
 
 pub fn receive_create_process(process: CreateRequest) -> ZomeApiResult<ResponseData> {
     handle_create_process(&process)
@@ -67,12 +59,16 @@ pub fn receive_get_process(address: ProcessAddress) -> ZomeApiResult<ResponseDat
     handle_get_process(&address)
 }
 
+pub fn receive_get_processes_by_ids(addresses: Vec<ProcessAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+    Ok(handle_get_processes_by_ids(&addresses))
+}
+
 pub fn receive_update_process(process: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_process(&process)
 }
 
 pub fn receive_delete_process(address: ProcessAddress) -> ZomeApiResult<bool> {
-    delete_record::<Entry>(&address)
+    Ok(delete_record::<Entry>(&address)?)
 }
 
 pub fn receive_query_processes(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
@@ -80,135 +76,102 @@ pub fn receive_query_processes(params: QueryParams) -> ZomeApiResult<Vec<Respons
 }
 
 pub fn receive_link_committed_inputs(base_entry: CommitmentAddress, target_entries: Vec<ProcessAddress>, removed_entries: Vec<ProcessAddress>) -> ZomeApiResult<RemoteEntryLinkResponse> {
-    handle_sync_direct_remote_index_destination(
-        COMMITMENT_BASE_ENTRY_TYPE,
-        COMMITMENT_INPUT_OF_LINK_TYPE, COMMITMENT_INPUT_OF_LINK_TAG,
-        PROCESS_COMMITMENT_INPUTS_LINK_TYPE, PROCESS_COMMITMENT_INPUTS_LINK_TAG,
+    Ok(handle_sync_direct_remote_index_destination(
+        &COMMITMENT_INPUT_OF_INDEX,
         &base_entry, target_entries, removed_entries
-    )
+    )?)
 }
 
 pub fn receive_link_committed_outputs(base_entry: CommitmentAddress, target_entries: Vec<ProcessAddress>, removed_entries: Vec<ProcessAddress>) -> ZomeApiResult<RemoteEntryLinkResponse> {
-    handle_sync_direct_remote_index_destination(
-        COMMITMENT_BASE_ENTRY_TYPE,
-        COMMITMENT_OUTPUT_OF_LINK_TYPE, COMMITMENT_OUTPUT_OF_LINK_TAG,
-        PROCESS_COMMITMENT_OUTPUTS_LINK_TYPE, PROCESS_COMMITMENT_OUTPUTS_LINK_TAG,
+    Ok(handle_sync_direct_remote_index_destination(
+        &COMMITMENT_OUTPUT_OF_INDEX,
         &base_entry, target_entries, removed_entries
-    )
+    )?)
 }
 
 pub fn receive_link_intended_inputs(base_entry: IntentAddress, target_entries: Vec<ProcessAddress>, removed_entries: Vec<ProcessAddress>) -> ZomeApiResult<RemoteEntryLinkResponse> {
-    handle_sync_direct_remote_index_destination(
-        INTENT_BASE_ENTRY_TYPE,
-        INTENT_INPUT_OF_LINK_TYPE, INTENT_INPUT_OF_LINK_TAG,
-        PROCESS_INTENT_INPUTS_LINK_TYPE, PROCESS_INTENT_INPUTS_LINK_TAG,
+    Ok(handle_sync_direct_remote_index_destination(
+        &INTENT_INPUT_OF_INDEX,
         &base_entry, target_entries, removed_entries
-    )
+    )?)
 }
 
 pub fn receive_link_intended_outputs(base_entry: IntentAddress, target_entries: Vec<ProcessAddress>, removed_entries: Vec<ProcessAddress>) -> ZomeApiResult<RemoteEntryLinkResponse> {
-    handle_sync_direct_remote_index_destination(
-        INTENT_BASE_ENTRY_TYPE,
-        INTENT_OUTPUT_OF_LINK_TYPE, INTENT_OUTPUT_OF_LINK_TAG,
-        PROCESS_INTENT_OUTPUTS_LINK_TYPE, PROCESS_INTENT_OUTPUTS_LINK_TAG,
+    Ok(handle_sync_direct_remote_index_destination(
+        &INTENT_OUTPUT_OF_INDEX,
         &base_entry, target_entries, removed_entries
-    )
+    )?)
 }
 
 // :TODO: move to hdk_graph_helpers module
 
 fn handle_get_process(address: &ProcessAddress) -> ZomeApiResult<ResponseData> {
-    Ok(construct_response(address, &read_record_entry(address)?, get_link_fields(address)))
+    construct_response(address, &read_record_entry(address)?, get_link_fields(address)?)
+}
+
+fn handle_get_processes_by_ids(addresses: &[ProcessAddress]) -> Vec<ZomeApiResult<ResponseData>> {
+    addresses.iter()
+        .zip(read_record_entries(addresses))
+        .map(|(address, entry)| construct_response(address, &entry?, get_link_fields(address)?))
+        .collect()
 }
 
 fn handle_create_process(process: &CreateRequest) -> ZomeApiResult<ResponseData> {
     let (base_address, entry_resp): (ProcessAddress, Entry) = create_record(
         PROCESS_BASE_ENTRY_TYPE, PROCESS_ENTRY_TYPE,
         PROCESS_INITIAL_ENTRY_LINK_TYPE,
+        process.get_idempotency_key(),
         process.to_owned(),
     )?;
-    Ok(construct_response(&base_address, &entry_resp, get_link_fields(&base_address)))
+    construct_response(&base_address, &entry_resp, get_link_fields(&base_address)?)
 }
 
 fn handle_update_process(process: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let base_address = process.get_id();
-    let new_entry = update_record(PROCESS_ENTRY_TYPE, base_address, process)?;
-    Ok(construct_response(&base_address, &new_entry, get_link_fields(base_address)))
+    let new_entry = update_record(PROCESS_ENTRY_TYPE, base_address, process.get_revision_id(), process)?;
+    construct_response(&base_address, &new_entry, get_link_fields(base_address)?)
 }
 
 fn handle_query_processes(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(ProcessAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
-
-    // :TODO: proper search logic, not mutually exclusive ID filters
-
-    match &params.inputs {
-        Some(inputs) => {
-            entries_result = query_direct_index_with_foreign_key(inputs, EVENT_INPUT_OF_LINK_TYPE, EVENT_INPUT_OF_LINK_TAG);
-        },
-        _ => (),
-    };
-    match &params.outputs {
-        Some(outputs) => {
-            entries_result = query_direct_index_with_foreign_key(outputs, EVENT_OUTPUT_OF_LINK_TYPE, EVENT_OUTPUT_OF_LINK_TAG);
-        },
-        _ => (),
-    };
-    match &params.committed_inputs {
-        Some(committed_inputs) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
-                committed_inputs, COMMITMENT_BASE_ENTRY_TYPE,
-                COMMITMENT_INPUT_OF_LINK_TYPE, COMMITMENT_INPUT_OF_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
-    match &params.committed_outputs {
-        Some(committed_outputs) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
-                committed_outputs, COMMITMENT_BASE_ENTRY_TYPE,
-                COMMITMENT_OUTPUT_OF_LINK_TYPE, COMMITMENT_OUTPUT_OF_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
-    match &params.intended_inputs {
-        Some(intended_inputs) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
-                intended_inputs, INTENT_BASE_ENTRY_TYPE,
-                INTENT_INPUT_OF_LINK_TYPE, INTENT_INPUT_OF_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
-    match &params.intended_outputs {
-        Some(intended_outputs) => {
-            entries_result = query_direct_remote_index_with_foreign_key(
-                intended_outputs, INTENT_BASE_ENTRY_TYPE,
-                INTENT_OUTPUT_OF_LINK_TYPE, INTENT_OUTPUT_OF_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
+    let mut filters: Vec<IndexQuery> = vec![];
+
+    if let Some(inputs) = &params.inputs {
+        filters.push(IndexQuery::local(inputs, &EVENT_INPUT_OF_INDEX.forward));
+    }
+    if let Some(outputs) = &params.outputs {
+        filters.push(IndexQuery::local(outputs, &EVENT_OUTPUT_OF_INDEX.forward));
+    }
+    if let Some(committed_inputs) = &params.committed_inputs {
+        filters.push(IndexQuery::remote(committed_inputs, &COMMITMENT_INPUT_OF_INDEX.forward));
+    }
+    if let Some(committed_outputs) = &params.committed_outputs {
+        filters.push(IndexQuery::remote(committed_outputs, &COMMITMENT_OUTPUT_OF_INDEX.forward));
+    }
+    if let Some(intended_inputs) = &params.intended_inputs {
+        filters.push(IndexQuery::remote(intended_inputs, &INTENT_INPUT_OF_INDEX.forward));
+    }
+    if let Some(intended_outputs) = &params.intended_outputs {
+        filters.push(IndexQuery::remote(intended_outputs, &INTENT_OUTPUT_OF_INDEX.forward));
+    }
 
     // :TODO: unplanned_economic_events, working_agents
 
+    let entries_result: GraphHelperResult<Vec<(ProcessAddress, Option<Entry>)>> = query_indexes_with_foreign_key(&IndexQuery::And(filters), false);
+
     match entries_result {
-        Ok(entries) => Ok(
-            entries.iter()
-                .map(|(entry_base_address, maybe_entry)| {
-                    match maybe_entry {
-                        Some(entry) => Ok(construct_response(
-                            entry_base_address,
-                            &entry,
-                            get_link_fields(entry_base_address),
-                        )),
-                        None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
-                    }
+        Ok(entries) => entries.iter()
+            // skip any referenced entries which could not be located
+            .filter_map(|(entry_base_address, maybe_entry)| {
+                maybe_entry.as_ref().map(|entry| -> ZomeApiResult<ResponseData> {
+                    construct_response(
+                        entry_base_address,
+                        &entry,
+                        get_link_fields(entry_base_address)?,
+                    )
                 })
-                .filter_map(Result::ok)
-                .collect()
-        ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
+            })
+            .collect(),
+        Err(e) => Err(e.into()),
     }
 }
 
@@ -231,11 +194,12 @@ pub fn construct_response<'a>(
         Option<Cow<'a, Vec<AgentAddress>>>,
         Option<Cow<'a, Vec<EventAddress>>>, Option<Cow<'a, Vec<EventAddress>>>,
     ),
-) -> ResponseData {
-    ResponseData {
+) -> ZomeApiResult<ResponseData> {
+    Ok(ResponseData {
         process: Response {
             // entry fields
             id: address.to_owned(),
+            revision_id: get_revision_id(PROCESS_ENTRY_TYPE, e)?.into(),
             name: e.name.to_owned(),
             has_beginning: e.has_beginning.to_owned(),
             has_end: e.has_end.to_owned(),
@@ -263,13 +227,13 @@ pub fn construct_response<'a>(
             trace: trace.map(Cow::into_owned),
             track: track.map(Cow::into_owned),
         }
-    }
+    })
 }
 
 //---------------- READ ----------------
 
 // @see construct_response
-pub fn get_link_fields<'a>(process: &ProcessAddress) -> (
+pub fn get_link_fields<'a>(process: &ProcessAddress) -> ZomeApiResult<(
     Option<Cow<'a, Vec<EventAddress>>>,
     Option<Cow<'a, Vec<EventAddress>>>,
     Option<Cow<'a, Vec<EventAddress>>>,
@@ -282,43 +246,43 @@ pub fn get_link_fields<'a>(process: &ProcessAddress) -> (
     Option<Cow<'a, Vec<AgentAddress>>>,
     Option<Cow<'a, Vec<EventAddress>>>,
     Option<Cow<'a, Vec<EventAddress>>>,
-) {
-    (
-        Some(get_input_event_ids(process)),
-        Some(get_output_event_ids(process)),
+)> {
+    Ok((
+        Some(get_input_event_ids(process)?),
+        Some(get_output_event_ids(process)?),
         None,  // :TODO: unplanned_economic_events
-        Some(get_input_commitment_ids(process)),
-        Some(get_output_commitment_ids(process)),
-        Some(get_input_intent_ids(process)),
-        Some(get_output_intent_ids(process)),
+        Some(get_input_commitment_ids(process)?),
+        Some(get_output_commitment_ids(process)?),
+        Some(get_input_intent_ids(process)?),
+        Some(get_output_intent_ids(process)?),
         None, // :TODO: next_processes
         None, // :TODO: previous_processes
         None, // :TODO: working_agents
         None, // :TODO: trace
         None, // :TODO: track
-    )
+    ))
 }
 
-fn get_input_event_ids<'a>(process: &ProcessAddress) -> Cow<'a, Vec<EventAddress>> {
-    get_linked_addresses_as_type(process, PROCESS_EVENT_INPUTS_LINK_TYPE, PROCESS_EVENT_INPUTS_LINK_TAG)
+fn get_input_event_ids<'a>(process: &ProcessAddress) -> ZomeApiResult<Cow<'a, Vec<EventAddress>>> {
+    Ok(get_linked_addresses_as_type(process, PROCESS_EVENT_INPUTS_LINK_TYPE, PROCESS_EVENT_INPUTS_LINK_TAG)?)
 }
 
-fn get_output_event_ids<'a>(process: &ProcessAddress) -> Cow<'a, Vec<EventAddress>> {
-    get_linked_addresses_as_type(process, PROCESS_EVENT_OUTPUTS_LINK_TYPE, PROCESS_EVENT_OUTPUTS_LINK_TAG)
+fn get_output_event_ids<'a>(process: &ProcessAddress) -> ZomeApiResult<Cow<'a, Vec<EventAddress>>> {
+    Ok(get_linked_addresses_as_type(process, PROCESS_EVENT_OUTPUTS_LINK_TYPE, PROCESS_EVENT_OUTPUTS_LINK_TAG)?)
 }
 
-fn get_input_commitment_ids<'a>(process: &ProcessAddress) -> Cow<'a, Vec<CommitmentAddress>> {
-    get_linked_addresses_with_foreign_key_as_type(process, PROCESS_COMMITMENT_INPUTS_LINK_TYPE, PROCESS_COMMITMENT_INPUTS_LINK_TAG)
+fn get_input_commitment_ids<'a>(process: &ProcessAddress) -> ZomeApiResult<Cow<'a, Vec<CommitmentAddress>>> {
+    Ok(get_linked_addresses_with_foreign_key_as_type(process, PROCESS_COMMITMENT_INPUTS_LINK_TYPE, PROCESS_COMMITMENT_INPUTS_LINK_TAG)?)
 }
 
-fn get_output_commitment_ids<'a>(process: &ProcessAddress) -> Cow<'a, Vec<CommitmentAddress>> {
-    get_linked_addresses_with_foreign_key_as_type(process, PROCESS_COMMITMENT_OUTPUTS_LINK_TYPE, PROCESS_COMMITMENT_OUTPUTS_LINK_TAG)
+fn get_output_commitment_ids<'a>(process: &ProcessAddress) -> ZomeApiResult<Cow<'a, Vec<CommitmentAddress>>> {
+    Ok(get_linked_addresses_with_foreign_key_as_type(process, PROCESS_COMMITMENT_OUTPUTS_LINK_TYPE, PROCESS_COMMITMENT_OUTPUTS_LINK_TAG)?)
 }
 
-fn get_input_intent_ids<'a>(process: &ProcessAddress) -> Cow<'a, Vec<IntentAddress>> {
-    get_linked_addresses_with_foreign_key_as_type(process, PROCESS_INTENT_INPUTS_LINK_TYPE, PROCESS_INTENT_INPUTS_LINK_TAG)
+fn get_input_intent_ids<'a>(process: &ProcessAddress) -> ZomeApiResult<Cow<'a, Vec<IntentAddress>>> {
+    Ok(get_linked_addresses_with_foreign_key_as_type(process, PROCESS_INTENT_INPUTS_LINK_TYPE, PROCESS_INTENT_INPUTS_LINK_TAG)?)
 }
 
-fn get_output_intent_ids<'a>(process: &ProcessAddress) -> Cow<'a, Vec<IntentAddress>> {
-    get_linked_addresses_with_foreign_key_as_type(process, PROCESS_INTENT_OUTPUTS_LINK_TYPE, PROCESS_INTENT_OUTPUTS_LINK_TAG)
+fn get_output_intent_ids<'a>(process: &ProcessAddress) -> ZomeApiResult<Cow<'a, Vec<IntentAddress>>> {
+    Ok(get_linked_addresses_with_foreign_key_as_type(process, PROCESS_INTENT_OUTPUTS_LINK_TYPE, PROCESS_INTENT_OUTPUTS_LINK_TAG)?)
 }
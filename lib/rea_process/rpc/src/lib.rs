@@ -15,6 +15,8 @@ use hdk_graph_helpers::{
 };
 
 use vf_core::type_aliases::{
+    Address,
+    RevisionId,
     Timestamp,
     ExternalURL,
     ProcessSpecificationAddress,
@@ -35,6 +37,7 @@ pub use vf_core::type_aliases::ProcessAddress;
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub id: ProcessAddress,
+    pub revision_id: RevisionId,
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub has_beginning: Option<Timestamp>,
@@ -118,10 +121,18 @@ pub struct CreateRequest {
     pub in_scope_of: MaybeUndefined<Vec<String>>,
     #[serde(default)]
     pub note: MaybeUndefined<String>,
+
+    // key supplied by the client to detect retried requests
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 impl<'a> CreateRequest {
     // :TODO: accessors for field data
+
+    pub fn get_idempotency_key(&'a self) -> Option<&String> {
+        self.idempotency_key.as_ref()
+    }
 }
 
 //---------------- UPDATE REQUEST ----------------
@@ -132,6 +143,8 @@ impl<'a> CreateRequest {
 pub struct UpdateRequest {
     pub id: ProcessAddress,
     #[serde(default)]
+    pub revision_id: Option<RevisionId>,
+    #[serde(default)]
     pub name: MaybeUndefined<String>,
     #[serde(default)]
     pub has_beginning: MaybeUndefined<Timestamp>,
@@ -160,6 +173,10 @@ impl<'a> UpdateRequest {
         &self.id
     }
 
+    pub fn get_revision_id(&'a self) -> Option<&Address> {
+        self.revision_id.as_ref().map(AsRef::as_ref)
+    }
+
     // :TODO: accessors for other field data
 }
 
@@ -12,41 +12,69 @@ use hdk_graph_helpers::{
     records::{
         create_record,
         read_record_entry,
+        read_record_entries,
         update_record,
+        get_revision_id,
         delete_record,
     },
+    integrity::{
+        RecordIndexDefinition,
+        IndexIntegrityReport,
+        check_index_integrity,
+    },
 };
 
 use hc_zome_rea_process_specification_storage_consts::*;
 use hc_zome_rea_process_specification_storage::*;
 use hc_zome_rea_process_specification_rpc::*;
 
+/// Records managed by the process specification zome, as checked by `receive_check_index_integrity`.
+/// Process specifications are not yet indexed against other records, so only their `key indexes` are checked.
+const PROCESS_SPECIFICATION_INDEXES: &[RecordIndexDefinition] = &[
+    RecordIndexDefinition {
+        base_entry_type: PROCESS_SPECIFICATION_BASE_ENTRY_TYPE,
+        indexes: &[],
+        remote: false,
+    },
+];
+
 pub fn receive_create_process_specification(process_specification: CreateRequest) -> ZomeApiResult<ResponseData> {
     let (base_address, entry_resp): (ProcessSpecificationAddress, Entry) = create_record(
         PROCESS_SPECIFICATION_BASE_ENTRY_TYPE,
         PROCESS_SPECIFICATION_ENTRY_TYPE,
         PROCESS_SPECIFICATION_INITIAL_ENTRY_LINK_TYPE,
+        process_specification.get_idempotency_key(),
         process_specification.to_owned(),
     )?;
-    Ok(construct_response(&base_address, &entry_resp))
+    construct_response(&base_address, &entry_resp)
 }
 pub fn receive_get_process_specification(address: ProcessSpecificationAddress) -> ZomeApiResult<ResponseData> {
-    Ok(construct_response(&address, &read_record_entry(&address)?))
+    construct_response(&address, &read_record_entry(&address)?)
+}
+pub fn receive_get_process_specifications_by_ids(addresses: Vec<ProcessSpecificationAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+    Ok(addresses.iter()
+        .zip(read_record_entries(&addresses))
+        .map(|(address, entry)| construct_response(address, &entry?))
+        .collect())
 }
 pub fn receive_update_process_specification(process_specification: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_process_specification(&process_specification)
 }
 pub fn receive_delete_process_specification(address: ProcessSpecificationAddress) -> ZomeApiResult<bool> {
-    delete_record::<Entry>(&address)
+    Ok(delete_record::<Entry>(&address)?)
 }
 pub fn receive_query_process_specifications(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
     handle_query_process_specifications(&params)
 }
 
+pub fn receive_check_index_integrity(repair: bool) -> ZomeApiResult<IndexIntegrityReport> {
+    Ok(check_index_integrity(PROCESS_SPECIFICATION_INDEXES, repair)?)
+}
+
 fn handle_update_process_specification(process_specification: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let address = process_specification.get_id();
-    let new_entry = update_record(PROCESS_SPECIFICATION_ENTRY_TYPE, &address, process_specification)?;
-    Ok(construct_response(address, &new_entry))
+    let new_entry = update_record(PROCESS_SPECIFICATION_ENTRY_TYPE, &address, process_specification.get_revision_id(), process_specification)?;
+    construct_response(address, &new_entry)
 }
 
 fn handle_query_process_specifications(_params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
@@ -59,10 +87,10 @@ fn handle_query_process_specifications(_params: &QueryParams) -> ZomeApiResult<V
             entries.iter()
                 .map(|(entry_base_address, maybe_entry)| {
                     match maybe_entry {
-                        Some(entry) => Ok(construct_response(
+                        Some(entry) => construct_response(
                             entry_base_address,
                             &entry,
-                        )),
+                        ),
                         None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
                     }
                 })
@@ -77,14 +105,15 @@ fn handle_query_process_specifications(_params: &QueryParams) -> ZomeApiResult<V
 /// Create response from input DHT primitives
 pub fn construct_response<'a>(
     address: &ProcessSpecificationAddress, e: &Entry,
-) -> ResponseData {
-    ResponseData {
+) -> ZomeApiResult<ResponseData> {
+    Ok(ResponseData {
         process_specification: Response {
             id: address.to_owned(),
+            revision_id: get_revision_id(PROCESS_SPECIFICATION_ENTRY_TYPE, e)?.into(),
             name: e.name.to_owned(),
             note: e.note.to_owned(),
         }
-    }
+    })
 }
 
 //---------------- READ ----------------
@@ -15,6 +15,7 @@ use holochain_json_api::{ json::JsonString, error::JsonError };
 use holochain_json_derive::{ DefaultJson };
 
 use hdk_graph_helpers::MaybeUndefined;
+use vf_core::type_aliases::{ Address, RevisionId };
 
 //---------------- EXTERNAL RECORD STRUCTURE ----------------
 
@@ -26,6 +27,7 @@ pub use vf_core::type_aliases::{ ProcessSpecificationAddress };
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub id: ProcessSpecificationAddress,
+    pub revision_id: RevisionId,
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
@@ -47,10 +49,18 @@ pub struct CreateRequest {
     pub name: String,
     #[serde(default)]
     pub note: MaybeUndefined<String>,
+
+    // key supplied by the client to detect retried requests
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 impl<'a> CreateRequest {
     // :TODO: accessors for field data
+
+    pub fn get_idempotency_key(&'a self) -> Option<&String> {
+        self.idempotency_key.as_ref()
+    }
 }
 
 //---------------- UPDATE REQUEST ----------------
@@ -62,6 +72,8 @@ impl<'a> CreateRequest {
 pub struct UpdateRequest {
     pub id: ProcessSpecificationAddress,
     #[serde(default)]
+    pub revision_id: Option<RevisionId>,
+    #[serde(default)]
     pub name: MaybeUndefined<String>,
     #[serde(default)]
     pub note: MaybeUndefined<String>,
@@ -72,6 +84,10 @@ impl<'a> UpdateRequest {
         &self.id
     }
 
+    pub fn get_revision_id(&'a self) -> Option<&Address> {
+        self.revision_id.as_ref().map(AsRef::as_ref)
+    }
+
     // :TODO: accessors for other field data
 }
 
@@ -8,16 +8,16 @@
 */
 use hdk::error::ZomeApiResult;
 
-use std::borrow::Cow;
-
 use hdk_graph_helpers::{
-    links::get_linked_addresses_as_type,
+    record_schema,
+    field_selection::FieldSelection,
     // local_indexes::query_direct_index_with_foreign_key,
     // remote_indexes::{
     //   RemoteEntryLinkResponse,
     //   handle_sync_direct_remote_index_destination,
     // },
-    records::{create_record, delete_record, read_record_entry, update_record},
+    records::{create_record, delete_record, read_record_entry, read_record_entries, update_record},
+    integrity::{check_index_integrity, IndexIntegrityReport, RecordIndexDefinition},
 };
 
 use vf_core::type_aliases::{ProposedIntentAddress, ProposedToAddress};
@@ -26,6 +26,67 @@ use hc_zome_rea_proposal_rpc::*;
 use hc_zome_rea_proposal_storage::*;
 use hc_zome_rea_proposal_storage_consts::*;
 
+use hc_zome_rea_proposed_intent_storage_consts::{
+    PROPOSED_INTENT_BASE_ENTRY_TYPE,
+    PROPOSED_INTENT_PUBLISHED_IN_LINK_TYPE, PROPOSED_INTENT_PUBLISHED_IN_LINK_TAG,
+    PROPOSED_INTENT_PUBLISHED_IN_INDEX,
+};
+use hc_zome_rea_proposed_to_storage_consts::{
+    PROPOSED_TO_BASE_ENTRY_TYPE,
+    PROPOSED_TO_PROPOSED_LINK_TYPE, PROPOSED_TO_PROPOSED_LINK_TAG,
+    PROPOSED_TO_PROPOSED_INDEX,
+};
+
+// Storage identifiers are declared in `hc_zome_rea_proposal_storage_consts`, where the
+// proposed intent & proposed to indexes refer to them.
+record_schema! {
+    record: Entry,
+    address: ProposalAddress,
+    description: "Published requests or offers, sometimes with what is expected in return.",
+    entry_type: PROPOSAL_ENTRY_TYPE,
+    base_entry_type: PROPOSAL_BASE_ENTRY_TYPE,
+    initial_entry_link_type: PROPOSAL_INITIAL_ENTRY_LINK_TYPE,
+    response: ResponseData { proposal: Response },
+    fields: [name, has_beginning, has_end, unit_based, created, note, in_scope_of],
+    local_links: [
+        publishes as "publishes": ProposedIntentAddress => PROPOSED_INTENT_BASE_ENTRY_TYPE,
+            link_type: PROPOSAL_PUBLISHES_LINK_TYPE,
+            link_tag: PROPOSAL_PUBLISHES_LINK_TAG,
+            index: PROPOSAL_PUBLISHES_INDEX,
+            reverse_link_type: PROPOSED_INTENT_PUBLISHED_IN_LINK_TYPE,
+            reverse_link_tag: PROPOSED_INTENT_PUBLISHED_IN_LINK_TAG;
+        published_to as "publishedTo": ProposedToAddress => PROPOSED_TO_BASE_ENTRY_TYPE,
+            link_type: PROPOSAL_PUBLISHED_TO_LINK_TYPE,
+            link_tag: PROPOSAL_PUBLISHED_TO_LINK_TAG,
+            index: PROPOSAL_PUBLISHED_TO_INDEX,
+            reverse_link_type: PROPOSED_TO_PROPOSED_LINK_TYPE,
+            reverse_link_tag: PROPOSED_TO_PROPOSED_LINK_TAG;
+    ],
+    remote_links: [],
+}
+
+/// Indexes managed by the proposal DNA, as checked by `receive_check_index_integrity`
+const PROPOSAL_INDEXES: &[RecordIndexDefinition] = &[
+    RecordIndexDefinition {
+        base_entry_type: PROPOSAL_BASE_ENTRY_TYPE,
+        indexes: &[
+            PROPOSAL_PUBLISHES_INDEX,
+            PROPOSAL_PUBLISHED_TO_INDEX,
+        ],
+        remote: false,
+    },
+    RecordIndexDefinition {
+        base_entry_type: PROPOSED_INTENT_BASE_ENTRY_TYPE,
+        indexes: &[PROPOSED_INTENT_PUBLISHED_IN_INDEX],
+        remote: false,
+    },
+    RecordIndexDefinition {
+        base_entry_type: PROPOSED_TO_BASE_ENTRY_TYPE,
+        indexes: &[PROPOSED_TO_PROPOSED_INDEX],
+        remote: false,
+    },
+];
+
 pub fn receive_create_proposal(proposal: CreateRequest) -> ZomeApiResult<ResponseData> {
     handle_create_proposal(&proposal)
 }
@@ -34,12 +95,20 @@ pub fn receive_get_proposal(address: ProposalAddress) -> ZomeApiResult<ResponseD
     handle_get_proposal(&address)
 }
 
+pub fn receive_get_proposals_by_ids(addresses: Vec<ProposalAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+    Ok(handle_get_proposals_by_ids(&addresses))
+}
+
 pub fn receive_update_proposal(proposal: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_proposal(&proposal)
 }
 
 pub fn receive_delete_proposal(address: ProposalAddress) -> ZomeApiResult<bool> {
-    delete_record::<Entry>(&address)
+    Ok(delete_record::<Entry>(&address)?)
+}
+
+pub fn receive_check_index_integrity(repair: bool) -> ZomeApiResult<IndexIntegrityReport> {
+    Ok(check_index_integrity(PROPOSAL_INDEXES, repair)?)
 }
 
 // pub fn receive_query_proposals(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
@@ -47,11 +116,18 @@ pub fn receive_delete_proposal(address: ProposalAddress) -> ZomeApiResult<bool>
 // }
 
 fn handle_get_proposal(address: &ProposalAddress) -> ZomeApiResult<ResponseData> {
-    Ok(construct_response(
+    construct_response(
         address,
         &read_record_entry(address)?,
-        get_link_fields(address),
-    ))
+        get_link_fields(address, &FieldSelection::all())?,
+    )
+}
+
+fn handle_get_proposals_by_ids(addresses: &[ProposalAddress]) -> Vec<ZomeApiResult<ResponseData>> {
+    addresses.iter()
+        .zip(read_record_entries(addresses))
+        .map(|(address, entry)| construct_response(address, &entry?, get_link_fields(address, &FieldSelection::all())?))
+        .collect()
 }
 
 fn handle_create_proposal(proposal: &CreateRequest) -> ZomeApiResult<ResponseData> {
@@ -59,23 +135,24 @@ fn handle_create_proposal(proposal: &CreateRequest) -> ZomeApiResult<ResponseDat
         PROPOSAL_BASE_ENTRY_TYPE,
         PROPOSAL_ENTRY_TYPE,
         PROPOSAL_INITIAL_ENTRY_LINK_TYPE,
+        proposal.get_idempotency_key(),
         proposal.to_owned(),
     )?;
-    Ok(construct_response(
+    construct_response(
         &base_address,
         &entry_resp,
-        get_link_fields(&base_address),
-    ))
+        get_link_fields(&base_address, &FieldSelection::all())?,
+    )
 }
 
 fn handle_update_proposal(proposal: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let base_address = proposal.get_id();
-    let new_entry = update_record(PROPOSAL_ENTRY_TYPE, base_address, proposal)?;
-    Ok(construct_response(
+    let new_entry = update_record(PROPOSAL_ENTRY_TYPE, base_address, proposal.get_revision_id(), proposal)?;
+    construct_response(
         base_address,
         &new_entry,
-        get_link_fields(base_address),
-    ))
+        get_link_fields(base_address, &FieldSelection::all())?,
+    )
 }
 
 /*
@@ -86,9 +163,7 @@ fn handle_query_proposals(params: &QueryParams) -> ZomeApiResult<Vec<ResponseDat
     match &params.publishes {
         Some(publishes) => {
             entries_result = query_direct_index_with_foreign_key(
-                publishes,
-                PROPOSAL_PUBLISHES_LINK_TYPE,
-                PROPOSAL_PUBLISHES_LINK_TAG,
+                publishes, &PROPOSED_INTENT_PUBLISHED_IN_INDEX.forward,
             );
         }
         _ => (),
@@ -97,9 +172,7 @@ fn handle_query_proposals(params: &QueryParams) -> ZomeApiResult<Vec<ResponseDat
     match &params.published_to {
         Some(published_to) => {
             entries_result = query_direct_index_with_foreign_key(
-                published_to,
-                PROPOSAL_PUBLISHED_TO_LINK_TYPE,
-                PROPOSAL_PUBLISHED_TO_LINK_TAG,
+                published_to, &PROPOSED_TO_PROPOSED_INDEX.forward,
             );
         }
         _ => (),
@@ -109,75 +182,18 @@ fn handle_query_proposals(params: &QueryParams) -> ZomeApiResult<Vec<ResponseDat
         Ok(entries) => Ok(entries
             .iter()
             .map(|(entry_base_address, maybe_entry)| match maybe_entry {
-                Some(entry) => Ok(construct_response(
+                Some(entry) => construct_response(
                     entry_base_address,
                     &entry,
-                    get_link_fields(entry_base_address),
-                )),
+                    get_link_fields(entry_base_address, &FieldSelection::all())?,
+                ),
                 None => Err(ZomeApiError::Internal(
                     "referenced entry not found".to_string(),
                 )),
             })
             .filter_map(Result::ok)
             .collect()),
-        _ => Err(ZomeApiError::Internal(
-            "could not load linked addresses".to_string(),
-        )),
+        Err(e) => Err(e),
     }
 }
 */
-
-/// Create response from input DHT primitives
-pub fn construct_response<'a>(
-    address: &ProposalAddress,
-    e: &Entry,
-    (publishes, published_to): (
-        Option<Cow<'a, Vec<ProposedIntentAddress>>>,
-        Option<Cow<'a, Vec<ProposedToAddress>>>,
-    ),
-) -> ResponseData {
-    ResponseData {
-        proposal: Response {
-            // entry fields
-            id: address.to_owned(),
-            name: e.name.to_owned(),
-            has_beginning: e.has_beginning.to_owned(),
-            has_end: e.has_end.to_owned(),
-            unit_based: e.unit_based.to_owned(),
-            created: e.created.to_owned(),
-            note: e.note.to_owned(),
-            in_scope_of: e.in_scope_of.to_owned(),
-            // link fields
-            publishes: publishes.map(Cow::into_owned),
-            published_to: published_to.map(Cow::into_owned),
-        },
-    }
-}
-
-pub fn get_link_fields<'a>(
-    proposal: &ProposalAddress,
-) -> (
-    Option<Cow<'a, Vec<ProposedIntentAddress>>>,
-    Option<Cow<'a, Vec<ProposedToAddress>>>,
-) {
-    (
-        Some(get_publishes_ids(proposal)),
-        Some(get_published_to_ids(proposal)),
-    )
-}
-
-fn get_publishes_ids<'a>(p_to: &ProposalAddress) -> Cow<'a, Vec<ProposedIntentAddress>> {
-    get_linked_addresses_as_type(
-        p_to,
-        PROPOSAL_PUBLISHES_LINK_TYPE,
-        PROPOSAL_PUBLISHES_LINK_TAG,
-    )
-}
-
-fn get_published_to_ids<'a>(p_to: &ProposalAddress) -> Cow<'a, Vec<ProposedToAddress>> {
-    get_linked_addresses_as_type(
-        p_to,
-        PROPOSAL_PUBLISHED_TO_LINK_TYPE,
-        PROPOSAL_PUBLISHED_TO_LINK_TAG,
-    )
-}
@@ -15,7 +15,7 @@ use holochain_json_api::{error::JsonError, json::JsonString};
 use holochain_json_derive::DefaultJson;
 
 use hdk_graph_helpers::MaybeUndefined;
-use vf_core::type_aliases::{ProposedIntentAddress, ProposedToAddress, Timestamp};
+use vf_core::type_aliases::{Address, RevisionId, ProposedIntentAddress, ProposedToAddress, Timestamp};
 
 //---------------- EXTERNAL RECORD STRUCTURE ----------------
 
@@ -28,6 +28,7 @@ pub use vf_core::type_aliases::ProposalAddress;
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub id: ProposalAddress,
+    pub revision_id: RevisionId,
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub has_beginning: Option<Timestamp>,
@@ -79,10 +80,18 @@ pub struct CreateRequest {
     pub note: MaybeUndefined<String>,
     #[serde(default)]
     pub in_scope_of: MaybeUndefined<Vec<String>>,
+
+    // key supplied by the client to detect retried requests
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 impl<'a> CreateRequest {
     // :TODO: accessors for field data
+
+    pub fn get_idempotency_key(&'a self) -> Option<&String> {
+        self.idempotency_key.as_ref()
+    }
 }
 
 //---------------- UPDATE REQUEST ----------------
@@ -94,6 +103,8 @@ impl<'a> CreateRequest {
 pub struct UpdateRequest {
     pub id: ProposalAddress,
     #[serde(default)]
+    pub revision_id: Option<RevisionId>,
+    #[serde(default)]
     pub name: MaybeUndefined<String>,
     #[serde(default)]
     pub has_beginning: MaybeUndefined<Timestamp>,
@@ -112,6 +123,10 @@ impl<'a> UpdateRequest {
         &self.id
     }
 
+    pub fn get_revision_id(&'a self) -> Option<&Address> {
+        self.revision_id.as_ref().map(AsRef::as_ref)
+    }
+
     // :TODO: accessors for other field data
 }
 
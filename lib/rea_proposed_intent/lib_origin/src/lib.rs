@@ -7,16 +7,18 @@
  * @package Holo-REA
  */
 use hdk::{
-    call,
     error::{ZomeApiError, ZomeApiResult},
     PUBLIC_TOKEN,
 };
 
 use hdk_graph_helpers::{
     local_indexes::{
-        create_direct_index, delete_direct_index, query_direct_index_with_foreign_key,
+        create_direct_index, delete_direct_index, IndexQuery, query_indexes_with_foreign_key,
     },
-    records::{create_record, delete_record, read_record_entry},
+    records::{create_record, delete_record, read_record_entry, read_record_entries},
+    idempotency::get_idempotent_record_address,
+    rpc::read_from_zome,
+    errors::{throw_any_error, GraphHelperResult},
 };
 
 use hc_zome_rea_proposed_intent_rpc::*;
@@ -37,22 +39,23 @@ pub fn receive_get_proposed_intent(address: ProposedIntentAddress) -> ZomeApiRes
     handle_get_proposed_intent(&address)
 }
 
+pub fn receive_get_proposed_intents_by_ids(addresses: Vec<ProposedIntentAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+    Ok(handle_get_proposed_intents_by_ids(&addresses))
+}
+
 pub fn receive_delete_proposed_intent(address: ProposedIntentAddress) -> ZomeApiResult<bool> {
     let entry: Entry = read_record_entry(&address)?;
 
-    let _ = delete_direct_index(
+    throw_any_error(delete_direct_index(
         address.as_ref(),
         entry.published_in.as_ref(),
-        PROPOSED_INTENT_PUBLISHED_IN_LINK_TYPE,
-        PROPOSED_INTENT_PUBLISHED_IN_LINK_TAG,
-        PROPOSAL_PUBLISHES_LINK_TYPE,
-        PROPOSAL_PUBLISHES_LINK_TAG,
-    );
+        &PROPOSED_INTENT_PUBLISHED_IN_INDEX,
+    ))?;
 
     let res = delete_record::<Entry>(&address);
 
     // update in the associated foreign DNA as well
-    call(
+    let _pingback: bool = read_from_zome(
         BRIDGED_PLANNING_DHT,
         "proposed_intent",
         Address::from(PUBLIC_TOKEN.to_string()),
@@ -62,7 +65,7 @@ pub fn receive_delete_proposed_intent(address: ProposedIntentAddress) -> ZomeApi
         }.into(),
     )?;
 
-    res
+    Ok(res?)
 }
 
 pub fn receive_query_proposed_intents(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
@@ -73,24 +76,34 @@ fn handle_get_proposed_intent(address: &ProposedIntentAddress) -> ZomeApiResult<
     Ok(construct_response(address, &read_record_entry(address)?))
 }
 
+fn handle_get_proposed_intents_by_ids(addresses: &[ProposedIntentAddress]) -> Vec<ZomeApiResult<ResponseData>> {
+    addresses.iter()
+        .zip(read_record_entries(addresses))
+        .map(|(address, entry)| Ok(construct_response(address, &entry?)))
+        .collect()
+}
+
 fn handle_create_proposed_intent(proposed_intent: &CreateRequest) -> ZomeApiResult<ResponseData> {
+    // retried requests resolve to the record created by the original, whose indexes have already been written
+    if let Some(existing) = get_idempotent_record_address(PROPOSED_INTENT_BASE_ENTRY_TYPE, proposed_intent.get_idempotency_key())? {
+        return handle_get_proposed_intent(&existing);
+    }
+
     let (base_address, entry_resp): (ProposedIntentAddress, Entry) = create_record(
         PROPOSED_INTENT_BASE_ENTRY_TYPE,
         PROPOSED_INTENT_ENTRY_TYPE,
         PROPOSED_INTENT_INITIAL_ENTRY_LINK_TYPE,
+        proposed_intent.get_idempotency_key(),
         proposed_intent.to_owned(),
     )?;
     // handle link fields
-    let _ = create_direct_index(
+    throw_any_error(create_direct_index(
         base_address.as_ref(),
         proposed_intent.published_in.as_ref(),
-        PROPOSED_INTENT_PUBLISHED_IN_LINK_TYPE,
-        PROPOSED_INTENT_PUBLISHED_IN_LINK_TAG,
-        PROPOSAL_PUBLISHES_LINK_TYPE,
-        PROPOSAL_PUBLISHES_LINK_TAG,
-    );
+        &PROPOSED_INTENT_PUBLISHED_IN_INDEX,
+    ))?;
     // update in the associated foreign DNA as well
-    let pingback = call(
+    let _pingback: ResponseData = read_from_zome(
         BRIDGED_PLANNING_DHT,
         "proposed_intent",
         Address::from(PUBLIC_TOKEN.to_string()),
@@ -99,26 +112,19 @@ fn handle_create_proposed_intent(proposed_intent: &CreateRequest) -> ZomeApiResu
             proposed_intent: proposed_intent.to_owned(),
         }
         .into(),
-    );
-    let _ = hdk::debug(format!("grepme {:?}", pingback));
+    )?;
     Ok(construct_response(&base_address, &entry_resp))
 }
 
 fn handle_query_proposed_intents(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(ProposedIntentAddress, Option<Entry>)>> =
-        Err(ZomeApiError::Internal("No results found".to_string()));
-
-    // :TODO: replace with real query filter logic
-    match &params.published_in {
-        Some(published_in) => {
-            entries_result = query_direct_index_with_foreign_key(
-                published_in,
-                PROPOSED_INTENT_PUBLISHED_IN_LINK_TYPE,
-                PROPOSED_INTENT_PUBLISHED_IN_LINK_TAG,
-            );
-        }
-        _ => (),
-    };
+    let mut filters: Vec<IndexQuery> = vec![];
+
+    if let Some(published_in) = &params.published_in {
+        filters.push(IndexQuery::local(published_in, &PROPOSED_INTENT_PUBLISHED_IN_INDEX.reverse));
+    }
+
+    let entries_result: GraphHelperResult<Vec<(ProposedIntentAddress, Option<Entry>)>> =
+        query_indexes_with_foreign_key(&IndexQuery::And(filters), false);
 
     match entries_result {
         Ok(entries) => Ok(entries
@@ -131,9 +137,7 @@ fn handle_query_proposed_intents(params: &QueryParams) -> ZomeApiResult<Vec<Resp
             })
             .filter_map(Result::ok)
             .collect()),
-        _ => Err(ZomeApiError::Internal(
-            "could not load linked addresses".to_string(),
-        )),
+        Err(e) => Err(e.into()),
     }
 }
 
@@ -51,10 +51,18 @@ pub struct CreateRequest {
     pub reciprocal: bool,
     pub published_in: ProposalAddress,
     pub publishes: IntentAddress,
+
+    // key supplied by the client to detect retried requests
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 impl<'a> CreateRequest {
     // :TODO: accessors for field data
+
+    pub fn get_idempotency_key(&'a self) -> Option<&String> {
+        self.idempotency_key.as_ref()
+    }
 }
 
 /// I/O struct for forwarding records to other DNAs via zome API
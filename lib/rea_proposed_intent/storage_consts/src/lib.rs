@@ -5,6 +5,16 @@
  *
  * @package Holo-REA
  */
+use hdk_graph_helpers::{ LinkSpec, BidirectionalIndex };
+use hc_zome_rea_proposal_storage_consts::{
+    PROPOSAL_BASE_ENTRY_TYPE,
+    PROPOSAL_PUBLISHES_LINK_TYPE, PROPOSAL_PUBLISHES_LINK_TAG,
+};
+use hc_zome_rea_intent_storage_consts::{
+    INTENT_BASE_ENTRY_TYPE,
+    INTENT_PUBLISHED_IN_LINK_TYPE, INTENT_PUBLISHED_IN_LINK_TAG,
+};
+
 pub const PROPOSED_INTENT_ENTRY_TYPE: &str = "vf_proposed_intent";
 pub const PROPOSED_INTENT_BASE_ENTRY_TYPE: &str = "vf_proposed_intent_baseurl";
 pub const PROPOSED_INTENT_INITIAL_ENTRY_LINK_TYPE: &str = "vf_proposed_intent_entry";
@@ -15,4 +25,15 @@ pub const PROPOSED_INTENT_PUBLISHED_IN_LINK_TAG: &str = "published_in";
 pub const PROPOSED_INTENT_PUBLISHES_LINK_TYPE: &str = "vf_proposed_intent_publishes";
 pub const PROPOSED_INTENT_PUBLISHES_LINK_TAG: &str = "publishes";
 
+/// Proposed intents published in a proposal
+pub const PROPOSED_INTENT_PUBLISHED_IN_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: PROPOSED_INTENT_BASE_ENTRY_TYPE, link_type: PROPOSED_INTENT_PUBLISHED_IN_LINK_TYPE, link_tag: PROPOSED_INTENT_PUBLISHED_IN_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: PROPOSAL_BASE_ENTRY_TYPE, link_type: PROPOSAL_PUBLISHES_LINK_TYPE, link_tag: PROPOSAL_PUBLISHES_LINK_TAG },
+};
+/// Intents published via a proposed intent
+pub const PROPOSED_INTENT_PUBLISHES_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: PROPOSED_INTENT_BASE_ENTRY_TYPE, link_type: PROPOSED_INTENT_PUBLISHES_LINK_TYPE, link_tag: PROPOSED_INTENT_PUBLISHES_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: INTENT_BASE_ENTRY_TYPE, link_type: INTENT_PUBLISHED_IN_LINK_TYPE, link_tag: INTENT_PUBLISHED_IN_LINK_TAG },
+};
+
 pub const BRIDGED_PLANNING_DHT: &str = "vf_planning";
@@ -19,8 +19,10 @@ use hdk_graph_helpers::{
     // handle_sync_direct_remote_index_destination,
     // },
     local_indexes::{create_direct_index, delete_direct_index},
-    local_indexes::query_direct_index_with_foreign_key,
-    records::{create_record, delete_record, read_record_entry},
+    local_indexes::{IndexQuery, query_indexes_with_foreign_key},
+    records::{create_record, delete_record, read_record_entry, read_record_entries},
+    idempotency::get_idempotent_record_address,
+    errors::{throw_any_error, GraphHelperResult},
 };
 
 use hc_zome_rea_proposed_to_rpc::*;
@@ -37,20 +39,21 @@ pub fn receive_get_proposed_to(address: ProposedToAddress) -> ZomeApiResult<Resp
     handle_get_proposed_to(&address)
 }
 
+pub fn receive_get_proposed_tos_by_ids(addresses: Vec<ProposedToAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+    Ok(handle_get_proposed_tos_by_ids(&addresses))
+}
+
 pub fn receive_delete_proposed_to(address: ProposedToAddress) -> ZomeApiResult<bool> {
     let entry: Entry = read_record_entry(&address)?;
 
     // :TODO: error handling
-    let _ = delete_direct_index(
+    throw_any_error(delete_direct_index(
         address.as_ref(),
         entry.proposed.as_ref(),
-        PROPOSED_TO_PROPOSED_LINK_TYPE,
-        PROPOSED_TO_PROPOSED_LINK_TAG,
-        PROPOSAL_PUBLISHED_TO_LINK_TYPE,
-        PROPOSAL_PUBLISHED_TO_LINK_TAG,
-    );
+        &PROPOSED_TO_PROPOSED_INDEX,
+    ))?;
 
-    delete_record::<Entry>(&address)
+    Ok(delete_record::<Entry>(&address)?)
 }
 
 pub fn receive_query_proposed_to(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
@@ -61,39 +64,44 @@ fn handle_get_proposed_to(address: &ProposedToAddress) -> ZomeApiResult<Response
     Ok(construct_response(address, &read_record_entry(address)?))
 }
 
+fn handle_get_proposed_tos_by_ids(addresses: &[ProposedToAddress]) -> Vec<ZomeApiResult<ResponseData>> {
+    addresses.iter()
+        .zip(read_record_entries(addresses))
+        .map(|(address, entry)| Ok(construct_response(address, &entry?)))
+        .collect()
+}
+
 fn handle_create_proposed_to(proposed_to: &CreateRequest) -> ZomeApiResult<ResponseData> {
+    // retried requests resolve to the record created by the original, whose indexes have already been written
+    if let Some(existing) = get_idempotent_record_address(PROPOSED_TO_BASE_ENTRY_TYPE, proposed_to.get_idempotency_key())? {
+        return handle_get_proposed_to(&existing);
+    }
+
     let (base_address, entry_resp): (ProposedToAddress, Entry) = create_record(
         PROPOSED_TO_BASE_ENTRY_TYPE,
         PROPOSED_TO_ENTRY_TYPE,
         PROPOSED_TO_INITIAL_ENTRY_LINK_TYPE,
+        proposed_to.get_idempotency_key(),
         proposed_to.to_owned(),
     )?;
     // :TODO: error handling
-    let _ = create_direct_index(
+    throw_any_error(create_direct_index(
         base_address.as_ref(),
         proposed_to.proposed.as_ref(),
-        PROPOSED_TO_PROPOSED_LINK_TYPE,
-        PROPOSED_TO_PROPOSED_LINK_TAG,
-        PROPOSAL_PUBLISHED_TO_LINK_TYPE,
-        PROPOSAL_PUBLISHED_TO_LINK_TAG,
-    );
+        &PROPOSED_TO_PROPOSED_INDEX,
+    ))?;
     Ok(construct_response(&base_address, &entry_resp))
 }
 
 fn handle_query_proposed_to(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(ProposedToAddress, Option<Entry>)>> =
-        Err(ZomeApiError::Internal("No results found".to_string()));
-
-    match &params.proposed {
-        Some(proposed) => {
-            entries_result = query_direct_index_with_foreign_key(
-                proposed,
-                PROPOSED_TO_PROPOSED_LINK_TYPE,
-                PROPOSED_TO_PROPOSED_LINK_TAG,
-            );
-        }
-        _ => (),
-    };
+    let mut filters: Vec<IndexQuery> = vec![];
+
+    if let Some(proposed) = &params.proposed {
+        filters.push(IndexQuery::local(proposed, &PROPOSED_TO_PROPOSED_INDEX.reverse));
+    }
+
+    let entries_result: GraphHelperResult<Vec<(ProposedToAddress, Option<Entry>)>> =
+        query_indexes_with_foreign_key(&IndexQuery::And(filters), false);
 
     match entries_result {
         Ok(entries) => Ok(entries
@@ -106,9 +114,7 @@ fn handle_query_proposed_to(params: &QueryParams) -> ZomeApiResult<Vec<ResponseD
             })
             .filter_map(Result::ok)
             .collect()),
-        _ => Err(ZomeApiError::Internal(
-            "could not load linked addresses".to_string(),
-        )),
+        Err(e) => Err(e.into()),
     }
 }
 
@@ -123,14 +129,14 @@ pub fn construct_response<'a>(address: &ProposedToAddress, e: &Entry) -> Respons
     }
 }
 
-pub fn get_link_fields<'a>(p_to: &ProposedToAddress) -> Option<Cow<'a, Vec<ProposalAddress>>> {
-    Some(get_proposed_ids(p_to))
+pub fn get_link_fields<'a>(p_to: &ProposedToAddress) -> ZomeApiResult<Option<Cow<'a, Vec<ProposalAddress>>>> {
+    Ok(Some(get_proposed_ids(p_to)?))
 }
 
-fn get_proposed_ids<'a>(p_to: &ProposedToAddress) -> Cow<'a, Vec<ProposalAddress>> {
-    get_linked_addresses_with_foreign_key_as_type(
+fn get_proposed_ids<'a>(p_to: &ProposedToAddress) -> ZomeApiResult<Cow<'a, Vec<ProposalAddress>>> {
+    Ok(get_linked_addresses_with_foreign_key_as_type(
         p_to,
         PROPOSED_TO_PROPOSED_LINK_TYPE,
         PROPOSED_TO_PROPOSED_LINK_TAG,
-    )
+    )?)
 }
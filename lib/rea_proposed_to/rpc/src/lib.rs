@@ -50,10 +50,18 @@ pub struct ResponseData {
 pub struct CreateRequest {
     pub proposed_to: AgentAddress,
     pub proposed: ProposalAddress,
+
+    // key supplied by the client to detect retried requests
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 impl<'a> CreateRequest {
     // :TODO: accessors for field data
+
+    pub fn get_idempotency_key(&'a self) -> Option<&String> {
+        self.idempotency_key.as_ref()
+    }
 }
 
 //---------------- QUERY FILTER REQUEST ----------------
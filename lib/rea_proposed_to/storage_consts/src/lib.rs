@@ -5,9 +5,21 @@
  *
  * @package Holo-REA
  */
+use hdk_graph_helpers::{ LinkSpec, BidirectionalIndex };
+use hc_zome_rea_proposal_storage_consts::{
+    PROPOSAL_BASE_ENTRY_TYPE,
+    PROPOSAL_PUBLISHED_TO_LINK_TYPE, PROPOSAL_PUBLISHED_TO_LINK_TAG,
+};
+
 pub const PROPOSED_TO_ENTRY_TYPE: &str = "vf_proposed_to";
 pub const PROPOSED_TO_BASE_ENTRY_TYPE: &str = "vf_proposed_to_id";
 pub const PROPOSED_TO_INITIAL_ENTRY_LINK_TYPE: &str = "vf_proposed_to_entry";
 
 pub const PROPOSED_TO_PROPOSED_LINK_TYPE: &str = "proposed_to_proposed";
 pub const PROPOSED_TO_PROPOSED_LINK_TAG: &str = "proposed";
+
+/// Recipients of a proposal
+pub const PROPOSED_TO_PROPOSED_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: PROPOSED_TO_BASE_ENTRY_TYPE, link_type: PROPOSED_TO_PROPOSED_LINK_TYPE, link_tag: PROPOSED_TO_PROPOSED_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: PROPOSAL_BASE_ENTRY_TYPE, link_type: PROPOSAL_PUBLISHED_TO_LINK_TYPE, link_tag: PROPOSAL_PUBLISHED_TO_LINK_TAG },
+};
@@ -13,9 +13,16 @@ use hdk_graph_helpers::{
     records::{
         create_record,
         read_record_entry,
+        read_record_entries,
         update_record,
+        get_revision_id,
         delete_record,
     },
+    integrity::{
+        RecordIndexDefinition,
+        IndexIntegrityReport,
+        check_index_integrity,
+    },
 };
 
 use vf_core::type_aliases::{
@@ -26,33 +33,66 @@ use hc_zome_rea_resource_specification_storage_consts::*;
 use hc_zome_rea_resource_specification_storage::*;
 use hc_zome_rea_resource_specification_rpc::*;
 
+use hc_zome_rea_economic_resource_storage_consts::{RESOURCE_BASE_ENTRY_TYPE, RESOURCE_CONFORMS_TO_INDEX};
+
+/// Indexes managed by the resource specification zome, as checked by `receive_check_index_integrity`
+const RESOURCE_SPECIFICATION_INDEXES: &[RecordIndexDefinition] = &[
+    RecordIndexDefinition {
+        base_entry_type: ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE,
+        indexes: &[
+            RESOURCE_CONFORMS_TO_INDEX.reversed(),
+        ],
+        remote: false,
+    },
+    // destination of remote indexes from the observation DNA
+    RecordIndexDefinition {
+        base_entry_type: RESOURCE_BASE_ENTRY_TYPE,
+        indexes: &[
+            RESOURCE_CONFORMS_TO_INDEX,
+        ],
+        remote: true,
+    },
+];
+
 pub fn receive_create_resource_specification(resource_specification: CreateRequest) -> ZomeApiResult<ResponseData> {
     let (base_address, entry_resp): (ResourceSpecificationAddress, Entry) = create_record(
         ECONOMIC_RESOURCE_SPECIFICATION_BASE_ENTRY_TYPE,
         ECONOMIC_RESOURCE_SPECIFICATION_ENTRY_TYPE,
         ECONOMIC_RESOURCE_SPECIFICATION_INITIAL_ENTRY_LINK_TYPE,
+        resource_specification.get_idempotency_key(),
         resource_specification.to_owned(),
     )?;
-    Ok(construct_response(&base_address, &entry_resp, None))
+    construct_response(&base_address, &entry_resp, None)
 }
 pub fn receive_get_resource_specification(address: ResourceSpecificationAddress) -> ZomeApiResult<ResponseData> {
-    Ok(construct_response(&address, &read_record_entry(&address)?, None))
+    construct_response(&address, &read_record_entry(&address)?, None)
+}
+
+pub fn receive_get_resource_specifications_by_ids(addresses: Vec<ResourceSpecificationAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+    Ok(addresses.iter()
+        .zip(read_record_entries(&addresses))
+        .map(|(address, entry)| construct_response(address, &entry?, None))
+        .collect())
 }
 
 pub fn receive_update_resource_specification(resource_specification: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_resource_specification(&resource_specification)
 }
 pub fn receive_delete_resource_specification(id: ResourceSpecificationAddress) -> ZomeApiResult<bool> {
-    delete_record::<Entry>(&id)
+    Ok(delete_record::<Entry>(&id)?)
 }
 pub fn receive_query_resource_specifications(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
     handle_query_resource_specifications(&params)
 }
 
+pub fn receive_check_index_integrity(repair: bool) -> ZomeApiResult<IndexIntegrityReport> {
+    Ok(check_index_integrity(RESOURCE_SPECIFICATION_INDEXES, repair)?)
+}
+
 fn handle_update_resource_specification(resource_specification: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let address = resource_specification.get_id();
-    let new_entry = update_record(ECONOMIC_RESOURCE_SPECIFICATION_ENTRY_TYPE, &address, resource_specification)?;
-    Ok(construct_response(address, &new_entry, None))
+    let new_entry = update_record(ECONOMIC_RESOURCE_SPECIFICATION_ENTRY_TYPE, &address, resource_specification.get_revision_id(), resource_specification)?;
+    construct_response(address, &new_entry, None)
 }
 
 fn handle_query_resource_specifications(_params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
@@ -65,11 +105,11 @@ fn handle_query_resource_specifications(_params: &QueryParams) -> ZomeApiResult<
             entries.iter()
                 .map(|(entry_base_address, maybe_entry)| {
                     match maybe_entry {
-                        Some(entry) => Ok(construct_response(
+                        Some(entry) => construct_response(
                             entry_base_address,
                             &entry,
                             None,
-                        )),
+                        ),
                         None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
                     }
                 })
@@ -86,11 +126,12 @@ pub fn construct_response<'a>(
     e: &Entry,
     // :TODO: link conforming resources in associated link registry DNA module
     _conforming_resources : Option<Cow<'a, Vec<ResourceAddress>>>
-) -> ResponseData {
-    ResponseData {
+) -> ZomeApiResult<ResponseData> {
+    Ok(ResponseData {
         resource_specification: Response {
             // entry fields
             id: address.to_owned(),
+            revision_id: get_revision_id(ECONOMIC_RESOURCE_SPECIFICATION_ENTRY_TYPE, e)?.into(),
             name: e.name.to_owned(),
             image: e.image.to_owned(),
             note: e.note.to_owned(),
@@ -98,7 +139,7 @@ pub fn construct_response<'a>(
 
             // conforming_resources: conforming_resources.map(Cow::into_owned),
         }
-    }
+    })
 }
 
 //---------------- READ ----------------
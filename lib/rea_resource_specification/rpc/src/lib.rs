@@ -16,6 +16,8 @@ use holochain_json_derive::{ DefaultJson };
 
 use hdk_graph_helpers::MaybeUndefined;
 use vf_core::type_aliases::{
+    Address,
+    RevisionId,
     ExternalURL,
     UnitId,
 };
@@ -31,6 +33,7 @@ pub use vf_core::type_aliases::{ ResourceSpecificationAddress };
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub id: ResourceSpecificationAddress,
+    pub revision_id: RevisionId,
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image: Option<ExternalURL>,
@@ -64,10 +67,18 @@ pub struct CreateRequest {
     pub note: MaybeUndefined<String>,
     #[serde(default)]
     pub default_unit_of_effort: MaybeUndefined<UnitId>,
+
+    // key supplied by the client to detect retried requests
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 impl<'a> CreateRequest {
     // :TODO: accessors for field data
+
+    pub fn get_idempotency_key(&'a self) -> Option<&String> {
+        self.idempotency_key.as_ref()
+    }
 }
 
 //---------------- UPDATE REQUEST ----------------
@@ -79,6 +90,8 @@ impl<'a> CreateRequest {
 pub struct UpdateRequest {
     pub id: ResourceSpecificationAddress,
     #[serde(default)]
+    pub revision_id: Option<RevisionId>,
+    #[serde(default)]
     pub name: MaybeUndefined<String>,
     #[serde(default)]
     pub image: MaybeUndefined<ExternalURL>,
@@ -93,6 +106,10 @@ impl<'a> UpdateRequest {
         &self.id
     }
 
+    pub fn get_revision_id(&'a self) -> Option<&Address> {
+        self.revision_id.as_ref().map(AsRef::as_ref)
+    }
+
     // :TODO: accessors for other field data
 }
 
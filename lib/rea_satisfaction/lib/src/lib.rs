@@ -9,20 +9,25 @@
  *
  * @package Holo-REA
  */
+use hdk::error::ZomeApiResult;
+
+use hdk_graph_helpers::records::get_revision_id;
 use vf_core::type_aliases::SatisfactionAddress;
+use hc_zome_rea_satisfaction_storage_consts::SATISFACTION_ENTRY_TYPE;
 use hc_zome_rea_satisfaction_storage::Entry;
 use hc_zome_rea_satisfaction_rpc::*;
 
 /// Create response from input DHT primitives
-pub fn construct_response(address: &SatisfactionAddress, e: &Entry) -> ResponseData {
-    ResponseData {
+pub fn construct_response(address: &SatisfactionAddress, e: &Entry) -> ZomeApiResult<ResponseData> {
+    Ok(ResponseData {
         satisfaction: Response {
             id: address.to_owned().into(),
+            revision_id: get_revision_id(SATISFACTION_ENTRY_TYPE, e)?.into(),
             satisfied_by: e.satisfied_by.to_owned(),
             satisfies: e.satisfies.to_owned(),
             resource_quantity: e.resource_quantity.to_owned(),
             effort_quantity: e.effort_quantity.to_owned(),
             note: e.note.to_owned(),
         }
-    }
+    })
 }
@@ -15,16 +15,20 @@ use hdk_graph_helpers::{
     records::{
         create_record,
         read_record_entry,
+        read_record_entries,
         update_record,
         delete_record,
+        discard_created_record,
     },
+    idempotency::get_idempotent_record_address,
+    saga::run_saga,
     local_indexes::{
-        query_direct_index_with_foreign_key,
-        create_direct_index,
+        IndexQuery,
+        query_indexes_with_foreign_key,
     },
+    errors::GraphHelperResult,
 };
 
-use hc_zome_rea_economic_event_storage_consts::{EVENT_SATISFIES_LINK_TYPE, EVENT_SATISFIES_LINK_TAG};
 use hc_zome_rea_satisfaction_storage_consts::*;
 use hc_zome_rea_satisfaction_storage::Entry;
 use hc_zome_rea_satisfaction_rpc::*;
@@ -38,12 +42,16 @@ pub fn receive_get_satisfaction(address: SatisfactionAddress) -> ZomeApiResult<R
     handle_get_satisfaction(&address)
 }
 
+pub fn receive_get_satisfactions_by_ids(addresses: Vec<SatisfactionAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+    Ok(handle_get_satisfactions_by_ids(&addresses))
+}
+
 pub fn receive_update_satisfaction(satisfaction: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_satisfaction(&satisfaction)
 }
 
 pub fn receive_delete_satisfaction(address: SatisfactionAddress) -> ZomeApiResult<bool> {
-    delete_record::<Entry>(&address)
+    Ok(delete_record::<Entry>(&address)?)
 }
 
 pub fn receive_query_satisfactions(params: QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
@@ -51,19 +59,28 @@ pub fn receive_query_satisfactions(params: QueryParams) -> ZomeApiResult<Vec<Res
 }
 
 fn handle_create_satisfaction(satisfaction: &CreateRequest) -> ZomeApiResult<ResponseData> {
-    let (satisfaction_address, entry_resp): (SatisfactionAddress, Entry) = create_record(
-        SATISFACTION_BASE_ENTRY_TYPE, SATISFACTION_ENTRY_TYPE,
-        SATISFACTION_INITIAL_ENTRY_LINK_TYPE,
-        satisfaction.to_owned()
-    )?;
-
-    // link entries in the local DNA
-    let _results = create_direct_index(
-        satisfaction_address.as_ref(),
-        satisfaction.get_satisfied_by().as_ref(),
-        SATISFACTION_SATISFIEDBY_LINK_TYPE, SATISFACTION_SATISFIEDBY_LINK_TAG,
-        EVENT_SATISFIES_LINK_TYPE, EVENT_SATISFIES_LINK_TAG,
-    );
+    // retried requests resolve to the record created by the original, whose indexes have already been written
+    if let Some(existing) = get_idempotent_record_address(SATISFACTION_BASE_ENTRY_TYPE, satisfaction.get_idempotency_key())? {
+        return handle_get_satisfaction(&existing);
+    }
+
+    // the record is removed again if it cannot be indexed, so that the origin DNA's request can be retried
+    let (satisfaction_address, entry_resp) = run_saga(|saga| {
+        let (satisfaction_address, entry_resp): (SatisfactionAddress, Entry) = saga.step("create satisfaction",
+            || create_record(
+                SATISFACTION_BASE_ENTRY_TYPE, SATISFACTION_ENTRY_TYPE,
+                SATISFACTION_INITIAL_ENTRY_LINK_TYPE,
+                satisfaction.get_idempotency_key(),
+                satisfaction.to_owned()
+            ),
+            |(address, _): (SatisfactionAddress, Entry)| discard_created_record::<Entry>(SATISFACTION_BASE_ENTRY_TYPE, &address, satisfaction.get_idempotency_key()),
+        )?;
+
+        // link entries in the local DNA
+        saga.create_direct_index("index satisfying event", satisfaction_address.as_ref(), satisfaction.get_satisfied_by().as_ref(), &SATISFACTION_SATISFIEDBY_EVENT_INDEX)?;
+
+        Ok((satisfaction_address, entry_resp))
+    })?;
 
     // register in the associated foreign DNA as well
     // :TODO: probably need to remove this and rethink to use a message broadcast / respond flow
@@ -75,32 +92,37 @@ fn handle_create_satisfaction(satisfaction: &CreateRequest) -> ZomeApiResult<Res
     //     fulfillment.into(),
     // );
 
-    Ok(construct_response(&satisfaction_address, &entry_resp))
+    construct_response(&satisfaction_address, &entry_resp)
 }
 
 fn handle_update_satisfaction(satisfaction: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let base_address = satisfaction.get_id();
-    let new_entry = update_record(SATISFACTION_ENTRY_TYPE, &base_address, satisfaction)?;
-    Ok(construct_response(&base_address, &new_entry))
+    // :NOTE: revisions are checked by the origin DNA prior to forwarding, this copy only mirrors it
+    let new_entry = update_record(SATISFACTION_ENTRY_TYPE, &base_address, None, satisfaction)?;
+    construct_response(&base_address, &new_entry)
 }
 
 /// Read an individual fulfillment's details
 fn handle_get_satisfaction(base_address: &SatisfactionAddress) -> ZomeApiResult<ResponseData> {
     let entry = read_record_entry(base_address)?;
-    Ok(construct_response(&base_address, &entry))
+    construct_response(&base_address, &entry)
+}
+
+fn handle_get_satisfactions_by_ids(addresses: &[SatisfactionAddress]) -> Vec<ZomeApiResult<ResponseData>> {
+    addresses.iter()
+        .zip(read_record_entries(addresses))
+        .map(|(address, entry)| construct_response(address, &entry?))
+        .collect()
 }
 
 fn handle_query_satisfactions(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(SatisfactionAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
+    let mut filters: Vec<IndexQuery> = vec![];
+
+    if let Some(satisfied_by) = &params.satisfied_by {
+        filters.push(IndexQuery::local(satisfied_by, &SATISFACTION_SATISFIEDBY_EVENT_INDEX.reverse));
+    }
 
-    match &params.satisfied_by {
-        Some(satisfied_by) => {
-            entries_result = query_direct_index_with_foreign_key(
-                satisfied_by, EVENT_SATISFIES_LINK_TYPE, EVENT_SATISFIES_LINK_TAG,
-            );
-        },
-        _ => (),
-    };
+    let entries_result: GraphHelperResult<Vec<(SatisfactionAddress, Option<Entry>)>> = query_indexes_with_foreign_key(&IndexQuery::And(filters), false);
 
     match entries_result {
         Ok(entries) => Ok(
@@ -108,13 +130,13 @@ fn handle_query_satisfactions(params: &QueryParams) -> ZomeApiResult<Vec<Respons
                 .map(|(entry_base_address, maybe_entry)| {
                     // :TODO: avoid cloning entry
                     match maybe_entry {
-                        Some(entry) => Ok(construct_response(&entry_base_address, &entry)),
+                        Some(entry) => construct_response(&entry_base_address, &entry),
                         None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
                     }
                 })
                 .filter_map(Result::ok)
                 .collect()
         ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
+        Err(e) => Err(e.into()),
     }
 }
@@ -14,25 +14,30 @@ use hdk::{
     THIS_INSTANCE,
     holochain_persistence_api::cas::content::Address,
     error::{ZomeApiResult, ZomeApiError},
-    call,
 };
 
 use hdk_graph_helpers::{
     records::{
         create_record,
         read_record_entry,
+        read_record_entries,
         update_record,
+        revert_record,
         delete_record,
+        discard_created_record,
     },
+    idempotency::get_idempotent_record_address,
+    saga::run_saga,
     rpc::read_from_zome,
     local_indexes::{
-        query_direct_index_with_foreign_key,
-        create_direct_index,
+        IndexQuery,
+        query_indexes_with_foreign_key,
     },
+    errors::GraphHelperResult,
 };
 
-use hc_zome_rea_intent_storage_consts::{INTENT_SATISFIEDBY_LINK_TYPE, INTENT_SATISFIEDBY_LINK_TAG};
-use hc_zome_rea_commitment_storage_consts::{COMMITMENT_SATISFIES_LINK_TYPE, COMMITMENT_SATISFIES_LINK_TAG};
+use vf_core::type_aliases::EventOrCommitmentAddress;
+
 use hc_zome_rea_commitment_rpc::{ResponseData as CommitmentResponse};
 use hc_zome_rea_satisfaction_storage_consts::*;
 use hc_zome_rea_satisfaction_storage::*;
@@ -47,6 +52,10 @@ pub fn receive_get_satisfaction(address: SatisfactionAddress) -> ZomeApiResult<R
     handle_get_satisfaction(&address)
 }
 
+pub fn receive_get_satisfactions_by_ids(addresses: Vec<SatisfactionAddress>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+    Ok(handle_get_satisfactions_by_ids(&addresses))
+}
+
 pub fn receive_update_satisfaction(satisfaction: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_satisfaction(&satisfaction)
 }
@@ -60,113 +69,140 @@ pub fn receive_query_satisfactions(params: QueryParams) -> ZomeApiResult<Vec<Res
 }
 
 fn handle_create_satisfaction(satisfaction: &CreateRequest) -> ZomeApiResult<ResponseData> {
-    let (satisfaction_address, entry_resp): (SatisfactionAddress, Entry) = create_record(
-        SATISFACTION_BASE_ENTRY_TYPE, SATISFACTION_ENTRY_TYPE,
-        SATISFACTION_INITIAL_ENTRY_LINK_TYPE,
-        satisfaction.to_owned(),
-    )?;
-
-    // link entries in the local DNA
-    let _results1 = create_direct_index(
-        satisfaction_address.as_ref(),
-        satisfaction.get_satisfies().as_ref(),
-        SATISFACTION_SATISFIES_LINK_TYPE, SATISFACTION_SATISFIES_LINK_TAG,
-        INTENT_SATISFIEDBY_LINK_TYPE, INTENT_SATISFIEDBY_LINK_TAG,
-    );
-
-    // link entries which may be local or remote
-    // :TODO: Should not have to do this-
-    //        One option is that linking to a nonexistent entry should autocreate the base.
-    //        This would also make it safe to create things out of order at the expense of validation of external data.
-    //        (Alternative: every link has to get a successful pingback from the destination object with its trait signature intact.)
-    // :TODO: use of URIs and a Holochain protocol resolver would also make this type of logic entirely unnecessary
-    let event_or_commitment = satisfaction.get_satisfied_by();
-    let satisfying_commitment: ZomeApiResult<CommitmentResponse> = read_from_zome(
-        THIS_INSTANCE,
-        "commitment",
-        Address::from(PUBLIC_TOKEN.to_string()),    // :TODO:
-        "get_commitment",
-        CheckCommitmentRequest { address: event_or_commitment.to_owned().into() }.into(),
-    );
+    // retried requests resolve to the record created by the original, whose indexes have already been written
+    if let Some(existing) = get_idempotent_record_address(SATISFACTION_BASE_ENTRY_TYPE, satisfaction.get_idempotency_key())? {
+        return handle_get_satisfaction(&existing);
+    }
 
-    match satisfying_commitment {
-        // links to local commitment, create link index pair
-        Ok(_) => {
-            let _results2 = create_direct_index(
-                satisfaction_address.as_ref(),
-                event_or_commitment.as_ref().into(),
-                SATISFACTION_SATISFIEDBY_LINK_TYPE, SATISFACTION_SATISFIEDBY_LINK_TAG,
-                COMMITMENT_SATISFIES_LINK_TYPE, COMMITMENT_SATISFIES_LINK_TAG,
-            );
-        },
-        // links to remote event, ping associated foreign DNA
-        _ => {
-            let _pingback = call(
+    // the local record is removed again if it cannot be registered in the foreign DNA
+    let (satisfaction_address, entry_resp) = run_saga(|saga| {
+        let (satisfaction_address, entry_resp): (SatisfactionAddress, Entry) = saga.step("create satisfaction",
+            || create_record(
+                SATISFACTION_BASE_ENTRY_TYPE, SATISFACTION_ENTRY_TYPE,
+                SATISFACTION_INITIAL_ENTRY_LINK_TYPE,
+                satisfaction.get_idempotency_key(),
+                satisfaction.to_owned(),
+            ),
+            |(address, _): (SatisfactionAddress, Entry)| discard_created_record::<Entry>(SATISFACTION_BASE_ENTRY_TYPE, &address, satisfaction.get_idempotency_key()),
+        )?;
+
+        // link entries in the local DNA
+        saga.create_direct_index("index satisfied intent", satisfaction_address.as_ref(), satisfaction.get_satisfies().as_ref(), &SATISFACTION_SATISFIES_INDEX)?;
+
+        // link entries which may be local or remote
+        // :TODO: Should not have to do this-
+        //        One option is that linking to a nonexistent entry should autocreate the base.
+        //        This would also make it safe to create things out of order at the expense of validation of external data.
+        //        (Alternative: every link has to get a successful pingback from the destination object with its trait signature intact.)
+        // :TODO: use of URIs and a Holochain protocol resolver would also make this type of logic entirely unnecessary
+        let event_or_commitment = satisfaction.get_satisfied_by();
+
+        if is_satisfied_by_commitment(event_or_commitment) {
+            // links to local commitment, create link index pair
+            saga.create_direct_index("index satisfying commitment", satisfaction_address.as_ref(), event_or_commitment.as_ref().into(), &SATISFACTION_SATISFIEDBY_COMMITMENT_INDEX)?;
+        } else {
+            // links to remote event, ping associated foreign DNA
+            let _pingback: ResponseData = read_from_zome(
                 BRIDGED_OBSERVATION_DHT,
                 "satisfaction",
                 Address::from(PUBLIC_TOKEN.to_string()),    // :TODO:
                 "satisfaction_created",
                 FwdCreateRequest { satisfaction: satisfaction.to_owned() }.into()
-            );
-        },
-    };
+            )?;
+        }
+
+        Ok((satisfaction_address, entry_resp))
+    })?;
 
-    Ok(construct_response(&satisfaction_address, &entry_resp))
+    construct_response(&satisfaction_address, &entry_resp)
 }
 
 /// Read an individual satisfaction's details
 fn handle_get_satisfaction(base_address: &SatisfactionAddress) -> ZomeApiResult<ResponseData> {
     let entry = read_record_entry(base_address)?;
-    Ok(construct_response(&base_address, &entry))
+    construct_response(&base_address, &entry)
+}
+
+fn handle_get_satisfactions_by_ids(addresses: &[SatisfactionAddress]) -> Vec<ZomeApiResult<ResponseData>> {
+    addresses.iter()
+        .zip(read_record_entries(addresses))
+        .map(|(address, entry)| construct_response(address, &entry?))
+        .collect()
 }
 
 fn handle_update_satisfaction(satisfaction: &UpdateRequest) -> ZomeApiResult<ResponseData> {
     let base_address = satisfaction.get_id();
-    let new_entry = update_record(SATISFACTION_ENTRY_TYPE, &base_address, satisfaction)?;
-
-    // update in the associated foreign DNA as well
-    let _pingback = call(
-        BRIDGED_OBSERVATION_DHT,
-        "satisfaction",
-        Address::from(PUBLIC_TOKEN.to_string()),
-        "satisfaction_updated",
-        FwdUpdateRequest { satisfaction: satisfaction.clone() }.into()
-    );
 
-    Ok(construct_response(base_address, &new_entry))
+    // the local changes are undone again if they cannot be applied in the foreign DNA
+    let new_entry = run_saga(|saga| {
+        let prev_entry: Entry = read_record_entry(base_address)?;
+        let new_entry: Entry = update_record(SATISFACTION_ENTRY_TYPE, base_address, satisfaction.get_revision_id(), satisfaction)?;
+        saga.completed("update satisfaction", { let address = base_address.to_owned(); move || {
+            revert_record(SATISFACTION_ENTRY_TYPE, &address, prev_entry)
+        }});
+
+        // update in the associated foreign DNA as well, if the satisfaction refers to an event there
+        if !is_satisfied_by_commitment(&new_entry.satisfied_by) {
+            let _pingback: ResponseData = read_from_zome(
+                BRIDGED_OBSERVATION_DHT,
+                "satisfaction",
+                Address::from(PUBLIC_TOKEN.to_string()),
+                "satisfaction_updated",
+                FwdUpdateRequest { satisfaction: satisfaction.clone() }.into()
+            )?;
+        }
+
+        Ok(new_entry)
+    })?;
+
+    construct_response(base_address, &new_entry)
 }
 
 fn handle_delete_satisfaction(address: &SatisfactionAddress) -> ZomeApiResult<bool> {
-    let result = delete_record::<Entry>(address);
-
-    // update in the associated foreign DNA as well
-    let _pingback = call(
-        BRIDGED_OBSERVATION_DHT,
-        "satisfaction",
-        Address::from(PUBLIC_TOKEN.to_string()),
-        "satisfaction_deleted",
-        address.into(),
-    );
+    let entry: Entry = read_record_entry(address)?;
+
+    // Removed from the foreign DNA first, so that a failure leaves both sides intact. Should the
+    // local delete then fail, retrying is safe since the foreign DNA ignores records already removed.
+    if !is_satisfied_by_commitment(&entry.satisfied_by) {
+        let _pingback: bool = read_from_zome(
+            BRIDGED_OBSERVATION_DHT,
+            "satisfaction",
+            Address::from(PUBLIC_TOKEN.to_string()),
+            "satisfaction_deleted",
+            address.into(),
+        )?;
+    }
 
-    result
+    Ok(delete_record::<Entry>(address)?)
+}
+
+/// Determine whether the record satisfying an intent is a commitment in this DNA,
+/// as opposed to an economic event in the bridged observation DNA.
+///
+/// :TODO: use of URIs and a Holochain protocol resolver would make this type of logic entirely unnecessary
+///
+fn is_satisfied_by_commitment(event_or_commitment: &EventOrCommitmentAddress) -> bool {
+    let satisfying_commitment: GraphHelperResult<CommitmentResponse> = read_from_zome(
+        THIS_INSTANCE,
+        "commitment",
+        Address::from(PUBLIC_TOKEN.to_string()),    // :TODO:
+        "get_commitment",
+        CheckCommitmentRequest { address: event_or_commitment.to_owned().into() }.into(),
+    );
+    satisfying_commitment.is_ok()
 }
 
 fn handle_query_satisfactions(params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let mut entries_result: ZomeApiResult<Vec<(SatisfactionAddress, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
-
-    // :TODO: implement proper AND search rather than exclusive operations
-    match &params.satisfies {
-        Some(satisfies) => {
-            entries_result = query_direct_index_with_foreign_key(satisfies, INTENT_SATISFIEDBY_LINK_TYPE, INTENT_SATISFIEDBY_LINK_TAG);
-        },
-        _ => (),
-    };
-    match &params.satisfied_by {
-        Some(satisfied_by) => {
-            entries_result = query_direct_index_with_foreign_key(satisfied_by, COMMITMENT_SATISFIES_LINK_TYPE, COMMITMENT_SATISFIES_LINK_TAG);
-        },
-        _ => (),
-    };
+    let mut filters: Vec<IndexQuery> = vec![];
+
+    if let Some(satisfies) = &params.satisfies {
+        filters.push(IndexQuery::local(satisfies, &SATISFACTION_SATISFIES_INDEX.reverse));
+    }
+    if let Some(satisfied_by) = &params.satisfied_by {
+        filters.push(IndexQuery::local(satisfied_by, &SATISFACTION_SATISFIEDBY_COMMITMENT_INDEX.reverse));
+    }
+
+    let entries_result: GraphHelperResult<Vec<(SatisfactionAddress, Option<Entry>)>> = query_indexes_with_foreign_key(&IndexQuery::And(filters), false);
 
     match entries_result {
         Ok(entries) => Ok(
@@ -174,13 +210,13 @@ fn handle_query_satisfactions(params: &QueryParams) -> ZomeApiResult<Vec<Respons
                 .map(|(entry_base_address, maybe_entry)| {
                     // :TODO: avoid cloning entry
                     match maybe_entry {
-                        Some(entry) => Ok(construct_response(entry_base_address, entry)),
+                        Some(entry) => construct_response(entry_base_address, entry),
                         None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
                     }
                 })
                 .filter_map(Result::ok)
                 .collect()
         ),
-        _ => Err(ZomeApiError::Internal("could not load linked addresses".to_string()))
+        Err(e) => Err(e.into()),
     }
 }
@@ -4,6 +4,9 @@
  * Required by packages wishing to interact with the zome via its standard RPC interface,
  * and by the zome API handlers accepting these parameters.
  *
+ * Non-integer `hasNumericalValue`s of satisfied quantities are returned as decimal strings.
+ * @see `vf_core::measurement::QuantityValue`
+ *
  * @package Holo-REA
  */
 extern crate serde;
@@ -18,6 +21,8 @@ use hdk_graph_helpers::MaybeUndefined;
 use vf_core::{
     measurement::QuantityValue,
     type_aliases::{
+        Address,
+        RevisionId,
         EventOrCommitmentAddress,
         IntentAddress,
         CommitmentAddress,
@@ -36,6 +41,7 @@ pub use vf_core::type_aliases::{ SatisfactionAddress };
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub id: SatisfactionAddress,
+    pub revision_id: RevisionId,
     pub satisfied_by: EventOrCommitmentAddress,
     pub satisfies: IntentAddress,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -74,6 +80,11 @@ pub struct CreateRequest {
     #[serde(default)]
     #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
     pub note: MaybeUndefined<String>,
+
+    // key supplied by the client to detect retried requests
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
 }
 
 impl<'a> CreateRequest {
@@ -86,6 +97,10 @@ impl<'a> CreateRequest {
     pub fn get_satisfies(&'a self) -> &IntentAddress {
         &self.satisfies
     }
+
+    pub fn get_idempotency_key(&'a self) -> Option<&String> {
+        self.idempotency_key.as_ref()
+    }
 }
 
 /// I/O struct for forwarding records to other DNAs via zome API
@@ -111,6 +126,9 @@ pub struct CheckCommitmentRequest {
 pub struct UpdateRequest {
     pub id: SatisfactionAddress,
     #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision_id: Option<RevisionId>,
+    #[serde(default)]
     #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
     pub satisfied_by: MaybeUndefined<EventOrCommitmentAddress>, // note this setup allows None to be passed but `update_with` ignores it
     #[serde(default)]
@@ -132,6 +150,10 @@ impl<'a> UpdateRequest {
         &self.id
     }
 
+    pub fn get_revision_id(&'a self) -> Option<&Address> {
+        self.revision_id.as_ref().map(AsRef::as_ref)
+    }
+
     // :TODO: accessors for other field data
 }
 
@@ -15,8 +15,7 @@ use holochain_json_api::{ json::JsonString, error::JsonError };
 use holochain_json_derive::{ DefaultJson };
 
 use hdk_graph_helpers::{
-    MaybeUndefined,
-    record_interface::Updateable,
+    record_interface::{ Updateable, Revisioned },
 };
 
 use vf_core::{
@@ -31,13 +30,27 @@ use hc_zome_rea_satisfaction_rpc::{ CreateRequest, UpdateRequest };
 
 //---------------- RECORD INTERNALS & VALIDATION ----------------
 
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone)]
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Clone, Updateable)]
 pub struct Entry {
     pub satisfied_by: EventOrCommitmentAddress,
     pub satisfies: IntentAddress,
     pub resource_quantity: Option<QuantityValue>,
     pub effort_quantity: Option<QuantityValue>,
     pub note: Option<String>,
+    // distinguishes revisions with identical content, eg. once a failed update has been undone
+    #[serde(default)]
+    #[updateable(revision)]
+    pub revision: u32,
+}
+
+impl Revisioned for Entry {
+    fn get_revision(&self) -> u32 {
+        self.revision
+    }
+
+    fn with_revision(&self, revision: u32) -> Entry {
+        Entry { revision, ..self.to_owned() }
+    }
 }
 
 //---------------- CREATE ----------------
@@ -51,27 +64,7 @@ impl From<CreateRequest> for Entry {
             resource_quantity: e.resource_quantity.into(),
             effort_quantity: e.effort_quantity.into(),
             note: e.note.into(),
-        }
-    }
-}
-
-//---------------- UPDATE ----------------
-
-/// Handles update operations by merging any newly provided fields
-impl Updateable<UpdateRequest> for Entry {
-    fn update_with(&self, e: &UpdateRequest) -> Entry {
-        Entry {
-            satisfied_by: match &e.satisfied_by {
-                MaybeUndefined::Some(satisfied_by) => satisfied_by.clone(),
-                _ => self.satisfied_by.clone(),
-            },
-            satisfies: match &e.satisfies {
-                MaybeUndefined::Some(satisfies) => satisfies.clone(),
-                _ => self.satisfies.clone(),
-            },
-            resource_quantity: if e.resource_quantity== MaybeUndefined::Undefined { self.resource_quantity.clone() } else { e.resource_quantity.clone().into() },
-            effort_quantity: if e.effort_quantity== MaybeUndefined::Undefined { self.effort_quantity.clone() } else { e.effort_quantity.clone().into() },
-            note: if e.note== MaybeUndefined::Undefined { self.note.clone() } else { e.note.clone().into() },
+            revision: 0,
         }
     }
 }
@@ -5,6 +5,20 @@
  *
  * @package Holo-REA
  */
+use hdk_graph_helpers::{ LinkSpec, BidirectionalIndex };
+use hc_zome_rea_intent_storage_consts::{
+    INTENT_BASE_ENTRY_TYPE,
+    INTENT_SATISFIEDBY_LINK_TYPE, INTENT_SATISFIEDBY_LINK_TAG,
+};
+use hc_zome_rea_commitment_storage_consts::{
+    COMMITMENT_BASE_ENTRY_TYPE,
+    COMMITMENT_SATISFIES_LINK_TYPE, COMMITMENT_SATISFIES_LINK_TAG,
+};
+use hc_zome_rea_economic_event_storage_consts::{
+    EVENT_BASE_ENTRY_TYPE,
+    EVENT_SATISFIES_LINK_TYPE, EVENT_SATISFIES_LINK_TAG,
+};
+
 pub const SATISFACTION_BASE_ENTRY_TYPE: &str = "vf_satisfaction_baseurl";
 pub const SATISFACTION_INITIAL_ENTRY_LINK_TYPE: &str = "vf_satisfaction_entry";
 pub const SATISFACTION_ENTRY_TYPE: &str = "vf_satisfaction";
@@ -13,4 +27,20 @@ pub const SATISFACTION_SATISFIES_LINK_TAG: &str = "satisfies";
 pub const SATISFACTION_SATISFIEDBY_LINK_TYPE: &str = "vf_satisfaction_satisfied_by";
 pub const SATISFACTION_SATISFIEDBY_LINK_TAG: &str = "satisfied_by";
 
+/// Satisfactions of an intent
+pub const SATISFACTION_SATISFIES_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: SATISFACTION_BASE_ENTRY_TYPE, link_type: SATISFACTION_SATISFIES_LINK_TYPE, link_tag: SATISFACTION_SATISFIES_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: INTENT_BASE_ENTRY_TYPE, link_type: INTENT_SATISFIEDBY_LINK_TYPE, link_tag: INTENT_SATISFIEDBY_LINK_TAG },
+};
+/// Satisfactions by a commitment
+pub const SATISFACTION_SATISFIEDBY_COMMITMENT_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: SATISFACTION_BASE_ENTRY_TYPE, link_type: SATISFACTION_SATISFIEDBY_LINK_TYPE, link_tag: SATISFACTION_SATISFIEDBY_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: COMMITMENT_BASE_ENTRY_TYPE, link_type: COMMITMENT_SATISFIES_LINK_TYPE, link_tag: COMMITMENT_SATISFIES_LINK_TAG },
+};
+/// Satisfactions by an economic event
+pub const SATISFACTION_SATISFIEDBY_EVENT_INDEX: BidirectionalIndex = BidirectionalIndex {
+    forward: LinkSpec { base_entry_type: SATISFACTION_BASE_ENTRY_TYPE, link_type: SATISFACTION_SATISFIEDBY_LINK_TYPE, link_tag: SATISFACTION_SATISFIEDBY_LINK_TAG },
+    reverse: LinkSpec { base_entry_type: EVENT_BASE_ENTRY_TYPE, link_type: EVENT_SATISFIES_LINK_TYPE, link_tag: EVENT_SATISFIES_LINK_TAG },
+};
+
 pub const BRIDGED_OBSERVATION_DHT: &str = "vf_observation";
@@ -8,9 +8,10 @@
  * @package Holo-REA
  */
 use hdk::prelude::*;
+use hdk_graph_helpers::anchors::validate_unique_anchor_link;
 
 use hc_zome_rea_unit_storage_consts::*;
-use hc_zome_rea_unit_storage::Entry;
+use hc_zome_rea_unit_storage::{ Entry, validate_unit_id };
 use hc_zome_rea_unit_rpc::UnitId;
 
 pub fn entry_def() -> ValidatingEntryType {
@@ -21,7 +22,24 @@ pub fn entry_def() -> ValidatingEntryType {
         validation_package: || {
             hdk::ValidationPackageDefinition::Entry
         },
-        validation: |_validation_data: hdk::EntryValidationData<Entry>| {
+        validation: |validation_data: hdk::EntryValidationData<Entry>| {
+            // CREATE
+            if let EntryValidationData::Create{ entry, validation_data: _ } = validation_data {
+                let record: Entry = entry;
+                return record.validate_id();
+            }
+
+            // UPDATE
+            if let EntryValidationData::Modify{ new_entry, old_entry: _, old_entry_header: _, validation_data: _ } = validation_data {
+                let record: Entry = new_entry;
+                return record.validate_id();
+            }
+
+            // DELETE
+            // if let EntryValidationData::Delete{ old_entry, old_entry_header: _, validation_data: _ } = validation_data {
+
+            // }
+
             Ok(())
         },
         links: [
@@ -37,7 +55,11 @@ pub fn id_anchor_entry_def() -> ValidatingEntryType {
         validation_package: || {
             hdk::ValidationPackageDefinition::Entry
         },
-        validation: |_validation_data: hdk::EntryValidationData<UnitId>| {
+        validation: |validation_data: hdk::EntryValidationData<UnitId>| {
+            if let EntryValidationData::Create{ entry, validation_data: _ } = validation_data {
+                let id: UnitId = entry;
+                return validate_unit_id(id.as_ref());
+            }
             Ok(())
         },
         links: [
@@ -45,10 +67,12 @@ pub fn id_anchor_entry_def() -> ValidatingEntryType {
                 UNIT_ENTRY_TYPE,
                 link_type: UNIT_INITIAL_ENTRY_LINK_TYPE,
                 validation_package: || {
-                    hdk::ValidationPackageDefinition::Entry
+                    // the author's chain entries are needed to determine whether they already linked this ID
+                    hdk::ValidationPackageDefinition::ChainEntries
                 },
-                validation: | _validation_data: hdk::LinkValidationData| {
-                    Ok(())
+                validation: | validation_data: hdk::LinkValidationData| {
+                    // each unit ID may only reference a single unit
+                    validate_unique_anchor_link(&validation_data)
                 }
             )
         ]
@@ -17,6 +17,11 @@ use hdk_graph_helpers::{
     },
 };
 
+use vf_core::{
+    measurement::Unit,
+    builtin_units::{ get_builtin_unit, get_all_builtin_units },
+};
+
 use hc_zome_rea_unit_storage_consts::*;
 use hc_zome_rea_unit_storage::*;
 use hc_zome_rea_unit_rpc::*;
@@ -27,6 +32,10 @@ pub fn receive_create_unit(unit: CreateRequest) -> ZomeApiResult<ResponseData> {
 pub fn receive_get_unit(id: UnitId) -> ZomeApiResult<ResponseData> {
     handle_get_unit(&id)
 }
+pub fn receive_get_units_by_ids(ids: Vec<UnitId>) -> ZomeApiResult<Vec<ZomeApiResult<ResponseData>>> {
+    // units are anchored by their ID rather than stored against a `key index`
+    Ok(ids.iter().map(handle_get_unit).collect())
+}
 pub fn receive_update_unit(unit: UpdateRequest) -> ZomeApiResult<ResponseData> {
     handle_update_unit(&unit)
 }
@@ -38,32 +47,43 @@ pub fn receive_query_units(params: QueryParams) -> ZomeApiResult<Vec<ResponseDat
 }
 
 fn handle_create_unit(unit: &CreateRequest) -> ZomeApiResult<ResponseData> {
+    check_not_builtin(unit.get_symbol())?;
     let (entry_id, entry_resp) = create_anchored_record(UNIT_ID_ENTRY_TYPE, UNIT_INITIAL_ENTRY_LINK_TYPE, UNIT_ENTRY_TYPE, unit.to_owned())?;
     Ok(construct_response(&entry_id.into(), &entry_resp))
 }
 
 fn handle_get_unit(id: &UnitId) -> ZomeApiResult<ResponseData> {
+    if let Some(unit) = get_builtin_unit(id.as_ref()) {
+        return Ok(construct_builtin_response(unit));
+    }
     let entry = read_anchored_record_entry(&UNIT_ID_ENTRY_TYPE.to_string(), UNIT_INITIAL_ENTRY_LINK_TYPE, id.as_ref())?;
     Ok(construct_response(id, &entry))
 }
 
 fn handle_update_unit(unit: &UpdateRequest) -> ZomeApiResult<ResponseData> {
+    check_not_builtin(unit.get_id().as_ref())?;
+    if let Some(new_symbol) = unit.get_symbol() {
+        check_not_builtin(&new_symbol)?;
+    }
     let (new_id, new_entry) = update_anchored_record(UNIT_ID_ENTRY_TYPE, UNIT_INITIAL_ENTRY_LINK_TYPE, UNIT_ENTRY_TYPE, unit)?;
     Ok(construct_response(&new_id.into(), &new_entry))
 }
 
 fn handle_delete_unit(id: &UnitId) -> ZomeApiResult<bool> {
-    delete_anchored_record::<Entry>(UNIT_ID_ENTRY_TYPE, UNIT_INITIAL_ENTRY_LINK_TYPE, id.as_ref())
+    check_not_builtin(id.as_ref())?;
+    Ok(delete_anchored_record::<Entry>(UNIT_ID_ENTRY_TYPE, UNIT_INITIAL_ENTRY_LINK_TYPE, id.as_ref())?)
 }
 
 fn handle_query_units(_params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>> {
-    let entries_result: ZomeApiResult<Vec<(UnitId, Option<Entry>)>> = Err(ZomeApiError::Internal("No results found".to_string()));
+    let entries_result: ZomeApiResult<Vec<(UnitId, Option<Entry>)>> = Ok(vec![]);
 
     // :TODO: implement "all" query and filters
 
+    let builtins = get_all_builtin_units().into_iter().map(construct_builtin_response);
+
     match entries_result {
         Ok(entries) => Ok(
-            entries.iter()
+            builtins.chain(entries.iter()
                 .map(|(entry_base_address, maybe_entry)| {
                     match maybe_entry {
                         Some(entry) => Ok(construct_response(
@@ -73,7 +93,7 @@ fn handle_query_units(_params: &QueryParams) -> ZomeApiResult<Vec<ResponseData>>
                         None => Err(ZomeApiError::Internal("referenced entry not found".to_string()))
                     }
                 })
-                .filter_map(Result::ok)
+                .filter_map(Result::ok))
                 .collect()
         ),
         Err(e) => Err(e)
@@ -89,6 +109,28 @@ pub fn construct_response<'a>(
             id: id.to_owned(),
             label: e.label.to_owned(),
             symbol: e.symbol.to_owned(),
+            dimension: e.dimension.to_owned(),
+            conversion: e.conversion.to_owned(),
+            precision: e.precision.to_owned(),
+        }
+    }
+}
+
+/// Checked before writing so that users receive a clear error rather than a validation failure
+///
+fn check_not_builtin(id: &str) -> ZomeApiResult<()> {
+    validate_unit_id(id).map_err(ZomeApiError::Internal)
+}
+
+pub fn construct_builtin_response(unit: Unit) -> ResponseData {
+    ResponseData {
+        unit: Response {
+            id: unit.id,
+            label: unit.label,
+            symbol: unit.symbol,
+            dimension: unit.dimension,
+            conversion: unit.conversion,
+            precision: unit.precision,
         }
     }
 }
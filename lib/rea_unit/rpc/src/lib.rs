@@ -4,6 +4,8 @@
  * Required by packages wishing to interact with the zome via its standard RPC interface,
  * and by the zome API handlers accepting these parameters.
  *
+ * Non-integer conversion `factor`s are returned as decimal strings. @see `vf_core::decimal`
+ *
  * @package Holo-REA
  */
 extern crate serde;
@@ -23,6 +25,7 @@ use hdk_graph_helpers::{
 
 // Export external type interface to allow consuming zomes to easily import & define zome API
 pub use vf_core::type_aliases::{ UnitId };
+pub use vf_core::measurement::{ Dimension, UnitConversion, UnitPrecision };
 
 /// I/O struct to describe the complete record, including all managed link fields
 ///
@@ -32,6 +35,12 @@ pub struct Response {
     pub id: UnitId,
     pub label: String,
     pub symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimension: Option<Dimension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conversion: Option<UnitConversion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub precision: Option<UnitPrecision>,
 }
 
 /// I/O struct to describe what is returned outside the gateway.
@@ -53,6 +62,12 @@ pub struct ResponseData {
 pub struct CreateRequest {
     pub label: String,
     pub symbol: String,
+    #[serde(default)]
+    pub dimension: MaybeUndefined<Dimension>,
+    #[serde(default)]
+    pub conversion: MaybeUndefined<UnitConversion>,
+    #[serde(default)]
+    pub precision: MaybeUndefined<UnitPrecision>,
 }
 
 impl<'a> CreateRequest {
@@ -77,6 +92,12 @@ pub struct UpdateRequest {
     pub id: UnitId,
     pub label: MaybeUndefined<String>,
     pub symbol: MaybeUndefined<String>,
+    #[serde(default)]
+    pub dimension: MaybeUndefined<Dimension>,
+    #[serde(default)]
+    pub conversion: MaybeUndefined<UnitConversion>,
+    #[serde(default)]
+    pub precision: MaybeUndefined<UnitPrecision>,
 }
 
 impl<'a> UpdateRequest {
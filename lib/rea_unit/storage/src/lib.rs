@@ -18,20 +18,43 @@ use hdk_graph_helpers::{
     record_interface::Updateable,
 };
 
+use vf_core::measurement::{ Dimension, UnitConversion, UnitPrecision };
+use vf_core::builtin_units::{ BUILTIN_UNIT_PREFIX, is_builtin_unit, is_builtin_unit_namespace };
+
 use hc_zome_rea_unit_rpc::{ CreateRequest, UpdateRequest };
 
 //---------------- RECORD INTERNALS & VALIDATION ----------------
 
-#[derive(Serialize, Deserialize, Debug, DefaultJson, Default, Clone)]
+#[derive(Serialize, Deserialize, Debug, DefaultJson, Default, Clone, Updateable)]
 pub struct Entry {
     pub label: String,
     pub symbol: String,
+    pub dimension: Option<Dimension>,
+    pub conversion: Option<UnitConversion>,
+    pub precision: Option<UnitPrecision>,
 }
 
 impl<'a> Entry {
     pub fn get_symbol(&'a self) -> String {
         self.symbol.to_owned()
     }
+
+    pub fn validate_id(&self) -> Result<(), String> {
+        validate_unit_id(&self.symbol)
+    }
+}
+
+/// Built-in units are available in every network and may not be redefined by users,
+/// nor may new units be stored in the namespace reserved for them.
+///
+pub fn validate_unit_id(id: &str) -> Result<(), String> {
+    if is_builtin_unit(id) {
+        return Err(format!("Unit '{}' is built-in and cannot be modified", id));
+    }
+    if is_builtin_unit_namespace(id) {
+        return Err(format!("Unit IDs beginning with '{}' are reserved for built-in units", BUILTIN_UNIT_PREFIX));
+    }
+    Ok(())
 }
 
 //---------------- CREATE ----------------
@@ -42,18 +65,9 @@ impl From<CreateRequest> for Entry {
         Entry {
             label: e.label.into(),
             symbol: e.symbol.into(),
-        }
-    }
-}
-
-//---------------- UPDATE ----------------
-
-/// Handles update operations by merging any newly provided fields
-impl Updateable<UpdateRequest> for Entry {
-    fn update_with(&self, e: &UpdateRequest) -> Entry {
-        Entry {
-            label:   if !e.label.is_some()   { self.label.to_owned()   } else { e.label.to_owned().unwrap() },
-            symbol: if !e.symbol.is_some() { self.symbol.to_owned() } else { e.symbol.to_owned().unwrap() },
+            dimension: e.dimension.into(),
+            conversion: e.conversion.into(),
+            precision: e.precision.into(),
         }
     }
 }
@@ -11,6 +11,7 @@ extern crate serde_json;
 use holochain_json_api::json::JsonString;
 
 use vf_core::type_aliases::{ ActionId, ProcessAddress, ResourceAddress };
+use vf_core::measurement::{ Dimension, QuantityValue, UnitConversions, MeasurementError, check_dimension };
 
 pub mod builtins;
 pub use builtins::{ get_builtin_action, get_all_builtin_actions };
@@ -52,6 +53,30 @@ pub struct Action {
     pub pairs_with: String, // any of the action labels, or "notApplicable"
 }
 
+/**
+ * Dimension which the quantities of EconomicEvents and Commitments with the given action must
+ * measure, where the action constrains it
+ */
+pub fn get_action_dimension(action_id: &str) -> Option<Dimension> {
+    match action_id {
+        "work" => Some(Dimension::Time),
+        _ => None,
+    }
+}
+
+/**
+ * Check that `quantities` measure the dimension required by the given action, if any.
+ * Quantities whose units declare no dimension in `units` are not checked.
+ */
+pub fn check_action_dimension(action_id: &str, quantities: &[&QuantityValue], units: &dyn UnitConversions) -> Result<(), MeasurementError> {
+    match get_action_dimension(action_id) {
+        Some(dimension) => quantities.iter()
+            .map(|quantity| check_dimension(quantity, dimension, units))
+            .collect(),
+        None => Ok(()),
+    }
+}
+
 /**
  * Validation for EconomicEvent, Commitment and Process to ensure correct use of actions & Processes
  */
@@ -0,0 +1,199 @@
+/**
+ * Standard units of measure.
+ *
+ * Predefines commonly used units so that quantities can be measured in them without
+ * requiring genesis entries in the `unit` zome, and so that every network agrees on
+ * what 'kg' or 'h' means.
+ *
+ * Units are identified by their UCUM codes namespaced by `BUILTIN_UNIT_PREFIX` (eg.
+ * `om2:kg`), so that they never collide with units stored in the `unit` zome. They are
+ * labelled with their OM2 names. Each is defined in terms of the base unit of its
+ * `Dimension`, directly or via another built-in unit.
+ *
+ * :TODO: currencies, which have no fixed conversions between them
+ *
+ * @see https://ucum.org/ucum
+ * @see http://www.ontology-of-units-of-measure.org/resource/om-2/
+ */
+use std::str::FromStr;
+
+use super::{
+    type_aliases::UnitId,
+    decimal::Decimal,
+    measurement::{ Unit, Dimension, UnitConversion, UnitPrecision, UnitConversions, MeasurementError },
+};
+
+/// Namespace of built-in unit IDs. Units stored in the `unit` zome may not use it.
+pub const BUILTIN_UNIT_PREFIX: &str = "om2:";
+
+// UCUM code, label, symbol, dimension, (reference unit UCUM code, factor)
+type BuiltinUnitDef = (&'static str, &'static str, &'static str, Dimension, Option<(&'static str, &'static str)>);
+
+const BUILTIN_UNITS: &[BuiltinUnitDef] = &[
+    // mass
+    ("kg", "kilogram", "kg", Dimension::Mass, None),
+    ("g", "gram", "g", Dimension::Mass, Some(("kg", "0.001"))),
+    ("mg", "milligram", "mg", Dimension::Mass, Some(("g", "0.001"))),
+    ("t", "tonne", "t", Dimension::Mass, Some(("kg", "1000"))),
+    ("[lb_av]", "pound (avoirdupois)", "lb", Dimension::Mass, Some(("kg", "0.45359237"))),
+    ("[oz_av]", "ounce (avoirdupois)", "oz", Dimension::Mass, Some(("[lb_av]", "0.0625"))),
+    // length
+    ("m", "metre", "m", Dimension::Length, None),
+    ("km", "kilometre", "km", Dimension::Length, Some(("m", "1000"))),
+    ("cm", "centimetre", "cm", Dimension::Length, Some(("m", "0.01"))),
+    ("mm", "millimetre", "mm", Dimension::Length, Some(("m", "0.001"))),
+    ("[in_i]", "inch (international)", "in", Dimension::Length, Some(("cm", "2.54"))),
+    ("[ft_i]", "foot (international)", "ft", Dimension::Length, Some(("[in_i]", "12"))),
+    ("[mi_i]", "mile (international)", "mi", Dimension::Length, Some(("[ft_i]", "5280"))),
+    // area
+    ("m2", "square metre", "m²", Dimension::Area, None),
+    ("km2", "square kilometre", "km²", Dimension::Area, Some(("m2", "1000000"))),
+    ("har", "hectare", "ha", Dimension::Area, Some(("m2", "10000"))),
+    // volume
+    ("m3", "cubic metre", "m³", Dimension::Volume, None),
+    ("L", "litre", "L", Dimension::Volume, Some(("m3", "0.001"))),
+    ("mL", "millilitre", "mL", Dimension::Volume, Some(("L", "0.001"))),
+    ("[gal_us]", "gallon (US)", "gal", Dimension::Volume, Some(("L", "3.785411784"))),
+    // time
+    ("s", "second", "s", Dimension::Time, None),
+    ("min", "minute", "min", Dimension::Time, Some(("s", "60"))),
+    ("h", "hour", "h", Dimension::Time, Some(("min", "60"))),
+    ("d", "day", "d", Dimension::Time, Some(("h", "24"))),
+    ("wk", "week", "wk", Dimension::Time, Some(("d", "7"))),
+    // count
+    ("one", "one", "1", Dimension::Count, None),
+];
+
+fn builtin_unit_id(code: &str) -> UnitId {
+    UnitId::from(format!("{}{}", BUILTIN_UNIT_PREFIX, code))
+}
+
+fn find_builtin_unit(id: &str) -> Option<&'static BuiltinUnitDef> {
+    if !id.starts_with(BUILTIN_UNIT_PREFIX) {
+        return None;
+    }
+    let code = &id[BUILTIN_UNIT_PREFIX.len()..];
+    BUILTIN_UNITS.iter().find(|(unit_code, _, _, _, _)| *unit_code == code)
+}
+
+fn build_unit(def: &BuiltinUnitDef) -> Unit {
+    let (code, label, symbol, dimension, conversion) = def;
+    Unit {
+        id: builtin_unit_id(code),
+        label: label.to_string(),
+        symbol: symbol.to_string(),
+        dimension: Some(*dimension),
+        conversion: conversion.map(|(reference_unit, factor)| UnitConversion {
+            reference_unit: builtin_unit_id(reference_unit),
+            factor: Decimal::from_str(factor).expect("Developer error: invalid built-in unit conversion factor"),
+        }),
+        precision: None,
+    }
+}
+
+pub fn get_builtin_unit(id: &str) -> Option<Unit> {
+    find_builtin_unit(id).map(build_unit)
+}
+
+pub fn is_builtin_unit(id: &str) -> bool {
+    find_builtin_unit(id).is_some()
+}
+
+/// Whether `id` falls within the namespace reserved for built-in units, whether or not it names one
+///
+pub fn is_builtin_unit_namespace(id: &str) -> bool {
+    id.starts_with(BUILTIN_UNIT_PREFIX)
+}
+
+pub fn get_all_builtin_units() -> Vec<Unit> {
+    BUILTIN_UNITS.iter().map(build_unit).collect()
+}
+
+/// Resolves built-in units only. Other units declare nothing.
+///
+/// For use where units cannot be read from the `unit` zome, such as entry validation callbacks.
+///
+pub struct BuiltinUnits;
+
+impl UnitConversions for BuiltinUnits {
+    fn get_conversion(&self, unit: &UnitId) -> Result<Option<UnitConversion>, MeasurementError> {
+        Ok(get_builtin_unit(unit.as_ref()).and_then(|u| u.conversion))
+    }
+
+    fn get_precision(&self, unit: &UnitId) -> Result<Option<UnitPrecision>, MeasurementError> {
+        Ok(get_builtin_unit(unit.as_ref()).and_then(|u| u.precision))
+    }
+
+    fn get_dimension(&self, unit: &UnitId) -> Result<Option<Dimension>, MeasurementError> {
+        Ok(get_builtin_unit(unit.as_ref()).and_then(|u| u.dimension))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::measurement::{ QuantityValue, convert, check_dimension };
+
+    fn qty(value: &str, unit: &str) -> QuantityValue {
+        QuantityValue::new(Decimal::from_str(value).unwrap(), Some(UnitId::from(unit.to_string())))
+    }
+
+    fn catalogue() -> HashMap<String, Unit> {
+        get_all_builtin_units().into_iter()
+            .map(|u| (u.id.as_ref().to_string(), u))
+            .collect()
+    }
+
+    #[test]
+    fn test_builtin_unit_lookup() {
+        let unit = get_builtin_unit("om2:g").unwrap();
+
+        assert_eq!(unit.label, "gram");
+        assert_eq!(unit.dimension, Some(Dimension::Mass));
+        assert_eq!(unit.conversion, Some(UnitConversion {
+            reference_unit: UnitId::from("om2:kg".to_string()),
+            factor: Decimal::new(1, 3),
+        }));
+        assert!(is_builtin_unit("om2:g"));
+        assert_eq!(get_builtin_unit("furlong"), None);
+        assert_eq!(get_builtin_unit("g"), None, "built-in units are namespaced");
+        assert!(is_builtin_unit_namespace("om2:furlong"));
+        assert!(!is_builtin_unit_namespace("g"));
+    }
+
+    #[test]
+    fn test_builtin_units_resolve_to_dimension_base_units() {
+        let units = catalogue();
+        assert_eq!(units.len(), BUILTIN_UNITS.len(), "built-in unit IDs are unique");
+
+        for unit in units.values() {
+            let dimension = unit.dimension.unwrap();
+            let mut current = unit.to_owned();
+            while let Some(conversion) = current.conversion {
+                current = units.get(conversion.reference_unit.as_ref())
+                    .unwrap_or_else(|| panic!("reference unit of '{}' is built-in", unit.id.as_ref()))
+                    .to_owned();
+                assert_eq!(current.dimension, Some(dimension), "'{}' converts within its dimension", unit.id.as_ref());
+            }
+            assert_eq!(Some(current.id), dimension.base_unit());
+        }
+    }
+
+    #[test]
+    fn test_builtin_conversions() {
+        let units = catalogue();
+
+        assert_eq!(convert(qty("1", "om2:wk"), UnitId::from("om2:s".to_string()), &units), Ok(qty("604800", "om2:s")));
+        assert_eq!(convert(qty("1", "om2:[mi_i]"), UnitId::from("om2:km".to_string()), &units), Ok(qty("1.609344", "om2:km")));
+        assert_eq!(convert(qty("16", "om2:[oz_av]"), UnitId::from("om2:g".to_string()), &units), Ok(qty("453.59237", "om2:g")));
+        assert_eq!(convert(qty("2", "om2:har"), UnitId::from("om2:m2".to_string()), &units), Ok(qty("20000", "om2:m2")));
+    }
+
+    #[test]
+    fn test_builtin_units_checked_without_catalogue() {
+        assert_eq!(convert(qty("2", "om2:h"), UnitId::from("om2:min".to_string()), &BuiltinUnits), Ok(qty("120", "om2:min")));
+        assert!(check_dimension(&qty("2", "om2:kg"), Dimension::Time, &BuiltinUnits).is_err());
+        assert_eq!(check_dimension(&qty("2", "furlong"), Dimension::Time, &BuiltinUnits), Ok(()), "other units declare nothing");
+    }
+}
@@ -0,0 +1,445 @@
+/**
+ * Exact decimal numbers, for the numerical values of measured quantities
+ *
+ * Binary floating point cannot represent most decimal fractions exactly, so quantities
+ * updated by many events drift away from their true values (eg. `0.1 + 0.2 != 0.3`).
+ * `Decimal` is a fixed-point representation holding up to `MAX_SCALE` decimal places,
+ * in which addition and subtraction are exact. Results of multiplication and division
+ * are rounded to `MAX_SCALE` places, or to the precision requested by the caller.
+ *
+ * For compatibility with existing clients and stored entries, decimals are read from
+ * JSON numbers via their shortest decimal representation, so values sent as JSON numbers
+ * are exact up to 15 significant digits. Strings such as `"0.1"` are also accepted, for
+ * clients which need more. Integers are written as JSON numbers; all other values are
+ * written as strings, so that they survive storage without being rounded through `f64`.
+ */
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{
+    Serialize, Serializer, Deserialize, Deserializer,
+    de::{ self, Visitor },
+};
+
+/// Maximum number of decimal places held by a `Decimal`
+pub const MAX_SCALE: u32 = 18;
+
+/// A decimal number, stored as `mantissa * 10^-scale`.
+///
+/// :NOTE: values are always normalized to the smallest possible scale, such that
+/// equal numbers have identical representations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+/// Rules for rounding values to fewer decimal places
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RoundingMode {
+    /// Round to the nearest value, and ties to the even neighbour ("banker's rounding")
+    HalfEven,
+    /// Round to the nearest value, and ties away from zero
+    HalfUp,
+    /// Round to the nearest value, and ties towards zero
+    HalfDown,
+    /// Round away from zero
+    Up,
+    /// Round towards zero (truncate)
+    Down,
+    /// Round towards positive infinity
+    Ceiling,
+    /// Round towards negative infinity
+    Floor,
+}
+
+impl Default for RoundingMode {
+    fn default() -> Self {
+        RoundingMode::HalfEven
+    }
+}
+
+/// Error returned when a value cannot be represented as a `Decimal`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecimalError(String);
+
+impl fmt::Display for DecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for DecimalError {}
+
+fn pow10(exponent: u32) -> Option<i128> {
+    10i128.checked_pow(exponent)
+}
+
+/// Divide `n` by `d`, rounding the result to an integer with `mode`.
+/// Returns `None` on division by zero or overflow.
+fn divide_rounded(n: i128, d: i128, mode: RoundingMode) -> Option<i128> {
+    if d == 0 {
+        return None;
+    }
+    let negative = (n < 0) != (d < 0);
+    let n = n.checked_abs()?;
+    let d = d.checked_abs()?;
+    let (quotient, remainder) = (n / d, n % d);
+
+    let round_away = remainder != 0 && match mode {
+        RoundingMode::Down => false,
+        RoundingMode::Up => true,
+        RoundingMode::Ceiling => !negative,
+        RoundingMode::Floor => negative,
+        // compare remainder with half the divisor, without overflowing
+        RoundingMode::HalfUp => remainder >= d - remainder,
+        RoundingMode::HalfDown => remainder > d - remainder,
+        RoundingMode::HalfEven => remainder > d - remainder || (remainder == d - remainder && quotient % 2 == 1),
+    };
+
+    let magnitude = if round_away { quotient.checked_add(1)? } else { quotient };
+    Some(if negative { -magnitude } else { magnitude })
+}
+
+impl Decimal {
+    /// Create a decimal of `mantissa * 10^-scale`. Values with more than `MAX_SCALE`
+    /// decimal places are rounded to the nearest representable value.
+    ///
+    pub fn new(mantissa: i128, scale: u32) -> Decimal {
+        if scale > MAX_SCALE {
+            // rounding only reduces magnitude here, so cannot overflow
+            let reduced = match pow10(scale - MAX_SCALE) {
+                Some(divisor) => divide_rounded(mantissa, divisor, RoundingMode::HalfEven).unwrap(),
+                None => 0,
+            };
+            return Decimal::new(reduced, MAX_SCALE);
+        }
+        Decimal { mantissa, scale }.normalized()
+    }
+
+    pub fn zero() -> Decimal {
+        Decimal { mantissa: 0, scale: 0 }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.mantissa < 0
+    }
+
+    /// Number of decimal places needed to represent the value exactly
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    fn normalized(mut self) -> Decimal {
+        while self.scale > 0 && self.mantissa % 10 == 0 {
+            self.mantissa /= 10;
+            self.scale -= 1;
+        }
+        self
+    }
+
+    /// Express the mantissa at a larger `scale`
+    fn mantissa_at(&self, scale: u32) -> Option<i128> {
+        self.mantissa.checked_mul(pow10(scale - self.scale)?)
+    }
+
+    pub fn checked_add(self, other: Decimal) -> Option<Decimal> {
+        let scale = self.scale.max(other.scale);
+        Some(Decimal::new(self.mantissa_at(scale)?.checked_add(other.mantissa_at(scale)?)?, scale))
+    }
+
+    pub fn checked_sub(self, other: Decimal) -> Option<Decimal> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    pub fn checked_neg(self) -> Option<Decimal> {
+        Some(Decimal { mantissa: self.mantissa.checked_neg()?, scale: self.scale })
+    }
+
+    /// Multiply by `other`, rounding the result to `MAX_SCALE` places if necessary
+    pub fn checked_mul(self, other: Decimal) -> Option<Decimal> {
+        Some(Decimal::new(self.mantissa.checked_mul(other.mantissa)?, self.scale + other.scale))
+    }
+
+    /// Divide by `other`, rounding the result to `MAX_SCALE` places if necessary.
+    /// Returns `None` when dividing by zero.
+    pub fn checked_div(self, other: Decimal) -> Option<Decimal> {
+        if other.is_zero() {
+            return None;
+        }
+        // use as many decimal places for the result as can be held without overflowing
+        (0..=MAX_SCALE).rev()
+            .filter(|scale| scale + other.scale >= self.scale)
+            .filter_map(|scale| {
+                let numerator = self.mantissa.checked_mul(pow10(scale + other.scale - self.scale)?)?;
+                Some(Decimal::new(divide_rounded(numerator, other.mantissa, RoundingMode::HalfEven)?, scale))
+            })
+            .next()
+    }
+
+    /// Round to at most `decimal_places` places, using `mode`
+    pub fn round(self, decimal_places: u32, mode: RoundingMode) -> Decimal {
+        if self.scale <= decimal_places {
+            return self;
+        }
+        // rounding only reduces magnitude by at least a factor of ten, so cannot overflow
+        let divisor = pow10(self.scale - decimal_places).unwrap();
+        Decimal::new(divide_rounded(self.mantissa, divisor, mode).unwrap(), decimal_places)
+    }
+
+    /// Nearest floating point representation of the value
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+}
+
+impl Default for Decimal {
+    fn default() -> Self {
+        Decimal::zero()
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // compare integer parts first, so that scaling the fractional parts cannot overflow
+        let (self_unit, other_unit) = (pow10(self.scale).unwrap(), pow10(other.scale).unwrap());
+        (self.mantissa / self_unit).cmp(&(other.mantissa / other_unit))
+            .then_with(|| {
+                let self_fraction = (self.mantissa % self_unit) * pow10(MAX_SCALE - self.scale).unwrap();
+                let other_fraction = (other.mantissa % other_unit) * pow10(MAX_SCALE - other.scale).unwrap();
+                self_fraction.cmp(&other_fraction)
+            })
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+//---------------- CONVERSIONS ----------------
+
+impl From<i64> for Decimal {
+    fn from(v: i64) -> Decimal {
+        Decimal::new(v as i128, 0)
+    }
+}
+
+impl TryFrom<f64> for Decimal {
+    type Error = DecimalError;
+
+    /// Reads the shortest decimal representation of `v`, ie. the value it was written as
+    fn try_from(v: f64) -> Result<Decimal, DecimalError> {
+        if !v.is_finite() {
+            return Err(DecimalError(format!("{} is not a decimal number", v)));
+        }
+        Decimal::from_str(&v.to_string())
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = DecimalError;
+
+    fn from_str(s: &str) -> Result<Decimal, DecimalError> {
+        let invalid = || DecimalError(format!("'{}' is not a decimal number", s));
+        let out_of_range = || DecimalError(format!("'{}' is out of range for a decimal number", s));
+
+        let (negative, unsigned) = match s.chars().next() {
+            Some('-') => (true, &s[1..]),
+            Some('+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (integer, fraction) = match unsigned.find('.') {
+            Some(point) => (&unsigned[..point], &unsigned[point + 1..]),
+            None => (unsigned, ""),
+        };
+        if (integer.is_empty() && fraction.is_empty()) || !(integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())) {
+            return Err(invalid());
+        }
+
+        // round away any digits beyond the maximum scale
+        let (kept, dropped) = fraction.split_at(fraction.len().min(MAX_SCALE as usize));
+        let mut mantissa: i128 = format!("{}{}", integer, kept).trim_start_matches('0').parse()
+            .or_else(|_| if integer.chars().chain(kept.chars()).all(|c| c == '0') { Ok(0) } else { Err(out_of_range()) })?;
+        let round_up = match dropped.chars().next() {
+            Some(digit) if digit > '5' => true,
+            Some('5') => dropped[1..].chars().any(|c| c != '0') || mantissa % 2 == 1,
+            _ => false,
+        };
+        if round_up {
+            mantissa = mantissa.checked_add(1).ok_or_else(out_of_range)?;
+        }
+
+        Ok(Decimal::new(if negative { -mantissa } else { mantissa }, kept.len() as u32))
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = self.mantissa.to_string();
+        let (sign, digits) = if self.mantissa < 0 { ("-", &digits[1..]) } else { ("", &digits[..]) };
+        if self.scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        let padded = format!("{:0>width$}", digits, width = self.scale as usize + 1);
+        let (integer, fraction) = padded.split_at(padded.len() - self.scale as usize);
+        write!(f, "{}{}.{}", sign, integer, fraction)
+    }
+}
+
+//---------------- SERIALIZATION ----------------
+
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.scale == 0 {
+            if let Ok(integer) = i64::try_from(self.mantissa) {
+                return serializer.serialize_i64(integer);
+            }
+        }
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+struct DecimalVisitor;
+
+impl<'de> Visitor<'de> for DecimalVisitor {
+    type Value = Decimal;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number, or a string containing a decimal number")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Decimal, E> {
+        Ok(Decimal::from(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Decimal, E> {
+        Ok(Decimal::new(v as i128, 0))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Decimal, E> {
+        Decimal::try_from(v).map_err(E::custom)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Decimal, E> {
+        Decimal::from_str(v).map_err(E::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+        deserializer.deserialize_any(DecimalVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(dec("12.50"), Decimal::new(125, 1), "trailing zeros are normalized away");
+        assert_eq!(dec("-0.005").to_string(), "-0.005");
+        assert_eq!(dec("+.5").to_string(), "0.5");
+        assert_eq!(dec("100").to_string(), "100");
+        assert_eq!(dec("000").to_string(), "0");
+        assert!("1e5".parse::<Decimal>().is_err());
+        assert!("-".parse::<Decimal>().is_err());
+        assert!("1.2.3".parse::<Decimal>().is_err());
+        assert!("1000000000000000000000000000000000000000".parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn test_excess_places_rounded() {
+        assert_eq!(dec("0.1234567890123456785"), dec("0.123456789012345678"), "ties to even");
+        assert_eq!(dec("0.1234567890123456775"), dec("0.123456789012345678"));
+        assert_eq!(dec("0.12345678901234567851"), dec("0.123456789012345679"));
+    }
+
+    #[test]
+    fn test_exact_addition() {
+        assert_eq!(dec("0.1").checked_add(dec("0.2")), Some(dec("0.3")));
+
+        let mut total = Decimal::zero();
+        for _ in 0..10000 {
+            total = total.checked_add(dec("0.1")).unwrap();
+        }
+        assert_eq!(total, Decimal::from(1000));
+        assert_eq!(dec("1").checked_sub(dec("1.25")), Some(dec("-0.25")));
+    }
+
+    #[test]
+    fn test_multiplication_and_division() {
+        assert_eq!(dec("2.5").checked_mul(dec("0.001")), Some(dec("0.0025")));
+        assert_eq!(dec("2").checked_div(dec("0.001")), Some(Decimal::from(2000)));
+        assert_eq!(dec("1").checked_div(dec("3")), Some(dec("0.333333333333333333")));
+        assert_eq!(dec("2").checked_div(dec("3")), Some(dec("0.666666666666666667")));
+        assert_eq!(dec("1").checked_div(Decimal::zero()), None);
+        assert_eq!(
+            dec("100000000000000000000").checked_div(dec("3")), Some(dec("33333333333333333333.333333333333333333")),
+            "large values keep as many places as fit",
+        );
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        let cases = [
+            (RoundingMode::HalfEven, ["2.2", "2.4", "-2.4", "2.6"]),
+            (RoundingMode::HalfUp, ["2.2", "2.5", "-2.5", "2.6"]),
+            (RoundingMode::HalfDown, ["2.2", "2.4", "-2.4", "2.6"]),
+            (RoundingMode::Up, ["2.3", "2.5", "-2.5", "2.6"]),
+            (RoundingMode::Down, ["2.2", "2.4", "-2.4", "2.5"]),
+            (RoundingMode::Ceiling, ["2.3", "2.5", "-2.4", "2.6"]),
+            (RoundingMode::Floor, ["2.2", "2.4", "-2.5", "2.5"]),
+        ];
+        for (mode, expected) in cases.iter() {
+            let rounded: Vec<String> = ["2.21", "2.45", "-2.45", "2.59"].iter()
+                .map(|v| dec(v).round(1, *mode).to_string())
+                .collect();
+            assert_eq!(rounded, expected.to_vec(), "{:?}", mode);
+        }
+        assert_eq!(dec("2.5").round(0, RoundingMode::HalfEven), Decimal::from(2));
+        assert_eq!(dec("1.5").round(3, RoundingMode::Down), dec("1.5"), "values with fewer places are unchanged");
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(dec("0.3") > dec("0.25"));
+        assert!(dec("-1.5") < dec("-1.25"));
+        assert!(dec("10") > dec("9.999999999999999999"));
+        assert_eq!(dec("2.50").cmp(&dec("2.5")), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_json_compatibility() {
+        let values: Vec<Decimal> = serde_json::from_str(r#"[0.1, 25, -3, "0.123456789012345678", 1.5e3]"#).unwrap();
+        assert_eq!(values, vec![dec("0.1"), Decimal::from(25), Decimal::from(-3), dec("0.123456789012345678"), Decimal::from(1500)]);
+
+        assert_eq!(serde_json::to_string(&vec![dec("0.3"), Decimal::from(2500), dec("-0.25")]).unwrap(), r#"["0.3",2500,"-0.25"]"#);
+        assert!(serde_json::from_str::<Decimal>("\"abc\"").is_err());
+    }
+
+    #[test]
+    fn test_json_round_trip_lossless() {
+        let values = vec![
+            dec("1").checked_div(dec("3")).unwrap(),
+            dec("0.123456789012345678"),
+            dec("33333333333333333333.333333333333333333"),
+            dec("100000000000000000000"),
+        ];
+        let json = serde_json::to_string(&values).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<Decimal>>(&json).unwrap(), values);
+    }
+}
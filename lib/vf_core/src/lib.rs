@@ -7,4 +7,6 @@ extern crate holochain_json_derive;
 extern crate holochain_persistence_api;
 
 pub mod type_aliases;
+pub mod decimal;
 pub mod measurement;
+pub mod builtin_units;
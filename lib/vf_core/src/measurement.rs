@@ -1,50 +1,718 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{ Add, Sub, Mul, Div, Neg };
+
 use super::type_aliases::UnitId;
+use super::decimal::{ Decimal, RoundingMode };
+
+/// The kinds of quantity which units may measure. Quantities of different dimensions
+/// cannot be converted to one another, nor combined.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum Dimension {
+    Mass,
+    Length,
+    Area,
+    Volume,
+    Time,
+    Count,
+    Currency,
+}
+
+impl Dimension {
+    /// The unit which other units of the dimension are scaled relative to. Conversions
+    /// declared for units of the dimension should (eventually) reference this unit.
+    ///
+    /// Currencies have no base unit, as exchange rates between them are not fixed.
+    ///
+    pub fn base_unit(&self) -> Option<UnitId> {
+        let id = match self {
+            Dimension::Mass => "om2:kg",
+            Dimension::Length => "om2:m",
+            Dimension::Area => "om2:m2",
+            Dimension::Volume => "om2:m3",
+            Dimension::Time => "om2:s",
+            Dimension::Count => "om2:one",
+            Dimension::Currency => return None,
+        };
+        Some(UnitId::from(id.to_string()))
+    }
+}
 
-#[derive(Debug, Clone)]
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            Dimension::Mass => "mass",
+            Dimension::Length => "length",
+            Dimension::Area => "area",
+            Dimension::Volume => "volume",
+            Dimension::Time => "time",
+            Dimension::Count => "count",
+            Dimension::Currency => "currency",
+        })
+    }
+}
+
+/// Definition of a unit of measure
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct Unit {
-    id: UnitId,
-    name: Option<String>,
-    symbol: Option<String>,
+    pub id: UnitId,
+    pub label: String,
+    pub symbol: String,
+    #[serde(default)]
+    pub dimension: Option<Dimension>,
+    #[serde(default)]
+    pub conversion: Option<UnitConversion>,
+    #[serde(default)]
+    pub precision: Option<UnitPrecision>,
 }
 
+/// An amount of some unit.
+///
+/// Whole numerical values are sent over the wire as JSON numbers, and all others as decimal
+/// strings (eg. `{ "hasNumericalValue": "0.1" }`), so that they are not rounded through
+/// floating point. Either form is accepted in requests. @see `decimal`
+///
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct QuantityValue {
-    // :TODO: https://users.rust-lang.org/t/currency-in-rust/890/9 ?
-    has_numerical_value: f64,     // :NOTE: uses https://en.wikipedia.org/wiki/IEEE_754 for math
+    has_numerical_value: Decimal,
     #[serde(default)]
     has_unit: Option<UnitId>,
 }
 
 impl<'a> QuantityValue {
-    pub fn new(has_numerical_value: f64, has_unit: Option<UnitId>) -> QuantityValue {
+    pub fn new(has_numerical_value: Decimal, has_unit: Option<UnitId>) -> QuantityValue {
         QuantityValue {
             has_numerical_value,
             has_unit,
         }
     }
 
+    pub fn get_numerical_value(&'a self) -> Decimal {
+        self.has_numerical_value
+    }
+
     pub fn get_unit(&'a self) -> Option<UnitId> {
         self.has_unit.to_owned()
     }
 }
 
-pub fn add(q1: QuantityValue, q2: QuantityValue) -> QuantityValue {
-    if q1.has_unit != q2.has_unit {
-        panic!("Unimplemented! Need to enable unit conversions in QuantityValue math");
+//---------------- UNIT CONVERSIONS ----------------
+
+/// Defines a unit as a multiple of some other `reference_unit`, such that
+/// 1 of the unit is equal to `factor` of the reference unit.
+///
+/// For example, grams may be defined as `{ "referenceUnit": "kg", "factor": 0.001 }`. As with
+/// quantities, non-integer factors are returned as strings, eg. `"factor": "0.001"`.
+/// Quantities can be converted between any two units which share a reference unit,
+/// directly or via the reference units of their reference units.
+///
+/// :TODO: offsets, for units such as degrees Celsius / Fahrenheit
+///
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnitConversion {
+    pub reference_unit: UnitId,
+    pub factor: Decimal,
+}
+
+/// The number of decimal places quantities of a unit are held to, and how values with
+/// more places are rounded. For example, amounts of most currencies are held to 2 places.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnitPrecision {
+    pub decimal_places: u32,
+    #[serde(default)]
+    pub rounding: RoundingMode,
+}
+
+/// Source of the conversion factors, precision & dimensions declared for units, eg. the
+/// unit records managed by the `rea_unit` zome.
+///
+/// Sources which read units from elsewhere should return `MeasurementError::UnitUnavailable`
+/// where a unit's definition cannot be determined, rather than treating it as declaring nothing.
+///
+pub trait UnitConversions {
+    /// Read the conversion declared for `unit`, if any
+    fn get_conversion(&self, unit: &UnitId) -> Result<Option<UnitConversion>, MeasurementError>;
+
+    /// Read the precision declared for `unit`, if any. Quantities of units without a
+    /// declared precision are held to `decimal::MAX_SCALE` places.
+    fn get_precision(&self, _unit: &UnitId) -> Result<Option<UnitPrecision>, MeasurementError> {
+        Ok(None)
+    }
+
+    /// Read the dimension declared for `unit`, if any
+    fn get_dimension(&self, _unit: &UnitId) -> Result<Option<Dimension>, MeasurementError> {
+        Ok(None)
+    }
+}
+
+/// Conversions held in memory, keyed by the unit they convert from
+impl UnitConversions for HashMap<String, UnitConversion> {
+    fn get_conversion(&self, unit: &UnitId) -> Result<Option<UnitConversion>, MeasurementError> {
+        Ok(self.get(unit.as_ref()).cloned())
     }
-    QuantityValue {
-        has_numerical_value: q1.has_numerical_value + q2.has_numerical_value,
-        has_unit: q1.has_unit,
+}
+
+/// Unit definitions held in memory, keyed by unit ID
+impl UnitConversions for HashMap<String, Unit> {
+    fn get_conversion(&self, unit: &UnitId) -> Result<Option<UnitConversion>, MeasurementError> {
+        Ok(self.get(unit.as_ref()).and_then(|u| u.conversion.to_owned()))
+    }
+
+    fn get_precision(&self, unit: &UnitId) -> Result<Option<UnitPrecision>, MeasurementError> {
+        Ok(self.get(unit.as_ref()).and_then(|u| u.precision.to_owned()))
+    }
+
+    fn get_dimension(&self, unit: &UnitId) -> Result<Option<Dimension>, MeasurementError> {
+        Ok(self.get(unit.as_ref()).and_then(|u| u.dimension))
+    }
+}
+
+/// Permits arithmetic only between quantities of the same unit
+pub struct NoConversions;
+
+impl UnitConversions for NoConversions {
+    fn get_conversion(&self, _unit: &UnitId) -> Result<Option<UnitConversion>, MeasurementError> {
+        Ok(None)
+    }
+}
+
+/// Errors encountered when performing arithmetic on `QuantityValue`s
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeasurementError {
+    /// No conversion exists between the units of two quantities
+    NoConversion { from: Option<UnitId>, to: Option<UnitId> },
+    /// The conversions declared for a unit lead back to itself
+    ConversionCycle(UnitId),
+    /// The result of an operation is too large to be represented
+    Overflow,
+    /// A quantity was divided by zero
+    DivisionByZero,
+    /// A quantity measures a different kind of thing than was expected
+    DimensionMismatch { expected: Dimension, found: Dimension },
+    /// The definition of a unit could not be read. `cause` describes why.
+    UnitUnavailable { unit: UnitId, cause: String },
+}
+
+impl fmt::Display for MeasurementError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MeasurementError::NoConversion { from, to } => write!(f,
+                "No conversion from {} to {}", describe_unit(from), describe_unit(to),
+            ),
+            MeasurementError::ConversionCycle(unit) => write!(f,
+                "Conversions for unit '{}' are circular", unit.as_ref(),
+            ),
+            MeasurementError::Overflow => write!(f, "Quantity is out of range"),
+            MeasurementError::DivisionByZero => write!(f, "Quantity divided by zero"),
+            MeasurementError::DimensionMismatch { expected, found } => write!(f,
+                "Expected a quantity of {}, but found a quantity of {}", expected, found,
+            ),
+            MeasurementError::UnitUnavailable { unit, cause } => write!(f,
+                "Unit '{}' could not be read: {}", unit.as_ref(), cause,
+            ),
+        }
     }
 }
 
-pub fn subtract(q1: QuantityValue, q2: QuantityValue) -> QuantityValue {
-    if q1.has_unit != q2.has_unit {
-        panic!("Unimplemented! Need to enable unit conversions in QuantityValue math");
+impl std::error::Error for MeasurementError {}
+
+fn describe_unit(unit: &Option<UnitId>) -> String {
+    match unit {
+        Some(id) => format!("'{}'", id.as_ref()),
+        None => "unitless quantity".to_string(),
+    }
+}
+
+/// Follow the conversions declared for `unit` to the unit which has none, returning
+/// that unit along with the factor to multiply quantities of `unit` by to express them in it.
+fn reduce_unit(unit: &UnitId, conversions: &dyn UnitConversions) -> Result<(UnitId, Decimal), MeasurementError> {
+    let mut visited = vec![unit.to_owned()];
+    let mut current = unit.to_owned();
+    let mut factor = Decimal::from(1);
+
+    while let Some(conversion) = conversions.get_conversion(&current)? {
+        if visited.contains(&conversion.reference_unit) {
+            return Err(MeasurementError::ConversionCycle(unit.to_owned()));
+        }
+        factor = factor.checked_mul(conversion.factor).ok_or(MeasurementError::Overflow)?;
+        current = conversion.reference_unit;
+        visited.push(current.to_owned());
+    }
+
+    Ok((current, factor))
+}
+
+/// Express `quantity` in `to_unit`, using the unit conversions available from `conversions`.
+/// The result is rounded to the precision declared for `to_unit`.
+///
+pub fn convert(quantity: QuantityValue, to_unit: UnitId, conversions: &dyn UnitConversions) -> Result<QuantityValue, MeasurementError> {
+    let from_unit = match &quantity.has_unit {
+        Some(unit) if *unit == to_unit => return Ok(quantity),
+        Some(unit) => unit.to_owned(),
+        None => return Err(MeasurementError::NoConversion { from: None, to: Some(to_unit) }),
+    };
+
+    if let Some(expected) = conversions.get_dimension(&to_unit)? {
+        check_dimension(&quantity, expected, conversions)?;
+    }
+
+    let (from_base, from_factor) = reduce_unit(&from_unit, conversions)?;
+    let (to_base, to_factor) = reduce_unit(&to_unit, conversions)?;
+    if from_base != to_base {
+        return Err(MeasurementError::NoConversion { from: Some(from_unit), to: Some(to_unit) });
     }
+
+    let value = quantity.has_numerical_value.checked_mul(from_factor)
+        .and_then(|v| v.checked_div(to_factor))
+        .ok_or(MeasurementError::Overflow)?;
+
     QuantityValue {
-        has_numerical_value: q1.has_numerical_value - q2.has_numerical_value,
-        has_unit: q1.has_unit,
+        has_numerical_value: value,
+        has_unit: Some(to_unit),
+    }.round_to_precision(conversions)
+}
+
+/// Express `quantity` in the same unit as `like`
+fn convert_to_unit_of(quantity: QuantityValue, like: &QuantityValue, conversions: &dyn UnitConversions) -> Result<QuantityValue, MeasurementError> {
+    if quantity.has_unit == like.has_unit {
+        return Ok(quantity);
+    }
+    match &like.has_unit {
+        Some(unit) => convert(quantity, unit.to_owned(), conversions),
+        None => Err(MeasurementError::NoConversion { from: quantity.has_unit, to: None }),
+    }
+}
+
+//---------------- DIMENSIONS ----------------
+
+/// Determine the dimension of `quantity`, where its unit declares one
+pub fn dimension_of(quantity: &QuantityValue, conversions: &dyn UnitConversions) -> Result<Option<Dimension>, MeasurementError> {
+    match &quantity.has_unit {
+        Some(unit) => conversions.get_dimension(unit),
+        None => Ok(None),
+    }
+}
+
+/// Check that `quantity` measures the `expected` dimension.
+/// Quantities whose units do not declare a dimension are not checked.
+///
+pub fn check_dimension(quantity: &QuantityValue, expected: Dimension, conversions: &dyn UnitConversions) -> Result<(), MeasurementError> {
+    match dimension_of(quantity, conversions)? {
+        Some(found) if found != expected => Err(MeasurementError::DimensionMismatch { expected, found }),
+        _ => Ok(()),
+    }
+}
+
+/// Check that two quantities measure the same dimension, where both declare one
+///
+pub fn check_same_dimension(expected: &QuantityValue, quantity: &QuantityValue, conversions: &dyn UnitConversions) -> Result<(), MeasurementError> {
+    match dimension_of(expected, conversions)? {
+        Some(dimension) => check_dimension(quantity, dimension, conversions),
+        None => Ok(()),
+    }
+}
+
+//---------------- ARITHMETIC ----------------
+
+/// Arithmetic on quantities. Where quantities of different units are combined, the other
+/// quantity is first converted to the unit of `self` using `conversions`, and the result
+/// is expressed in the unit of `self`.
+///
+impl QuantityValue {
+    fn with_value(&self, value: Option<Decimal>) -> Result<QuantityValue, MeasurementError> {
+        Ok(QuantityValue {
+            has_numerical_value: value.ok_or(MeasurementError::Overflow)?,
+            has_unit: self.has_unit.to_owned(),
+        })
+    }
+
+    pub fn checked_add(&self, other: &QuantityValue, conversions: &dyn UnitConversions) -> Result<QuantityValue, MeasurementError> {
+        let other = convert_to_unit_of(other.to_owned(), self, conversions)?;
+        self.with_value(self.has_numerical_value.checked_add(other.has_numerical_value))
+    }
+
+    pub fn checked_sub(&self, other: &QuantityValue, conversions: &dyn UnitConversions) -> Result<QuantityValue, MeasurementError> {
+        let other = convert_to_unit_of(other.to_owned(), self, conversions)?;
+        self.with_value(self.has_numerical_value.checked_sub(other.has_numerical_value))
+    }
+
+    /// Multiply by a unitless `factor`
+    pub fn checked_mul(&self, factor: Decimal) -> Result<QuantityValue, MeasurementError> {
+        self.with_value(self.has_numerical_value.checked_mul(factor))
+    }
+
+    /// Divide by a unitless `divisor`
+    pub fn checked_div(&self, divisor: Decimal) -> Result<QuantityValue, MeasurementError> {
+        if divisor.is_zero() {
+            return Err(MeasurementError::DivisionByZero);
+        }
+        self.with_value(self.has_numerical_value.checked_div(divisor))
+    }
+
+    /// Determine how many times `other` fits into this quantity, eg. the proportion of a
+    /// commitment's quantity which has been fulfilled so far.
+    ///
+    pub fn ratio(&self, other: &QuantityValue, conversions: &dyn UnitConversions) -> Result<Decimal, MeasurementError> {
+        let other = convert_to_unit_of(other.to_owned(), self, conversions)?;
+        if other.has_numerical_value.is_zero() {
+            return Err(MeasurementError::DivisionByZero);
+        }
+        self.has_numerical_value.checked_div(other.has_numerical_value).ok_or(MeasurementError::Overflow)
+    }
+
+    /// Compare with a quantity which may be of a different unit
+    pub fn compare(&self, other: &QuantityValue, conversions: &dyn UnitConversions) -> Result<Ordering, MeasurementError> {
+        let other = convert_to_unit_of(other.to_owned(), self, conversions)?;
+        Ok(self.has_numerical_value.cmp(&other.has_numerical_value))
+    }
+
+    pub fn negate(&self) -> Result<QuantityValue, MeasurementError> {
+        self.with_value(self.has_numerical_value.checked_neg())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.has_numerical_value.is_zero()
+    }
+
+    /// Round to the precision declared for the unit of this quantity, if any
+    pub fn round_to_precision(&self, conversions: &dyn UnitConversions) -> Result<QuantityValue, MeasurementError> {
+        let precision = match &self.has_unit {
+            Some(unit) => conversions.get_precision(unit)?,
+            None => None,
+        };
+        Ok(match precision {
+            Some(precision) => QuantityValue {
+                has_numerical_value: self.has_numerical_value.round(precision.decimal_places, precision.rounding),
+                has_unit: self.has_unit.to_owned(),
+            },
+            None => self.to_owned(),
+        })
+    }
+}
+
+/// Quantities of the same unit are ordered by value. Quantities of different units
+/// are unordered, use `QuantityValue::compare` to order them via unit conversions.
+///
+impl PartialOrd for QuantityValue {
+    fn partial_cmp(&self, other: &QuantityValue) -> Option<Ordering> {
+        if self.has_unit != other.has_unit {
+            return None;
+        }
+        Some(self.has_numerical_value.cmp(&other.has_numerical_value))
+    }
+}
+
+// Operators combine quantities of the same unit only. Use the `checked_*` methods to
+// combine quantities of different units.
+
+impl Add for QuantityValue {
+    type Output = Result<QuantityValue, MeasurementError>;
+
+    fn add(self, other: QuantityValue) -> Self::Output {
+        self.checked_add(&other, &NoConversions)
+    }
+}
+
+impl Sub for QuantityValue {
+    type Output = Result<QuantityValue, MeasurementError>;
+
+    fn sub(self, other: QuantityValue) -> Self::Output {
+        self.checked_sub(&other, &NoConversions)
+    }
+}
+
+impl Mul<Decimal> for QuantityValue {
+    type Output = Result<QuantityValue, MeasurementError>;
+
+    fn mul(self, factor: Decimal) -> Self::Output {
+        self.checked_mul(factor)
+    }
+}
+
+impl Div<Decimal> for QuantityValue {
+    type Output = Result<QuantityValue, MeasurementError>;
+
+    fn div(self, divisor: Decimal) -> Self::Output {
+        self.checked_div(divisor)
+    }
+}
+
+impl Neg for QuantityValue {
+    type Output = Result<QuantityValue, MeasurementError>;
+
+    fn neg(self) -> Self::Output {
+        self.negate()
+    }
+}
+
+/// Add `q2` to `q1`. The result is expressed in the unit of `q1`.
+///
+pub fn add(q1: QuantityValue, q2: QuantityValue, conversions: &dyn UnitConversions) -> Result<QuantityValue, MeasurementError> {
+    q1.checked_add(&q2, conversions)
+}
+
+/// Subtract `q2` from `q1`. The result is expressed in the unit of `q1`.
+///
+pub fn subtract(q1: QuantityValue, q2: QuantityValue, conversions: &dyn UnitConversions) -> Result<QuantityValue, MeasurementError> {
+    q1.checked_sub(&q2, conversions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(id: &str) -> UnitId {
+        UnitId::from(id.to_string())
+    }
+
+    fn dec(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    fn qty(value: &str, id: &str) -> QuantityValue {
+        QuantityValue::new(dec(value), Some(unit(id)))
+    }
+
+    fn conversion(reference_unit: &str, factor: &str) -> UnitConversion {
+        UnitConversion { reference_unit: unit(reference_unit), factor: dec(factor) }
+    }
+
+    fn mass_conversions() -> HashMap<String, UnitConversion> {
+        let mut conversions = HashMap::new();
+        conversions.insert("g".to_string(), conversion("kg", "0.001"));
+        conversions.insert("mg".to_string(), conversion("g", "0.001"));
+        conversions.insert("t".to_string(), conversion("kg", "1000"));
+        conversions.insert("min".to_string(), conversion("h", "0.016666666666666667"));
+        conversions
+    }
+
+    struct CurrencyUnits;
+
+    impl UnitConversions for CurrencyUnits {
+        fn get_conversion(&self, unit: &UnitId) -> Result<Option<UnitConversion>, MeasurementError> {
+            Ok(match &unit.as_ref()[..] {
+                "JPY" => Some(conversion("USD", "0.0093")),
+                _ => None,
+            })
+        }
+
+        fn get_precision(&self, unit: &UnitId) -> Result<Option<UnitPrecision>, MeasurementError> {
+            Ok(match &unit.as_ref()[..] {
+                "USD" => Some(UnitPrecision { decimal_places: 2, rounding: RoundingMode::HalfEven }),
+                "JPY" => Some(UnitPrecision { decimal_places: 0, rounding: RoundingMode::Down }),
+                _ => None,
+            })
+        }
+    }
+
+    #[test]
+    fn test_same_unit_arithmetic() {
+        assert_eq!(add(qty("1.5", "kg"), qty("2", "kg"), &NoConversions), Ok(qty("3.5", "kg")));
+        assert_eq!(subtract(qty("1.5", "kg"), qty("2", "kg"), &NoConversions), Ok(qty("-0.5", "kg")));
+        assert_eq!(
+            add(QuantityValue::new(dec("1"), None), QuantityValue::new(dec("2"), None), &NoConversions),
+            Ok(QuantityValue::new(dec("3"), None)),
+        );
+    }
+
+    #[test]
+    fn test_arithmetic_is_exact() {
+        assert_eq!(add(qty("0.1", "USD"), qty("0.2", "USD"), &NoConversions), Ok(qty("0.3", "USD")));
+
+        let total = (0..10000).fold(qty("0", "USD"), |total, _| add(total, qty("0.01", "USD"), &NoConversions).unwrap());
+        assert_eq!(total, qty("100", "USD"));
+    }
+
+    #[test]
+    fn test_conversions() {
+        let conversions = mass_conversions();
+
+        assert_eq!(convert(qty("2", "kg"), unit("g"), &conversions), Ok(qty("2000", "g")));
+        assert_eq!(convert(qty("3", "t"), unit("g"), &conversions), Ok(qty("3000000", "g")), "converted via a shared reference unit");
+        assert_eq!(convert(qty("5000", "mg"), unit("g"), &conversions), Ok(qty("5", "g")));
+    }
+
+    #[test]
+    fn test_converted_arithmetic() {
+        let conversions = mass_conversions();
+
+        assert_eq!(add(qty("500", "g"), qty("2", "kg"), &conversions), Ok(qty("2500", "g")), "result is in the unit of the first quantity");
+        assert_eq!(subtract(qty("2", "t"), qty("500", "kg"), &conversions), Ok(qty("1.5", "t")));
+    }
+
+    #[test]
+    fn test_conversions_rounded_to_unit_precision() {
+        assert_eq!(convert(qty("1234", "JPY"), unit("USD"), &CurrencyUnits), Ok(qty("11.48", "USD")), "11.4762 rounded to cents");
+        assert_eq!(convert(qty("10", "USD"), unit("JPY"), &CurrencyUnits), Ok(qty("1075", "JPY")), "1075.268... truncated to whole yen");
+        assert_eq!(add(qty("0.015", "USD"), qty("0", "USD"), &CurrencyUnits), Ok(qty("0.015", "USD")), "values in the unit itself are kept as given");
+        assert_eq!(qty("0.015", "USD").round_to_precision(&CurrencyUnits), Ok(qty("0.02", "USD")), "unless explicitly rounded");
+        assert_eq!(qty("0.015", "EUR").round_to_precision(&CurrencyUnits), Ok(qty("0.015", "EUR")), "units without a declared precision are unaffected");
+    }
+
+    #[test]
+    fn test_unconvertible_quantities() {
+        let conversions = mass_conversions();
+
+        assert_eq!(
+            add(qty("1", "kg"), qty("30", "min"), &conversions),
+            Err(MeasurementError::NoConversion { from: Some(unit("min")), to: Some(unit("kg")) }),
+        );
+        assert_eq!(
+            add(qty("1", "kg"), qty("1", "g"), &NoConversions),
+            Err(MeasurementError::NoConversion { from: Some(unit("g")), to: Some(unit("kg")) }),
+        );
+        assert_eq!(
+            subtract(qty("1", "kg"), QuantityValue::new(dec("1"), None), &conversions),
+            Err(MeasurementError::NoConversion { from: None, to: Some(unit("kg")) }),
+        );
+        assert_eq!(
+            convert(qty("1", "kg"), unit("h"), &conversions).unwrap_err().to_string(),
+            "No conversion from 'kg' to 'h'",
+        );
+    }
+
+    #[test]
+    fn test_circular_conversions() {
+        let mut conversions = HashMap::new();
+        conversions.insert("a".to_string(), conversion("b", "2"));
+        conversions.insert("b".to_string(), conversion("a", "0.5"));
+
+        assert_eq!(convert(qty("1", "a"), unit("c"), &conversions), Err(MeasurementError::ConversionCycle(unit("a"))));
+    }
+
+    #[test]
+    fn test_scalar_arithmetic() {
+        assert_eq!(qty("2.5", "kg").checked_mul(dec("4")), Ok(qty("10", "kg")));
+        assert_eq!(qty("10", "kg").checked_div(dec("4")), Ok(qty("2.5", "kg")));
+        assert_eq!(qty("10", "kg").checked_div(dec("0")), Err(MeasurementError::DivisionByZero));
+        assert_eq!(qty("10", "kg").negate(), Ok(qty("-10", "kg")));
+        assert!(qty("0.000", "kg").is_zero());
+        assert!(!qty("0.001", "kg").is_zero());
+    }
+
+    #[test]
+    fn test_ratio() {
+        let conversions = mass_conversions();
+
+        assert_eq!(qty("750", "g").ratio(&qty("1", "kg"), &conversions), Ok(dec("0.75")));
+        assert_eq!(qty("1", "kg").ratio(&qty("0", "g"), &conversions), Err(MeasurementError::DivisionByZero));
+        assert_eq!(
+            qty("1", "kg").ratio(&qty("1", "h"), &conversions),
+            Err(MeasurementError::NoConversion { from: Some(unit("h")), to: Some(unit("kg")) }),
+        );
+    }
+
+    #[test]
+    fn test_comparison() {
+        let conversions = mass_conversions();
+
+        assert!(qty("2", "kg") > qty("1.5", "kg"));
+        assert_eq!(qty("2", "kg").partial_cmp(&qty("1500", "g")), None, "different units are unordered without conversions");
+        assert_eq!(qty("2", "kg").compare(&qty("1500", "g"), &conversions), Ok(Ordering::Greater));
+        assert_eq!(qty("2", "kg").compare(&qty("2000", "g"), &conversions), Ok(Ordering::Equal));
+        assert!(qty("2", "kg").compare(&qty("1", "h"), &conversions).is_err());
+    }
+
+    #[test]
+    fn test_operators() {
+        assert_eq!(qty("1.5", "kg") + qty("1", "kg"), Ok(qty("2.5", "kg")));
+        assert_eq!(qty("1.5", "kg") - qty("1", "kg"), Ok(qty("0.5", "kg")));
+        assert_eq!(qty("1.5", "kg") * dec("2"), Ok(qty("3", "kg")));
+        assert_eq!(qty("1.5", "kg") / dec("3"), Ok(qty("0.5", "kg")));
+        assert_eq!(-qty("1.5", "kg"), Ok(qty("-1.5", "kg")));
+        assert_eq!(
+            qty("1", "kg") + qty("1", "g"),
+            Err(MeasurementError::NoConversion { from: Some(unit("g")), to: Some(unit("kg")) }),
+            "operators do not convert units",
+        );
+    }
+
+    fn unit_def(id: &str, dimension: Dimension, conversion: Option<UnitConversion>) -> Unit {
+        Unit { id: unit(id), label: id.to_string(), symbol: id.to_string(), dimension: Some(dimension), conversion, precision: None }
+    }
+
+    fn dimensioned_units() -> HashMap<String, Unit> {
+        let mut units = HashMap::new();
+        units.insert("kg".to_string(), unit_def("kg", Dimension::Mass, None));
+        units.insert("g".to_string(), unit_def("g", Dimension::Mass, Some(conversion("kg", "0.001"))));
+        units.insert("h".to_string(), unit_def("h", Dimension::Time, Some(conversion("s", "3600"))));
+        units.insert("s".to_string(), unit_def("s", Dimension::Time, None));
+        // misconfigured: declares a conversion to a unit of another dimension
+        units.insert("lb".to_string(), unit_def("lb", Dimension::Mass, Some(conversion("s", "0.45359237"))));
+        units
+    }
+
+    #[test]
+    fn test_dimension_checks() {
+        let units = dimensioned_units();
+
+        assert_eq!(dimension_of(&qty("1", "g"), &units), Ok(Some(Dimension::Mass)));
+        assert_eq!(dimension_of(&qty("1", "furlong"), &units), Ok(None));
+        assert_eq!(check_dimension(&qty("2", "h"), Dimension::Time, &units), Ok(()));
+        assert_eq!(
+            check_dimension(&qty("2", "kg"), Dimension::Time, &units),
+            Err(MeasurementError::DimensionMismatch { expected: Dimension::Time, found: Dimension::Mass }),
+        );
+        assert_eq!(check_dimension(&qty("2", "furlong"), Dimension::Time, &units), Ok(()), "undeclared dimensions are not checked");
+        assert_eq!(check_same_dimension(&qty("1", "kg"), &qty("5", "g"), &units), Ok(()));
+        assert_eq!(
+            check_same_dimension(&qty("1", "kg"), &qty("5", "h"), &units).unwrap_err().to_string(),
+            "Expected a quantity of mass, but found a quantity of time",
+        );
+    }
+
+    #[test]
+    fn test_conversions_between_dimensions_rejected() {
+        let units = dimensioned_units();
+
+        assert_eq!(convert(qty("1", "h"), unit("s"), &units), Ok(qty("3600", "s")));
+        assert_eq!(add(qty("1", "kg"), qty("500", "g"), &units), Ok(qty("1.5", "kg")));
+        assert_eq!(
+            convert(qty("1", "lb"), unit("s"), &units),
+            Err(MeasurementError::DimensionMismatch { expected: Dimension::Time, found: Dimension::Mass }),
+        );
+    }
+
+    struct UnavailableUnits;
+
+    impl UnitConversions for UnavailableUnits {
+        fn get_conversion(&self, unit: &UnitId) -> Result<Option<UnitConversion>, MeasurementError> {
+            Err(MeasurementError::UnitUnavailable { unit: unit.to_owned(), cause: "offline".to_string() })
+        }
+
+        fn get_dimension(&self, unit: &UnitId) -> Result<Option<Dimension>, MeasurementError> {
+            Err(MeasurementError::UnitUnavailable { unit: unit.to_owned(), cause: "offline".to_string() })
+        }
+    }
+
+    #[test]
+    fn test_unavailable_units_not_skipped() {
+        assert_eq!(
+            check_dimension(&qty("2", "h"), Dimension::Time, &UnavailableUnits).unwrap_err().to_string(),
+            "Unit 'h' could not be read: offline",
+        );
+        assert!(convert(qty("1", "g"), unit("kg"), &UnavailableUnits).is_err());
+        assert_eq!(add(qty("1", "kg"), qty("2", "kg"), &UnavailableUnits), Ok(qty("3", "kg")), "units are not read for same-unit arithmetic");
+    }
+
+    #[test]
+    fn test_base_units() {
+        assert_eq!(Dimension::Mass.base_unit(), Some(unit("om2:kg")));
+        assert_eq!(Dimension::Currency.base_unit(), None);
+    }
+
+    #[test]
+    fn test_json_compatibility() {
+        let quantity: QuantityValue = serde_json::from_str(r#"{ "hasNumericalValue": 0.1, "hasUnit": "kg" }"#).unwrap();
+        assert_eq!(quantity, qty("0.1", "kg"));
+        assert_eq!(serde_json::to_string(&qty("2500", "g")).unwrap(), r#"{"hasNumericalValue":2500,"hasUnit":"g"}"#);
     }
 }
@@ -38,6 +38,8 @@ macro_rules! simple_alias {
 
 simple_alias!(ActionId => String);
 
+simple_alias!(RevisionId => Address);
+
 simple_alias!(Timestamp => Iso8601);
 
 simple_alias!(ExternalURL => String);
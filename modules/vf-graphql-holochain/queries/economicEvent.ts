@@ -5,7 +5,7 @@
  * @since:   2019-05-27
  */
 
-import { DNAIdMappings, injectTypename, addTypename } from '../types'
+import { DNAIdMappings, injectTypename, addTypename, getRequestedFields } from '../types'
 import { mapZomeFn } from '../connection'
 
 import {
@@ -19,12 +19,12 @@ export default (dnaConfig?: DNAIdMappings, conductorUri?: string) => {
   const readAll = mapZomeFn(dnaConfig, conductorUri, 'observation', 'economic_event', 'get_all_events')
 
   return {
-    economicEvent: injectTypename('EconomicEvent', async (root, args): Promise<EconomicEvent> => {
-      return (await readOne({ address: args.id })).economicEvent
+    economicEvent: injectTypename('EconomicEvent', async (root, args, context, info): Promise<EconomicEvent> => {
+      return (await readOne({ address: args.id, fields: getRequestedFields(info) })).economicEvent
     }),
 
-    economicEvents: async (root, args): Promise<EconomicEvent[]> => {
-      return (await readAll({})).map(e => withTypename(e.economicEvent))
+    economicEvents: async (root, args, context, info): Promise<EconomicEvent[]> => {
+      return (await readAll({ fields: getRequestedFields(info) })).map(e => withTypename(e.economicEvent))
     },
   }
 }
@@ -5,7 +5,7 @@
  * @since:   2019-10-31
  */
 
-import { DNAIdMappings } from '../types'
+import { DNAIdMappings, getRequestedFields } from '../types'
 import { mapZomeFn } from '../connection'
 
 import {
@@ -17,12 +17,12 @@ export default (dnaConfig?: DNAIdMappings, conductorUri?: string) => {
   const readAll = mapZomeFn(dnaConfig, conductorUri, 'observation', 'economic_resource', 'get_all_resources')
 
   return {
-    economicResource: async (root, args): Promise<EconomicResource> => {
-      return (await readOne({ address: args.id })).economicResource
+    economicResource: async (root, args, context, info): Promise<EconomicResource> => {
+      return (await readOne({ address: args.id, fields: getRequestedFields(info) })).economicResource
     },
 
-    economicResources: async (root, args): Promise<EconomicResource[]> => {
-      return (await readAll({})).map(e => e.economicResource)
+    economicResources: async (root, args, context, info): Promise<EconomicResource[]> => {
+      return (await readAll({ fields: getRequestedFields(info) })).map(e => e.economicResource)
     },
   }
 }
@@ -10,7 +10,7 @@
  */
 
 import { IResolvers } from '@graphql-tools/utils'
-import { GraphQLScalarType } from 'graphql'
+import { GraphQLScalarType, GraphQLResolveInfo, SelectionSetNode } from 'graphql'
 import { parse } from 'fecha'
 import { Kind } from 'graphql/language'
 
@@ -61,7 +61,7 @@ export type APIOptions = ResolverOptions & ExtensionOptions
 // ...this might be unnecessarily present due to lack of familiarity with GraphQL?
 
 type ObjDecorator<T> = (obj: T) => T
-type Resolver<T> = (root, args) => Promise<T>
+type Resolver<T> = (root, args, context?, info?: GraphQLResolveInfo) => Promise<T>
 
 export function addTypename<T> (name: string): ObjDecorator<T> {
   return (obj) => {
@@ -71,13 +71,46 @@ export function addTypename<T> (name: string): ObjDecorator<T> {
 }
 
 export function injectTypename<T> (name: string, fn: Resolver<T>): Resolver<T> {
-  return async (root, args): Promise<T> => {
-    const data = await fn(root, args)
+  return async (root, args, context?, info?: GraphQLResolveInfo): Promise<T> => {
+    const data = await fn(root, args, context, info)
     data['__typename'] = name
     return data
   }
 }
 
+// helper for sparse field selection, to avoid loading link fields which a query has not asked for.
+// Returns the names of the fields selected on the record being resolved, or `undefined` (all fields)
+// if no resolve info is available.
+
+export function getRequestedFields (info?: GraphQLResolveInfo): string[] | undefined {
+  if (!info) {
+    return undefined
+  }
+  const fields: string[] = []
+  const collect = (selectionSet?: SelectionSetNode) => {
+    if (!selectionSet) return
+    selectionSet.selections.forEach(selection => {
+      switch (selection.kind) {
+        case Kind.FIELD:
+          if (-1 === fields.indexOf(selection.name.value)) {
+            fields.push(selection.name.value)
+          }
+          break
+        case Kind.INLINE_FRAGMENT:
+          collect(selection.selectionSet)
+          break
+        case Kind.FRAGMENT_SPREAD: {
+          const fragment = info.fragments[selection.name.value]
+          collect(fragment && fragment.selectionSet)
+          break
+        }
+      }
+    })
+  }
+  info.fieldNodes.forEach(node => collect(node.selectionSet))
+  return fields
+}
+
 // default 'full suite' VF module set supported by Holo-REA
 
 export const DEFAULT_VF_MODULES = [
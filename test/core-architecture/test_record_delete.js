@@ -46,6 +46,37 @@ runner.registerScenario('record deletion API', async (s, t) => {
   t.equal(readResp.Err.Internal, 'No entry at this address', 'record not retrievable once deleted')
 })
 
+runner.registerScenario('soft-deleted records can be restored', async (s, t) => {
+  const { planning } = await s.players({ planning: config }, true)
+
+  const commitment = {
+    note: 'a commitment to provide something',
+    ...testEventProps,
+  }
+  const commitmentResponse = await planning.call('planning', 'commitment', 'create_commitment', { commitment })
+  t.ok(commitmentResponse.Ok.commitment && commitmentResponse.Ok.commitment.id, 'commitment created successfully')
+  await s.consistency()
+  const commitmentId = commitmentResponse.Ok.commitment.id
+
+  const delResp = await planning.call('planning', 'commitment', 'delete_commitment', { address: commitmentId })
+  t.ok(delResp.Ok, 'record deleted successfully')
+  await s.consistency()
+
+  let readResp = await planning.call('planning', 'commitment', 'get_commitment', { address: commitmentId })
+  t.equal(readResp.Err.Internal, 'No entry at this address', 'record not retrievable once deleted')
+
+  const restoreResp = await planning.call('planning', 'commitment', 'restore_commitment', { address: commitmentId })
+  t.ok(restoreResp.Ok, 'record restored successfully')
+  await s.consistency()
+
+  readResp = await planning.call('planning', 'commitment', 'get_commitment', { address: commitmentId })
+  t.equal(readResp.Ok.commitment.id, commitmentId, 'record retrievable once restored')
+  t.equal(readResp.Ok.commitment.note, commitment.note, 'record data preserved')
+
+  const restoreAgainResp = await planning.call('planning', 'commitment', 'restore_commitment', { address: commitmentId })
+  t.equal(restoreAgainResp.Ok, false, 'restoring a record which is not deleted has no effect')
+})
+
 runner.registerScenario('Cannot delete records of a different type via zome API deletion handlers', async (s, t) => {
   const { planning } = await s.players({ planning: config }, true)
 
@@ -68,4 +68,38 @@ runner.registerScenario('updates with fields nulled remove original value', asyn
   t.equal(readResponse.Ok.economicEvent.note, undefined, 'field removed if nulled')
 })
 
+runner.registerScenario('updates against an outdated revision are rejected', async (s, t) => {
+  const { observation } = await s.players({ observation: config }, true)
+
+  const event = {
+    note: 'test event 3',
+    action: 'raise',
+    ...testEventProps,
+  }
+
+  const createEventResponse = await observation.call('observation', 'economic_event', 'create_event', { event })
+  t.ok(createEventResponse.Ok.economicEvent && createEventResponse.Ok.economicEvent.revisionId, 'revision ID returned on creation')
+  await s.consistency()
+  const eventId = createEventResponse.Ok.economicEvent.id
+  const initialRevision = createEventResponse.Ok.economicEvent.revisionId
+
+  const updateEventResponse = await observation.call('observation', 'economic_event', 'update_event', {
+    event: { id: eventId, revisionId: initialRevision, note: 'first edit' },
+  })
+  t.equal(updateEventResponse.Ok.economicEvent.note, 'first edit', 'update against current revision applied')
+  t.notEqual(updateEventResponse.Ok.economicEvent.revisionId, initialRevision, 'revision ID changes on update')
+  await s.consistency()
+  const currentRevision = updateEventResponse.Ok.economicEvent.revisionId
+
+  const conflictResponse = await observation.call('observation', 'economic_event', 'update_event', {
+    event: { id: eventId, revisionId: initialRevision, note: 'conflicting edit' },
+  })
+  t.ok(conflictResponse.Err.Internal.indexOf(currentRevision) !== -1, 'update against outdated revision rejected with current revision ID')
+  await s.consistency()
+
+  const readResponse = await observation.call('observation', 'economic_event', 'get_event', { address: eventId })
+  t.equal(readResponse.Ok.economicEvent.note, 'first edit', 'conflicting update not applied')
+  t.equal(readResponse.Ok.economicEvent.revisionId, currentRevision, 'revision ID returned on read')
+})
+
 runner.run()
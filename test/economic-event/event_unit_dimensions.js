@@ -0,0 +1,66 @@
+const {
+  getDNA,
+  buildConfig,
+  buildRunner,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig({
+  observation: getDNA('observation'),
+  specification: getDNA('specification'),
+}, {
+  vf_specification: ['observation', 'specification'],
+})
+
+const testEventProps = {
+  provider: 'agentid-1-todo',
+  receiver: 'agentid-2-todo',
+  hasPointInTime: '2019-11-19T04:29:55.056Z',
+  resourceClassifiedAs: ['http://www.productontology.org/doc/Apple.ttl'],
+}
+
+runner.registerScenario('EconomicEvent quantities must measure the expected dimension', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  // 'om2:kg' & 'om2:h' are built-in units with declared dimensions
+  const hResp = await alice.call('specification', 'unit', 'get_unit', { id: 'om2:h' })
+  t.equal(hResp.Ok.unit.dimension, 'time', 'unit dimension available')
+
+  // SCENARIO: work must be measured in time
+  let resp = await alice.call('observation', 'economic_event', 'create_event', {
+    event: { action: 'work', effortQuantity: { hasNumericalValue: 3, hasUnit: 'om2:kg' }, ...testEventProps },
+  })
+  await s.consistency()
+  t.equal(resp.Err && resp.Err.Internal, 'Expected a quantity of time, but found a quantity of mass', 'work event measured in mass rejected')
+
+  resp = await alice.call('observation', 'economic_event', 'create_event', {
+    event: { action: 'work', effortQuantity: { hasNumericalValue: 3, hasUnit: 'om2:h' }, ...testEventProps },
+  })
+  await s.consistency()
+  t.ok(resp.Ok, 'work event measured in time accepted')
+
+  // SCENARIO: events affecting resources must measure the same dimension as the resource
+  resp = await alice.call('observation', 'economic_event', 'create_event', {
+    event: { action: 'raise', resourceQuantity: { hasNumericalValue: 10, hasUnit: 'om2:kg' }, ...testEventProps },
+    new_inventoried_resource: { note: 'apples, by weight' },
+  })
+  await s.consistency()
+  const resourceId = resp.Ok.economicResource.id
+
+  resp = await alice.call('observation', 'economic_event', 'create_event', {
+    event: {
+      action: 'transfer',
+      resourceInventoriedAs: resourceId,
+      resourceQuantity: { hasNumericalValue: 2, hasUnit: 'om2:h' },
+      ...testEventProps,
+    },
+  })
+  await s.consistency()
+  t.equal(resp.Err && resp.Err.Internal, 'Expected a quantity of mass, but found a quantity of time', 'transfer of mismatched dimension rejected')
+
+  const readResp = await alice.call('observation', 'economic_resource', 'get_resource', { address: resourceId })
+  t.deepEqual(readResp.Ok.economicResource.accountingQuantity, { hasNumericalValue: 10, hasUnit: 'om2:kg' }, 'resource unaffected by rejected event')
+})
+
+runner.run()
@@ -31,4 +31,44 @@ runner.registerScenario('create simplest event', async (s, t) => {
   t.deepEqual(createEventResponse.Ok.economicEvent.inScopeOf, ['some-accounting-scope'], 'event inScopeOf saved')
 })
 
+runner.registerScenario('retried event creation is idempotent', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  const event = {
+    note: 'test event',
+    action: 'raise',
+    provider: 'todo-some-agent-id',
+    receiver: 'todo-some-agent-id',
+    hasPointInTime: '2019-11-19T12:12:42.739Z',
+    resourceClassifiedAs: ['some-resource-type'],
+    resourceQuantity: { hasNumericalValue: 1 },
+    idempotencyKey: 'client-request-1',
+  }
+  const resource = { note: 'test resource' }
+
+  const cResp1 = await alice.call('observation', 'economic_event', 'create_event', { event, new_inventoried_resource: resource })
+  await s.consistency()
+  t.ok(cResp1.Ok.economicEvent, 'event created')
+  t.ok(cResp1.Ok.economicResource, 'resource created')
+
+  const cResp2 = await alice.call('observation', 'economic_event', 'create_event', { event, new_inventoried_resource: resource })
+  await s.consistency()
+  t.equal(cResp2.Ok.economicEvent.id, cResp1.Ok.economicEvent.id, 'retry returns the original event')
+  t.equal(cResp2.Ok.economicResource.id, cResp1.Ok.economicResource.id, 'retry returns the original resource')
+
+  const cResp3 = await alice.call('observation', 'economic_event', 'create_event', {
+    event: { ...event, resourceInventoriedAs: cResp1.Ok.economicResource.id, idempotencyKey: 'client-request-2' },
+  })
+  await s.consistency()
+  t.ok(cResp3.Ok.economicEvent, 'event against resource created')
+  const cResp4 = await alice.call('observation', 'economic_event', 'create_event', {
+    event: { ...event, resourceInventoriedAs: cResp1.Ok.economicResource.id, idempotencyKey: 'client-request-2' },
+  })
+  await s.consistency()
+  t.equal(cResp4.Ok.economicEvent.id, cResp3.Ok.economicEvent.id, 'retry returns the original event')
+
+  const readResp = await alice.call('observation', 'economic_resource', 'get_resource', { address: cResp1.Ok.economicResource.id })
+  t.equal(readResp.Ok.economicResource.accountingQuantity.hasNumericalValue, 2, 'resource quantity only updated once')
+})
+
 runner.run()
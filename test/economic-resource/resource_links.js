@@ -138,6 +138,21 @@ runner.registerScenario('EconomicResource composition / containment functionalit
   t.equal(readResp.data.contained.containedIn.id, resourceId1, 'containedIn ref OK in GraphQL API')
 
 
+  // SCENARIO: sparse field selection
+  readResp = await alice.call('observation', 'economic_resource', 'get_resource', { address: resourceId1, fields: ['id', 'note'] })
+  readResource = readResp.Ok.economicResource
+  t.equal(readResource.note, 'container resource', 'entry fields always returned')
+  t.notOk(readResource.contains, 'unselected link fields not loaded')
+
+  readResp = await alice.call('observation', 'economic_resource', 'get_resource', { address: resourceId1, fields: ['contains'] })
+  readResource = readResp.Ok.economicResource
+  t.equal(readResource.contains && readResource.contains.length, 1, 'selected link fields loaded')
+
+  readResp = await alice.call('observation', 'economic_resource', 'query_resources', { params: { containedIn: resourceId1, fields: ['id'] } })
+  t.equal(readResp.Ok.length, 1, 'field selection accepted by queries')
+  t.notOk(readResp.Ok[0].economicResource.containedIn, 'unselected link fields not loaded in query results')
+
+
   // SCENARIO: delete resource, check links are removed
   // :TODO: needs some thought
   // const dResp = await alice.call('observation', 'economic_resource', 'delete_resource', { address: resourceId3 })
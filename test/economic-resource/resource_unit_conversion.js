@@ -0,0 +1,142 @@
+const {
+  getDNA,
+  buildConfig,
+  buildRunner,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig({
+  observation: getDNA('observation'),
+  specification: getDNA('specification'),
+}, {
+  vf_specification: ['observation', 'specification'],
+})
+
+const testEventProps = {
+  provider: 'agentid-1-todo',
+  receiver: 'agentid-2-todo',
+  hasPointInTime: '2019-11-19T04:29:55.056Z',
+  resourceClassifiedAs: ['http://www.productontology.org/doc/Apple.ttl'],
+}
+
+runner.registerScenario('EconomicEvent quantities are converted to the units of affected resources', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  // SCENARIO: declare units & conversions; 'om2:kg' & 'om2:g' are built-in
+  const jinResp = await alice.call('specification', 'unit', 'create_unit', { unit: {
+    label: 'catties',
+    symbol: 'jin',
+    conversion: { referenceUnit: 'om2:kg', factor: 0.5 },
+  } })
+  const bktResp = await alice.call('specification', 'unit', 'create_unit', { unit: { label: 'baskets', symbol: 'bkt' } })
+  await s.consistency()
+  t.ok(jinResp.Ok && bktResp.Ok, 'units created')
+  t.deepEqual(jinResp.Ok.unit.conversion, { referenceUnit: 'om2:kg', factor: '0.5' }, 'unit conversion stored')
+
+  // SCENARIO: resource tracked in grams
+  const cResp = await alice.call('observation', 'economic_event', 'create_event', {
+    event: {
+      action: 'raise',
+      resourceQuantity: { hasNumericalValue: 500, hasUnit: 'om2:g' },
+      ...testEventProps,
+    },
+    new_inventoried_resource: { note: 'apples, by weight' },
+  })
+  await s.consistency()
+  t.ok(cResp.Ok.economicResource, 'resource created')
+  const resourceId = cResp.Ok.economicResource.id
+
+  // SCENARIO: event in a convertible unit
+  const produceResp = await alice.call('observation', 'economic_event', 'create_event', {
+    event: {
+      action: 'produce',
+      resourceInventoriedAs: resourceId,
+      resourceQuantity: { hasNumericalValue: 2, hasUnit: 'om2:kg' },
+      ...testEventProps,
+    },
+  })
+  await s.consistency()
+  t.ok(produceResp.Ok, 'event in other unit of the same kind accepted')
+
+  let readResp = await alice.call('observation', 'economic_resource', 'get_resource', { address: resourceId })
+  t.deepEqual(readResp.Ok.economicResource.accountingQuantity, { hasNumericalValue: 2500, hasUnit: 'om2:g' }, 'event quantity converted to resource unit')
+
+  // SCENARIO: event in a user-defined unit converting to a built-in one
+  const customResp = await alice.call('observation', 'economic_event', 'create_event', {
+    event: {
+      action: 'produce',
+      resourceInventoriedAs: resourceId,
+      resourceQuantity: { hasNumericalValue: 1, hasUnit: 'jin' },
+      ...testEventProps,
+    },
+  })
+  await s.consistency()
+  t.ok(customResp.Ok, 'event in user-defined unit accepted')
+
+  readResp = await alice.call('observation', 'economic_resource', 'get_resource', { address: resourceId })
+  t.deepEqual(readResp.Ok.economicResource.accountingQuantity, { hasNumericalValue: 3000, hasUnit: 'om2:g' }, 'user-defined unit converted via built-in unit')
+
+  // SCENARIO: event in an unrelated unit
+  const badResp = await alice.call('observation', 'economic_event', 'create_event', {
+    event: {
+      action: 'produce',
+      resourceInventoriedAs: resourceId,
+      resourceQuantity: { hasNumericalValue: 1, hasUnit: 'bkt' },
+      ...testEventProps,
+    },
+  })
+  await s.consistency()
+  t.ok(badResp.Err, 'event in unconvertible unit rejected')
+  t.equal(badResp.Err && badResp.Err.Internal, "No conversion from 'bkt' to 'om2:g'", 'correct error reported')
+
+  readResp = await alice.call('observation', 'economic_resource', 'get_resource', { address: resourceId })
+  t.deepEqual(readResp.Ok.economicResource.accountingQuantity, { hasNumericalValue: 3000, hasUnit: 'om2:g' }, 'resource unaffected by rejected event')
+})
+
+runner.registerScenario('EconomicResource fractional quantities are sent as decimal strings', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  const cResp = await alice.call('observation', 'economic_event', 'create_event', {
+    event: {
+      action: 'raise',
+      resourceQuantity: { hasNumericalValue: 0.1, hasUnit: 'om2:kg' },
+      ...testEventProps,
+    },
+    new_inventoried_resource: { note: 'apples, by weight' },
+  })
+  await s.consistency()
+  t.deepEqual(cResp.Ok.economicEvent.resourceQuantity, { hasNumericalValue: '0.1', hasUnit: 'om2:kg' }, 'fractional event quantity returned as a string')
+  const resourceId = cResp.Ok.economicResource.id
+
+  // strings are accepted in requests, too
+  const produceResp = await alice.call('observation', 'economic_event', 'create_event', {
+    event: {
+      action: 'produce',
+      resourceInventoriedAs: resourceId,
+      resourceQuantity: { hasNumericalValue: '0.2', hasUnit: 'om2:kg' },
+      ...testEventProps,
+    },
+  })
+  await s.consistency()
+  t.ok(produceResp.Ok, 'event with string quantity accepted')
+
+  let readResp = await alice.call('observation', 'economic_resource', 'get_resource', { address: resourceId })
+  t.deepEqual(readResp.Ok.economicResource.accountingQuantity, { hasNumericalValue: '0.3', hasUnit: 'om2:kg' }, 'fractional quantities summed exactly and returned as a string')
+
+  const wholeResp = await alice.call('observation', 'economic_event', 'create_event', {
+    event: {
+      action: 'produce',
+      resourceInventoriedAs: resourceId,
+      resourceQuantity: { hasNumericalValue: 0.7, hasUnit: 'om2:kg' },
+      ...testEventProps,
+    },
+  })
+  await s.consistency()
+  t.ok(wholeResp.Ok, 'event accepted')
+
+  readResp = await alice.call('observation', 'economic_resource', 'get_resource', { address: resourceId })
+  t.deepEqual(readResp.Ok.economicResource.accountingQuantity, { hasNumericalValue: 1, hasUnit: 'om2:kg' }, 'whole quantities returned as numbers')
+})
+
+runner.run()
@@ -0,0 +1,82 @@
+const {
+  getDNA,
+  buildConfig,
+  buildRunner,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig({
+  observation: getDNA('observation'),
+  specification: getDNA('specification'),
+}, {
+  vf_specification: ['observation', 'specification'],
+})
+
+const testEventProps = {
+  provider: 'agentid-1-todo',
+  receiver: 'agentid-2-todo',
+  hasPointInTime: '2019-11-19T04:29:55.056Z',
+  resourceClassifiedAs: ['resource-type-uri'],
+  resourceQuantity: { hasNumericalValue: 1, hasUnit: 'dangling-unit-todo-tidy-up' },
+}
+
+runner.registerScenario('EconomicResource upstream trace', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  // SCENARIO: a resource produced by a process with a single input, which consumes another resource
+  const ueResp = await alice.call('observation', 'economic_event', 'create_event', {
+    event: {
+      note: 'test upstream resource event',
+      action: 'produce',
+      ...testEventProps,
+    },
+    new_inventoried_resource: { note: 'upstream resource' },
+  })
+  await s.consistency()
+  const uEventId = ueResp.Ok.economicEvent.id
+  const upstreamResourceId = ueResp.Ok.economicResource.id
+
+  const pResp = await alice.call('observation', 'process', 'create_process', { process: { name: 'test process for tracing' } })
+  await s.consistency()
+  const processId = pResp.Ok.process.id
+
+  const ieResp = await alice.call('observation', 'economic_event', 'create_event', { event: {
+    note: 'test input event',
+    action: 'consume',
+    inputOf: processId,
+    resourceInventoriedAs: upstreamResourceId,
+    ...testEventProps,
+  } })
+  await s.consistency()
+  const iEventId = ieResp.Ok.economicEvent.id
+
+  const oeResp = await alice.call('observation', 'economic_event', 'create_event', {
+    event: {
+      note: 'test output event',
+      action: 'produce',
+      outputOf: processId,
+      ...testEventProps,
+    },
+    new_inventoried_resource: { note: 'traced resource' },
+  })
+  await s.consistency()
+  const oEventId = oeResp.Ok.economicEvent.id
+  const resourceId = oeResp.Ok.economicResource.id
+
+  // SCENARIO: trace
+  const traceResp = await alice.call('observation', 'economic_resource', 'trace_resource_upstream', { address: resourceId, max_depth: null, max_nodes: null })
+  t.ok(traceResp.Ok, 'trace succeeded')
+  const nodeIds = traceResp.Ok.nodes.map(n => n.address)
+  t.deepEqual(nodeIds, [resourceId, oEventId, processId, iEventId, upstreamResourceId, uEventId], 'resource, producing event, process, its input, the consumed resource and the event creating it visited in order')
+  t.deepEqual(traceResp.Ok.nodes.map(n => n.depth), [0, 1, 2, 3, 4, 5], 'node depths OK')
+  t.ok(nodeIds.includes(upstreamResourceId), 'upstream resource appears in trace')
+  // events link back to the resources they affect, so each resource <-> event link is returned in both directions
+  t.equal(traceResp.Ok.edges.length, 8, 'edges between visited records returned')
+  t.equal(traceResp.Ok.truncated, false, 'trace not truncated')
+
+  const limitedResp = await alice.call('observation', 'economic_resource', 'trace_resource_upstream', { address: resourceId, max_depth: 1, max_nodes: null })
+  t.deepEqual(limitedResp.Ok.nodes.map(n => n.address), [resourceId, oEventId], 'trace depth limited')
+})
+
+runner.run()
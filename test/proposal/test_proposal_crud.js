@@ -93,6 +93,32 @@ runner.registerScenario('Proposal record API', async (s, t) => {
   })
   t.deepEqual(updatedGetResp.data.res, { id: psId, created: exampleEntry.created, ...updatedExampleEntry }, 'record updated OK')
 
+  // explicit nulls erase optional fields; omitted fields are left untouched
+  await alice.graphQL(`
+    mutation($rs: ProposalUpdateParams!) {
+      res: updateProposal(proposal: $rs) {
+        proposal {
+          id
+        }
+      }
+    }
+  `, {
+    rs: { id: psId, name: null },
+  })
+  await s.consistency()
+
+  const erasedGetResp = await alice.graphQL(`
+    query($id: ID!) {
+      res: proposal(id: $id) {
+        name
+        note
+      }
+    }
+  `, {
+    id: psId,
+  })
+  t.deepEqual(erasedGetResp.data.res, { name: null, note: updatedExampleEntry.note }, 'null field erased in update')
+
   const deleteResult = await alice.graphQL(`
     mutation($id: ID!) {
       res: deleteProposal(id: $id)
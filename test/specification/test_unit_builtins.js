@@ -0,0 +1,52 @@
+// :NOTE: this is a minimal test- the unit catalogue is tested in Rust unit tests
+
+const {
+  getDNA,
+  buildConfig,
+  buildRunner,
+} = require('../init')
+
+const runner = buildRunner()
+
+const config = buildConfig({
+  specification: getDNA('specification'),
+}, {})
+
+runner.registerScenario('Built-in unit API', async (s, t) => {
+  const { alice } = await s.players({ alice: config }, true)
+
+  const getResp = await alice.call('specification', 'unit', 'get_unit', { id: 'om2:g' })
+  t.deepEqual(getResp.Ok.unit, {
+    id: 'om2:g',
+    label: 'gram',
+    symbol: 'g',
+    dimension: 'mass',
+    conversion: { referenceUnit: 'om2:kg', factor: '0.001' },
+  }, 'built-in unit read OK, with fractional factor as a decimal string')
+
+  const queryResp = await alice.call('specification', 'unit', 'query_units', { params: {} })
+  const unitIds = queryResp.Ok.map(u => u.unit.id)
+  t.ok(unitIds.includes('om2:kg') && unitIds.includes('om2:h') && unitIds.includes('om2:one'), 'built-in units listed')
+
+  const redefineResp = await alice.call('specification', 'unit', 'create_unit', { unit: { label: 'kilos', symbol: 'om2:kg' } })
+  t.equal(redefineResp.Err && redefineResp.Err.Internal, "Unit 'om2:kg' is built-in and cannot be modified", 'built-in units cannot be redefined')
+
+  const reservedResp = await alice.call('specification', 'unit', 'create_unit', { unit: { label: 'furlongs', symbol: 'om2:fur' } })
+  t.equal(reservedResp.Err && reservedResp.Err.Internal, "Unit IDs beginning with 'om2:' are reserved for built-in units", 'built-in unit namespace reserved')
+
+  const deleteResp = await alice.call('specification', 'unit', 'delete_unit', { id: 'om2:kg' })
+  t.ok(deleteResp.Err, 'built-in units cannot be deleted')
+
+  const stillThere = await alice.call('specification', 'unit', 'get_unit', { id: 'om2:kg' })
+  t.equal(stillThere.Ok.unit.label, 'kilogram', 'built-in unit unaffected')
+
+  // SCENARIO: stored units may share the symbol of a built-in unit without shadowing it
+  const storedResp = await alice.call('specification', 'unit', 'create_unit', { unit: { label: 'kilos', symbol: 'kg' } })
+  await s.consistency()
+  t.ok(storedResp.Ok, 'unit with built-in symbol created')
+
+  const storedRead = await alice.call('specification', 'unit', 'get_unit', { id: 'kg' })
+  t.equal(storedRead.Ok.unit.label, 'kilos', 'stored unit read by its own ID')
+})
+
+runner.run()
@@ -113,4 +113,40 @@ runner.registerScenario('Unit record API', async (s, t) => {
   t.notEqual(-1, queryForDeleted.errors[0].message.indexOf('No entry at this address'), 'correct error reported')
 })
 
+runner.registerScenario('Unit IDs must be unique', async (s, t) => {
+  const alice = await buildPlayer(s, 'alice', config)
+
+  const createMutation = `
+    mutation($rs: UnitCreateParams!) {
+      res: createUnit(unit: $rs) {
+        unit {
+          id
+        }
+      }
+    }
+  `
+
+  const createResp = await alice.graphQL(createMutation, { rs: updatedExampleEntry })
+  await s.consistency()
+  t.equal(createResp.data.res.unit.id, updatedExampleEntry.symbol, 'record created')
+
+  const conflictResp = await alice.graphQL(createMutation, { rs: { ...updatedExampleEntry, label: 'kilos' } })
+  await s.consistency()
+  t.equal(conflictResp.errors.length, 1, 'creating a record with a duplicate ID is an error')
+  t.notEqual(-1, conflictResp.errors[0].message.indexOf('An entry is already anchored with this ID'), 'correct error reported')
+
+  const getResp = await alice.graphQL(`
+    query($id: ID!) {
+      res: unit(id: $id) {
+        id
+        label
+        symbol
+      }
+    }
+  `, {
+    id: updatedExampleEntry.symbol,
+  })
+  t.deepEqual(getResp.data.res, { id: updatedExampleEntry.symbol, ...updatedExampleEntry }, 'original record unaffected')
+})
+
 runner.run()